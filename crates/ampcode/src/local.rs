@@ -21,8 +21,9 @@ use std::path::{Path, PathBuf};
 /// Uses `~/.local/share/amp/threads/` on both macOS and Linux.
 #[must_use]
 pub fn threads_dir() -> PathBuf {
-    let home = std::env::var("HOME").unwrap_or_else(|_| "/tmp".into());
-    PathBuf::from(home).join(".local/share/amp/threads")
+    dirs::home_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join(".local/share/amp/threads")
 }
 
 /// Validate a thread ID to prevent path traversal.
@@ -22,11 +22,13 @@ const AMPCODE_HOST: &str = "https://ampcode.com/";
 
 /// Resolve the Amp secrets file path.
 ///
-/// Uses `$HOME` — falls back to `/tmp` if `HOME` is unset.
+/// Lives under the user's home directory — falls back to the system temp
+/// directory if it cannot be determined.
 #[must_use]
 pub fn secrets_path() -> PathBuf {
-    let home = std::env::var("HOME").unwrap_or_else(|_| "/tmp".into());
-    PathBuf::from(home).join(".local/share/amp/secrets.json")
+    dirs::home_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join(".local/share/amp/secrets.json")
 }
 
 /// Load the bearer token for `ampcode.com` from the native secrets file.
@@ -13,6 +13,9 @@ const TIMEOUT_SECS: u64 = 120;
 const SUCCESS_HTML: &[u8] =
     b"HTTP/1.1 200 OK\r\nContent-Type: text/html\r\nConnection: close\r\n\r\n\
     <html><body><h1>Login successful!</h1><p>You may close this tab.</p></body></html>";
+const FAILURE_HTML: &[u8] =
+    b"HTTP/1.1 400 Bad Request\r\nContent-Type: text/html\r\nConnection: close\r\n\r\n\
+    <html><body><h1>Login failed</h1><p>Check the terminal for details. You may close this tab.</p></body></html>";
 
 /// Listeners bound on both IPv4 and IPv6 loopback (whichever are available).
 pub struct CallbackListeners {
@@ -82,14 +85,37 @@ async fn handle_callback_stream(
 
     let request = String::from_utf8_lossy(&buf[..n]);
     let params = parse_query_from_request(&request)?;
+    let denied = callback_error(&params);
 
+    let page = if denied.is_some() {
+        FAILURE_HTML
+    } else {
+        SUCCESS_HTML
+    };
     stream
-        .write_all(SUCCESS_HTML)
+        .write_all(page)
         .await
         .map_err(|e| ByokError::Auth(format!("write error: {e}")))?;
     let _ = stream.shutdown().await;
 
-    Ok(params)
+    match denied {
+        Some(e) => Err(e),
+        None => Ok(params),
+    }
+}
+
+/// Turns an OAuth error redirect (`?error=...&error_description=...`) into
+/// a [`ByokError::Auth`] carrying the provider's reason.
+fn callback_error(params: &HashMap<String, String>) -> Option<ByokError> {
+    let error = params.get("error")?;
+    let detail = match params.get("error_description") {
+        Some(description) => description.clone(),
+        None if error == "access_denied" => "user denied access".to_owned(),
+        None => "provider rejected the authorization request".to_owned(),
+    };
+    Some(ByokError::Auth(format!(
+        "authorization failed ({error}): {detail}"
+    )))
 }
 
 /// Bind a local port, wait for a single OAuth callback, and return its query parameters.
@@ -104,6 +130,39 @@ pub async fn wait_for_callback(port: u16) -> Result<HashMap<String, String>> {
     accept_callback(listeners).await
 }
 
+/// Read an OAuth redirect URL pasted into stdin and return its query parameters.
+///
+/// For headless machines: the browser runs elsewhere, so its redirect to
+/// `localhost` never reaches byokey. The user copies the URL from the address
+/// bar instead. A bare query string is accepted too.
+///
+/// # Errors
+///
+/// Returns an error if stdin cannot be read, the query is malformed, or the
+/// provider redirected with an OAuth error.
+pub async fn read_pasted_callback() -> Result<HashMap<String, String>> {
+    use tokio::io::AsyncBufReadExt as _;
+
+    let mut line = String::new();
+    tokio::io::BufReader::new(tokio::io::stdin())
+        .read_line(&mut line)
+        .await
+        .map_err(|e| ByokError::Auth(format!("failed to read callback URL: {e}")))?;
+    parse_pasted_callback(&line)
+}
+
+fn parse_pasted_callback(input: &str) -> Result<HashMap<String, String>> {
+    let input = input.trim();
+    let query = input.split_once('?').map_or(input, |(_, q)| q);
+    let query = query.split_once('#').map_or(query, |(q, _)| q);
+    let params: HashMap<String, String> = serde_urlencoded::from_str(query)
+        .map_err(|e| ByokError::Auth(format!("invalid callback URL: {e}")))?;
+    match callback_error(&params) {
+        Some(e) => Err(e),
+        None => Ok(params),
+    }
+}
+
 fn parse_query_from_request(request: &str) -> Result<HashMap<String, String>> {
     // First line format: "GET /?code=...&state=... HTTP/1.1"
     let first_line = request.lines().next().unwrap_or("");
@@ -132,6 +191,49 @@ mod tests {
         assert!(params.is_empty());
     }
 
+    #[test]
+    fn test_callback_error_access_denied() {
+        let req = "GET /?error=access_denied&state=xyz HTTP/1.1\r\n\r\n";
+        let params = parse_query_from_request(req).unwrap();
+        let err = callback_error(&params).unwrap().to_string();
+        assert!(err.contains("access_denied"), "{err}");
+        assert!(err.contains("user denied access"), "{err}");
+        assert!(!err.contains("missing code"), "{err}");
+    }
+
+    #[test]
+    fn test_callback_error_uses_description() {
+        let req =
+            "GET /?error=invalid_scope&error_description=Scope%20not%20allowed HTTP/1.1\r\n\r\n";
+        let params = parse_query_from_request(req).unwrap();
+        let err = callback_error(&params).unwrap().to_string();
+        assert!(err.contains("Scope not allowed"), "{err}");
+    }
+
+    #[test]
+    fn test_callback_error_absent_on_success() {
+        let req = "GET /?code=abc&state=xyz HTTP/1.1\r\n\r\n";
+        let params = parse_query_from_request(req).unwrap();
+        assert!(callback_error(&params).is_none());
+    }
+
+    #[test]
+    fn test_parse_pasted_callback_url() {
+        let params =
+            parse_pasted_callback("  http://localhost:54545/callback?code=a%2Bb&state=st\n")
+                .unwrap();
+        assert_eq!(params.get("code").map(String::as_str), Some("a+b"));
+        assert_eq!(params.get("state").map(String::as_str), Some("st"));
+
+        let params = parse_pasted_callback("code=abc&state=xyz#frag").unwrap();
+        assert_eq!(params.get("state").map(String::as_str), Some("xyz"));
+
+        let err = parse_pasted_callback("http://localhost:8085/callback?error=access_denied")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("user denied access"), "{err}");
+    }
+
     #[test]
     fn test_parse_query_encoded() {
         let req = "GET /?code=a%2Bb&state=st HTTP/1.1\r\n\r\n";
@@ -52,6 +52,17 @@ pub trait AuthCodeFlow: Send + Sync {
     }
 }
 
+/// How the OAuth redirect gets back to byokey.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CallbackMode {
+    /// Open the browser and listen on the provider's local callback port.
+    #[default]
+    Listen,
+    /// Print the authorization URL and read the redirected URL from stdin,
+    /// for machines without a local browser (e.g. over SSH).
+    Paste,
+}
+
 /// Run the Authorization Code flow for any provider implementing [`AuthCodeFlow`].
 ///
 /// # Errors
@@ -63,7 +74,34 @@ pub async fn run<P: AuthCodeFlow>(
     auth: &AuthManager,
     http: &rquest::Client,
     account: Option<&str>,
+    label: Option<&str>,
+    events: Option<&mpsc::Sender<LoginProgress>>,
+) -> Result<()> {
+    run_with_mode(
+        provider,
+        auth,
+        http,
+        account,
+        label,
+        events,
+        CallbackMode::Listen,
+    )
+    .await
+}
+
+/// Like [`run`], receiving the OAuth redirect as selected by `mode`.
+///
+/// # Errors
+///
+/// Same as [`run`].
+pub async fn run_with_mode<P: AuthCodeFlow>(
+    provider: &P,
+    auth: &AuthManager,
+    http: &rquest::Client,
+    account: Option<&str>,
+    label: Option<&str>,
     events: Option<&mpsc::Sender<LoginProgress>>,
+    mode: CallbackMode,
 ) -> Result<()> {
     tracing::info!(provider = %provider.provider_name(), "starting OAuth login");
     if events.is_none() {
@@ -83,6 +121,58 @@ pub async fn run<P: AuthCodeFlow>(
     let state = pkce::random_state();
     let auth_url = provider.build_auth_url(&creds.client_id, &challenge, &state);
 
+    let params = match mode {
+        CallbackMode::Listen => listen_for_callback(provider, &auth_url, events).await?,
+        CallbackMode::Paste => {
+            eprintln!();
+            eprintln!("Open this URL in a browser on any machine and sign in:");
+            eprintln!("  {auth_url}");
+            eprintln!();
+            eprintln!(
+                "The browser then fails to load a http://localhost:{}/... page.",
+                provider.callback_port()
+            );
+            eprintln!("Copy that page's full URL from the address bar and paste it here:");
+            emit(
+                events,
+                LoginProgress::OpenedBrowser {
+                    url: auth_url.clone(),
+                    user_code: None,
+                },
+            )
+            .await;
+            callback::read_pasted_callback().await?
+        }
+    };
+
+    verify_state(&params, &state)?;
+    let code = extract_code(&params)?;
+    tracing::info!(provider = %provider.provider_name(), "received OAuth code, exchanging");
+    if events.is_none() {
+        eprintln!("[login] received OAuth code, exchanging for token...");
+    }
+    // Note: LoginProgress::GotCode is intentionally NOT emitted here.
+    // The next `exchange_code` call *is* the "exchanging" work, so emitting
+    // GotCode immediately before Exchanging would produce two consecutive
+    // events with no observable gap. GotCode remains in the enum as a
+    // reserved value for future use (e.g. flows with a distinct pre-exchange
+    // stage such as email/SMS verification).
+    emit(events, LoginProgress::Exchanging).await;
+    let tok = provider
+        .exchange_code(http, &creds, code, &verifier, &state)
+        .await?;
+    let tok = provider.post_process(tok, http).await?;
+
+    save_login_token(auth, &provider.provider_id(), tok, account, label).await?;
+    Ok(())
+}
+
+/// Bind the provider's callback port, open the browser and wait for the redirect.
+async fn listen_for_callback<P: AuthCodeFlow>(
+    provider: &P,
+    auth_url: &str,
+    events: Option<&mpsc::Sender<LoginProgress>>,
+) -> Result<std::collections::HashMap<String, String>> {
     let listeners = callback::bind_callback(provider.callback_port()).await?;
     if events.is_none() {
         eprintln!(
@@ -98,11 +188,11 @@ pub async fn run<P: AuthCodeFlow>(
             provider.callback_port()
         );
     }
-    open_browser(&auth_url);
+    open_browser(auth_url);
     emit(
         events,
         LoginProgress::OpenedBrowser {
-            url: auth_url.clone(),
+            url: auth_url.to_owned(),
             user_code: None,
         },
     )
@@ -111,28 +201,7 @@ pub async fn run<P: AuthCodeFlow>(
     if events.is_none() {
         eprintln!("[login] waiting for OAuth callback...");
     }
-    let params = callback::accept_callback(listeners).await?;
-
-    verify_state(&params, &state)?;
-    let code = extract_code(&params)?;
-    tracing::info!(provider = %provider.provider_name(), "received OAuth code, exchanging");
-    if events.is_none() {
-        eprintln!("[login] received OAuth code, exchanging for token...");
-    }
-    // Note: LoginProgress::GotCode is intentionally NOT emitted here.
-    // The next `exchange_code` call *is* the "exchanging" work, so emitting
-    // GotCode immediately before Exchanging would produce two consecutive
-    // events with no observable gap. GotCode remains in the enum as a
-    // reserved value for future use (e.g. flows with a distinct pre-exchange
-    // stage such as email/SMS verification).
-    emit(events, LoginProgress::Exchanging).await;
-    let tok = provider
-        .exchange_code(http, &creds, code, &verifier, &state)
-        .await?;
-    let tok = provider.post_process(tok, http).await?;
-
-    save_login_token(auth, &provider.provider_id(), tok, account).await?;
-    Ok(())
+    callback::accept_callback(listeners).await
 }
 
 /// Send an HTTP response and parse it as a standard OAuth token JSON.
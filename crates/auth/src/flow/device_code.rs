@@ -7,6 +7,7 @@ use async_trait::async_trait;
 use byokey_types::{ByokError, OAuthToken, ProviderId, Result};
 use std::time::Duration;
 use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
 
 use super::{LoginProgress, emit, open_browser, save_login_token};
 use crate::{AuthManager, credentials::OAuthCredentials, token, token::DeviceCodeResponse};
@@ -60,16 +61,20 @@ pub trait DeviceCodeFlow: Send + Sync {
 
 /// Run the Device Code flow for any provider implementing [`DeviceCodeFlow`].
 ///
+/// Cancelling `cancel` aborts the polling loop promptly.
+///
 /// # Errors
 ///
-/// Returns an error on network failure, device code expiration, or token parse failure.
-#[allow(clippy::cast_precision_loss)]
+/// Returns an error on network failure, device code expiration, token parse
+/// failure, or `ByokError::Auth("login cancelled")` when `cancel` fires.
 pub async fn run<P: DeviceCodeFlow>(
     provider: &P,
     auth: &AuthManager,
     http: &rquest::Client,
     account: Option<&str>,
+    label: Option<&str>,
     events: Option<&mpsc::Sender<LoginProgress>>,
+    cancel: &CancellationToken,
 ) -> Result<()> {
     emit(events, LoginProgress::Started).await;
     let creds = crate::credentials::fetch(provider.provider_name(), http).await?;
@@ -94,28 +99,46 @@ pub async fn run<P: DeviceCodeFlow>(
     )
     .await;
 
+    let tok = poll_until_token(provider, http, &creds, &dc, cancel).await?;
+    // Device Code flow has no distinct "exchange" step: the successful poll
+    // *is* the token return. Go straight to saving and letting DONE be
+    // emitted by the handler.
+    save_login_token(auth, &provider_id, tok, account, label).await?;
+    if events.is_none() {
+        println!("{provider_id} login successful");
+    }
+    tracing::info!(provider = %provider_id, "login successful");
+    Ok(())
+}
+
+/// Poll the token endpoint until success, expiry, or cancellation.
+#[allow(clippy::cast_precision_loss)]
+async fn poll_until_token<P: DeviceCodeFlow>(
+    provider: &P,
+    http: &rquest::Client,
+    creds: &OAuthCredentials,
+    dc: &DeviceCodeResponse,
+    cancel: &CancellationToken,
+) -> Result<OAuthToken> {
     let deadline = tokio::time::Instant::now() + Duration::from_secs(dc.expires_in);
     let mut interval = dc.interval as f64;
 
     loop {
-        tokio::time::sleep(Duration::from_secs_f64(interval)).await;
+        tokio::select! {
+            () = cancel.cancelled() => return Err(cancelled()),
+            () = tokio::time::sleep(Duration::from_secs_f64(interval)) => {}
+        }
 
         if tokio::time::Instant::now() >= deadline {
             return Err(ByokError::Auth("device code expired".into()));
         }
 
-        match provider.poll_token(http, &creds, &dc.device_code).await? {
-            PollResult::Success(tok) => {
-                // Device Code flow has no distinct "exchange" step: the
-                // successful poll *is* the token return. Go straight to
-                // saving and letting DONE be emitted by the handler.
-                save_login_token(auth, &provider_id, tok, account).await?;
-                if events.is_none() {
-                    println!("{provider_id} login successful");
-                }
-                tracing::info!(provider = %provider_id, "login successful");
-                return Ok(());
-            }
+        let poll = tokio::select! {
+            () = cancel.cancelled() => return Err(cancelled()),
+            poll = provider.poll_token(http, creds, &dc.device_code) => poll?,
+        };
+        match poll {
+            PollResult::Success(tok) => return Ok(tok),
             PollResult::Pending => {}
             PollResult::SlowDown => {
                 interval = provider.apply_slow_down(interval);
@@ -124,6 +147,10 @@ pub async fn run<P: DeviceCodeFlow>(
     }
 }
 
+fn cancelled() -> ByokError {
+    ByokError::Auth("login cancelled".into())
+}
+
 /// Parse a token poll response into a [`PollResult`].
 ///
 /// Shared helper for [`DeviceCodeFlow::poll_token`] implementations.
@@ -161,4 +188,65 @@ mod tests {
         assert!(prompt.contains("https://github.com/login/device"));
         assert!(prompt.contains("ABCD-1234"));
     }
+
+    struct AlwaysPending;
+
+    #[async_trait]
+    impl DeviceCodeFlow for AlwaysPending {
+        fn provider_id(&self) -> ProviderId {
+            ProviderId::Copilot
+        }
+
+        fn provider_name(&self) -> &'static str {
+            "copilot"
+        }
+
+        async fn request_device_code(
+            &self,
+            _http: &rquest::Client,
+            _creds: &OAuthCredentials,
+        ) -> Result<DeviceCodeResponse> {
+            unreachable!("not used by poll_until_token")
+        }
+
+        async fn poll_token(
+            &self,
+            _http: &rquest::Client,
+            _creds: &OAuthCredentials,
+            _device_code: &str,
+        ) -> Result<PollResult> {
+            Ok(PollResult::Pending)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cancel_mid_poll_returns_promptly() {
+        let dc = DeviceCodeResponse {
+            device_code: "device-code".into(),
+            user_code: "ABCD-1234".into(),
+            verification_uri: "https://github.com/login/device".into(),
+            expires_in: 900,
+            interval: 30,
+        };
+        let creds = OAuthCredentials {
+            client_id: "client".into(),
+            client_secret: None,
+            token_url: None,
+            device_code_url: None,
+        };
+        let cancel = CancellationToken::new();
+
+        let trigger = cancel.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            trigger.cancel();
+        });
+
+        let started = std::time::Instant::now();
+        let err = poll_until_token(&AlwaysPending, &rquest::Client::new(), &creds, &dc, &cancel)
+            .await
+            .unwrap_err();
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert!(matches!(err, ByokError::Auth(ref m) if m == "login cancelled"));
+    }
 }
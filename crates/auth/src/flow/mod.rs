@@ -9,9 +9,11 @@ pub mod device_code;
 
 use byokey_types::{ByokError, OAuthToken, ProviderId, Result};
 use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
 
 use crate::AuthManager;
 use crate::provider::{amp, antigravity, claude, codex, copilot, gemini, iflow, kimi, qwen};
+use auth_code::CallbackMode;
 
 /// Progress event emitted by streaming login flows.
 ///
@@ -41,21 +43,72 @@ pub enum LoginProgress {
 /// Run the full interactive login flow for the given provider.
 ///
 /// When `account` is `Some`, the token is stored under that account identifier
-/// instead of the default active account.
+/// instead of the default active account; `label` attaches a human-readable
+/// name to it (an existing label is kept when `None`). When `proxy_url` is
+/// `Some`, every request of the flow (including the token exchange) goes
+/// through that proxy.
 ///
 /// # Errors
 ///
-/// Returns an error if the login flow fails for any reason (e.g., network error,
-/// state mismatch, missing callback parameters, or token parse failure).
-pub async fn login(provider: &ProviderId, auth: &AuthManager, account: Option<&str>) -> Result<()> {
-    login_with_events(provider, auth, account, None).await
+/// Returns an error if the login flow fails for any reason (e.g., invalid proxy
+/// URL, network error, state mismatch, missing callback parameters, or token
+/// parse failure).
+pub async fn login(
+    provider: &ProviderId,
+    auth: &AuthManager,
+    account: Option<&str>,
+    label: Option<&str>,
+    proxy_url: Option<&str>,
+) -> Result<()> {
+    login_with_events(
+        provider,
+        auth,
+        account,
+        label,
+        proxy_url,
+        None,
+        &CancellationToken::new(),
+    )
+    .await
+}
+
+/// Run the login flow without a local browser.
+///
+/// Authorization Code providers print the authorization URL and read the
+/// URL the browser was redirected to from stdin instead of listening on a
+/// local callback port, so the browser may run on another machine. Device
+/// Code providers behave as in [`login`].
+///
+/// # Errors
+///
+/// Same as [`login`].
+pub async fn login_pasted(
+    provider: &ProviderId,
+    auth: &AuthManager,
+    account: Option<&str>,
+    label: Option<&str>,
+    proxy_url: Option<&str>,
+) -> Result<()> {
+    let http = login_http_client(proxy_url)?;
+    dispatch(
+        provider,
+        auth,
+        &http,
+        account,
+        label,
+        None,
+        &CancellationToken::new(),
+        CallbackMode::Paste,
+    )
+    .await
 }
 
 /// Run the login flow and emit progress events to the optional channel.
 ///
 /// Identical to [`login`] but additionally forwards [`LoginProgress`] events
 /// to the given channel. Used by the streaming management RPC so UIs can
-/// render live progress.
+/// render live progress. Cancelling `cancel` aborts device-code polling with
+/// `ByokError::Auth("login cancelled")`.
 ///
 /// # Errors
 ///
@@ -64,44 +117,131 @@ pub async fn login_with_events(
     provider: &ProviderId,
     auth: &AuthManager,
     account: Option<&str>,
+    label: Option<&str>,
+    proxy_url: Option<&str>,
     events: Option<mpsc::Sender<LoginProgress>>,
+    cancel: &CancellationToken,
+) -> Result<()> {
+    let http = login_http_client(proxy_url)?;
+    dispatch(
+        provider,
+        auth,
+        &http,
+        account,
+        label,
+        events.as_ref(),
+        cancel,
+        CallbackMode::Listen,
+    )
+    .await
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn dispatch(
+    provider: &ProviderId,
+    auth: &AuthManager,
+    http: &rquest::Client,
+    account: Option<&str>,
+    label: Option<&str>,
+    ev: Option<&mpsc::Sender<LoginProgress>>,
+    cancel: &CancellationToken,
+    mode: CallbackMode,
 ) -> Result<()> {
-    let http = rquest::Client::new();
-    let ev = events.as_ref();
     match provider {
         // Authorization Code + PKCE flows
-        ProviderId::Claude => auth_code::run(&claude::Claude, auth, &http, account, ev).await,
-        ProviderId::Codex => auth_code::run(&codex::Codex, auth, &http, account, ev).await,
-        ProviderId::Gemini => auth_code::run(&gemini::Gemini, auth, &http, account, ev).await,
+        ProviderId::Claude => {
+            auth_code::run_with_mode(&claude::Claude, auth, http, account, label, ev, mode).await
+        }
+        ProviderId::Codex => {
+            auth_code::run_with_mode(&codex::Codex, auth, http, account, label, ev, mode).await
+        }
+        ProviderId::Gemini => {
+            auth_code::run_with_mode(&gemini::Gemini, auth, http, account, label, ev, mode).await
+        }
         ProviderId::Antigravity => {
-            auth_code::run(&antigravity::Antigravity, auth, &http, account, ev).await
+            auth_code::run_with_mode(
+                &antigravity::Antigravity,
+                auth,
+                http,
+                account,
+                label,
+                ev,
+                mode,
+            )
+            .await
+        }
+        ProviderId::IFlow => {
+            auth_code::run_with_mode(&iflow::IFlow, auth, http, account, label, ev, mode).await
         }
-        ProviderId::IFlow => auth_code::run(&iflow::IFlow, auth, &http, account, ev).await,
         // Device Code flows
-        ProviderId::Copilot => device_code::run(&copilot::Copilot, auth, &http, account, ev).await,
-        ProviderId::Qwen => device_code::run(&qwen::Qwen::new(), auth, &http, account, ev).await,
-        ProviderId::Kimi => device_code::run(&kimi::Kimi, auth, &http, account, ev).await,
-        ProviderId::Amp => auth_code::run(&amp::Amp, auth, &http, account, ev).await,
+        ProviderId::Copilot => {
+            device_code::run(&copilot::Copilot, auth, http, account, label, ev, cancel).await
+        }
+        ProviderId::Qwen => {
+            device_code::run(&qwen::Qwen::new(), auth, http, account, label, ev, cancel).await
+        }
+        ProviderId::Kimi => {
+            device_code::run(&kimi::Kimi, auth, http, account, label, ev, cancel).await
+        }
+        ProviderId::Amp => {
+            auth_code::run_with_mode(&amp::Amp, auth, http, account, label, ev, mode).await
+        }
         ProviderId::Kiro => Err(ByokError::Auth(
             "Kiro OAuth login not yet implemented".into(),
         )),
+        ProviderId::Mistral => Err(ByokError::Auth(
+            "Mistral has no login; set `api_key` in the config".into(),
+        )),
+        ProviderId::Custom => Err(ByokError::Auth(
+            "the custom provider has no login; set `api_key` in the config".into(),
+        )),
+        ProviderId::Mock => Err(ByokError::Auth("the mock provider has no login".into())),
     }
 }
 
+/// Build the HTTP client used by a login flow, routed through `proxy_url` if set.
+///
+/// Unlike the proxy server, an invalid proxy URL is an error rather than a
+/// silent fallback to a direct connection: logins that need a proxy are
+/// typically region-locked and would fail anyway.
+///
+/// # Errors
+///
+/// Returns [`ByokError::Config`] if the proxy URL is invalid or the client
+/// cannot be built.
+pub fn login_http_client(proxy_url: Option<&str>) -> Result<rquest::Client> {
+    let Some(url) = proxy_url else {
+        return Ok(rquest::Client::new());
+    };
+    let proxy = rquest::Proxy::all(url)
+        .map_err(|e| ByokError::Config(format!("invalid login proxy '{url}': {e}")))?;
+    rquest::Client::builder()
+        .proxy(proxy)
+        .build()
+        .map_err(|e| ByokError::Config(format!("failed to build login client: {e}")))
+}
+
 // ── Shared helpers ────────────────────────────────────────────────────────────
 
-/// Save a token for a provider, routing to the named account if specified.
+/// Save a token for a provider under the named account, or under the active
+/// account (`"default"` when there is none) if no account is given. A label
+/// only changes which columns are written, never the target account.
 pub(crate) async fn save_login_token(
     auth: &AuthManager,
     provider: &ProviderId,
     token: OAuthToken,
     account: Option<&str>,
+    label: Option<&str>,
 ) -> Result<()> {
-    if let Some(account_id) = account {
-        auth.save_token_for(provider, account_id, None, token).await
-    } else {
-        auth.save_token(provider, token).await
-    }
+    let account_id = match account {
+        Some(id) => id.to_string(),
+        None => auth
+            .active_account_id(provider)
+            .await
+            .unwrap_or_else(|| byokey_types::DEFAULT_ACCOUNT.to_string()),
+    };
+    auth.save_token_for(provider, &account_id, label, token)
+        .await
 }
 
 pub(crate) fn open_browser(url: &str) {
@@ -117,3 +257,74 @@ pub(crate) async fn emit(events: Option<&mpsc::Sender<LoginProgress>>, p: LoginP
         let _ = tx.send(p).await;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt as _, AsyncWriteExt as _};
+    use tokio::net::TcpListener;
+
+    #[tokio::test]
+    async fn test_login_client_uses_configured_proxy() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let proxy_url = format!("http://{}", listener.local_addr().unwrap());
+
+        let server = tokio::spawn(async move {
+            let (mut sock, _) = listener.accept().await.unwrap();
+            let mut buf = vec![0u8; 1024];
+            let n = sock.read(&mut buf).await.unwrap();
+            sock.write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\n\r\n")
+                .await
+                .unwrap();
+            String::from_utf8_lossy(&buf[..n]).into_owned()
+        });
+
+        let client = login_http_client(Some(&proxy_url)).unwrap();
+        let resp = client
+            .get("http://auth.example.invalid/oauth/token")
+            .send()
+            .await
+            .unwrap();
+        assert!(resp.status().is_success());
+
+        let request = server.await.unwrap();
+        assert!(
+            request.starts_with("GET http://auth.example.invalid/oauth/token"),
+            "request did not go through proxy: {request}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_labelled_login_targets_active_account() {
+        let auth = AuthManager::new(
+            std::sync::Arc::new(byokey_store::InMemoryTokenStore::new()),
+            rquest::Client::new(),
+        );
+        let provider = ProviderId::Claude;
+        auth.save_token_for(&provider, "work", None, OAuthToken::new("old"))
+            .await
+            .unwrap();
+
+        save_login_token(&auth, &provider, OAuthToken::new("new"), None, Some("Work"))
+            .await
+            .unwrap();
+
+        let accounts = auth.list_accounts(&provider).await.unwrap();
+        assert_eq!(accounts.len(), 1, "no stray default account: {accounts:?}");
+        assert_eq!(accounts[0].account_id, "work");
+        assert_eq!(accounts[0].label.as_deref(), Some("Work"));
+        let token = auth.get_all_tokens(&provider).await.unwrap();
+        assert_eq!(token[0].1.access_token, "new");
+    }
+
+    #[test]
+    fn test_login_client_rejects_invalid_proxy() {
+        let err = login_http_client(Some("not a url")).unwrap_err();
+        assert!(matches!(err, ByokError::Config(_)));
+    }
+
+    #[test]
+    fn test_login_client_without_proxy() {
+        assert!(login_http_client(None).is_ok());
+    }
+}
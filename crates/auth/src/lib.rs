@@ -18,4 +18,4 @@ pub mod pkce;
 pub mod provider;
 pub mod token;
 
-pub use manager::AuthManager;
+pub use manager::{AuthManager, RefreshCounts, ServedAccount, track_served_account};
@@ -3,32 +3,109 @@
 //! Responsibilities:
 //! - Load tokens from a [`TokenStore`].
 //! - Detect expiration and trigger refresh via the provider's token endpoint.
-//! - Cooldown duration to prevent excessive refresh attempts (30 s).
+//! - Cooldown duration to prevent excessive refresh attempts (30 s by default).
 //! - Multi-account support: save, switch, and list accounts per provider.
 use byokey_types::{
     AccountInfo, ByokError, OAuthToken, ProviderId, Result, TokenState, TokenStore,
 };
 use std::{
+    cell::RefCell,
     collections::HashMap,
+    pin::Pin,
     sync::{Arc, Mutex},
     time::{Duration, Instant},
 };
 use tokio::sync::Mutex as TokioMutex;
 
-use crate::{credentials, provider::iflow, token};
+use crate::{
+    credentials,
+    provider::{antigravity, gemini, iflow},
+    token,
+};
+
+/// Default minimum interval between refresh attempts for one provider.
+pub const DEFAULT_REFRESH_COOLDOWN: Duration = Duration::from_secs(30);
+
+/// How long logout waits for an upstream token revocation.
+const REVOKE_TIMEOUT: Duration = Duration::from_secs(5);
+
+// `Duration::from_mins` is not yet a const fn on stable.
+/// Minimum interval between `last_used_at` writes for one account.
+#[allow(clippy::duration_suboptimal_units)]
+const LAST_USED_WRITE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// The account whose token served a request, as seen by
+/// [`track_served_account`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ServedAccount {
+    /// The provider's active account ([`AuthManager::get_token`]).
+    Active,
+    /// A specific account ([`AuthManager::get_token_for`]).
+    Account(String),
+}
+
+tokio::task_local! {
+    /// Account of the last token handed out in a [`track_served_account`] scope.
+    static SERVED_ACCOUNT: RefCell<Option<ServedAccount>>;
+}
+
+/// Runs `fut`, returning its output along with the account of the last
+/// token [`AuthManager`] handed out while it ran, if any.
+pub async fn track_served_account<F: Future>(fut: F) -> (F::Output, Option<ServedAccount>) {
+    SERVED_ACCOUNT
+        .scope(RefCell::new(None), async move {
+            let output = fut.await;
+            let account = SERVED_ACCOUNT.with(RefCell::take);
+            (output, account)
+        })
+        .await
+}
+
+/// Records into the current [`track_served_account`] scope, if any.
+fn record_served(account: ServedAccount) {
+    let _ = SERVED_ACCOUNT.try_with(|slot| *slot.borrow_mut() = Some(account));
+}
 
-const REFRESH_COOLDOWN: Duration = Duration::from_secs(30);
+/// Refresh bookkeeping is kept per account so accounts of one provider
+/// refresh independently.
+type AccountKey = (ProviderId, String);
 
 struct ProviderState {
     last_refresh_attempt: Option<Instant>,
 }
 
+/// Outcomes of token refresh round-trips for one provider, across accounts.
+///
+/// Requests rejected by the cooldown or served by a concurrent refresh are
+/// not counted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RefreshCounts {
+    pub succeeded: u64,
+    pub failed: u64,
+}
+
+/// Key for `account_id`, or for the active account when `None`.
+fn account_key(provider: &ProviderId, account_id: Option<&str>) -> AccountKey {
+    (
+        provider.clone(),
+        account_id
+            .unwrap_or(byokey_types::DEFAULT_ACCOUNT)
+            .to_string(),
+    )
+}
+
 pub struct AuthManager {
     store: Arc<dyn TokenStore>,
     http: rquest::Client,
-    state: Mutex<HashMap<ProviderId, ProviderState>>,
+    state: Mutex<HashMap<AccountKey, ProviderState>>,
+    /// Minimum interval between refresh attempts for one provider.
+    refresh_cooldown: Duration,
     /// Per-provider async locks to deduplicate concurrent refresh attempts.
-    refresh_locks: Mutex<HashMap<ProviderId, Arc<TokioMutex<()>>>>,
+    refresh_locks: Mutex<HashMap<AccountKey, Arc<TokioMutex<()>>>>,
+    /// Refresh outcomes per provider, exposed as metrics.
+    refresh_counts: Mutex<HashMap<ProviderId, RefreshCounts>>,
+    /// Last `last_used_at` write per account, to throttle store writes.
+    used_marks: Mutex<HashMap<(ProviderId, ServedAccount), Instant>>,
 }
 
 impl AuthManager {
@@ -37,19 +114,36 @@ impl AuthManager {
             store,
             http,
             state: Mutex::new(HashMap::new()),
+            refresh_cooldown: DEFAULT_REFRESH_COOLDOWN,
             refresh_locks: Mutex::new(HashMap::new()),
+            refresh_counts: Mutex::new(HashMap::new()),
+            used_marks: Mutex::new(HashMap::new()),
         }
     }
 
-    /// Return (or create) the per-provider async mutex used for refresh dedup.
-    fn get_refresh_lock(&self, provider: &ProviderId) -> Arc<TokioMutex<()>> {
+    /// Overrides the minimum interval between refresh attempts for one
+    /// provider (default [`DEFAULT_REFRESH_COOLDOWN`]). Zero disables it.
+    #[must_use]
+    pub fn with_refresh_cooldown(mut self, cooldown: Duration) -> Self {
+        self.refresh_cooldown = cooldown;
+        self
+    }
+
+    /// Return (or create) the per-account async mutex used for refresh dedup.
+    fn get_refresh_lock(&self, key: &AccountKey) -> Arc<TokioMutex<()>> {
         let mut locks = self.refresh_locks.lock().unwrap();
         locks
-            .entry(provider.clone())
+            .entry(key.clone())
             .or_insert_with(|| Arc::new(TokioMutex::new(())))
             .clone()
     }
 
+    /// Refresh outcomes recorded so far, per provider.
+    #[must_use]
+    pub fn refresh_counts(&self) -> HashMap<ProviderId, RefreshCounts> {
+        self.refresh_counts.lock().unwrap().clone()
+    }
+
     // ── Active-account methods (backward-compatible) ─────────────────────
 
     /// Retrieve a valid token for the active account, attempting a refresh if expired.
@@ -67,26 +161,33 @@ impl AuthManager {
             .load(provider)
             .await?
             .ok_or_else(|| ByokError::TokenNotFound(provider.clone()))?;
+        record_served(ServedAccount::Active);
 
         match token.state() {
             TokenState::Valid => {
                 // Proactive refresh: spawn a background task if the token is
                 // nearing expiry but still usable, so the next caller gets a
-                // fresh token without waiting.
-                if token.should_proactive_refresh() && self.should_spawn_proactive_refresh(provider)
-                {
-                    let this = Arc::clone(self);
-                    let provider = provider.clone();
-                    let token = token.clone();
-                    tokio::spawn(async move {
-                        if let Err(e) = this.refresh_token(&provider, &token).await {
-                            tracing::debug!(%provider, %e, "proactive refresh failed (non-critical)");
-                        }
-                    });
+                // fresh token without waiting. The guard and the refresh are
+                // keyed by the same resolved account id.
+                if token.should_proactive_refresh() {
+                    let account_id = self.active_account_id(provider).await;
+                    if self.should_spawn_proactive_refresh(provider, account_id.as_deref()) {
+                        let this = Arc::clone(self);
+                        let provider = provider.clone();
+                        let token = token.clone();
+                        tokio::spawn(async move {
+                            if let Err(e) = this
+                                .refresh_token(&provider, account_id.as_deref(), &token)
+                                .await
+                            {
+                                tracing::debug!(%provider, %e, "proactive refresh failed (non-critical)");
+                            }
+                        });
+                    }
                 }
                 Ok(token)
             }
-            TokenState::Expired => self.refresh_token(provider, &token).await,
+            TokenState::Expired => self.refresh_token(provider, None, &token).await,
             TokenState::Invalid => Err(ByokError::TokenExpired(provider.clone())),
         }
     }
@@ -109,6 +210,22 @@ impl AuthManager {
         }
     }
 
+    /// Return the active-account [`TokenState`] of every provider.
+    ///
+    /// Loads all tokens with a single [`TokenStore::load_all_active`] call.
+    /// Providers without a token — or every provider, if the store fails —
+    /// report [`TokenState::Invalid`].
+    pub async fn token_states_all(&self) -> HashMap<ProviderId, TokenState> {
+        let tokens = self.store.load_all_active().await.unwrap_or_default();
+        ProviderId::all()
+            .iter()
+            .map(|p| {
+                let state = tokens.get(p).map_or(TokenState::Invalid, OAuthToken::state);
+                (p.clone(), state)
+            })
+            .collect()
+    }
+
     /// Save a new token for the active account (backward-compatible shortcut).
     ///
     /// # Errors
@@ -120,11 +237,28 @@ impl AuthManager {
 
     /// Remove the active account's token (logout).
     ///
+    /// Once removed locally, the token is also revoked upstream where the
+    /// provider supports it (see [`revoke_upstream`](Self::revoke_upstream)).
+    ///
     /// # Errors
     ///
     /// Returns an error if the underlying store fails to remove the token.
     pub async fn remove_token(&self, provider: &ProviderId) -> Result<()> {
-        self.store.remove(provider).await
+        let token = self.store.load(provider).await.ok().flatten();
+        self.store.remove(provider).await?;
+        if let Some(token) = token {
+            self.revoke_upstream(provider, &token).await;
+        }
+        Ok(())
+    }
+
+    /// Remove every account of every provider (full logout).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the underlying store fails.
+    pub async fn clear_all_tokens(&self) -> Result<()> {
+        self.store.clear_all().await
     }
 
     // ── Multi-account methods ────────────────────────────────────────────
@@ -161,34 +295,51 @@ impl AuthManager {
             .load_account(provider, account_id)
             .await?
             .ok_or_else(|| ByokError::TokenNotFound(provider.clone()))?;
+        record_served(ServedAccount::Account(account_id.to_string()));
 
         match token.state() {
             TokenState::Valid => {
-                if token.should_proactive_refresh() && self.should_spawn_proactive_refresh(provider)
+                if token.should_proactive_refresh()
+                    && self.should_spawn_proactive_refresh(provider, Some(account_id))
                 {
                     let this = Arc::clone(self);
                     let provider = provider.clone();
+                    let account_id = account_id.to_string();
                     let token = token.clone();
                     tokio::spawn(async move {
-                        if let Err(e) = this.refresh_token(&provider, &token).await {
-                            tracing::debug!(%provider, %e, "proactive refresh failed (non-critical)");
+                        if let Err(e) = this
+                            .refresh_token(&provider, Some(&account_id), &token)
+                            .await
+                        {
+                            tracing::debug!(%provider, %account_id, %e, "proactive refresh failed (non-critical)");
                         }
                     });
                 }
                 Ok(token)
             }
-            TokenState::Expired => self.refresh_token(provider, &token).await,
+            TokenState::Expired => self.refresh_token(provider, Some(account_id), &token).await,
             TokenState::Invalid => Err(ByokError::TokenExpired(provider.clone())),
         }
     }
 
-    /// Remove a specific account's token.
+    /// Remove a specific account's token, then revoke it upstream where
+    /// the provider supports it.
     ///
     /// # Errors
     ///
     /// Returns an error if the underlying store fails.
     pub async fn remove_token_for(&self, provider: &ProviderId, account_id: &str) -> Result<()> {
-        self.store.remove_account(provider, account_id).await
+        let token = self
+            .store
+            .load_account(provider, account_id)
+            .await
+            .ok()
+            .flatten();
+        self.store.remove_account(provider, account_id).await?;
+        if let Some(token) = token {
+            self.revoke_upstream(provider, &token).await;
+        }
+        Ok(())
     }
 
     /// List all accounts for a provider.
@@ -274,6 +425,40 @@ impl AuthManager {
         self.store.load_all_tokens(provider).await
     }
 
+    /// Records that `account` of `provider` (as reported by
+    /// [`track_served_account`]) just served a request.
+    ///
+    /// The store write runs in the background and is throttled to one per
+    /// [`LAST_USED_WRITE_INTERVAL`] per account; failures are only logged.
+    pub fn mark_used(self: &Arc<Self>, provider: &ProviderId, account: ServedAccount) {
+        {
+            let mut marks = self.used_marks.lock().unwrap();
+            let now = Instant::now();
+            let key = (provider.clone(), account.clone());
+            if marks
+                .get(&key)
+                .is_some_and(|last| now.duration_since(*last) < LAST_USED_WRITE_INTERVAL)
+            {
+                return;
+            }
+            marks.insert(key, now);
+        }
+        let this = Arc::clone(self);
+        let provider = provider.clone();
+        tokio::spawn(async move {
+            let account_id = match account {
+                ServedAccount::Account(id) => id,
+                ServedAccount::Active => this
+                    .active_account_id(&provider)
+                    .await
+                    .unwrap_or_else(|| byokey_types::DEFAULT_ACCOUNT.to_string()),
+            };
+            if let Err(e) = this.store.touch_last_used(&provider, &account_id).await {
+                tracing::debug!(%provider, %e, "mark_used: touch_last_used failed");
+            }
+        });
+    }
+
     // ── Background refresh ────────────────────────────────────────────────
 
     /// Spawns a background loop that periodically checks all providers and
@@ -315,14 +500,8 @@ impl AuthManager {
                 continue;
             }
 
-            // Check if token expires within lead_time.
-            let now = std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap_or_default()
-                .as_secs();
-            let expires_at = token.expires_at.unwrap_or(u64::MAX);
-            if expires_at > now + lead_time.as_secs() {
-                // Not yet due for refresh.
+            // Check if token expires within lead_time (no expiry → never due).
+            if token.expires_in().is_none_or(|left| left > lead_time) {
                 continue;
             }
             if token.is_expired() {
@@ -331,7 +510,7 @@ impl AuthManager {
             }
 
             tracing::debug!(%provider, "auto-refresh: token nearing expiry, refreshing");
-            if let Err(e) = self.refresh_token(provider, &token).await {
+            if let Err(e) = self.refresh_token(provider, None, &token).await {
                 tracing::debug!(%provider, %e, "auto-refresh failed");
             }
         }
@@ -339,26 +518,92 @@ impl AuthManager {
 
     // ── Private helpers ──────────────────────────────────────────────────
 
+    /// Best-effort revocation of `token` at the provider, so logging out
+    /// invalidates it rather than leaving it valid until expiry.
+    ///
+    /// Only Google (Gemini, Antigravity) offers a revocation endpoint usable
+    /// by a public client. GitHub's (Copilot) requires the OAuth app's client
+    /// secret, which the device flow does not have, so Copilot tokens and
+    /// all other providers are skipped. Runs after the local removal and is
+    /// bounded by [`REVOKE_TIMEOUT`]; failures are only logged.
+    async fn revoke_upstream(&self, provider: &ProviderId, token: &OAuthToken) {
+        let revoke: Pin<Box<dyn Future<Output = Result<()>> + Send + '_>> = match provider {
+            ProviderId::Gemini => Box::pin(gemini::revoke(&self.http, token)),
+            ProviderId::Antigravity => Box::pin(antigravity::revoke(&self.http, token)),
+            _ => return,
+        };
+        match tokio::time::timeout(REVOKE_TIMEOUT, revoke).await {
+            Ok(Ok(())) => tracing::info!(%provider, "revoked token upstream"),
+            Ok(Err(e)) => {
+                tracing::warn!(%provider, error = %e, "token revocation failed; removed locally only");
+            }
+            Err(_) => {
+                tracing::warn!(%provider, "token revocation timed out; removed locally only");
+            }
+        }
+    }
+
+    /// The active account's id, or `None` when the provider has no named
+    /// accounts (or the store cannot list them).
+    pub(crate) async fn active_account_id(&self, provider: &ProviderId) -> Option<String> {
+        match self.store.list_accounts(provider).await {
+            Ok(accts) => accts
+                .into_iter()
+                .find(|a| a.is_active)
+                .map(|a| a.account_id),
+            Err(e) => {
+                tracing::debug!(%provider, %e, "list_accounts failed resolving the active account");
+                None
+            }
+        }
+    }
+
     /// Check whether a proactive (background) refresh should be spawned.
     /// Returns `false` if a refresh was attempted within the cooldown period,
     /// avoiding redundant background tasks.
-    fn should_spawn_proactive_refresh(&self, provider: &ProviderId) -> bool {
+    fn should_spawn_proactive_refresh(
+        &self,
+        provider: &ProviderId,
+        account_id: Option<&str>,
+    ) -> bool {
         let state = self.state.lock().unwrap();
-        state.get(provider).is_none_or(|ps| {
-            ps.last_refresh_attempt
-                .is_none_or(|last| last.elapsed() >= REFRESH_COOLDOWN)
-        })
+        state
+            .get(&account_key(provider, account_id))
+            .is_none_or(|ps| {
+                ps.last_refresh_attempt
+                    .is_none_or(|last| last.elapsed() >= self.refresh_cooldown)
+            })
     }
 
-    async fn refresh_token(&self, provider: &ProviderId, token: &OAuthToken) -> Result<OAuthToken> {
-        // Acquire the per-provider async lock so that concurrent callers
+    /// Refreshes `token` for `account_id`, or for the active account when
+    /// `None`, and stores the result under the same account.
+    async fn refresh_token(
+        &self,
+        provider: &ProviderId,
+        account_id: Option<&str>,
+        token: &OAuthToken,
+    ) -> Result<OAuthToken> {
+        // Resolve the active account up front so a refresh through it and an
+        // explicit refresh of the same account share one lock and cooldown.
+        let active = match account_id {
+            Some(_) => None,
+            None => self.active_account_id(provider).await,
+        };
+        let account_id = account_id.or(active.as_deref());
+        let key = account_key(provider, account_id);
+
+        // Acquire the per-account async lock so that concurrent callers
         // coalesce into a single refresh round-trip.
-        let lock = self.get_refresh_lock(provider);
+        let lock = self.get_refresh_lock(&key);
         let _guard = lock.lock().await;
 
         // Re-check: another task may have completed the refresh while we
         // were waiting for the lock.
-        if let Ok(Some(current)) = self.store.load(provider).await
+        let current = match account_id {
+            Some(id) => self.store.load_account(provider, id).await,
+            None => self.store.load(provider).await,
+        };
+        if let Ok(Some(current)) = current
             && current.expires_at != token.expires_at
             && !current.is_expired()
         {
@@ -368,9 +613,9 @@ impl AuthManager {
         // Check cooldown period
         {
             let state = self.state.lock().unwrap();
-            if let Some(ps) = state.get(provider)
+            if let Some(ps) = state.get(&key)
                 && let Some(last) = ps.last_refresh_attempt
-                && last.elapsed() < REFRESH_COOLDOWN
+                && last.elapsed() < self.refresh_cooldown
             {
                 return Err(ByokError::Auth(format!(
                     "refresh cooldown active for {provider}"
@@ -381,13 +626,33 @@ impl AuthManager {
         {
             let mut state = self.state.lock().unwrap();
             state.insert(
-                provider.clone(),
+                key,
                 ProviderState {
                     last_refresh_attempt: Some(Instant::now()),
                 },
             );
         }
 
+        let result = self.attempt_refresh(provider, account_id, token).await;
+        {
+            let mut counts = self.refresh_counts.lock().unwrap();
+            let entry = counts.entry(provider.clone()).or_default();
+            if result.is_ok() {
+                entry.succeeded += 1;
+            } else {
+                entry.failed += 1;
+            }
+        }
+        result
+    }
+
+    /// Performs one refresh round-trip and stores the new token.
+    async fn attempt_refresh(
+        &self,
+        provider: &ProviderId,
+        account_id: Option<&str>,
+        token: &OAuthToken,
+    ) -> Result<OAuthToken> {
         let refresh_token = token
             .refresh_token
             .as_deref()
@@ -419,7 +684,11 @@ impl AuthManager {
             Ok(t) => t,
             Err(ByokError::Auth(ref msg)) if msg.starts_with("invalid_grant:") => {
                 tracing::error!(%provider, "refresh token revoked or expired — user must re-authenticate");
-                if let Err(e) = self.store.remove(provider).await {
+                let removed = match account_id {
+                    Some(id) => self.store.remove_account(provider, id).await,
+                    None => self.store.remove(provider).await,
+                };
+                if let Err(e) = removed {
                     tracing::warn!(%provider, error = %e, "failed to remove revoked token from store");
                 }
                 return Err(ByokError::TokenExpired(provider.clone()));
@@ -438,7 +707,14 @@ impl AuthManager {
             new_token
         };
 
-        self.store.save(provider, &new_token).await?;
+        match account_id {
+            Some(id) => {
+                self.store
+                    .save_account(provider, id, None, &new_token)
+                    .await?;
+            }
+            None => self.store.save(provider, &new_token).await?,
+        }
         tracing::info!(%provider, "token refreshed successfully");
         Ok(new_token)
     }
@@ -580,6 +856,49 @@ mod tests {
             .saturating_sub(secs)
     }
 
+    #[tokio::test]
+    async fn test_token_states_all_mixed() {
+        let m = make_manager();
+        m.save_token(
+            &ProviderId::Claude,
+            OAuthToken::new("valid").with_expiry(3600),
+        )
+        .await
+        .unwrap();
+        m.save_token(
+            &ProviderId::Codex,
+            OAuthToken {
+                access_token: "old".into(),
+                refresh_token: Some("refresh".into()),
+                expires_at: Some(past_ts(60)),
+                token_type: None,
+            },
+        )
+        .await
+        .unwrap();
+        m.save_token(
+            &ProviderId::Gemini,
+            OAuthToken {
+                access_token: "dead".into(),
+                refresh_token: None,
+                expires_at: Some(past_ts(60)),
+                token_type: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        let states = m.token_states_all().await;
+        assert_eq!(states.len(), ProviderId::all().len());
+        assert_eq!(states[&ProviderId::Claude], TokenState::Valid);
+        assert_eq!(states[&ProviderId::Codex], TokenState::Expired);
+        assert_eq!(states[&ProviderId::Gemini], TokenState::Invalid);
+        assert_eq!(states[&ProviderId::Kimi], TokenState::Invalid);
+        for p in ProviderId::all() {
+            assert_eq!(states[p], m.token_state(p).await, "{p}");
+        }
+    }
+
     #[tokio::test]
     async fn test_get_token_not_found() {
         let m = make_manager();
@@ -610,6 +929,28 @@ mod tests {
         assert!(matches!(err, ByokError::TokenExpired(_)));
     }
 
+    #[tokio::test]
+    async fn test_failed_refresh_is_counted() {
+        let m = make_manager();
+        let tok = OAuthToken {
+            access_token: "old".into(),
+            refresh_token: Some("refresh".into()),
+            expires_at: Some(past_ts(100)),
+            token_type: None,
+        };
+        m.save_token(&ProviderId::Copilot, tok).await.unwrap();
+        assert!(m.get_token(&ProviderId::Copilot).await.is_err());
+        // Within the cooldown: rejected without a round-trip, not counted.
+        assert!(m.get_token(&ProviderId::Copilot).await.is_err());
+        assert_eq!(
+            m.refresh_counts()[&ProviderId::Copilot],
+            RefreshCounts {
+                succeeded: 0,
+                failed: 1,
+            }
+        );
+    }
+
     #[tokio::test]
     async fn test_is_authenticated_false_when_missing() {
         let m = make_manager();
@@ -660,8 +1001,115 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_zero_refresh_cooldown() {
+        let m = Arc::new(
+            AuthManager::new(Arc::new(InMemoryTokenStore::new()), rquest::Client::new())
+                .with_refresh_cooldown(Duration::ZERO),
+        );
+        let tok = OAuthToken {
+            access_token: "old".into(),
+            refresh_token: Some("ref".into()),
+            expires_at: Some(past_ts(100)),
+            token_type: None,
+        };
+        m.save_token(&ProviderId::Copilot, tok).await.unwrap();
+
+        m.get_token(&ProviderId::Copilot).await.unwrap_err();
+        let msg = m
+            .get_token(&ProviderId::Copilot)
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(!msg.contains("cooldown"), "unexpected cooldown: {msg}");
+    }
+
     // ── Multi-account tests ──────────────────────────────────────────────
 
+    #[tokio::test]
+    async fn test_refresh_cooldown_is_per_account() {
+        let m = make_manager();
+        for account in ["a", "b"] {
+            let tok = OAuthToken {
+                access_token: format!("old-{account}"),
+                refresh_token: Some("ref".into()),
+                expires_at: Some(past_ts(100)),
+                token_type: None,
+            };
+            m.save_token_for(&ProviderId::Copilot, account, None, tok)
+                .await
+                .unwrap();
+        }
+
+        // Both attempts fail (Copilot cannot refresh), but account "b" must
+        // not inherit the cooldown started by account "a".
+        for account in ["a", "b"] {
+            let msg = m
+                .get_token_for(&ProviderId::Copilot, account)
+                .await
+                .unwrap_err()
+                .to_string();
+            assert!(!msg.contains("cooldown"), "{account}: {msg}");
+        }
+        let msg = m
+            .get_token_for(&ProviderId::Copilot, "a")
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(msg.contains("cooldown"), "expected cooldown, got: {msg}");
+    }
+
+    #[tokio::test]
+    async fn test_active_refresh_shares_cooldown_with_explicit_account() {
+        let m = make_manager();
+        let tok = OAuthToken {
+            access_token: "old".into(),
+            refresh_token: Some("ref".into()),
+            expires_at: Some(past_ts(100)),
+            token_type: None,
+        };
+        m.save_token_for(&ProviderId::Copilot, "work", None, tok)
+            .await
+            .unwrap();
+
+        // A refresh through the active account is keyed by its real id, so
+        // an explicit refresh of "work" sees the same cooldown.
+        let msg = m
+            .get_token(&ProviderId::Copilot)
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(!msg.contains("cooldown"), "{msg}");
+        let msg = m
+            .get_token_for(&ProviderId::Copilot, "work")
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(msg.contains("cooldown"), "expected cooldown, got: {msg}");
+    }
+
+    #[tokio::test]
+    async fn test_proactive_refresh_of_named_active_account_spawns_once() {
+        let m = make_manager();
+        // Inside the proactive window; Copilot refreshes fail without a round-trip.
+        let tok = OAuthToken::new("tok").with_refresh("ref").with_expiry(120);
+        m.save_token_for(&ProviderId::Copilot, "work", None, tok)
+            .await
+            .unwrap();
+
+        m.get_token(&ProviderId::Copilot).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(m.refresh_counts()[&ProviderId::Copilot].failed, 1);
+
+        // A second spawned refresh would queue on the account's lock.
+        let lock = m.get_refresh_lock(&account_key(&ProviderId::Copilot, Some("work")));
+        let _held = lock.lock().await;
+        m.get_token(&ProviderId::Copilot).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(Arc::strong_count(&lock), 2, "second refresh was spawned");
+        assert_eq!(m.refresh_counts()[&ProviderId::Copilot].failed, 1);
+    }
+
     #[tokio::test]
     async fn test_save_and_get_token_for() {
         let m = make_manager();
@@ -740,4 +1188,36 @@ mod tests {
         let all = m.get_all_tokens(&ProviderId::Claude).await.unwrap();
         assert_eq!(all.len(), 2);
     }
+
+    #[tokio::test]
+    async fn test_mark_used_touches_serving_account() {
+        let m = make_manager();
+        for id in ["a", "b"] {
+            m.save_token_for(&ProviderId::Claude, id, None, OAuthToken::new(id))
+                .await
+                .unwrap();
+        }
+
+        let (token, served) = track_served_account(m.get_token_for(&ProviderId::Claude, "b")).await;
+        assert_eq!(token.unwrap().access_token, "b");
+        assert_eq!(served, Some(ServedAccount::Account("b".into())));
+        let (_, served) = track_served_account(m.get_token(&ProviderId::Claude)).await;
+        assert_eq!(served, Some(ServedAccount::Active));
+
+        m.mark_used(&ProviderId::Claude, ServedAccount::Account("b".into()));
+        let mut accounts = Vec::new();
+        for _ in 0..100 {
+            accounts = m.list_accounts(&ProviderId::Claude).await.unwrap();
+            if accounts.iter().any(|a| a.last_used_at.is_some()) {
+                break;
+            }
+            tokio::task::yield_now().await;
+        }
+        let used: Vec<_> = accounts
+            .iter()
+            .filter(|a| a.last_used_at.is_some())
+            .map(|a| a.account_id.as_str())
+            .collect();
+        assert_eq!(used, ["b"]);
+    }
 }
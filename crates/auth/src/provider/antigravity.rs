@@ -47,6 +47,15 @@ pub fn token_form_params(
     ]
 }
 
+/// Revoke `token` at Google's revocation endpoint (shared with Gemini).
+///
+/// # Errors
+///
+/// Returns an error if the request fails or Google rejects the token.
+pub async fn revoke(http: &rquest::Client, token: &OAuthToken) -> Result<()> {
+    super::gemini::revoke(http, token).await
+}
+
 // ── AuthCodeFlow implementation ───────────────────────────────────────────────
 
 use async_trait::async_trait;
@@ -121,9 +121,9 @@ async fn load_raw() -> Result<Option<String>, ByokError> {
 
 #[cfg(not(target_os = "macos"))]
 async fn load_raw() -> Result<Option<String>, ByokError> {
-    let home = std::env::var("HOME")
-        .map_err(|_| ByokError::Auth("HOME environment variable not set".into()))?;
-    let path = std::path::PathBuf::from(home).join(".claude/.credentials.json");
+    let home = dirs::home_dir()
+        .ok_or_else(|| ByokError::Auth("cannot determine home directory".into()))?;
+    let path = home.join(".claude/.credentials.json");
     match tokio::fs::read_to_string(&path).await {
         Ok(s) => Ok(Some(s)),
         Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
@@ -88,9 +88,9 @@ fn decode_jwt_exp(jwt: &str) -> Option<u64> {
 }
 
 async fn load_raw() -> Result<Option<String>, ByokError> {
-    let home = std::env::var("HOME")
-        .map_err(|_| ByokError::Auth("HOME environment variable not set".into()))?;
-    let path = std::path::PathBuf::from(home).join(".codex/auth.json");
+    let home = dirs::home_dir()
+        .ok_or_else(|| ByokError::Auth("cannot determine home directory".into()))?;
+    let path = home.join(".codex/auth.json");
     match tokio::fs::read_to_string(&path).await {
         Ok(s) => Ok(Some(s)),
         Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
@@ -9,6 +9,9 @@ pub const CALLBACK_PORT: u16 = 8085;
 /// Google OAuth 2.0 authorization endpoint.
 pub const AUTH_URL: &str = "https://accounts.google.com/o/oauth2/v2/auth";
 
+/// Google OAuth 2.0 token revocation endpoint.
+pub const REVOKE_URL: &str = "https://oauth2.googleapis.com/revoke";
+
 /// OAuth scopes requested during authorization.
 pub const SCOPES: &[&str] = &[
     "openid",
@@ -45,6 +48,41 @@ pub fn token_form_params(
     ]
 }
 
+/// Build the form parameters for revoking `token`.
+///
+/// Revoking the refresh token invalidates the whole grant, so it is preferred
+/// over the access token. Returns `None` when the token carries neither.
+#[must_use]
+pub fn revoke_form_params(token: &OAuthToken) -> Option<Vec<(String, String)>> {
+    let value = token
+        .refresh_token
+        .as_deref()
+        .filter(|t| !t.is_empty())
+        .or_else(|| Some(token.access_token.as_str()).filter(|t| !t.is_empty()))?;
+    Some(vec![("token".into(), value.into())])
+}
+
+/// Revoke `token` at Google's revocation endpoint.
+///
+/// # Errors
+///
+/// Returns an error if the request fails or Google rejects the token.
+pub async fn revoke(http: &rquest::Client, token: &OAuthToken) -> Result<()> {
+    let Some(params) = revoke_form_params(token) else {
+        return Ok(());
+    };
+    let resp = http.post(REVOKE_URL).form(&params).send().await?;
+    let status = resp.status();
+    if status.is_success() {
+        Ok(())
+    } else {
+        let body = resp.text().await.unwrap_or_default();
+        Err(ByokError::Auth(format!(
+            "google revocation failed ({status}): {body}"
+        )))
+    }
+}
+
 // ── AuthCodeFlow implementation ───────────────────────────────────────────────
 
 use async_trait::async_trait;
@@ -135,4 +173,16 @@ mod tests {
         assert_eq!(map["redirect_uri"], REDIRECT_URI);
         assert_eq!(map["code_verifier"], "myverifier");
     }
+
+    #[test]
+    fn test_revoke_prefers_refresh_token() {
+        let token = OAuthToken::new("access").with_refresh("refresh");
+        let params = revoke_form_params(&token).unwrap();
+        assert_eq!(params, vec![("token".to_string(), "refresh".to_string())]);
+
+        let params = revoke_form_params(&OAuthToken::new("access")).unwrap();
+        assert_eq!(params, vec![("token".to_string(), "access".to_string())]);
+
+        assert!(revoke_form_params(&OAuthToken::new("")).is_none());
+    }
 }
@@ -9,10 +9,18 @@ use rand::RngCore as _;
 use crate::token::DeviceCodeResponse;
 
 pub const SCOPES: &[&str] = &["openid", "offline_access"];
-pub const PLATFORM: &str = "mac";
-pub const VERSION: &str = "0.13.0";
+/// Kimi CLI release that the `X-Msh-*` headers and User-Agent identify as.
+pub const VERSION: &str = "1.10.6";
+/// `X-Msh-Platform` value sent by Kimi CLI.
+pub const PLATFORM: &str = "kimi_cli";
 pub const DEVICE_MODEL: &str = "MacBookPro";
 
+/// Kimi CLI's User-Agent for [`VERSION`].
+#[must_use]
+pub fn user_agent() -> String {
+    format!("KimiCLI/{VERSION}")
+}
+
 #[must_use]
 pub fn device_id() -> String {
     let mut bytes = [0u8; 16];
@@ -46,14 +54,22 @@ pub fn device_name() -> String {
     "byokey-client".to_string()
 }
 
+/// `X-Msh-*` headers with a freshly generated device id.
 #[must_use]
 pub fn x_msh_headers() -> Vec<(&'static str, String)> {
+    x_msh_headers_for(&device_id())
+}
+
+/// `X-Msh-*` platform headers for `device_id`. Kimi expects these on chat
+/// requests as well as during login, so the executor shares this helper.
+#[must_use]
+pub fn x_msh_headers_for(device_id: &str) -> Vec<(&'static str, String)> {
     vec![
         ("X-Msh-Platform", PLATFORM.to_string()),
         ("X-Msh-Version", VERSION.to_string()),
         ("X-Msh-Device-Name", device_name()),
         ("X-Msh-Device-Model", DEVICE_MODEL.to_string()),
-        ("X-Msh-Device-Id", device_id()),
+        ("X-Msh-Device-Id", device_id.to_string()),
     ]
 }
 
@@ -203,9 +219,9 @@ mod tests {
         let headers = x_msh_headers();
         assert_eq!(headers.len(), 5);
         assert_eq!(headers[0].0, "X-Msh-Platform");
-        assert_eq!(headers[0].1, "mac");
+        assert_eq!(headers[0].1, "kimi_cli");
         assert_eq!(headers[1].0, "X-Msh-Version");
-        assert_eq!(headers[1].1, "0.13.0");
+        assert_eq!(headers[1].1, "1.10.6");
         assert_eq!(headers[2].0, "X-Msh-Device-Name");
         assert_eq!(headers[2].1, "byokey-client");
         assert_eq!(headers[3].0, "X-Msh-Device-Model");
@@ -7,9 +7,12 @@ pub mod schema;
 pub mod watcher;
 
 pub use schema::{
-    AmpConfig, ApiKeyEntry, ClaudeHeaderDefaults, CloakConfig, CodexHeaderDefaults, Config,
-    KeyRoutingStrategy, LogConfig, LogFormat, ModelAlias, PayloadFilterRule, PayloadRule,
-    PayloadRules, PolicyStrategyKind, ProviderConfig, RoutingPolicyEntry, StreamingConfig,
-    TelemetryConfig,
+    AmpConfig, ApiKeyEntry, AppliedPayloadRule, ChunkRule, CircuitBreakerConfig,
+    ClaudeHeaderDefaults, CloakConfig, CodexHeaderDefaults, Config, DEFAULT_CACHE_MIN_CHARS,
+    KeyRoutingStrategy, LogConfig, LogFormat, ModelAlias, ModelRegistryFile, ModelRoute,
+    PayloadFilterRule, PayloadRule, PayloadRuleKind, PayloadRules, PolicyStrategyKind,
+    ProviderConfig, ResponsePayloadRules, RetryBackoffConfig, RetryBudgetConfig,
+    RoutingPolicyEntry, StreamingConfig, TelemetryConfig, UpstreamHttpVersion, UsageConfig,
+    WeightedModelTarget,
 };
 pub use watcher::ConfigWatcher;
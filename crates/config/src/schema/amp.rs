@@ -36,12 +36,7 @@ impl AmpConfig {
     /// Default path for Amp CLI settings: `~/.config/amp/settings.json`.
     #[must_use]
     pub fn default_settings_path() -> Option<PathBuf> {
-        std::env::var("HOME").ok().map(|h| {
-            Path::new(&h)
-                .join(".config")
-                .join("amp")
-                .join("settings.json")
-        })
+        dirs::home_dir().map(|h| h.join(".config").join("amp").join("settings.json"))
     }
 
     /// Merge this config's `settings` into the Amp CLI settings file,
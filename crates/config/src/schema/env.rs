@@ -0,0 +1,201 @@
+//! `${VAR}` / `${VAR:-default}` substitution in configuration strings.
+//!
+//! Runs on the merged figment value tree before extraction. Only credential
+//! and URL fields ([`EXPANDED_KEYS`], including everything nested under them)
+//! are expanded; payload rules, prompts and other free text pass through
+//! verbatim. Inside expanded fields, `$${` produces a literal `${`, and a
+//! `${...}` whose contents are not a variable name is left untouched.
+
+use std::fmt::Write as _;
+
+use figment::value::Value;
+
+/// Config keys whose string values may reference the environment.
+const EXPANDED_KEYS: &[&str] = &[
+    "api_key",
+    "api_keys",
+    "base_url",
+    "proxy_url",
+    "upstream_key",
+];
+
+/// Expands variable references in the credential and URL strings of `value`.
+///
+/// # Errors
+///
+/// Returns a message naming the variable and the config key it appears in
+/// if a variable is unset and has no default.
+pub(super) fn expand(
+    value: &mut Value,
+    lookup: &dyn Fn(&str) -> Option<String>,
+) -> Result<(), String> {
+    expand_at(value, &mut String::new(), false, lookup)
+}
+
+fn expand_at(
+    value: &mut Value,
+    path: &mut String,
+    in_scope: bool,
+    lookup: &dyn Fn(&str) -> Option<String>,
+) -> Result<(), String> {
+    match value {
+        Value::String(_, s) if in_scope && s.contains("${") => {
+            *s = expand_str(s, lookup).map_err(|var| {
+                format!("environment variable `{var}` referenced by `{path}` is not set")
+            })?;
+        }
+        Value::Dict(_, dict) => {
+            for (key, child) in dict.iter_mut() {
+                let len = path.len();
+                if !path.is_empty() {
+                    path.push('.');
+                }
+                path.push_str(key);
+                let in_scope = in_scope || EXPANDED_KEYS.contains(&key.as_str());
+                expand_at(child, path, in_scope, lookup)?;
+                path.truncate(len);
+            }
+        }
+        Value::Array(_, items) => {
+            for (i, child) in items.iter_mut().enumerate() {
+                let len = path.len();
+                let _ = write!(path, "[{i}]");
+                expand_at(child, path, in_scope, lookup)?;
+                path.truncate(len);
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Expands references in one string; on failure returns the unset name.
+fn expand_str(s: &str, lookup: &dyn Fn(&str) -> Option<String>) -> Result<String, String> {
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(start) = rest.find("${") {
+        if rest[..start].ends_with('$') {
+            // `$${` escapes a literal `${`.
+            out.push_str(&rest[..start - 1]);
+            out.push_str("${");
+            rest = &rest[start + 2..];
+            continue;
+        }
+        let after = &rest[start + 2..];
+        let Some(end) = after.find('}') else {
+            break;
+        };
+        out.push_str(&rest[..start]);
+        let inner = &after[..end];
+        let (name, default) = match inner.split_once(":-") {
+            Some((name, default)) => (name, Some(default)),
+            None => (inner, None),
+        };
+        if is_var_name(name) {
+            match lookup(name).or_else(|| default.map(str::to_string)) {
+                Some(v) => out.push_str(&v),
+                None => return Err(name.to_string()),
+            }
+        } else {
+            out.push_str(&rest[start..=start + 2 + end]);
+        }
+        rest = &after[end + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+fn is_var_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lookup(name: &str) -> Option<String> {
+        (name == "API_KEY").then(|| "sk-test".to_string())
+    }
+
+    #[test]
+    fn set_var_is_substituted() {
+        assert_eq!(expand_str("${API_KEY}", &lookup).unwrap(), "sk-test");
+        assert_eq!(
+            expand_str("Bearer ${API_KEY}!", &lookup).unwrap(),
+            "Bearer sk-test!"
+        );
+        assert_eq!(
+            expand_str("${API_KEY:-fallback}", &lookup).unwrap(),
+            "sk-test"
+        );
+    }
+
+    #[test]
+    fn unset_var_uses_default() {
+        assert_eq!(
+            expand_str("${MISSING:-http://localhost:8000}", &lookup).unwrap(),
+            "http://localhost:8000"
+        );
+        assert_eq!(expand_str("${MISSING:-}", &lookup).unwrap(), "");
+    }
+
+    #[test]
+    fn unset_var_without_default_errors() {
+        assert_eq!(expand_str("${MISSING}", &lookup).unwrap_err(), "MISSING");
+    }
+
+    #[test]
+    fn dollar_escape_is_literal() {
+        assert_eq!(expand_str("$${API_KEY}", &lookup).unwrap(), "${API_KEY}");
+        assert_eq!(
+            expand_str("a$${MISSING}-${API_KEY}", &lookup).unwrap(),
+            "a${MISSING}-sk-test"
+        );
+    }
+
+    #[test]
+    fn only_credential_and_url_fields_expand() {
+        use figment::value::{Dict, Tag};
+
+        let string = |s: &str| Value::String(Tag::Default, s.to_string());
+        let mut rule = Dict::new();
+        rule.insert("value".into(), string("${MISSING}"));
+        let mut provider = Dict::new();
+        provider.insert("api_key".into(), string("${API_KEY}"));
+        provider.insert(
+            "api_keys".into(),
+            Value::Array(Tag::Default, vec![string("${API_KEY}")]),
+        );
+        provider.insert("prompt".into(), string("${MISSING}"));
+        provider.insert("payload".into(), Value::Dict(Tag::Default, rule));
+        let mut value = Value::Dict(Tag::Default, provider);
+
+        expand(&mut value, &lookup).unwrap();
+        let Value::Dict(_, dict) = &value else {
+            unreachable!()
+        };
+        assert_eq!(dict["api_key"].as_str(), Some("sk-test"));
+        let Value::Array(_, keys) = &dict["api_keys"] else {
+            unreachable!()
+        };
+        assert_eq!(keys[0].as_str(), Some("sk-test"));
+        assert_eq!(dict["prompt"].as_str(), Some("${MISSING}"));
+        let Value::Dict(_, rule) = &dict["payload"] else {
+            unreachable!()
+        };
+        assert_eq!(rule["value"].as_str(), Some("${MISSING}"));
+    }
+
+    #[test]
+    fn non_variable_braces_are_kept() {
+        assert_eq!(expand_str("${not a var}", &lookup).unwrap(), "${not a var}");
+        assert_eq!(
+            expand_str("tail ${API_KEY", &lookup).unwrap(),
+            "tail ${API_KEY"
+        );
+    }
+}
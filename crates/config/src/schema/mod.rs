@@ -1,38 +1,58 @@
 pub mod amp;
+mod env;
 pub mod model;
 pub mod payload;
 pub mod provider;
 pub mod runtime;
 
 pub use amp::AmpConfig;
-pub use model::ModelAlias;
-pub use payload::{PayloadFilterRule, PayloadRule, PayloadRules};
+pub use model::{ModelAlias, ModelRegistryFile, ModelRoute, WeightedModelTarget};
+pub use payload::{
+    AppliedPayloadRule, PayloadFilterRule, PayloadRule, PayloadRuleKind, PayloadRules,
+    ResponsePayloadRules,
+};
 pub use provider::{
-    ApiKeyEntry, ClaudeHeaderDefaults, CloakConfig, CodexHeaderDefaults, KeyRoutingStrategy,
-    PolicyStrategyKind, ProviderConfig, RoutingPolicyEntry,
+    ApiKeyEntry, CircuitBreakerConfig, ClaudeHeaderDefaults, CloakConfig, CodexHeaderDefaults,
+    DEFAULT_CACHE_MIN_CHARS, KeyRoutingStrategy, PolicyStrategyKind, ProviderConfig,
+    RetryBackoffConfig, RoutingPolicyEntry,
+};
+pub use runtime::{
+    ChunkRule, LogConfig, LogFormat, RetryBudgetConfig, StreamingConfig, TelemetryConfig,
+    UpstreamHttpVersion, UsageConfig,
 };
-pub use runtime::{LogConfig, LogFormat, StreamingConfig, TelemetryConfig};
 
 use byokey_types::ProviderId;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+/// Environment variable listing providers to disable at load time.
+const DISABLE_ENV: &str = "BYOKEY_DISABLE";
+
 fn default_port() -> u16 {
     8018
 }
 fn default_host() -> String {
     "127.0.0.1".to_string()
 }
+fn default_shutdown_grace_seconds() -> u64 {
+    30
+}
 
 /// Top-level application configuration.
+#[allow(clippy::struct_excessive_bools)]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     /// Listen port (defaults to 8018).
     #[serde(default = "default_port")]
     pub port: u16,
-    /// Listen address (defaults to `127.0.0.1`).
+    /// Listen address (defaults to `127.0.0.1`). `unix:/path` listens on a
+    /// Unix socket instead, ignoring `port`.
     #[serde(default = "default_host")]
     pub host: String,
+    /// Seconds in-flight requests (including open streams) get to finish
+    /// after SIGTERM/SIGINT before the server exits anyway.
+    #[serde(default = "default_shutdown_grace_seconds")]
+    pub shutdown_grace_seconds: u64,
     /// Provider configuration map.
     #[serde(default)]
     pub providers: HashMap<ProviderId, ProviderConfig>,
@@ -43,24 +63,78 @@ pub struct Config {
     /// All upstream requests will go through this proxy.
     #[serde(default)]
     pub proxy_url: Option<String>,
+    /// HTTP version for upstream requests: `auto` (default), `http1` or
+    /// `http2`. Forcing `http1` works around proxies that reset HTTP/2
+    /// streams. Like `proxy_url`, applied when the server starts.
+    #[serde(default)]
+    pub upstream_http_version: UpstreamHttpVersion,
     /// Model alias mappings per provider.
     #[serde(default)]
     pub model_alias: HashMap<ProviderId, Vec<ModelAlias>>,
+    /// Weighted routes: one public model name load-balanced across several
+    /// real models/providers (e.g. `smart` → 70% Claude, 30% Gemini).
+    /// Resolved per request before model aliases.
+    #[serde(default)]
+    pub model_routing: HashMap<String, Vec<WeightedModelTarget>>,
+    /// Optional model registry file (YAML) mapping model-name patterns to
+    /// providers and listing extra models per provider, merged over the
+    /// built-in registry and hot-reloaded by `ConfigWatcher`. Relative paths
+    /// resolve against the config file's directory.
+    #[serde(default)]
+    pub models_file: Option<std::path::PathBuf>,
     /// Models to exclude from the /v1/models listing, per provider.
-    /// Supports glob patterns (e.g. "claude-3-*", "*-thinking").
+    /// Supports glob patterns with `*` anywhere (e.g. "claude-3-*", "*-thinking",
+    /// "claude-*-2024*").
     #[serde(default)]
     pub excluded_models: HashMap<ProviderId, Vec<String>>,
+    /// Models (glob patterns) that are always requested non-streaming
+    /// upstream; streaming clients receive the result as a single chunk.
+    #[serde(default)]
+    pub force_nonstream_models: Vec<String>,
     /// Streaming SSE configuration.
     #[serde(default)]
     pub streaming: StreamingConfig,
+    /// Share one upstream call between identical concurrent non-streaming
+    /// chat requests (same model, messages and params). Off by default since
+    /// it assumes a shared, deterministic answer is acceptable.
+    #[serde(default)]
+    pub coalesce_requests: bool,
+    /// Upstream response headers (glob patterns, case-insensitive, e.g.
+    /// `x-ratelimit-*`) forwarded to chat clients. Hop-by-hop and
+    /// security-sensitive headers are never forwarded.
+    #[serde(default)]
+    pub forward_response_headers: Vec<String>,
+    /// Accept a `callback_url` on chat requests: reply `202 Accepted`, run
+    /// the completion in the background and POST the result to that URL.
+    /// Off by default since it lets clients make byokey call arbitrary URLs.
+    #[serde(default)]
+    pub allow_callback_url: bool,
+    /// Clamp an out-of-range `temperature` into the provider's accepted
+    /// range instead of passing it through (and letting upstream reject it).
+    #[serde(default)]
+    pub clamp_temperature: bool,
+    /// Add an `x-byokey-route` header to chat responses naming the provider,
+    /// backend, API key index or account, and whether fallback fired.
+    #[serde(default)]
+    pub route_header: bool,
+    /// Caps the upstream attempts and time one client request may spend
+    /// on retries and fallback. Unlimited by default.
+    #[serde(default)]
+    pub retry_budget: RetryBudgetConfig,
     /// Payload rules for modifying request bodies.
     #[serde(default)]
     pub payload: PayloadRules,
+    /// Payload rules for modifying response bodies.
+    #[serde(default)]
+    pub response_payload: ResponsePayloadRules,
     /// Per-(provider, optional family) routing policies for load-balancing
     /// across multiple accounts. Consumed by `byokey-provider`'s
     /// `AccountSelector` at request time.
     #[serde(default)]
     pub routing_policies: Vec<RoutingPolicyEntry>,
+    /// Usage snapshot persistence.
+    #[serde(default)]
+    pub usage: UsageConfig,
     /// Logging configuration.
     #[serde(default)]
     pub log: LogConfig,
@@ -74,59 +148,112 @@ impl Default for Config {
         Self {
             port: default_port(),
             host: default_host(),
+            shutdown_grace_seconds: default_shutdown_grace_seconds(),
             providers: HashMap::new(),
             amp: AmpConfig::default(),
             proxy_url: None,
+            upstream_http_version: UpstreamHttpVersion::default(),
             model_alias: HashMap::new(),
+            model_routing: HashMap::new(),
+            models_file: None,
             excluded_models: HashMap::new(),
+            force_nonstream_models: Vec::new(),
             streaming: StreamingConfig::default(),
+            coalesce_requests: false,
+            forward_response_headers: Vec::new(),
+            allow_callback_url: false,
+            clamp_temperature: false,
+            route_header: false,
+            retry_budget: RetryBudgetConfig::default(),
             payload: PayloadRules::default(),
+            response_payload: ResponsePayloadRules::default(),
             routing_policies: Vec::new(),
+            usage: UsageConfig::default(),
             log: LogConfig::default(),
             telemetry: TelemetryConfig::default(),
         }
     }
 }
 
-/// Simple glob matching with `*` wildcard support.
+/// Glob matching where `*` matches any run of characters, anywhere in the
+/// pattern (e.g. `claude-*-2024*`). Every other character matches literally.
 pub(crate) fn glob_match(pattern: &str, text: &str) -> bool {
-    if pattern == "*" {
-        return true;
-    }
-    if let Some(suffix) = pattern.strip_prefix('*') {
-        text.ends_with(suffix)
-    } else if let Some(prefix) = pattern.strip_suffix('*') {
-        text.starts_with(prefix)
-    } else {
-        pattern == text
+    let mut parts = pattern.split('*');
+    // `split` always yields at least one part.
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = text.strip_prefix(first) else {
+        return false;
+    };
+    let mut parts: Vec<&str> = parts.collect();
+    let Some(last) = parts.pop() else {
+        // No `*` at all: exact match.
+        return rest.is_empty();
+    };
+    // Greedy leftmost matching of the middle segments is sufficient because
+    // each is bounded by `*` on both sides.
+    for part in parts {
+        match rest.find(part) {
+            Some(idx) => rest = &rest[idx + part.len()..],
+            None => return false,
+        }
     }
+    rest.ends_with(last)
 }
 
 impl Config {
     /// Parses configuration from a YAML string, merged with defaults.
     ///
+    /// `${VAR}` and `${VAR:-default}` in string values are expanded from the
+    /// environment.
+    ///
     /// # Errors
     ///
-    /// Returns a [`figment::Error`] if the YAML is invalid or extraction fails.
+    /// Returns a [`figment::Error`] if the YAML is invalid, extraction fails,
+    /// or a referenced environment variable is unset without a default.
     #[allow(clippy::result_large_err)]
     pub fn from_yaml(yaml: &str) -> Result<Self, figment::Error> {
+        Self::from_yaml_with_env(yaml, &|name| std::env::var(name).ok())
+    }
+
+    #[allow(clippy::result_large_err)]
+    fn from_yaml_with_env(
+        yaml: &str,
+        lookup: &dyn Fn(&str) -> Option<String>,
+    ) -> Result<Self, figment::Error> {
         use figment::{
             Figment,
             providers::{Format as _, Serialized, Yaml},
         };
-        Figment::from(Serialized::defaults(Config::default()))
-            .merge(Yaml::string(yaml))
-            .extract()
+        let figment =
+            Figment::from(Serialized::defaults(Config::default())).merge(Yaml::string(yaml));
+        Self::extract_expanded(&figment, lookup)
+    }
+
+    /// Extracts the merged configuration after expanding environment
+    /// variable references in its string values.
+    #[allow(clippy::result_large_err)]
+    fn extract_expanded(
+        figment: &figment::Figment,
+        lookup: &dyn Fn(&str) -> Option<String>,
+    ) -> Result<Self, figment::Error> {
+        use figment::{Figment, providers::Serialized, value::Value};
+        let mut value: Value = figment.extract()?;
+        env::expand(&mut value, lookup)?;
+        Figment::from(Serialized::defaults(value)).extract()
     }
 
     /// Loads configuration from a file path, merged with defaults.
     ///
     /// The file format is determined by the file extension:
-    /// `.json` uses JSON, everything else uses YAML.
+    /// `.json` uses JSON, everything else uses YAML. Environment overrides
+    /// (see [`Config::with_env_overrides`]) are layered on top, and
+    /// `${VAR}` / `${VAR:-default}` references are expanded as in
+    /// [`Config::from_yaml`].
     ///
     /// # Errors
     ///
-    /// Returns a [`figment::Error`] if the file cannot be read or parsed.
+    /// Returns a [`figment::Error`] if the file cannot be read or parsed, or
+    /// a referenced environment variable is unset without a default.
     #[allow(clippy::result_large_err)]
     pub fn from_file(path: &std::path::Path) -> Result<Self, figment::Error> {
         use figment::{
@@ -139,13 +266,47 @@ impl Config {
         } else {
             base.merge(Yaml::file(path))
         };
-        figment.extract()
+        Self::extract_expanded(&figment, &|name| std::env::var(name).ok())
+            .map(Self::with_env_overrides)
+    }
+
+    /// Applies environment-based overrides on top of a loaded configuration.
+    ///
+    /// `BYOKEY_DISABLE` takes a comma-separated list of provider names
+    /// (e.g. `gemini,codex`) that are marked `enabled: false`.
+    #[must_use]
+    pub fn with_env_overrides(mut self) -> Self {
+        if let Ok(list) = std::env::var(DISABLE_ENV) {
+            self.apply_disable_list(&list);
+        }
+        self
+    }
+
+    /// Disables every provider named in a comma-separated list.
+    /// Unknown names are logged and skipped.
+    fn apply_disable_list(&mut self, list: &str) {
+        for name in list.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+            if let Ok(provider) = name.parse::<ProviderId>() {
+                self.providers.entry(provider).or_default().enabled = false;
+            } else {
+                tracing::warn!(provider = name, "{DISABLE_ENV}: unknown provider");
+            }
+        }
     }
 
     /// Resolves a model alias back to the original model name.
-    /// If the input is not an alias, returns it unchanged.
+    /// If the input is not an alias, or is the original name of a forked
+    /// alias (which stays public alongside it), returns it unchanged.
     #[must_use]
     pub fn resolve_alias(&self, model: &str) -> String {
+        let forked_original = self
+            .model_alias
+            .values()
+            .flatten()
+            .any(|alias| alias.fork && alias.name == model);
+        if forked_original {
+            return model.to_string();
+        }
         for aliases in self.model_alias.values() {
             for alias in aliases {
                 if alias.alias == model {
@@ -156,6 +317,23 @@ impl Config {
         model.to_string()
     }
 
+    /// Resolves a weighted route (`model_routing`) to one of its targets.
+    ///
+    /// Returns `None` if `model` is not a weighted route or all of its
+    /// target weights are zero.
+    pub fn resolve_weighted_route<R: rand::Rng + ?Sized>(
+        &self,
+        model: &str,
+        rng: &mut R,
+    ) -> Option<&str> {
+        let targets = self.model_routing.get(model)?;
+        let total: u64 = targets.iter().map(|t| u64::from(t.weight)).sum();
+        if total == 0 {
+            return None;
+        }
+        model::pick_weighted(targets, rng.gen_range(0..total))
+    }
+
     /// Returns true if the model matches any excluded pattern for its provider.
     #[must_use]
     pub fn is_model_excluded(&self, provider: &ProviderId, model: &str) -> bool {
@@ -168,11 +346,57 @@ impl Config {
         }
         false
     }
+
+    /// Returns true if `model` must be requested non-streaming upstream.
+    #[must_use]
+    pub fn is_nonstream_forced(&self, model: &str) -> bool {
+        self.force_nonstream_models
+            .iter()
+            .any(|pattern| glob_match(pattern, model))
+    }
+
+    /// Returns true if the upstream response header `name` is allowlisted
+    /// by `forward_response_headers`.
+    #[must_use]
+    pub fn forwards_response_header(&self, name: &str) -> bool {
+        let name = name.to_ascii_lowercase();
+        self.forward_response_headers
+            .iter()
+            .any(|pattern| glob_match(&pattern.to_ascii_lowercase(), &name))
+    }
+
+    /// Output token ceiling for `model` on `provider`, if configured.
+    ///
+    /// An exact `model_max_output_tokens` entry wins; otherwise the smallest
+    /// matching glob pattern applies, then the provider-wide
+    /// `max_output_tokens`.
+    #[must_use]
+    pub fn max_output_tokens(&self, provider: &ProviderId, model: &str) -> Option<u64> {
+        let pc = self.providers.get(provider)?;
+        if let Some(&ceiling) = pc.model_max_output_tokens.get(model) {
+            return Some(ceiling);
+        }
+        pc.model_max_output_tokens
+            .iter()
+            .filter(|(pattern, _)| glob_match(pattern, model))
+            .map(|(_, &ceiling)| ceiling)
+            .min()
+            .or(pc.max_output_tokens)
+    }
+
+    /// The custom provider's extra `model_prefix`, if configured.
+    #[must_use]
+    pub fn custom_model_prefix(&self) -> Option<&str> {
+        self.providers
+            .get(&ProviderId::Custom)
+            .and_then(|c| c.model_prefix.as_deref())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::time::Duration;
 
     const SAMPLE_YAML: &str = r#"
 port: 9000
@@ -185,6 +409,52 @@ providers:
     enabled: false
 "#;
 
+    #[test]
+    fn test_env_vars_expanded_in_string_values() {
+        let yaml = r#"
+providers:
+  claude:
+    api_key: "${ANTHROPIC_API_KEY}"
+  custom:
+    base_url: "${CUSTOM_BASE_URL:-http://localhost:8000/v1}"
+"#;
+        let lookup = |name: &str| (name == "ANTHROPIC_API_KEY").then(|| "sk-ant-env".to_string());
+        let c = Config::from_yaml_with_env(yaml, &lookup).unwrap();
+        assert_eq!(
+            c.providers[&ProviderId::Claude].api_key.as_deref(),
+            Some("sk-ant-env")
+        );
+        assert_eq!(
+            c.providers[&ProviderId::Custom].base_url.as_deref(),
+            Some("http://localhost:8000/v1")
+        );
+
+        let err = Config::from_yaml_with_env(yaml, &|_| None).unwrap_err();
+        let msg = err.to_string();
+        assert!(msg.contains("ANTHROPIC_API_KEY"), "{msg}");
+        assert!(msg.contains("providers.claude.api_key"), "{msg}");
+    }
+
+    #[test]
+    fn test_disable_list_disables_named_providers() {
+        let mut c = Config::from_yaml(SAMPLE_YAML).unwrap();
+        c.apply_disable_list("gemini, codex,,nope");
+        assert!(!c.providers[&ProviderId::Gemini].enabled);
+        assert!(!c.providers[&ProviderId::Codex].enabled);
+        assert!(c.providers[&ProviderId::Claude].enabled);
+        assert!(!c.providers.contains_key(&ProviderId::Kiro));
+    }
+
+    #[test]
+    fn test_upstream_http_version_parses() {
+        assert_eq!(
+            Config::default().upstream_http_version,
+            UpstreamHttpVersion::Auto
+        );
+        let c = Config::from_yaml("upstream_http_version: http1").unwrap();
+        assert_eq!(c.upstream_http_version, UpstreamHttpVersion::Http1);
+    }
+
     #[test]
     fn test_default_config() {
         let c = Config::default();
@@ -262,6 +532,92 @@ excluded_models:
         assert_eq!(excluded.len(), 2);
     }
 
+    #[test]
+    fn test_from_yaml_force_nonstream_models() {
+        let yaml = r#"
+force_nonstream_models:
+  - "gemini-*-preview"
+"#;
+        let c = Config::from_yaml(yaml).unwrap();
+        assert!(c.is_nonstream_forced("gemini-3-pro-preview"));
+        assert!(!c.is_nonstream_forced("gemini-2.5-pro"));
+        assert!(!Config::default().is_nonstream_forced("gemini-3-pro-preview"));
+    }
+
+    #[test]
+    fn test_max_output_tokens_lookup() {
+        let c = Config::from_yaml(
+            r#"
+providers:
+  claude:
+    max_output_tokens: 8192
+    model_max_output_tokens:
+      "claude-haiku-*": 4096
+      claude-haiku-4-5: 2048
+"#,
+        )
+        .unwrap();
+        let claude = ProviderId::Claude;
+        assert_eq!(c.max_output_tokens(&claude, "claude-opus-4-5"), Some(8192));
+        assert_eq!(c.max_output_tokens(&claude, "claude-haiku-4-0"), Some(4096));
+        assert_eq!(c.max_output_tokens(&claude, "claude-haiku-4-5"), Some(2048));
+        assert_eq!(
+            c.max_output_tokens(&ProviderId::Gemini, "gemini-2.5-pro"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_shutdown_grace_seconds_default_and_override() {
+        assert_eq!(Config::default().shutdown_grace_seconds, 30);
+        let c = Config::from_yaml("shutdown_grace_seconds: 5").unwrap();
+        assert_eq!(c.shutdown_grace_seconds, 5);
+    }
+
+    #[test]
+    fn test_model_registry_file_resolution() {
+        let file = ModelRegistryFile::from_yaml(
+            r#"
+routes:
+  - pattern: "claude-opus-5*"
+    provider: claude
+  - pattern: "*-next"
+    provider: codex
+models:
+  gemini: [gemini-4-pro]
+"#,
+        )
+        .unwrap();
+        let any = |_: &ProviderId| true;
+        assert_eq!(
+            file.resolve_provider_with("claude-opus-5-20270101", any),
+            Some(ProviderId::Claude)
+        );
+        assert_eq!(
+            file.resolve_provider_with("gemini-4-pro", any),
+            Some(ProviderId::Gemini)
+        );
+        assert_eq!(
+            file.resolve_provider_with("gpt-6-next", |p| *p != ProviderId::Codex),
+            None
+        );
+        assert_eq!(file.resolve_provider_with("claude-sonnet-4-5", any), None);
+    }
+
+    #[test]
+    fn test_from_yaml_forward_response_headers() {
+        let yaml = r#"
+forward_response_headers:
+  - "X-RateLimit-*"
+  - "x-request-id"
+"#;
+        let c = Config::from_yaml(yaml).unwrap();
+        assert!(c.forwards_response_header("x-ratelimit-remaining-requests"));
+        assert!(c.forwards_response_header("X-Request-Id"));
+        assert!(!c.forwards_response_header("server"));
+        assert!(!Config::default().forwards_response_header("x-request-id"));
+    }
+
     #[test]
     fn test_resolve_alias() {
         let yaml = r#"
@@ -275,6 +631,81 @@ model_alias:
         assert_eq!(c.resolve_alias("unknown"), "unknown");
     }
 
+    #[test]
+    fn test_resolve_alias_keeps_forked_original() {
+        let yaml = r#"
+model_alias:
+  claude:
+    - name: "claude-opus-4-5"
+      alias: "opus"
+      fork: true
+  codex:
+    - name: "gpt-5"
+      alias: "claude-opus-4-5"
+"#;
+        let c = Config::from_yaml(yaml).unwrap();
+        assert_eq!(c.resolve_alias("opus"), "claude-opus-4-5");
+        assert_eq!(c.resolve_alias("claude-opus-4-5"), "claude-opus-4-5");
+    }
+
+    #[test]
+    fn test_from_yaml_model_routing() {
+        let yaml = r#"
+model_routing:
+  smart:
+    - model: "claude-opus-4-5"
+      weight: 70
+    - model: "gemini/gemini-2.5-pro"
+      weight: 30
+  cheap:
+    - model: "gpt-4o-mini"
+"#;
+        let c = Config::from_yaml(yaml).unwrap();
+        let smart = c.model_routing.get("smart").unwrap();
+        assert_eq!(smart.len(), 2);
+        assert_eq!(smart[1].model, "gemini/gemini-2.5-pro");
+        assert_eq!(c.model_routing["cheap"][0].weight, 1);
+    }
+
+    #[test]
+    fn test_resolve_weighted_route_distribution() {
+        use rand::SeedableRng as _;
+
+        let yaml = r#"
+model_routing:
+  smart:
+    - model: "claude-opus-4-5"
+      weight: 70
+    - model: "gemini/gemini-2.5-pro"
+      weight: 30
+"#;
+        let c = Config::from_yaml(yaml).unwrap();
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        let n = 10_000;
+        let claude = (0..n)
+            .filter(|_| c.resolve_weighted_route("smart", &mut rng) == Some("claude-opus-4-5"))
+            .count();
+        // 70% ± 2%
+        assert!((6_800..=7_200).contains(&claude), "claude share: {claude}");
+    }
+
+    #[test]
+    fn test_resolve_weighted_route_unknown_or_zero() {
+        let yaml = r#"
+model_routing:
+  off:
+    - model: "claude-opus-4-5"
+      weight: 0
+"#;
+        let c = Config::from_yaml(yaml).unwrap();
+        let mut rng = rand::thread_rng();
+        assert!(c.resolve_weighted_route("off", &mut rng).is_none());
+        assert!(
+            c.resolve_weighted_route("claude-opus-4-5", &mut rng)
+                .is_none()
+        );
+    }
+
     #[test]
     fn test_is_model_excluded() {
         let yaml = r#"
@@ -290,6 +721,21 @@ excluded_models:
         assert!(!c.is_model_excluded(&ProviderId::Gemini, "claude-3-opus"));
     }
 
+    #[test]
+    fn test_request_timeout() {
+        let yaml = r"
+providers:
+  claude:
+    timeout_seconds: 30
+";
+        let c = Config::from_yaml(yaml).unwrap();
+        assert_eq!(
+            c.providers[&ProviderId::Claude].request_timeout(),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(ProviderConfig::default().request_timeout(), None);
+    }
+
     #[test]
     fn test_glob_match_exact() {
         assert!(glob_match("claude-3-opus", "claude-3-opus"));
@@ -314,4 +760,28 @@ excluded_models:
     fn test_glob_match_star_only() {
         assert!(glob_match("*", "anything"));
     }
+
+    #[test]
+    fn test_glob_match_star_mid_string() {
+        assert!(glob_match("claude-*-2024*", "claude-3-opus-20240229"));
+        assert!(glob_match("claude-*-2024*", "claude-sonnet-2024"));
+        assert!(!glob_match("claude-*-2024*", "claude-opus-4-5"));
+        assert!(!glob_match("claude-*-2024*", "gpt-4-2024-08-06"));
+        assert!(glob_match("*mini*", "gpt-4o-mini-2024"));
+        assert!(glob_match("a*b*b", "abb"));
+        assert!(!glob_match("a*b*b", "ab"));
+        assert!(!glob_match("ab*ba", "aba"));
+    }
+
+    #[test]
+    fn test_excluded_models_mid_string_wildcard() {
+        let yaml = r#"
+excluded_models:
+  claude:
+    - "claude-*-2024*"
+"#;
+        let c = Config::from_yaml(yaml).unwrap();
+        assert!(c.is_model_excluded(&ProviderId::Claude, "claude-3-5-sonnet-20241022"));
+        assert!(!c.is_model_excluded(&ProviderId::Claude, "claude-opus-4-5"));
+    }
 }
@@ -1,4 +1,6 @@
+use byokey_types::ProviderId;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 /// A single model alias mapping.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -11,3 +13,119 @@ pub struct ModelAlias {
     #[serde(default)]
     pub fork: bool,
 }
+
+fn default_weight() -> u32 {
+    1
+}
+
+/// One backend of a weighted model route.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct WeightedModelTarget {
+    /// Real model to route to; may be provider-qualified (`"gemini/gemini-2.5-pro"`).
+    pub model: String,
+    /// Relative share of requests (defaults to 1). Zero disables the target.
+    #[serde(default = "default_weight")]
+    pub weight: u32,
+}
+
+/// Picks a target from `targets` using `roll`, a uniform value in
+/// `0..total_weight`. Returns `None` when every weight is zero.
+pub(crate) fn pick_weighted(targets: &[WeightedModelTarget], roll: u64) -> Option<&str> {
+    let mut acc = 0u64;
+    for target in targets {
+        acc += u64::from(target.weight);
+        if roll < acc {
+            return Some(target.model.as_str());
+        }
+    }
+    None
+}
+
+/// Routes model names matching `pattern` (a glob, `*` anywhere) to `provider`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ModelRoute {
+    pub pattern: String,
+    pub provider: ProviderId,
+}
+
+/// User model registry loaded from `models_file`, merged over the built-in
+/// registry so new models work without a new release.
+///
+/// ```yaml
+/// routes:
+///   - pattern: "claude-opus-5*"
+///     provider: claude
+/// models:
+///   claude: [claude-opus-5]
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ModelRegistryFile {
+    /// Pattern routes, checked in order.
+    #[serde(default)]
+    pub routes: Vec<ModelRoute>,
+    /// Models advertised per provider. Each also routes to its provider.
+    #[serde(default)]
+    pub models: HashMap<ProviderId, Vec<String>>,
+}
+
+impl ModelRegistryFile {
+    /// Parses a model registry from a YAML string.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`figment::Error`] if the YAML is invalid.
+    #[allow(clippy::result_large_err)]
+    pub fn from_yaml(yaml: &str) -> Result<Self, figment::Error> {
+        use figment::{
+            Figment,
+            providers::{Format as _, Yaml},
+        };
+        Figment::from(Yaml::string(yaml)).extract()
+    }
+
+    /// Loads a model registry from a YAML (or `.json`) file.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`figment::Error`] if the file cannot be read or parsed.
+    #[allow(clippy::result_large_err)]
+    pub fn from_file(path: &std::path::Path) -> Result<Self, figment::Error> {
+        use figment::{
+            Figment,
+            providers::{Format as _, Json, Yaml},
+        };
+        let figment = if path.extension().is_some_and(|e| e == "json") {
+            Figment::from(Json::file(path))
+        } else {
+            Figment::from(Yaml::file(path))
+        };
+        figment.extract()
+    }
+
+    /// Resolves `model` to a provider accepted by `filter`: advertised
+    /// models first, then `routes` in order.
+    #[must_use]
+    pub fn resolve_provider_with<F>(&self, model: &str, filter: F) -> Option<ProviderId>
+    where
+        F: Fn(&ProviderId) -> bool,
+    {
+        // Several providers may list the same model; `ProviderId::all()`
+        // order makes the pick deterministic.
+        ProviderId::all()
+            .iter()
+            .find(|provider| {
+                filter(provider)
+                    && self
+                        .models
+                        .get(provider)
+                        .is_some_and(|models| models.iter().any(|m| m == model))
+            })
+            .or_else(|| {
+                self.routes
+                    .iter()
+                    .find(|r| filter(&r.provider) && super::glob_match(&r.pattern, model))
+                    .map(|r| &r.provider)
+            })
+            .cloned()
+    }
+}
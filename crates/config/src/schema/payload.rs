@@ -16,6 +16,20 @@ pub struct PayloadRules {
     pub filter: Vec<PayloadFilterRule>,
 }
 
+/// Rules for modifying response bodies based on model patterns.
+///
+/// Uses the same `default` / `override` / `filter` sections as
+/// [`PayloadRules`], applied to non-streaming responses.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ResponsePayloadRules {
+    /// The rule sections.
+    #[serde(flatten)]
+    pub rules: PayloadRules,
+    /// Also apply the rules to every streamed `chat.completion.chunk`.
+    #[serde(default)]
+    pub streaming: bool,
+}
+
 /// A rule that sets or overrides JSON fields for matching models.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PayloadRule {
@@ -23,6 +37,21 @@ pub struct PayloadRule {
     pub models: Vec<String>,
     /// JSON path → value pairs to set.
     pub params: HashMap<String, serde_json::Value>,
+    /// JSON path → value pairs the body must contain for the rule to apply.
+    /// A `null` value requires the path to be absent (or `null`). Empty means
+    /// the rule applies unconditionally.
+    #[serde(default)]
+    pub when: HashMap<String, serde_json::Value>,
+}
+
+impl PayloadRule {
+    /// Returns `true` if every `when` condition holds for `body`.
+    fn condition_holds(&self, body: &serde_json::Value) -> bool {
+        self.when.iter().all(|(path, expected)| {
+            let actual = dot_path_get(body, path).unwrap_or(&serde_json::Value::Null);
+            actual == expected
+        })
+    }
 }
 
 /// A rule that removes JSON fields for matching models.
@@ -34,94 +63,240 @@ pub struct PayloadFilterRule {
     pub params: Vec<String>,
 }
 
-impl Config {
-    /// Applies payload rules (default, override, filter) to a request body.
+/// Which section of [`PayloadRules`] a traced rule came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PayloadRuleKind {
+    Default,
+    Override,
+    Filter,
+}
+
+/// A payload rule that matched the model during
+/// [`Config::apply_payload_rules_traced`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AppliedPayloadRule {
+    /// Section the rule belongs to.
+    pub kind: PayloadRuleKind,
+    /// Position of the rule within its section.
+    pub index: usize,
+    /// The model pattern that matched.
+    pub pattern: String,
+    /// JSON paths the rule actually set or removed. A `default` rule whose
+    /// paths were all present already reports an empty list.
+    pub params: Vec<String>,
+}
+
+impl PayloadRules {
+    /// Returns `true` if no rule is configured in any section.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.default.is_empty() && self.r#override.is_empty() && self.filter.is_empty()
+    }
+
+    /// Applies the rules to `body`, reporting every rule whose model patterns
+    /// (and `when` conditions) matched, in application order.
     ///
     /// - `default` rules: set a value only if the path does not already exist.
     /// - `override` rules: always set the value, replacing existing.
     /// - `filter` rules: remove the specified paths.
     #[must_use]
-    pub fn apply_payload_rules(
+    pub fn apply_traced(
         &self,
         mut body: serde_json::Value,
         model: &str,
-    ) -> serde_json::Value {
+    ) -> (serde_json::Value, Vec<AppliedPayloadRule>) {
+        let mut trace = Vec::new();
+
         // Apply default rules: only set if missing.
-        for rule in &self.payload.default {
-            if rule.models.iter().any(|pat| glob_match(pat, model)) {
-                for (path, value) in &rule.params {
-                    if dot_path_get(&body, path).is_none() {
-                        dot_path_set(&mut body, path, value.clone());
-                    }
+        for (index, rule) in self.default.iter().enumerate() {
+            let Some(pattern) = matching_pattern(&rule.models, model) else {
+                continue;
+            };
+            if !rule.condition_holds(&body) {
+                continue;
+            }
+            let mut params = Vec::new();
+            for (path, value) in &rule.params {
+                if dot_path_get(&body, path).is_none() {
+                    dot_path_set(&mut body, path, value.clone());
+                    params.push(path.clone());
                 }
             }
+            trace.push(AppliedPayloadRule {
+                kind: PayloadRuleKind::Default,
+                index,
+                pattern: pattern.to_string(),
+                params,
+            });
         }
 
         // Apply override rules: always set.
-        for rule in &self.payload.r#override {
-            if rule.models.iter().any(|pat| glob_match(pat, model)) {
-                for (path, value) in &rule.params {
-                    dot_path_set(&mut body, path, value.clone());
-                }
+        for (index, rule) in self.r#override.iter().enumerate() {
+            let Some(pattern) = matching_pattern(&rule.models, model) else {
+                continue;
+            };
+            if !rule.condition_holds(&body) {
+                continue;
             }
+            for (path, value) in &rule.params {
+                dot_path_set(&mut body, path, value.clone());
+            }
+            trace.push(AppliedPayloadRule {
+                kind: PayloadRuleKind::Override,
+                index,
+                pattern: pattern.to_string(),
+                params: rule.params.keys().cloned().collect(),
+            });
         }
 
         // Apply filter rules: remove paths.
-        for rule in &self.payload.filter {
-            if rule.models.iter().any(|pat| glob_match(pat, model)) {
-                for path in &rule.params {
-                    dot_path_remove(&mut body, path);
-                }
+        for (index, rule) in self.filter.iter().enumerate() {
+            let Some(pattern) = matching_pattern(&rule.models, model) else {
+                continue;
+            };
+            for path in &rule.params {
+                dot_path_remove(&mut body, path);
             }
+            trace.push(AppliedPayloadRule {
+                kind: PayloadRuleKind::Filter,
+                index,
+                pattern: pattern.to_string(),
+                params: rule.params.clone(),
+            });
         }
 
-        body
+        (body, trace)
+    }
+}
+
+impl Config {
+    /// Applies payload rules (default, override, filter) to a request body.
+    ///
+    /// - `default` rules: set a value only if the path does not already exist.
+    /// - `override` rules: always set the value, replacing existing.
+    /// - `filter` rules: remove the specified paths.
+    #[must_use]
+    pub fn apply_payload_rules(&self, body: serde_json::Value, model: &str) -> serde_json::Value {
+        self.payload.apply_traced(body, model).0
+    }
+
+    /// Like [`apply_payload_rules`](Self::apply_payload_rules), but also
+    /// reports every rule whose model patterns matched, in application order.
+    #[must_use]
+    pub fn apply_payload_rules_traced(
+        &self,
+        body: serde_json::Value,
+        model: &str,
+    ) -> (serde_json::Value, Vec<AppliedPayloadRule>) {
+        self.payload.apply_traced(body, model)
+    }
+
+    /// Applies `response_payload` rules to a response body (or stream chunk).
+    #[must_use]
+    pub fn apply_response_payload_rules(
+        &self,
+        body: serde_json::Value,
+        model: &str,
+    ) -> serde_json::Value {
+        self.response_payload.rules.apply_traced(body, model).0
     }
 }
 
-/// Get a value at a dot-separated path (e.g. "a.b.c").
+/// Returns the first pattern in `models` that matches `model`.
+fn matching_pattern<'a>(models: &'a [String], model: &str) -> Option<&'a str> {
+    models
+        .iter()
+        .map(String::as_str)
+        .find(|pat| glob_match(pat, model))
+}
+
+/// Get a value at a dot-separated path (e.g. "a.b.c", "messages.0.role").
+///
+/// A numeric segment indexes into an array; on an object it is a plain key.
 fn dot_path_get<'a>(value: &'a serde_json::Value, path: &str) -> Option<&'a serde_json::Value> {
     let mut current = value;
     for key in path.split('.') {
-        current = current.get(key)?;
+        current = match current {
+            serde_json::Value::Array(arr) => arr.get(key.parse::<usize>().ok()?)?,
+            other => other.get(key)?,
+        };
     }
     Some(current)
 }
 
+/// Returns the child of `current` at `key`, creating it with `make` when it
+/// is missing. Objects gain the key; arrays accept an index equal to their
+/// length, which appends. Indices past the end, non-numeric keys on arrays,
+/// and scalars return `None`.
+fn child_or_insert_with<'a>(
+    current: &'a mut serde_json::Value,
+    key: &str,
+    make: impl FnOnce() -> serde_json::Value,
+) -> Option<&'a mut serde_json::Value> {
+    match current {
+        serde_json::Value::Object(obj) => Some(obj.entry(key).or_insert_with(make)),
+        serde_json::Value::Array(arr) => {
+            let index = key.parse::<usize>().ok()?;
+            if index == arr.len() {
+                arr.push(make());
+            }
+            arr.get_mut(index)
+        }
+        _ => None,
+    }
+}
+
 /// Set a value at a dot-separated path, creating intermediate objects as needed.
-fn dot_path_set(value: &mut serde_json::Value, path: &str, new_val: serde_json::Value) {
+///
+/// A numeric segment on an array replaces the element at that index, or
+/// appends when the index equals the array length. Indices further past the
+/// end, and paths running through a scalar, leave the value unchanged.
+pub(super) fn dot_path_set(value: &mut serde_json::Value, path: &str, new_val: serde_json::Value) {
     let parts: Vec<&str> = path.split('.').collect();
     let mut current = value;
     for &key in &parts[..parts.len() - 1] {
-        if !current.is_object() {
-            return;
-        }
-        let obj = current.as_object_mut().expect("checked is_object");
-        if !obj.contains_key(key) {
-            obj.insert(
-                key.to_string(),
-                serde_json::Value::Object(serde_json::Map::default()),
-            );
+        let make = || serde_json::Value::Object(serde_json::Map::default());
+        match child_or_insert_with(current, key, make) {
+            Some(next) => current = next,
+            None => return,
         }
-        current = obj.get_mut(key).expect("just inserted");
     }
-    if let Some(obj) = current.as_object_mut() {
-        obj.insert(parts[parts.len() - 1].to_string(), new_val);
+    if let Some(slot) =
+        child_or_insert_with(current, parts[parts.len() - 1], || serde_json::Value::Null)
+    {
+        *slot = new_val;
     }
 }
 
 /// Remove a value at a dot-separated path.
-fn dot_path_remove(value: &mut serde_json::Value, path: &str) {
+///
+/// A numeric segment on an array removes that element, shifting later ones
+/// down. Missing paths leave the value unchanged.
+pub(super) fn dot_path_remove(value: &mut serde_json::Value, path: &str) {
     let parts: Vec<&str> = path.split('.').collect();
     let mut current = value;
     for &key in &parts[..parts.len() - 1] {
-        match current.get_mut(key) {
+        let next = match current {
+            serde_json::Value::Array(arr) => key.parse::<usize>().ok().and_then(|i| arr.get_mut(i)),
+            other => other.get_mut(key),
+        };
+        match next {
             Some(next) => current = next,
             None => return,
         }
     }
-    if let Some(obj) = current.as_object_mut() {
-        obj.remove(parts[parts.len() - 1]);
+    let last = parts[parts.len() - 1];
+    match current {
+        serde_json::Value::Object(obj) => {
+            obj.remove(last);
+        }
+        serde_json::Value::Array(arr) => {
+            if let Some(index) = last.parse::<usize>().ok().filter(|&i| i < arr.len()) {
+                arr.remove(index);
+            }
+        }
+        _ => {}
     }
 }
 
@@ -251,6 +426,105 @@ payload:
         assert_eq!(result, body);
     }
 
+    #[test]
+    fn test_apply_payload_rules_traced_reports_matches() {
+        let yaml = r#"
+payload:
+  default:
+    - models: ["claude-*"]
+      params:
+        "max_tokens": 4096
+    - models: ["gemini-*"]
+      params:
+        "top_k": 40
+  override:
+    - models: ["gpt-*", "claude-opus-*"]
+      params:
+        "temperature": 0.2
+  filter:
+    - models: ["*"]
+      params: ["metadata"]
+"#;
+        let c = Config::from_yaml(yaml).unwrap();
+        let body = serde_json::json!({"model": "claude-opus-4-5", "metadata": {}});
+        let (after, trace) = c.apply_payload_rules_traced(body, "claude-opus-4-5");
+
+        assert_eq!(after["max_tokens"], 4096);
+        assert_eq!(after["temperature"], 0.2);
+        assert!(after.get("metadata").is_none());
+        assert_eq!(
+            trace,
+            vec![
+                AppliedPayloadRule {
+                    kind: PayloadRuleKind::Default,
+                    index: 0,
+                    pattern: "claude-*".into(),
+                    params: vec!["max_tokens".into()],
+                },
+                AppliedPayloadRule {
+                    kind: PayloadRuleKind::Override,
+                    index: 0,
+                    pattern: "claude-opus-*".into(),
+                    params: vec!["temperature".into()],
+                },
+                AppliedPayloadRule {
+                    kind: PayloadRuleKind::Filter,
+                    index: 0,
+                    pattern: "*".into(),
+                    params: vec!["metadata".into()],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_apply_payload_rules_traced_default_skipped_when_present() {
+        let yaml = r#"
+payload:
+  default:
+    - models: ["claude-*"]
+      params:
+        "max_tokens": 4096
+"#;
+        let c = Config::from_yaml(yaml).unwrap();
+        let body = serde_json::json!({"max_tokens": 100});
+        let (after, trace) = c.apply_payload_rules_traced(body, "claude-opus-4-5");
+        assert_eq!(after["max_tokens"], 100);
+        assert_eq!(trace.len(), 1);
+        assert!(trace[0].params.is_empty());
+    }
+
+    #[test]
+    fn test_apply_payload_when_condition() {
+        let yaml = r#"
+payload:
+  default:
+    - models: ["gpt-*"]
+      when:
+        stream: true
+      params:
+        "stream_options.include_usage": true
+  override:
+    - models: ["gpt-*"]
+      when:
+        tools: null
+      params:
+        "parallel_tool_calls": false
+"#;
+        let c = Config::from_yaml(yaml).unwrap();
+
+        let streaming = serde_json::json!({"model": "gpt-4o", "stream": true});
+        let result = c.apply_payload_rules(streaming, "gpt-4o");
+        assert_eq!(result["stream_options"]["include_usage"], true);
+        assert_eq!(result["parallel_tool_calls"], false);
+
+        let blocking = serde_json::json!({"model": "gpt-4o", "stream": false, "tools": []});
+        let (result, trace) = c.apply_payload_rules_traced(blocking, "gpt-4o");
+        assert!(result.get("stream_options").is_none());
+        assert!(result.get("parallel_tool_calls").is_none());
+        assert!(trace.is_empty());
+    }
+
     #[test]
     fn test_dot_path_helpers() {
         let val = serde_json::json!({"a": {"b": {"c": 42}}});
@@ -267,4 +541,85 @@ payload:
         assert!(val3["a"].as_object().unwrap().get("b").is_none());
         assert_eq!(val3["a"]["c"], 2);
     }
+
+    #[test]
+    fn test_dot_path_array_indices() {
+        let val = serde_json::json!({
+            "messages": [{"role": "system"}, {"role": "user", "content": "hi"}]
+        });
+        assert_eq!(
+            dot_path_get(&val, "messages.1.role"),
+            Some(&serde_json::json!("user"))
+        );
+        assert!(dot_path_get(&val, "messages.2.role").is_none());
+        assert!(dot_path_get(&val, "messages.x").is_none());
+
+        let mut set = val.clone();
+        dot_path_set(&mut set, "messages.0.role", serde_json::json!("developer"));
+        dot_path_set(
+            &mut set,
+            "messages.1",
+            serde_json::json!({"role": "assistant"}),
+        );
+        assert_eq!(set["messages"][0]["role"], "developer");
+        assert_eq!(set["messages"][1], serde_json::json!({"role": "assistant"}));
+
+        // Index == len appends; further past the end is a no-op.
+        dot_path_set(&mut set, "messages.2.role", serde_json::json!("user"));
+        assert_eq!(set["messages"][2], serde_json::json!({"role": "user"}));
+        dot_path_set(&mut set, "messages.9", serde_json::json!({}));
+        assert_eq!(set["messages"].as_array().unwrap().len(), 3);
+
+        let mut removed = val.clone();
+        dot_path_remove(&mut removed, "messages.1.content");
+        assert!(removed["messages"][1].get("content").is_none());
+        dot_path_remove(&mut removed, "messages.0");
+        assert_eq!(removed["messages"], serde_json::json!([{"role": "user"}]));
+        dot_path_remove(&mut removed, "messages.5");
+        assert_eq!(removed["messages"].as_array().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_dot_path_index_on_non_array() {
+        // "model" is a string, so indexing through it does nothing.
+        let mut val = serde_json::json!({"model": "gpt-4o", "meta": {"0": "key"}});
+        assert!(dot_path_get(&val, "model.0").is_none());
+        dot_path_set(&mut val, "model.0.x", serde_json::json!(1));
+        dot_path_remove(&mut val, "model.0");
+        assert_eq!(val["model"], "gpt-4o");
+
+        // On an object a numeric segment is an ordinary key.
+        assert_eq!(
+            dot_path_get(&val, "meta.0"),
+            Some(&serde_json::json!("key"))
+        );
+        dot_path_remove(&mut val, "meta.0");
+        assert_eq!(val["meta"], serde_json::json!({}));
+    }
+
+    #[test]
+    fn test_response_filter_removes_field() {
+        let yaml = r#"
+response_payload:
+  filter:
+    - models: ["gpt-*"]
+      params: ["system_fingerprint"]
+"#;
+        let c = Config::from_yaml(yaml).unwrap();
+        assert!(c.payload.is_empty());
+        assert!(!c.response_payload.rules.is_empty());
+        assert!(!c.response_payload.streaming);
+
+        let resp = serde_json::json!({
+            "object": "chat.completion",
+            "system_fingerprint": "fp_abc",
+            "choices": []
+        });
+        let out = c.apply_response_payload_rules(resp.clone(), "gpt-4o");
+        assert!(out.get("system_fingerprint").is_none());
+        assert_eq!(out["object"], "chat.completion");
+
+        let untouched = c.apply_response_payload_rules(resp, "claude-opus-4-5");
+        assert_eq!(untouched["system_fingerprint"], "fp_abc");
+    }
 }
@@ -1,10 +1,18 @@
 use byokey_types::ProviderId;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Default for [`ProviderConfig::cache_min_chars`].
+pub const DEFAULT_CACHE_MIN_CHARS: usize = 1024;
 
 fn default_true() -> bool {
     true
 }
 
+fn default_cache_min_chars() -> usize {
+    DEFAULT_CACHE_MIN_CHARS
+}
+
 /// Configuration for a single API key entry within a provider.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ApiKeyEntry {
@@ -17,6 +25,13 @@ pub struct ApiKeyEntry {
     /// Enables using different endpoints per key, e.g. official API + third-party proxy.
     #[serde(default)]
     pub base_url: Option<String>,
+    /// Relative share of round-robin traffic this key receives (defaults to 1).
+    #[serde(default = "default_weight")]
+    pub weight: u32,
+}
+
+fn default_weight() -> u32 {
+    1
 }
 
 /// Default header values injected into Claude API requests.
@@ -61,6 +76,60 @@ pub struct CodexHeaderDefaults {
     pub beta_features: Option<String>,
 }
 
+/// Circuit breaker in front of a provider that has a `fallback`.
+///
+/// After `failure_threshold` consecutive failures within `window_secs`, the
+/// primary is skipped for `open_secs` and requests go straight to the
+/// fallback; the next request after that probes the primary again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CircuitBreakerConfig {
+    /// Consecutive failures that open the breaker (`0` disables it).
+    pub failure_threshold: u32,
+    /// Window in which the failures must occur, in seconds.
+    pub window_secs: u64,
+    /// How long the breaker stays open before probing, in seconds.
+    pub open_secs: u64,
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 5,
+            window_secs: 60,
+            open_secs: 30,
+        }
+    }
+}
+
+/// Backoff between credential retries.
+///
+/// An upstream `Retry-After` is only waited out when no other key is ready;
+/// one longer than `max_retry_after_secs` ends the retries instead.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetryBackoffConfig {
+    /// Delay before the first retry, in milliseconds.
+    pub base_delay_ms: u64,
+    /// Upper bound on the computed delay, in milliseconds.
+    pub max_delay_ms: u64,
+    /// Factor applied to the delay on each further retry.
+    pub multiplier: f64,
+    /// Longest upstream `Retry-After` worth waiting for, in seconds.
+    pub max_retry_after_secs: u64,
+}
+
+impl Default for RetryBackoffConfig {
+    fn default() -> Self {
+        Self {
+            base_delay_ms: 250,
+            max_delay_ms: 8000,
+            multiplier: 2.0,
+            max_retry_after_secs: 10,
+        }
+    }
+}
+
 /// Strategy for selecting among multiple API keys.
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -139,9 +208,19 @@ pub struct ProviderConfig {
     /// Fallback provider to use when the primary provider fails.
     #[serde(default)]
     pub fallback: Option<ProviderId>,
+    /// Skip a repeatedly failing primary and go straight to `fallback`.
+    #[serde(default)]
+    pub circuit_breaker: CircuitBreakerConfig,
     /// Maximum number of credentials to try before giving up.
     #[serde(default)]
     pub max_retry_credentials: Option<usize>,
+    /// Randomise the backoff between credential retries ("full jitter") so
+    /// concurrent requests don't retry in lockstep (defaults to `true`).
+    #[serde(default = "default_true")]
+    pub retry_jitter: bool,
+    /// Backoff between credential retries.
+    #[serde(default)]
+    pub retry_backoff: RetryBackoffConfig,
     /// Default headers for Claude API requests.
     #[serde(default)]
     pub claude_headers: ClaudeHeaderDefaults,
@@ -154,6 +233,33 @@ pub struct ProviderConfig {
     /// Use WebSocket transport instead of HTTP (currently Codex only).
     #[serde(default)]
     pub websocket: bool,
+    /// Extra model-name prefix routed to this provider, stripped before
+    /// dispatch (currently `custom` only; `custom/` always works).
+    #[serde(default)]
+    pub model_prefix: Option<String>,
+    /// Ceiling on output tokens: a larger `max_tokens` /
+    /// `max_completion_tokens` from the client is clamped down to this.
+    #[serde(default)]
+    pub max_output_tokens: Option<u64>,
+    /// Per-model output token ceilings (glob patterns allowed), taking
+    /// precedence over `max_output_tokens`.
+    #[serde(default)]
+    pub model_max_output_tokens: HashMap<String, u64>,
+    /// Seconds one upstream attempt may take before failing with 504:
+    /// non-streaming calls must complete, streaming calls must deliver their
+    /// first byte. Applies to each key and to the fallback separately, so a
+    /// stalled attempt moves on to the next one. Unset means no timeout.
+    #[serde(default)]
+    pub timeout_seconds: Option<u64>,
+    /// Maximum concurrent upstream requests to this provider. Further
+    /// requests queue briefly, then fail with 503. Unset or 0 means unlimited.
+    #[serde(default)]
+    pub max_concurrency: Option<usize>,
+    /// Claude only: prompt-caching breakpoints are dropped from a system
+    /// prompt or last user turn shorter than this many characters, so cache
+    /// writes are spent on large, stable blocks only. `0` keeps them all.
+    #[serde(default = "default_cache_min_chars")]
+    pub cache_min_chars: usize,
 }
 
 impl Default for ProviderConfig {
@@ -166,11 +272,20 @@ impl Default for ProviderConfig {
             routing: KeyRoutingStrategy::default(),
             backend: None,
             fallback: None,
+            circuit_breaker: CircuitBreakerConfig::default(),
             max_retry_credentials: None,
+            retry_jitter: true,
+            retry_backoff: RetryBackoffConfig::default(),
             claude_headers: ClaudeHeaderDefaults::default(),
             codex_headers: CodexHeaderDefaults::default(),
             cloak: CloakConfig::default(),
             websocket: false,
+            model_prefix: None,
+            max_output_tokens: None,
+            model_max_output_tokens: HashMap::new(),
+            timeout_seconds: None,
+            max_concurrency: None,
+            cache_min_chars: DEFAULT_CACHE_MIN_CHARS,
         }
     }
 }
@@ -190,6 +305,23 @@ impl ProviderConfig {
         keys
     }
 
+    /// Per-attempt upstream timeout from `timeout_seconds`, if set.
+    #[must_use]
+    pub fn request_timeout(&self) -> Option<std::time::Duration> {
+        self.timeout_seconds.map(std::time::Duration::from_secs)
+    }
+
+    /// Returns the round-robin weight of each key, parallel to
+    /// [`all_api_keys`](Self::all_api_keys). The single `api_key` weighs 1.
+    #[must_use]
+    pub fn all_api_key_weights(&self) -> Vec<u32> {
+        let single = self.api_key.as_ref().map(|_| 1);
+        single
+            .into_iter()
+            .chain(self.api_keys.iter().map(|e| e.weight))
+            .collect()
+    }
+
     /// Returns all configured API keys with their resolved base URLs.
     ///
     /// Each entry is `(api_key, base_url)`. Per-key `base_url` takes precedence
@@ -300,6 +432,25 @@ providers:
         assert!(claude.api_keys[1].label.is_none());
     }
 
+    #[test]
+    fn test_from_yaml_retry_backoff() {
+        let yaml = r"
+providers:
+  claude:
+    retry_backoff:
+      base_delay_ms: 100
+      max_retry_after_secs: 3
+";
+        let c = Config::from_yaml(yaml).unwrap();
+        let backoff = c.providers[&ProviderId::Claude].retry_backoff;
+        assert_eq!(backoff.base_delay_ms, 100);
+        assert_eq!(backoff.max_retry_after_secs, 3);
+        assert_eq!(
+            backoff.max_delay_ms,
+            RetryBackoffConfig::default().max_delay_ms
+        );
+    }
+
     #[test]
     fn test_all_api_keys_merges() {
         let pc = ProviderConfig {
@@ -309,11 +460,13 @@ providers:
                     api_key: "multi-1".into(),
                     label: None,
                     base_url: None,
+                    weight: 1,
                 },
                 ApiKeyEntry {
                     api_key: "multi-2".into(),
                     label: None,
                     base_url: None,
+                    weight: 1,
                 },
             ],
             ..Default::default()
@@ -322,6 +475,24 @@ providers:
         assert_eq!(keys, vec!["single-key", "multi-1", "multi-2"]);
     }
 
+    #[test]
+    fn test_api_key_weights_default_to_one() {
+        let config = Config::from_yaml(
+            r#"
+providers:
+  claude:
+    api_key: "single"
+    api_keys:
+      - api_key: "a"
+        weight: 3
+      - api_key: "b"
+"#,
+        )
+        .unwrap();
+        let pc = &config.providers[&ProviderId::Claude];
+        assert_eq!(pc.all_api_key_weights(), vec![1, 3, 1]);
+    }
+
     #[test]
     fn test_all_api_keys_empty() {
         let pc = ProviderConfig::default();
@@ -1,4 +1,6 @@
+use super::{Config, glob_match};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 fn default_keepalive_seconds() -> u64 {
     15
@@ -13,7 +15,8 @@ fn default_nonstream_keepalive_interval() -> u64 {
 /// Streaming SSE configuration.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StreamingConfig {
-    /// SSE keepalive interval in seconds (sends empty comment lines).
+    /// SSE keepalive interval in seconds: a `: keepalive` comment is sent
+    /// after this long without data (`0` disables).
     #[serde(default = "default_keepalive_seconds")]
     pub keepalive_seconds: u64,
     /// Number of retries before the first byte arrives.
@@ -22,6 +25,54 @@ pub struct StreamingConfig {
     /// Non-streaming request keepalive interval in seconds.
     #[serde(default = "default_nonstream_keepalive_interval")]
     pub nonstream_keepalive_interval: u64,
+    /// Rewrites applied to every OpenAI-format SSE chunk sent to clients.
+    #[serde(default)]
+    pub chunk_rules: Vec<ChunkRule>,
+    /// Send `x-byokey-finish-reason` and token-count HTTP trailers after
+    /// streamed chat completions. Off by default; clients must also send
+    /// `TE: trailers` to receive them.
+    #[serde(default)]
+    pub trailers: bool,
+}
+
+/// A set/remove rewrite applied to each streamed `chat.completion.chunk`.
+///
+/// Paths use the same dot syntax as payload rules. A string value of
+/// `"${model}"` is replaced with the requested model name.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ChunkRule {
+    /// Model name patterns (glob with `*`). Empty matches every model.
+    #[serde(default)]
+    pub models: Vec<String>,
+    /// JSON path → value pairs to set on each chunk.
+    #[serde(default)]
+    pub set: HashMap<String, serde_json::Value>,
+    /// JSON paths to remove from each chunk.
+    #[serde(default)]
+    pub remove: Vec<String>,
+}
+
+impl Config {
+    /// Applies `streaming.chunk_rules` matching `model` to a single SSE chunk.
+    pub fn apply_chunk_rules(&self, chunk: &mut serde_json::Value, model: &str) {
+        for rule in &self.streaming.chunk_rules {
+            if !rule.models.is_empty() && !rule.models.iter().any(|pat| glob_match(pat, model)) {
+                continue;
+            }
+            for (path, value) in &rule.set {
+                let value = match value {
+                    serde_json::Value::String(s) if s == "${model}" => {
+                        serde_json::Value::String(model.to_string())
+                    }
+                    other => other.clone(),
+                };
+                super::payload::dot_path_set(chunk, path, value);
+            }
+            for path in &rule.remove {
+                super::payload::dot_path_remove(chunk, path);
+            }
+        }
+    }
 }
 
 impl Default for StreamingConfig {
@@ -30,10 +81,25 @@ impl Default for StreamingConfig {
             keepalive_seconds: default_keepalive_seconds(),
             bootstrap_retries: default_bootstrap_retries(),
             nonstream_keepalive_interval: default_nonstream_keepalive_interval(),
+            chunk_rules: Vec::new(),
+            trailers: false,
         }
     }
 }
 
+/// HTTP protocol version used for upstream connections.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum UpstreamHttpVersion {
+    /// Negotiate via ALPN (HTTP/2 when the upstream offers it).
+    #[default]
+    Auto,
+    /// Force HTTP/1.1.
+    Http1,
+    /// Force HTTP/2.
+    Http2,
+}
+
 /// Output format for structured logs.
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
@@ -56,22 +122,90 @@ pub struct LogConfig {
     /// Log level override (default: "info"). Overridden by `RUST_LOG` env var.
     #[serde(default = "default_log_level")]
     pub level: String,
+    /// Longest request/response body, in bytes, written to logs; longer
+    /// bodies are truncated with an ellipsis. `0` logs bodies in full.
+    #[serde(default = "default_max_body_bytes")]
+    pub max_body_bytes: usize,
+    /// Log the exact body sent to each provider and its raw response at
+    /// DEBUG level (truncated to `max_body_bytes`), with credential headers
+    /// redacted. Meant for debugging request translation.
+    #[serde(default)]
+    pub request_bodies: bool,
+}
+
+impl LogConfig {
+    /// Byte cap for upstream body logging, or `None` when it is disabled.
+    #[must_use]
+    pub fn body_log_limit(&self) -> Option<usize> {
+        self.request_bodies.then_some(self.max_body_bytes)
+    }
 }
 
 fn default_log_level() -> String {
     "info".to_string()
 }
 
+fn default_max_body_bytes() -> usize {
+    8 * 1024
+}
+
 impl Default for LogConfig {
     fn default() -> Self {
         Self {
             format: LogFormat::default(),
             file: None,
             level: default_log_level(),
+            max_body_bytes: default_max_body_bytes(),
+            request_bodies: false,
         }
     }
 }
 
+fn default_true() -> bool {
+    true
+}
+fn default_usage_flush_interval_secs() -> u64 {
+    60
+}
+
+/// Persistence of the in-memory usage counters across restarts.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageConfig {
+    /// Write the usage snapshot to disk and reload it on startup
+    /// (defaults to `true`).
+    #[serde(default = "default_true")]
+    pub persist: bool,
+    /// Snapshot file path (defaults to `~/.byokey/usage.json`).
+    #[serde(default)]
+    pub file: Option<String>,
+    /// Seconds between snapshot writes (defaults to 60). A final snapshot
+    /// is always written on graceful shutdown.
+    #[serde(default = "default_usage_flush_interval_secs")]
+    pub flush_interval_secs: u64,
+}
+
+impl Default for UsageConfig {
+    fn default() -> Self {
+        Self {
+            persist: true,
+            file: None,
+            flush_interval_secs: default_usage_flush_interval_secs(),
+        }
+    }
+}
+
+/// Per-request cap on upstream attempts across key rotation, provider
+/// fallback and provider-internal account rotation. `0` means unlimited.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetryBudgetConfig {
+    /// Total upstream calls one client request may make, including the
+    /// first.
+    pub max_attempts: u32,
+    /// Seconds after which no further retry is started.
+    pub max_elapsed_secs: u64,
+}
+
 fn default_telemetry_sample_rate() -> f32 {
     1.0
 }
@@ -157,6 +291,31 @@ streaming:
         assert_eq!(c.streaming.nonstream_keepalive_interval, 30);
     }
 
+    #[test]
+    fn test_chunk_rule_sets_model_on_every_chunk() {
+        let yaml = r#"
+streaming:
+  chunk_rules:
+    - set:
+        model: "${model}"
+      remove: ["system_fingerprint"]
+    - models: ["gpt-*"]
+      set:
+        "choices.0.logprobs": null
+"#;
+        let c = Config::from_yaml(yaml).unwrap();
+        assert_eq!(c.streaming.chunk_rules.len(), 2);
+
+        for mut chunk in [
+            serde_json::json!({"object": "chat.completion.chunk", "system_fingerprint": "fp"}),
+            serde_json::json!({"object": "chat.completion.chunk", "model": ""}),
+        ] {
+            c.apply_chunk_rules(&mut chunk, "claude-opus-4-5");
+            assert_eq!(chunk["model"], "claude-opus-4-5");
+            assert!(chunk.get("system_fingerprint").is_none());
+        }
+    }
+
     #[test]
     fn test_default_log_config() {
         let c = Config::default();
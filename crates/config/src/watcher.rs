@@ -1,26 +1,113 @@
-use crate::schema::Config;
-use arc_swap::ArcSwap;
-use std::{path::PathBuf, sync::Arc};
+use crate::schema::{Config, ModelRegistryFile};
+use arc_swap::{ArcSwap, ArcSwapOption};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
 
-/// Watches a configuration file for changes and hot-reloads on modification.
+/// Delay between attempts in [`ConfigWatcher::new_with_retry`].
+const RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// Quiet period after a file event before reloading, so the burst of events
+/// an editor emits for one save triggers a single reload.
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// How often the idle watch task checks whether its watcher was dropped.
+const OWNER_POLL: Duration = Duration::from_secs(1);
+
+/// Watches a configuration file (and the `models_file` it names) for changes
+/// and hot-reloads on modification.
 pub struct ConfigWatcher {
     /// Current configuration, atomically swappable.
     current: Arc<ArcSwap<Config>>,
+    /// Current user model registry, `None` when no `models_file` is set.
+    models: Arc<ArcSwapOption<ModelRegistryFile>>,
     /// Path to the configuration file.
     path: PathBuf,
 }
 
+/// Resolves `models_file` from `config`, relative to the config file's directory.
+fn models_path(config: &Config, config_path: &Path) -> Option<PathBuf> {
+    let file = config.models_file.as_ref()?;
+    Some(match config_path.parent() {
+        Some(dir) if file.is_relative() => dir.join(file),
+        _ => file.clone(),
+    })
+}
+
+/// Loads `config_path` and the model registry it names.
+#[allow(clippy::result_large_err)]
+fn load(config_path: &Path) -> Result<(Config, Option<ModelRegistryFile>), figment::Error> {
+    let config = Config::from_file(config_path)?;
+    let models = models_path(&config, config_path)
+        .map(|path| {
+            if !path.exists() {
+                return Err(figment::Error::from(format!(
+                    "models_file {} does not exist",
+                    path.display()
+                )));
+            }
+            ModelRegistryFile::from_file(&path)
+        })
+        .transpose()?;
+    Ok((config, models))
+}
+
 impl ConfigWatcher {
-    /// Creates a new watcher from a file path, loading the initial configuration immediately.
+    /// Creates a new watcher from a file path, loading the initial configuration
+    /// (and its `models_file`) immediately.
     ///
     /// # Errors
     ///
-    /// Returns a [`figment::Error`] if the configuration file cannot be read or parsed.
+    /// Returns a [`figment::Error`] if the configuration or model registry file
+    /// cannot be read or parsed.
     #[allow(clippy::result_large_err)]
     pub fn new(path: PathBuf) -> Result<Self, figment::Error> {
-        let config = Config::from_file(&path)?;
+        let (config, models) = load(&path)?;
         Ok(Self {
             current: Arc::new(ArcSwap::from_pointee(config)),
+            models: Arc::new(ArcSwapOption::from(models.map(Arc::new))),
+            path,
+        })
+    }
+
+    /// Like [`new`](Self::new), but while the file cannot be accessed retries
+    /// up to `attempts` times, one second apart, then falls back to the
+    /// default configuration with a warning.
+    ///
+    /// Suits autostart at boot, when the file may sit on a mount that is not
+    /// ready yet. [`reload`](Self::reload) picks up the file once it appears.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`figment::Error`] without retrying if the file exists but
+    /// cannot be parsed, references an unset environment variable, or names a
+    /// `models_file` that cannot be loaded.
+    #[allow(clippy::result_large_err)]
+    pub async fn new_with_retry(path: PathBuf, attempts: u32) -> Result<Self, figment::Error> {
+        let attempts = attempts.max(1);
+        for attempt in 1..=attempts {
+            match std::fs::metadata(&path) {
+                Ok(_) => return Self::new(path),
+                Err(e) if attempt < attempts => {
+                    tracing::warn!(attempt, error = %e, "config file not accessible, retrying");
+                    tokio::time::sleep(RETRY_DELAY).await;
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        path = %path.display(),
+                        error = %e,
+                        "config file not accessible, using defaults"
+                    );
+                }
+            }
+        }
+        Ok(Self {
+            current: Arc::new(ArcSwap::from_pointee(
+                Config::default().with_env_overrides(),
+            )),
+            models: Arc::new(ArcSwapOption::empty()),
             path,
         })
     }
@@ -37,20 +124,34 @@ impl ConfigWatcher {
         Arc::clone(&self.current)
     }
 
-    /// Manually reloads the configuration from disk.
+    /// Returns a shareable handle to the user model registry loaded from
+    /// `models_file`, swapped on every reload.
+    #[must_use]
+    pub fn models(&self) -> Arc<ArcSwapOption<ModelRegistryFile>> {
+        Arc::clone(&self.models)
+    }
+
+    /// Manually reloads the configuration and its model registry from disk.
+    ///
+    /// Nothing is swapped unless both files load.
     ///
     /// # Errors
     ///
-    /// Returns a [`figment::Error`] if the configuration file cannot be read or parsed.
+    /// Returns a [`figment::Error`] if the configuration or model registry file
+    /// cannot be read or parsed.
     #[allow(clippy::result_large_err)]
     pub fn reload(&self) -> Result<(), figment::Error> {
-        let new_config = Config::from_file(&self.path)?;
+        let (new_config, models) = load(&self.path)?;
         self.current.store(Arc::new(new_config));
+        self.models.store(models.map(Arc::new));
         Ok(())
     }
 
     /// Starts background file watching (spawns a tokio task) that automatically
-    /// reloads the configuration when the file changes.
+    /// reloads the configuration when the file, or the `models_file` it names,
+    /// changes.
+    ///
+    /// The task ends once every other handle to the watcher is dropped.
     ///
     /// # Panics
     ///
@@ -58,8 +159,10 @@ impl ConfigWatcher {
     /// cannot be registered for watching.
     pub fn watch(self: Arc<Self>) {
         use notify::{RecursiveMode, Watcher as _};
-        let watcher_self = Arc::clone(&self);
+        use std::sync::mpsc::RecvTimeoutError;
         let path = self.path.clone();
+        let weak = Arc::downgrade(&self);
+        drop(self);
 
         tokio::task::spawn_blocking(move || {
             let (tx, rx) = std::sync::mpsc::channel();
@@ -74,12 +177,46 @@ impl ConfigWatcher {
             watcher
                 .watch(&path, RecursiveMode::NonRecursive)
                 .expect("failed to watch config file");
+            let mut models_watched = None;
+            let mut watch_models = |watcher: &mut notify::RecommendedWatcher, this: &Self| {
+                let models = models_path(&this.load(), &path);
+                if models == models_watched {
+                    return;
+                }
+                if let Some(old) = models_watched.take() {
+                    let _ = watcher.unwatch(&old);
+                }
+                if let Some(new) = models {
+                    match watcher.watch(&new, RecursiveMode::NonRecursive) {
+                        Ok(()) => models_watched = Some(new),
+                        Err(e) => tracing::warn!(
+                            path = %new.display(),
+                            error = %e,
+                            "failed to watch models file"
+                        ),
+                    }
+                }
+            };
+            match weak.upgrade() {
+                Some(this) => watch_models(&mut watcher, &this),
+                None => return,
+            }
 
-            for () in rx {
-                match watcher_self.reload() {
+            loop {
+                match rx.recv_timeout(OWNER_POLL) {
+                    Ok(()) => {}
+                    Err(RecvTimeoutError::Timeout) if weak.strong_count() > 0 => continue,
+                    Err(_) => return,
+                }
+                while rx.recv_timeout(DEBOUNCE).is_ok() {}
+                let Some(this) = weak.upgrade() else {
+                    return;
+                };
+                match this.reload() {
                     Ok(()) => tracing::info!("configuration reloaded"),
                     Err(e) => tracing::warn!(error = %e, "config reload failed"),
                 }
+                watch_models(&mut watcher, &this);
             }
         });
     }
@@ -126,4 +263,74 @@ mod tests {
         let arc = watcher.arc();
         assert_eq!(arc.load().port, 1111);
     }
+
+    #[tokio::test]
+    async fn test_watch_reloads_models_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.yaml");
+        let models = dir.path().join("models.yaml");
+        write_config(&path, "models_file: models.yaml\n");
+        write_config(&models, "models:\n  claude: [claude-opus-5]\n");
+        let watcher = Arc::new(ConfigWatcher::new(path).unwrap());
+        let handle = watcher.models();
+        let resolve = || {
+            handle
+                .load()
+                .as_ref()
+                .and_then(|m| m.resolve_provider_with("claude-opus-5", |_| true))
+        };
+        assert_eq!(resolve(), Some(byokey_types::ProviderId::Claude));
+
+        Arc::clone(&watcher).watch();
+        // Let the background task register its watches before editing.
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        write_config(&models, "models:\n  codex: [claude-opus-5]\n");
+
+        let deadline = tokio::time::Instant::now() + DEBOUNCE + Duration::from_secs(5);
+        while resolve() != Some(byokey_types::ProviderId::Codex) {
+            assert!(
+                tokio::time::Instant::now() < deadline,
+                "models file edit was not picked up"
+            );
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_new_with_retry_waits_for_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.yaml");
+        let late = path.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(1500)).await;
+            write_config(&late, "port: 4242\n");
+        });
+        let watcher = ConfigWatcher::new_with_retry(path, 5).await.unwrap();
+        assert_eq!(watcher.load().port, 4242);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_new_with_retry_falls_back_to_default() {
+        let dir = tempfile::tempdir().unwrap();
+        let watcher = ConfigWatcher::new_with_retry(dir.path().join("missing.yaml"), 3)
+            .await
+            .unwrap();
+        assert_eq!(watcher.load().port, Config::default().port);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_new_with_retry_rejects_invalid_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.yaml");
+        write_config(&path, "port: [not, a, port]\n");
+        assert!(
+            ConfigWatcher::new_with_retry(path.clone(), 3)
+                .await
+                .is_err()
+        );
+
+        write_config(&path, "models_file: missing-models.yaml\n");
+        let err = ConfigWatcher::new_with_retry(path, 3).await.err().unwrap();
+        assert!(err.to_string().contains("missing-models.yaml"), "{err}");
+    }
 }
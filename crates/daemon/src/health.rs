@@ -0,0 +1,74 @@
+//! Readiness polling shared by background start and service install.
+
+use std::net::{TcpStream, ToSocketAddrs as _};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+const CONNECT_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Calls `probe` every 100 ms until it returns `true` or `timeout` elapses.
+///
+/// Returns whether the probe succeeded in time.
+#[must_use]
+pub fn poll_until(timeout: Duration, mut probe: impl FnMut() -> bool) -> bool {
+    let deadline = Instant::now() + timeout;
+    loop {
+        if probe() {
+            return true;
+        }
+        if Instant::now() >= deadline {
+            return false;
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// Waits until a TCP connection to `host:port` succeeds, up to `timeout`.
+///
+/// Wildcard listen addresses (`0.0.0.0`, `::`) are probed via loopback.
+#[must_use]
+pub fn wait_for_port(host: &str, port: u16, timeout: Duration) -> bool {
+    let host = match host {
+        "0.0.0.0" | "" => "127.0.0.1",
+        "::" | "[::]" => "::1",
+        other => other,
+    };
+    poll_until(timeout, || {
+        (host, port).to_socket_addrs().is_ok_and(|mut addrs| {
+            addrs.any(|addr| TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT).is_ok())
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+
+    #[test]
+    fn wait_for_port_sees_listener() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        assert!(wait_for_port("127.0.0.1", port, Duration::from_secs(2)));
+    }
+
+    #[test]
+    fn wait_for_port_times_out_when_nothing_listens() {
+        let port = {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            listener.local_addr().unwrap().port()
+        };
+        assert!(!wait_for_port("0.0.0.0", port, Duration::from_millis(300)));
+    }
+
+    #[test]
+    fn poll_until_retries_until_probe_succeeds() {
+        let mut calls = 0;
+        assert!(poll_until(Duration::from_secs(2), || {
+            calls += 1;
+            calls == 3
+        }));
+        assert_eq!(calls, 3);
+    }
+}
@@ -2,6 +2,7 @@
 
 pub mod control;
 pub mod error;
+pub mod health;
 pub mod paths;
 pub mod process;
 pub mod service;
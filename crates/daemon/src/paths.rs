@@ -1,33 +1,75 @@
+//! Default on-disk locations for byokey state.
+//!
+//! On macOS and Linux everything lives under `~/.byokey` and
+//! `~/.config/byokey`. Windows has no meaningful dotfile convention, so state
+//! goes to `%LOCALAPPDATA%\byokey` and config to `%APPDATA%\byokey`.
+
 use std::path::PathBuf;
 
 use crate::error::{DaemonError, Result};
 
-/// Return the user's home directory, or error if unset.
+/// Return the user's home directory, or error if it cannot be determined.
 pub fn home_dir() -> Result<PathBuf> {
-    std::env::var("HOME")
-        .map(PathBuf::from)
-        .map_err(|_| DaemonError::NoHomeDir)
+    dirs::home_dir().ok_or(DaemonError::NoHomeDir)
+}
+
+/// Directory holding runtime state: PID file, logs, token database.
+pub fn state_dir() -> Result<PathBuf> {
+    if cfg!(windows) {
+        dirs::data_local_dir()
+            .map(|d| d.join("byokey"))
+            .ok_or(DaemonError::NoHomeDir)
+    } else {
+        Ok(home_dir()?.join(".byokey"))
+    }
+}
+
+/// Directory holding `settings.json`.
+pub fn config_dir() -> Result<PathBuf> {
+    if cfg!(windows) {
+        dirs::config_dir()
+            .map(|d| d.join("byokey"))
+            .ok_or(DaemonError::NoHomeDir)
+    } else {
+        Ok(home_dir()?.join(".config").join("byokey"))
+    }
 }
 
 pub fn pid_path() -> Result<PathBuf> {
-    Ok(home_dir()?.join(".byokey").join("byokey.pid"))
+    Ok(state_dir()?.join("byokey.pid"))
 }
 
 pub fn control_sock_path() -> Result<PathBuf> {
-    Ok(home_dir()?.join(".byokey").join("control.sock"))
+    Ok(state_dir()?.join("control.sock"))
 }
 
 pub fn log_path() -> Result<PathBuf> {
-    Ok(home_dir()?.join(".byokey").join("server.log"))
+    Ok(state_dir()?.join("server.log"))
 }
 
 pub fn config_path() -> Result<PathBuf> {
-    Ok(home_dir()?
-        .join(".config")
-        .join("byokey")
-        .join("settings.json"))
+    Ok(config_dir()?.join("settings.json"))
 }
 
 pub fn db_path() -> Result<PathBuf> {
-    Ok(home_dir()?.join(".byokey").join("tokens.db"))
+    Ok(state_dir()?.join("tokens.db"))
+}
+
+pub fn usage_path() -> Result<PathBuf> {
+    Ok(state_dir()?.join("usage.json"))
+}
+
+#[cfg(all(test, not(windows)))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unix_layout_is_under_home() {
+        let home = home_dir().unwrap();
+        assert_eq!(db_path().unwrap(), home.join(".byokey").join("tokens.db"));
+        assert_eq!(
+            config_path().unwrap(),
+            home.join(".config").join("byokey").join("settings.json")
+        );
+    }
 }
@@ -5,6 +5,7 @@ use std::time::{Duration, Instant};
 
 use crate::control;
 use crate::error::{DaemonError, Result};
+use crate::health;
 use crate::paths;
 
 /// Options for starting the daemon.
@@ -24,8 +25,12 @@ pub struct StartResult {
     pub log_path: PathBuf,
 }
 
-pub struct StopResult {
-    pub pid: Option<u32>,
+pub enum StopResult {
+    /// A live server was shut down.
+    Stopped { pid: u32 },
+    /// Nothing was running. `stale_pid` is set when a leftover PID file
+    /// pointing at a dead process was cleaned up.
+    NotRunning { stale_pid: Option<u32> },
 }
 
 pub enum ServerStatus {
@@ -121,13 +126,7 @@ pub fn start(opts: StartOptions) -> Result<StartResult> {
 const READY_TIMEOUT_SECS: u64 = 10;
 
 fn wait_for_ready(timeout: Duration) {
-    let deadline = Instant::now() + timeout;
-    while Instant::now() < deadline {
-        if control::is_alive() {
-            return;
-        }
-        thread::sleep(Duration::from_millis(100));
-    }
+    let _ = health::poll_until(timeout, control::is_alive);
 }
 
 pub fn stop() -> Result<StopResult> {
@@ -137,32 +136,81 @@ pub fn stop() -> Result<StopResult> {
         control::shutdown()?;
         wait_for_shutdown();
         cleanup_pid_file();
-        return Ok(StopResult { pid: Some(pid) });
+        return Ok(StopResult::Stopped { pid });
     }
 
-    // Fallback: no live server behind the socket. If a pid file exists, try SIGTERM.
+    // Fallback: no live server behind the socket. If the pid file names a
+    // live process, try SIGTERM.
     let pid_path = paths::pid_path()?;
-    let pid_str = std::fs::read_to_string(&pid_path).map_err(|_| DaemonError::NotRunning)?;
-    let pid: u32 = pid_str
-        .trim()
-        .parse()
-        .map_err(|_| DaemonError::MalformedPidFile {
-            raw: pid_str.trim().to_owned(),
-        })?;
+    let contents = std::fs::read_to_string(&pid_path).ok();
+    match plan_stop(contents.as_deref(), pid_alive)? {
+        StopPlan::NotRunning { stale_pid } => {
+            let _ = std::fs::remove_file(&pid_path);
+            Ok(StopResult::NotRunning { stale_pid })
+        }
+        StopPlan::Signal { pid } => {
+            let signalled = std::process::Command::new("kill")
+                .arg(pid.to_string())
+                .status()
+                .is_ok_and(|s| s.success());
+            if !signalled && pid_alive(pid) {
+                return Err(DaemonError::StopFailed { pid });
+            }
+            if !wait_for_exit(pid) {
+                return Err(DaemonError::StopFailed { pid });
+            }
+            let _ = std::fs::remove_file(&pid_path);
+            Ok(StopResult::Stopped { pid })
+        }
+    }
+}
 
-    let ok = std::process::Command::new("kill")
-        .arg(pid.to_string())
-        .status()
-        .is_ok_and(|s| s.success());
-    let _ = std::fs::remove_file(&pid_path);
+/// What the pid-file fallback of [`stop`] should do.
+#[derive(Debug, PartialEq, Eq)]
+enum StopPlan {
+    NotRunning { stale_pid: Option<u32> },
+    Signal { pid: u32 },
+}
 
-    if ok {
-        Ok(StopResult { pid: Some(pid) })
+/// Decides how to stop from the pid file contents (`None` if missing) and a
+/// liveness probe.
+fn plan_stop(pid_file: Option<&str>, alive: impl Fn(u32) -> bool) -> Result<StopPlan> {
+    let Some(raw) = pid_file.map(str::trim) else {
+        return Ok(StopPlan::NotRunning { stale_pid: None });
+    };
+    let pid: u32 = raw.parse().map_err(|_| DaemonError::MalformedPidFile {
+        raw: raw.to_owned(),
+    })?;
+    if pid != 0 && alive(pid) {
+        Ok(StopPlan::Signal { pid })
     } else {
-        Err(DaemonError::StopFailed { pid })
+        Ok(StopPlan::NotRunning {
+            stale_pid: Some(pid),
+        })
     }
 }
 
+/// Probes whether `pid` is a live process (`kill -0`).
+fn pid_alive(pid: u32) -> bool {
+    std::process::Command::new("kill")
+        .args(["-0", &pid.to_string()])
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|s| s.success())
+}
+
+/// Waits up to [`SHUTDOWN_TIMEOUT`] for `pid` to exit.
+fn wait_for_exit(pid: u32) -> bool {
+    let deadline = Instant::now() + SHUTDOWN_TIMEOUT;
+    while Instant::now() < deadline {
+        if !pid_alive(pid) {
+            return true;
+        }
+        thread::sleep(Duration::from_millis(50));
+    }
+    false
+}
+
 pub fn restart(opts: StartOptions) -> Result<StartResult> {
     let _ = stop();
     // Give the socket/pid cleanup a moment (stop() already waited for graceful exit).
@@ -207,3 +255,37 @@ fn cleanup_pid_file() {
         let _ = std::fs::remove_file(p);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plan_stop_without_pid_file_is_not_running() {
+        let plan = plan_stop(None, |_| panic!("no pid to probe")).unwrap();
+        assert_eq!(plan, StopPlan::NotRunning { stale_pid: None });
+    }
+
+    #[test]
+    fn plan_stop_stale_pid_is_not_running() {
+        let plan = plan_stop(Some("4242\n"), |_| false).unwrap();
+        assert_eq!(
+            plan,
+            StopPlan::NotRunning {
+                stale_pid: Some(4242)
+            }
+        );
+    }
+
+    #[test]
+    fn plan_stop_live_pid_is_signalled() {
+        let plan = plan_stop(Some("4242"), |pid| pid == 4242).unwrap();
+        assert_eq!(plan, StopPlan::Signal { pid: 4242 });
+    }
+
+    #[test]
+    fn plan_stop_malformed_pid_file_errors() {
+        let err = plan_stop(Some("not-a-pid"), |_| true).unwrap_err();
+        assert!(matches!(err, DaemonError::MalformedPidFile { .. }));
+    }
+}
@@ -3,17 +3,23 @@
 //! Thin wrapper over the `service-manager` crate so the CLI talks to launchd,
 //! systemd-user, and Windows SCM through one API. Each subcommand maps to one
 //! method: `install`, `uninstall`, `start`, `stop`, `status`.
+//!
+//! Windows is the exception: SCM has no per-user services, so there the same
+//! methods register a Task Scheduler task that runs `byokey serve` at logon.
 
 use std::ffi::OsString;
 use std::path::PathBuf;
 
+#[cfg(not(windows))]
 use service_manager::{
     ServiceInstallCtx, ServiceLabel, ServiceLevel, ServiceManager, ServiceStartCtx, ServiceStatus,
     ServiceStatusCtx, ServiceStopCtx, ServiceUninstallCtx,
 };
 
+use crate::SERVICE_LABEL;
 use crate::error::{DaemonError, Result};
-use crate::{SERVICE_LABEL, paths};
+#[cfg(not(windows))]
+use crate::paths;
 
 /// Options carried to the service unit. Mirrors `process::StartOptions` but without
 /// the pid-file fields — the service is supervised by the OS, not by us.
@@ -39,12 +45,14 @@ pub struct ServiceStatusInfo {
     pub running: bool,
 }
 
+#[cfg(not(windows))]
 fn label() -> ServiceLabel {
     SERVICE_LABEL
         .parse()
         .expect("SERVICE_LABEL is a valid service label")
 }
 
+#[cfg(not(windows))]
 fn manager() -> Result<Box<dyn ServiceManager>> {
     let mut mgr = <dyn ServiceManager>::native().map_err(|_| DaemonError::PlatformUnsupported)?;
     mgr.set_level(ServiceLevel::User)
@@ -60,7 +68,7 @@ fn backend_name() -> &'static str {
     } else if cfg!(target_os = "linux") {
         "systemd (user)"
     } else if cfg!(target_os = "windows") {
-        "Windows SCM"
+        "Task Scheduler (user)"
     } else {
         "unknown"
     }
@@ -93,7 +101,6 @@ fn build_args(opts: &ServiceOptions) -> Vec<OsString> {
 
 #[allow(clippy::needless_pass_by_value)]
 pub fn install(opts: ServiceOptions) -> Result<ServiceInstallResult> {
-    let mgr = manager()?;
     let program = match opts.exe {
         Some(ref p) => p.clone(),
         None => std::env::current_exe().map_err(DaemonError::SpawnFailed)?,
@@ -109,18 +116,21 @@ pub fn install(opts: ServiceOptions) -> Result<ServiceInstallResult> {
     }
 
     let args = build_args(&opts);
-    let ctx = ServiceInstallCtx {
-        label: label(),
-        program,
-        args,
-        contents: None,
-        username: None,
-        working_directory: paths::home_dir().ok(),
-        environment: None,
-        autostart: true,
-        restart_policy: service_manager::RestartPolicy::default(),
-    };
-    mgr.install(ctx)
+    #[cfg(windows)]
+    schtasks::install(&program, &args)?;
+    #[cfg(not(windows))]
+    manager()?
+        .install(ServiceInstallCtx {
+            label: label(),
+            program,
+            args,
+            contents: None,
+            username: None,
+            working_directory: paths::home_dir().ok(),
+            environment: None,
+            autostart: true,
+            restart_policy: service_manager::RestartPolicy::default(),
+        })
         .map_err(|_| DaemonError::ServiceToolFailed { tool: "install" })?;
 
     Ok(ServiceInstallResult {
@@ -130,37 +140,187 @@ pub fn install(opts: ServiceOptions) -> Result<ServiceInstallResult> {
 }
 
 pub fn uninstall() -> Result<()> {
-    let mgr = manager()?;
-    let _ = mgr.stop(ServiceStopCtx { label: label() });
-    mgr.uninstall(ServiceUninstallCtx { label: label() })
-        .map_err(|_| DaemonError::ServiceToolFailed { tool: "uninstall" })
+    #[cfg(windows)]
+    {
+        schtasks::uninstall()
+    }
+    #[cfg(not(windows))]
+    {
+        let mgr = manager()?;
+        let _ = mgr.stop(ServiceStopCtx { label: label() });
+        mgr.uninstall(ServiceUninstallCtx { label: label() })
+            .map_err(|_| DaemonError::ServiceToolFailed { tool: "uninstall" })
+    }
 }
 
 pub fn start() -> Result<()> {
-    let mgr = manager()?;
-    mgr.start(ServiceStartCtx { label: label() })
-        .map_err(|_| DaemonError::ServiceToolFailed { tool: "start" })
+    #[cfg(windows)]
+    {
+        schtasks::start()
+    }
+    #[cfg(not(windows))]
+    {
+        let mgr = manager()?;
+        mgr.start(ServiceStartCtx { label: label() })
+            .map_err(|_| DaemonError::ServiceToolFailed { tool: "start" })
+    }
 }
 
 pub fn stop() -> Result<()> {
-    let mgr = manager()?;
-    mgr.stop(ServiceStopCtx { label: label() })
-        .map_err(|_| DaemonError::ServiceToolFailed { tool: "stop" })
+    #[cfg(windows)]
+    {
+        schtasks::stop()
+    }
+    #[cfg(not(windows))]
+    {
+        let mgr = manager()?;
+        mgr.stop(ServiceStopCtx { label: label() })
+            .map_err(|_| DaemonError::ServiceToolFailed { tool: "stop" })
+    }
 }
 
 pub fn status() -> Result<ServiceStatusInfo> {
+    #[cfg(windows)]
+    let (installed, running) = schtasks::status()?;
+    #[cfg(not(windows))]
+    let (installed, running) = native_status()?;
+    Ok(ServiceStatusInfo {
+        backend: backend_name(),
+        installed,
+        running,
+    })
+}
+
+#[cfg(not(windows))]
+fn native_status() -> Result<(bool, bool)> {
     let mgr = manager()?;
     let s = mgr
         .status(ServiceStatusCtx { label: label() })
         .map_err(|_| DaemonError::ServiceToolFailed { tool: "status" })?;
-    let (installed, running) = match s {
+    Ok(match s {
         ServiceStatus::NotInstalled => (false, false),
         ServiceStatus::Stopped(_) => (true, false),
         ServiceStatus::Running => (true, true),
-    };
-    Ok(ServiceStatusInfo {
-        backend: backend_name(),
-        installed,
-        running,
     })
 }
+
+/// Quotes one token of a Task Scheduler `/TR` command line.
+#[cfg(any(windows, test))]
+fn quote_arg(arg: &str) -> String {
+    if !arg.is_empty() && !arg.contains([' ', '\t', '"']) {
+        return arg.to_owned();
+    }
+    format!("\"{}\"", arg.replace('"', "\\\""))
+}
+
+/// Renders `program args…` as the single string `schtasks /TR` expects.
+#[cfg(any(windows, test))]
+fn task_command(program: &std::path::Path, args: &[OsString]) -> String {
+    std::iter::once(program.as_os_str())
+        .chain(args.iter().map(OsString::as_os_str))
+        .map(|a| quote_arg(&a.to_string_lossy()))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Per-user logon task driven through `schtasks.exe`.
+#[cfg(windows)]
+mod schtasks {
+    use std::ffi::OsString;
+    use std::path::Path;
+    use std::process::{Command, Output};
+
+    use crate::SERVICE_LABEL;
+    use crate::error::{DaemonError, Result};
+
+    fn run(args: &[&str], tool: &'static str) -> Result<Output> {
+        Command::new("schtasks")
+            .args(args)
+            .output()
+            .map_err(|_| DaemonError::ServiceToolFailed { tool })
+    }
+
+    fn run_ok(args: &[&str], tool: &'static str) -> Result<()> {
+        if run(args, tool)?.status.success() {
+            Ok(())
+        } else {
+            Err(DaemonError::ServiceToolFailed { tool })
+        }
+    }
+
+    pub(super) fn install(program: &Path, args: &[OsString]) -> Result<()> {
+        let command = super::task_command(program, args);
+        run_ok(
+            &[
+                "/Create",
+                "/TN",
+                SERVICE_LABEL,
+                "/TR",
+                &command,
+                "/SC",
+                "ONLOGON",
+                "/RL",
+                "LIMITED",
+                "/F",
+            ],
+            "schtasks /Create",
+        )
+    }
+
+    pub(super) fn uninstall() -> Result<()> {
+        let _ = stop();
+        if !status()?.0 {
+            return Err(DaemonError::ServiceNotInstalled);
+        }
+        run_ok(&["/Delete", "/TN", SERVICE_LABEL, "/F"], "schtasks /Delete")
+    }
+
+    pub(super) fn start() -> Result<()> {
+        run_ok(&["/Run", "/TN", SERVICE_LABEL], "schtasks /Run")
+    }
+
+    pub(super) fn stop() -> Result<()> {
+        run_ok(&["/End", "/TN", SERVICE_LABEL], "schtasks /End")
+    }
+
+    /// Returns `(installed, running)`. A failing query means the task does
+    /// not exist; otherwise the last CSV column is the task state.
+    pub(super) fn status() -> Result<(bool, bool)> {
+        let out = run(
+            &["/Query", "/TN", SERVICE_LABEL, "/FO", "CSV", "/NH"],
+            "schtasks /Query",
+        )?;
+        if !out.status.success() {
+            return Ok((false, false));
+        }
+        let stdout = String::from_utf8_lossy(&out.stdout);
+        let running = stdout
+            .lines()
+            .filter_map(|l| l.rsplit(',').next())
+            .any(|state| state.trim().trim_matches('"') == "Running");
+        Ok((true, running))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn task_command_forwards_serve_args() {
+        let opts = ServiceOptions {
+            config: Some(PathBuf::from(r"C:\Users\me\My Config\settings.json")),
+            port: Some(8019),
+            host: Some("127.0.0.1".into()),
+            ..Default::default()
+        };
+        let cmd = task_command(
+            std::path::Path::new(r"C:\Program Files\byokey\byokey.exe"),
+            &build_args(&opts),
+        );
+        assert_eq!(
+            cmd,
+            r#""C:\Program Files\byokey\byokey.exe" serve --config "C:\Users\me\My Config\settings.json" --port 8019 --host 127.0.0.1"#
+        );
+    }
+}
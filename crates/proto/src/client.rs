@@ -79,6 +79,25 @@ impl ManagementClient {
             .map(connectrpc::client::UnaryResponse::into_owned)
     }
 
+    /// Fetch cumulative usage counters with a per-provider breakdown;
+    /// `group_by` is `"provider"` or `"model"`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a ConnectRPC transport or application error from the server.
+    pub async fn get_usage_grouped(
+        &self,
+        group_by: &str,
+    ) -> Result<stat::GetUsageResponse, ConnectError> {
+        self.status
+            .get_usage(stat::GetUsageRequest {
+                group_by: Some(group_by.to_string()),
+                ..Default::default()
+            })
+            .await
+            .map(connectrpc::client::UnaryResponse::into_owned)
+    }
+
     /// Fetch configured provider accounts.
     ///
     /// # Errors
@@ -0,0 +1,255 @@
+//! Circuit breaker guarding a primary provider that has a fallback.
+//!
+//! Executors are built per request, so breaker state lives in a process-wide
+//! registry keyed by `(primary, fallback)` and survives between requests.
+
+use byokey_config::CircuitBreakerConfig;
+use byokey_types::ProviderId;
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+static BREAKERS: LazyLock<Mutex<HashMap<(ProviderId, ProviderId), Arc<CircuitBreaker>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    /// Primary is in use; counts consecutive failures since `since`.
+    Closed { failures: u32, since: Instant },
+    /// Primary is skipped until `until`.
+    Open { until: Instant },
+    /// Cooldown elapsed and one probe request, started at `probe_started`,
+    /// is deciding whether to close or reopen. Other requests skip the
+    /// primary until it finishes.
+    HalfOpen { probe_started: Instant },
+}
+
+/// Tracks consecutive primary failures and decides whether to try the primary.
+pub struct CircuitBreaker {
+    provider: ProviderId,
+    config: Mutex<CircuitBreakerConfig>,
+    state: Mutex<State>,
+}
+
+impl CircuitBreaker {
+    /// Creates a closed breaker for `provider`.
+    #[must_use]
+    pub fn new(provider: ProviderId, config: CircuitBreakerConfig) -> Self {
+        Self {
+            provider,
+            config: Mutex::new(config),
+            state: Mutex::new(State::Closed {
+                failures: 0,
+                since: Instant::now(),
+            }),
+        }
+    }
+
+    /// Returns the shared breaker for `primary` → `fallback`, creating it on
+    /// first use and applying the latest `config`. Returns `None` when the
+    /// breaker is disabled (`failure_threshold: 0`).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the registry mutex is poisoned.
+    #[must_use]
+    pub fn shared(
+        primary: &ProviderId,
+        fallback: &ProviderId,
+        config: CircuitBreakerConfig,
+    ) -> Option<Arc<Self>> {
+        if config.failure_threshold == 0 {
+            return None;
+        }
+        let mut breakers = BREAKERS.lock().expect("breakers lock");
+        let breaker = breakers
+            .entry((primary.clone(), fallback.clone()))
+            .or_insert_with(|| Arc::new(Self::new(primary.clone(), config)));
+        *breaker.config.lock().expect("config lock") = config;
+        Some(Arc::clone(breaker))
+    }
+
+    /// Returns `true` if the request should go to the primary.
+    ///
+    /// An open breaker whose cooldown has elapsed moves to half-open and lets
+    /// exactly one caller through as a probe; everyone else keeps using the
+    /// fallback until the probe reports back. A probe that never reports
+    /// (e.g. its request was cancelled) is replaced after another cooldown.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the internal mutex is poisoned.
+    pub fn allow_primary(&self) -> bool {
+        let open_for = Duration::from_secs(self.config.lock().expect("config lock").open_secs);
+        let now = Instant::now();
+        let mut state = self.state.lock().expect("state lock");
+        match *state {
+            State::Closed { .. } => true,
+            State::Open { until } if now >= until => {
+                tracing::info!(provider = %self.provider, "circuit breaker half-open, probing primary");
+                *state = State::HalfOpen { probe_started: now };
+                true
+            }
+            State::HalfOpen { probe_started } if now.duration_since(probe_started) >= open_for => {
+                tracing::info!(provider = %self.provider, "stale probe, probing primary again");
+                *state = State::HalfOpen { probe_started: now };
+                true
+            }
+            State::Open { .. } | State::HalfOpen { .. } => false,
+        }
+    }
+
+    /// Ends a half-open probe without a verdict (the primary failed for a
+    /// reason unrelated to its health), so the next request probes again.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the internal mutex is poisoned.
+    pub fn release_probe(&self) {
+        let mut state = self.state.lock().expect("state lock");
+        if matches!(*state, State::HalfOpen { .. }) {
+            *state = State::Open {
+                until: Instant::now(),
+            };
+        }
+    }
+
+    /// Records a successful primary request, closing the breaker.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the internal mutex is poisoned.
+    pub fn record_success(&self) {
+        let mut state = self.state.lock().expect("state lock");
+        if !matches!(*state, State::Closed { .. }) {
+            tracing::info!(provider = %self.provider, "circuit breaker closed");
+        }
+        *state = State::Closed {
+            failures: 0,
+            since: Instant::now(),
+        };
+    }
+
+    /// Records a failed primary request, opening the breaker once the
+    /// threshold is reached within the window, or straight away when the
+    /// failure was a half-open probe.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the internal mutex is poisoned.
+    pub fn record_failure(&self) {
+        let config = *self.config.lock().expect("config lock");
+        let now = Instant::now();
+        let open = State::Open {
+            until: now + Duration::from_secs(config.open_secs),
+        };
+        let mut state = self.state.lock().expect("state lock");
+        *state = match *state {
+            State::Closed { failures, since } => {
+                let window = Duration::from_secs(config.window_secs);
+                let (failures, since) = if now.duration_since(since) > window {
+                    (1, now)
+                } else {
+                    (failures + 1, since)
+                };
+                if failures >= config.failure_threshold {
+                    tracing::warn!(
+                        provider = %self.provider,
+                        failures,
+                        open_secs = config.open_secs,
+                        "circuit breaker opened"
+                    );
+                    open
+                } else {
+                    State::Closed { failures, since }
+                }
+            }
+            State::HalfOpen { .. } => {
+                tracing::warn!(provider = %self.provider, "probe failed, circuit breaker reopened");
+                open
+            }
+            State::Open { until } => State::Open { until },
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(open_secs: u64) -> CircuitBreakerConfig {
+        CircuitBreakerConfig {
+            failure_threshold: 2,
+            window_secs: 60,
+            open_secs,
+        }
+    }
+
+    #[test]
+    fn opens_after_threshold_and_probes_after_cooldown() {
+        let breaker = CircuitBreaker::new(ProviderId::Gemini, config(0));
+        breaker.record_failure();
+        assert!(breaker.allow_primary());
+        breaker.record_failure();
+        // open_secs = 0: the cooldown has already elapsed, so this is a probe.
+        assert!(breaker.allow_primary());
+        assert!(matches!(
+            *breaker.state.lock().unwrap(),
+            State::HalfOpen { .. }
+        ));
+        breaker.record_success();
+        assert!(matches!(
+            *breaker.state.lock().unwrap(),
+            State::Closed { failures: 0, .. }
+        ));
+    }
+
+    #[test]
+    fn half_open_admits_a_single_probe() {
+        let breaker = CircuitBreaker::new(ProviderId::Gemini, config(30));
+        *breaker.state.lock().unwrap() = State::Open {
+            until: Instant::now(),
+        };
+        let start = std::sync::Barrier::new(16);
+        let admitted = std::thread::scope(|s| {
+            let handles: Vec<_> = (0..16)
+                .map(|_| {
+                    s.spawn(|| {
+                        start.wait();
+                        breaker.allow_primary()
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|h| h.join().unwrap())
+                .filter(|allowed| *allowed)
+                .count()
+        });
+        assert_eq!(admitted, 1);
+        assert!(!breaker.allow_primary());
+
+        // A probe without a verdict lets the next caller probe.
+        breaker.release_probe();
+        assert!(breaker.allow_primary());
+        assert!(!breaker.allow_primary());
+    }
+
+    #[test]
+    fn success_resets_the_count() {
+        let breaker = CircuitBreaker::new(ProviderId::Gemini, config(30));
+        breaker.record_failure();
+        breaker.record_success();
+        breaker.record_failure();
+        assert!(breaker.allow_primary());
+    }
+
+    #[test]
+    fn disabled_breaker_is_not_shared() {
+        let disabled = CircuitBreakerConfig {
+            failure_threshold: 0,
+            ..config(30)
+        };
+        assert!(CircuitBreaker::shared(&ProviderId::Kimi, &ProviderId::Qwen, disabled).is_none());
+    }
+}
@@ -0,0 +1,143 @@
+//! Per-request retry budget shared by the composed executors.
+//!
+//! One client request can fan out into many upstream calls: key rotation in
+//! [`RetryExecutor`], the provider `fallback`, and provider-internal loops
+//! such as Copilot's account rotation. Inside [`with_retry_budget`], each of
+//! those layers asks [`allow_retry`] before making another call and gives up
+//! with its last error once the budget is spent. Without a budget in scope,
+//! retries are unlimited.
+//!
+//! The budget lives in a task-local, so work moved to another task or into a
+//! shared future must carry it along with [`propagate_retry_budget`].
+//!
+//! [`RetryExecutor`]: crate::retry::RetryExecutor
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::{Duration, Instant};
+
+use byokey_config::RetryBudgetConfig;
+
+tokio::task_local! {
+    /// Budget of the request being handled, set inside [`with_retry_budget`].
+    static BUDGET: Arc<RetryBudget>;
+}
+
+/// Remaining upstream attempts and time for one client request.
+#[derive(Debug)]
+pub struct RetryBudget {
+    max_attempts: Option<u32>,
+    deadline: Option<Instant>,
+    /// Attempts made so far; the initial call counts as the first.
+    attempts: AtomicU32,
+}
+
+impl RetryBudget {
+    /// Starts a budget now. `0` leaves the respective limit off.
+    #[must_use]
+    pub fn new(max_attempts: u32, max_elapsed: Duration) -> Self {
+        Self {
+            max_attempts: (max_attempts > 0).then_some(max_attempts),
+            deadline: (!max_elapsed.is_zero()).then(|| Instant::now() + max_elapsed),
+            attempts: AtomicU32::new(1),
+        }
+    }
+
+    /// Starts a budget now from the `retry_budget` config section.
+    #[must_use]
+    pub fn from_config(config: &RetryBudgetConfig) -> Self {
+        Self::new(
+            config.max_attempts,
+            Duration::from_secs(config.max_elapsed_secs),
+        )
+    }
+
+    /// Takes one attempt for a retry starting after `delay`, if both an
+    /// attempt and enough time are left.
+    fn try_retry(&self, delay: Duration) -> bool {
+        if self
+            .deadline
+            .is_some_and(|deadline| Instant::now() + delay >= deadline)
+        {
+            return false;
+        }
+        self.attempts
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |used| {
+                self.max_attempts
+                    .is_none_or(|max| used < max)
+                    .then_some(used + 1)
+            })
+            .is_ok()
+    }
+}
+
+/// Runs `fut` with `budget` limiting the retries of every executor it calls.
+pub async fn with_retry_budget<F: Future>(budget: RetryBudget, fut: F) -> F::Output {
+    BUDGET.scope(Arc::new(budget), fut).await
+}
+
+/// Binds `fut` to the budget in scope where this is called, so it keeps
+/// drawing on that budget when spawned or polled from another request.
+pub fn propagate_retry_budget<F: Future>(fut: F) -> impl Future<Output = F::Output> {
+    let budget = BUDGET.try_with(Arc::clone).ok();
+    async move {
+        match budget {
+            Some(budget) => BUDGET.scope(budget, fut).await,
+            None => fut.await,
+        }
+    }
+}
+
+/// Whether another upstream call may start after waiting `delay`; consumes
+/// one attempt when it may. Always `true` outside [`with_retry_budget`].
+pub(crate) fn allow_retry(delay: Duration) -> bool {
+    let allowed = BUDGET.try_with(|b| b.try_retry(delay)).unwrap_or(true);
+    if !allowed {
+        tracing::warn!("retry budget exhausted, giving up");
+    }
+    allowed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn attempts_are_capped() {
+        let budget = RetryBudget::new(3, Duration::ZERO);
+        assert!(budget.try_retry(Duration::ZERO));
+        assert!(budget.try_retry(Duration::ZERO));
+        assert!(!budget.try_retry(Duration::ZERO));
+    }
+
+    #[test]
+    fn retries_past_the_deadline_are_refused() {
+        let budget = RetryBudget::new(0, Duration::from_secs(10));
+        assert!(budget.try_retry(Duration::from_secs(1)));
+        assert!(!budget.try_retry(Duration::from_secs(10)));
+    }
+
+    #[tokio::test]
+    async fn unlimited_outside_scope() {
+        assert!(allow_retry(Duration::from_hours(1)));
+        let exhausted = with_retry_budget(RetryBudget::new(1, Duration::ZERO), async {
+            allow_retry(Duration::ZERO)
+        })
+        .await;
+        assert!(!exhausted);
+    }
+
+    #[tokio::test]
+    async fn propagated_budget_is_shared_across_tasks() {
+        let spawned = with_retry_budget(RetryBudget::new(2, Duration::ZERO), async {
+            let task = tokio::spawn(propagate_retry_budget(async {
+                allow_retry(Duration::ZERO)
+            }));
+            let first = task.await.unwrap();
+            (first, allow_retry(Duration::ZERO))
+        })
+        .await;
+        // The spawned task took the only retry, leaving none for the caller.
+        assert_eq!(spawned, (true, false));
+    }
+}
@@ -313,6 +313,10 @@ impl ProviderExecutor for AntigravityExecutor {
     fn supported_models(&self) -> Vec<String> {
         registry::models_for_provider(&ProviderId::Antigravity)
     }
+
+    fn provider_id(&self) -> Option<ProviderId> {
+        Some(ProviderId::Antigravity)
+    }
 }
 
 #[cfg(test)]
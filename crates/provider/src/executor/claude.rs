@@ -43,6 +43,10 @@ pub const ANTHROPIC_VERSION: &str = "2023-06-01";
 /// the beta header is now rejected by the API.
 pub const ANTHROPIC_BETA: &str = "claude-code-20250219,oauth-2025-04-20,interleaved-thinking-2025-05-14,redact-thinking-2026-02-12,context-management-2025-06-27,prompt-caching-scope-2026-01-05,advanced-tool-use-2025-11-20,effort-2025-11-24,structured-outputs-2025-12-15,fast-mode-2026-02-01,token-efficient-tools-2026-03-28";
 
+/// Beta flag raising the output cap to 128k tokens; added automatically for
+/// models with [`ModelEntry::long_output`](crate::registry::ModelEntry) set.
+pub const LONG_OUTPUT_BETA: &str = "output-128k-2025-02-19";
+
 /// User-Agent matching the Claude CLI version.
 pub const USER_AGENT: &str = "claude-cli/2.1.109 (external, cli)";
 
@@ -145,6 +149,7 @@ pub struct ClaudeExecutor {
     auth: Arc<AuthManager>,
     profile_cache: Option<Arc<DeviceProfileCache>>,
     cloak_config: Option<CloakConfig>,
+    cache_min_chars: usize,
 }
 
 #[bon::bon]
@@ -154,6 +159,7 @@ impl ClaudeExecutor {
     /// When `profile_cache` is `Some`, per-auth device fingerprints are
     /// stabilised across requests instead of using static constants.
     /// When `cloak_config` is `Some` and enabled, request cloaking is applied.
+    /// `cache_min_chars` defaults to [`byokey_config::DEFAULT_CACHE_MIN_CHARS`].
     #[builder]
     #[allow(clippy::needless_pass_by_value)]
     pub fn new(
@@ -164,6 +170,7 @@ impl ClaudeExecutor {
         ratelimit: Option<Arc<RateLimitStore>>,
         profile_cache: Option<Arc<DeviceProfileCache>>,
         cloak_config: Option<CloakConfig>,
+        cache_min_chars: Option<usize>,
     ) -> Self {
         let mut ph = ProviderHttp::new(http);
         if let Some(store) = ratelimit {
@@ -181,6 +188,7 @@ impl ClaudeExecutor {
             auth,
             profile_cache,
             cloak_config,
+            cache_min_chars: cache_min_chars.unwrap_or(byokey_config::DEFAULT_CACHE_MIN_CHARS),
         }
     }
 
@@ -202,6 +210,7 @@ impl ClaudeExecutor {
         &self,
         credential: &Credential,
         fingerprint: &DeviceProfile,
+        model: &str,
     ) -> Result<Transport> {
         let (secret, auth_mode) = match credential {
             Credential::ApiKey(k) => (k.clone(), AigwAuthMode::ApiKey),
@@ -212,7 +221,7 @@ impl ClaudeExecutor {
             auth_mode,
             base_url: self.base_url.clone(),
             version: ANTHROPIC_VERSION.to_owned(),
-            beta: Some(ANTHROPIC_BETA.to_owned()),
+            beta: Some(beta_header(model)),
             extra_headers: build_fingerprint_headers(
                 fingerprint,
                 matches!(credential, Credential::ApiKey(_)),
@@ -240,13 +249,15 @@ impl ProviderExecutor for ClaudeExecutor {
             .map_or_else(DeviceProfile::default, |cache| cache.resolve(&scope_key));
 
         // Build Transport + Translator.
-        let transport = self.build_transport(&credential, &fingerprint)?;
+        let transport = self.build_transport(&credential, &fingerprint, &request.model)?;
         let translator = AnthropicRequestTranslator::new(&transport, None);
 
         // Translate: BYOKEY ChatRequest → aigw ChatRequest → Anthropic body.
-        let aigw_request: aigw_core::model::ChatRequest =
-            serde_json::from_value(request.into_body())
-                .map_err(|e| byokey_types::ByokError::Translation(e.to_string()))?;
+        let mut openai_body = request.into_body();
+        stringify_object_tool_content(&mut openai_body);
+        let client_breakpoints = has_cache_control(&openai_body);
+        let aigw_request: aigw_core::model::ChatRequest = serde_json::from_value(openai_body)
+            .map_err(|e| byokey_types::ByokError::Translation(e.to_string()))?;
         let translated = translator
             .translate_request(&aigw_request)
             .map_err(|e| byokey_types::ByokError::Translation(e.to_string()))?;
@@ -254,11 +265,17 @@ impl ProviderExecutor for ClaudeExecutor {
         // Post-process on the Anthropic-format body. cache_control breakpoints
         // are now applied inside aigw's AnthropicRequestTranslator
         // (DefaultCacheControlStrategy + always-on enforce_breakpoint_cap +
-        // normalize_ttl_ordering), so we only need temperature normalization
-        // and cloaking here.
+        // normalize_ttl_ordering), so we only need temperature normalization,
+        // dropping injected breakpoints on small blocks, and cloaking here.
+        // Breakpoints the client placed itself are left as they are.
         let mut body: Value = serde_json::from_slice(&translated.body)
             .map_err(|e| byokey_types::ByokError::Translation(e.to_string()))?;
         normalize_temperature_for_thinking(&mut body);
+        normalize_tool_result_content(&mut body);
+        normalize_message_image_parts(&mut body);
+        if !client_breakpoints {
+            drop_small_cache_breakpoints(&mut body, self.cache_min_chars);
+        }
 
         // Apply cloaking with identity from the device profile.
         if let Some(ref cc) = self.cloak_config
@@ -297,11 +314,15 @@ impl ProviderExecutor for ClaudeExecutor {
         } else {
             // Use aigw's response translator for non-streaming responses.
             let resp_bytes = resp.bytes().await.map_err(byokey_types::ByokError::from)?;
+            crate::http_util::log_response_body(&resp_bytes);
             let aigw_response = AnthropicResponseTranslator
                 .translate_response(http::StatusCode::OK, &resp_bytes)
                 .map_err(|e| byokey_types::ByokError::Translation(e.to_string()))?;
-            let value = serde_json::to_value(aigw_response)
+            let mut value = serde_json::to_value(aigw_response)
                 .map_err(|e| byokey_types::ByokError::Translation(e.to_string()))?;
+            if let Ok(claude) = serde_json::from_slice::<Value>(&resp_bytes) {
+                restore_image_content(&claude, &mut value);
+            }
             Ok(ProviderResponse::Complete(value))
         }
     }
@@ -309,6 +330,20 @@ impl ProviderExecutor for ClaudeExecutor {
     fn supported_models(&self) -> Vec<String> {
         registry::models_for_provider(&ProviderId::Claude)
     }
+
+    fn provider_id(&self) -> Option<ProviderId> {
+        Some(ProviderId::Claude)
+    }
+}
+
+/// Builds the `anthropic-beta` header value for `model`, appending
+/// [`LONG_OUTPUT_BETA`] for models flagged in the registry.
+fn beta_header(model: &str) -> String {
+    if registry::needs_long_output_beta(model) {
+        format!("{ANTHROPIC_BETA},{LONG_OUTPUT_BETA}")
+    } else {
+        ANTHROPIC_BETA.to_owned()
+    }
 }
 
 /// Force `temperature` to `1` when thinking is active.
@@ -336,6 +371,226 @@ fn normalize_temperature_for_thinking(body: &mut Value) {
     }
 }
 
+/// JSON-stringify object `content` on `OpenAI` `role: "tool"` messages.
+///
+/// Tool content must be a string or an array of content parts; tools that
+/// return a bare JSON object would otherwise fail translation.
+fn stringify_object_tool_content(body: &mut Value) {
+    let Some(messages) = body.get_mut("messages").and_then(Value::as_array_mut) else {
+        return;
+    };
+    for msg in messages {
+        if msg.get("role").and_then(Value::as_str) != Some("tool") {
+            continue;
+        }
+        if let Some(content) = msg.get_mut("content")
+            && content.is_object()
+        {
+            *content = Value::String(content.to_string());
+        }
+    }
+}
+
+/// Normalize `tool_result.content` in an Anthropic-format body.
+///
+/// Strings are kept; arrays of `OpenAI` content parts become Anthropic
+/// `text` / `image` blocks; any other value is JSON-stringified.
+fn normalize_tool_result_content(body: &mut Value) {
+    let Some(messages) = body.get_mut("messages").and_then(Value::as_array_mut) else {
+        return;
+    };
+    for block in messages
+        .iter_mut()
+        .filter_map(|m| m.get_mut("content").and_then(Value::as_array_mut))
+        .flatten()
+    {
+        if block.get("type").and_then(Value::as_str) != Some("tool_result") {
+            continue;
+        }
+        let Some(content) = block.get_mut("content") else {
+            continue;
+        };
+        match content {
+            Value::String(_) | Value::Null => {}
+            Value::Array(parts) => {
+                for part in parts.iter_mut() {
+                    *part = openai_part_to_claude_block(part.take());
+                }
+            }
+            other => *other = Value::String(other.to_string()),
+        }
+    }
+}
+
+/// Convert `OpenAI` `image_url` parts left in user/assistant message content
+/// of an Anthropic-format body into Anthropic `image` blocks.
+///
+/// Data URIs become `base64` sources with the URI's media type; other URLs
+/// become `url` sources. All other blocks are left untouched.
+fn normalize_message_image_parts(body: &mut Value) {
+    let Some(messages) = body.get_mut("messages").and_then(Value::as_array_mut) else {
+        return;
+    };
+    for block in messages
+        .iter_mut()
+        .filter_map(|m| m.get_mut("content").and_then(Value::as_array_mut))
+        .flatten()
+    {
+        if block.get("type").and_then(Value::as_str) == Some("image_url") {
+            *block = openai_part_to_claude_block(block.take());
+        }
+    }
+}
+
+/// Returns `true` if any object in `value` carries a `cache_control` key,
+/// i.e. the client placed its own cache breakpoints.
+fn has_cache_control(value: &Value) -> bool {
+    match value {
+        Value::Array(items) => items.iter().any(has_cache_control),
+        Value::Object(map) => {
+            map.contains_key("cache_control") || map.values().any(has_cache_control)
+        }
+        _ => false,
+    }
+}
+
+/// Removes each `cache_control` breakpoint in the system prompt and the
+/// messages whose own block holds fewer than `min_chars` characters of text.
+///
+/// A cache write costs more than an uncached read, so breakpoints are only
+/// kept on large, stable blocks where later hits pay for it. `0` keeps all.
+/// Only call this for breakpoints byokey injected, not client-supplied ones.
+fn drop_small_cache_breakpoints(body: &mut Value, min_chars: usize) {
+    if min_chars == 0 {
+        return;
+    }
+    if let Some(system) = body.get_mut("system") {
+        drop_small_breakpoints_in(system, min_chars);
+    }
+    let Some(messages) = body.get_mut("messages").and_then(Value::as_array_mut) else {
+        return;
+    };
+    for content in messages.iter_mut().filter_map(|m| m.get_mut("content")) {
+        drop_small_breakpoints_in(content, min_chars);
+    }
+}
+
+/// Applies [`drop_small_cache_breakpoints`] to a content block or each block
+/// in a list, including blocks nested in `tool_result` content.
+fn drop_small_breakpoints_in(value: &mut Value, min_chars: usize) {
+    match value {
+        Value::Array(blocks) => {
+            for block in blocks {
+                drop_small_breakpoints_in(block, min_chars);
+            }
+        }
+        Value::Object(block) => {
+            if block.contains_key("cache_control") && block_chars(block) < min_chars {
+                block.remove("cache_control");
+            }
+            if let Some(content) = block.get_mut("content") {
+                drop_small_breakpoints_in(content, min_chars);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Counts the characters of text in a string or list of content blocks,
+/// including text nested in `tool_result` blocks.
+fn text_chars(value: &Value) -> usize {
+    match value {
+        Value::String(s) => s.chars().count(),
+        Value::Array(blocks) => blocks.iter().map(text_chars).sum(),
+        Value::Object(block) => block_chars(block),
+        _ => 0,
+    }
+}
+
+/// Counts the characters of text in one content block.
+fn block_chars(block: &serde_json::Map<String, Value>) -> usize {
+    ["text", "content"]
+        .iter()
+        .filter_map(|key| block.get(*key))
+        .map(text_chars)
+        .sum()
+}
+
+/// Convert one `OpenAI` content part into an Anthropic content block.
+/// Parts that are already Anthropic blocks pass through unchanged.
+fn openai_part_to_claude_block(part: Value) -> Value {
+    match part.get("type").and_then(Value::as_str) {
+        Some("text" | "image" | "document" | "search_result") => part,
+        Some("image_url") => {
+            let url = part
+                .pointer("/image_url/url")
+                .or_else(|| part.get("image_url"))
+                .and_then(Value::as_str)
+                .unwrap_or_default();
+            let source = match url
+                .strip_prefix("data:")
+                .and_then(|rest| rest.split_once(";base64,"))
+            {
+                Some((media_type, data)) => serde_json::json!({
+                    "type": "base64",
+                    "media_type": media_type,
+                    "data": data,
+                }),
+                None => serde_json::json!({"type": "url", "url": url}),
+            };
+            serde_json::json!({"type": "image", "source": source})
+        }
+        _ => match part {
+            Value::String(text) => serde_json::json!({"type": "text", "text": text}),
+            other => serde_json::json!({"type": "text", "text": other.to_string()}),
+        },
+    }
+}
+
+/// Re-renders `message.content` of a translated response as `OpenAI` content
+/// parts when the Claude response carried `image` blocks, which the text-only
+/// translation would otherwise drop.
+///
+/// Text blocks become `text` parts and images become `image_url` parts (a
+/// data URI for `base64` sources). Other blocks — thinking, tool use — are
+/// already represented elsewhere in the translated message.
+fn restore_image_content(claude: &Value, openai: &mut Value) {
+    let Some(blocks) = claude.get("content").and_then(Value::as_array) else {
+        return;
+    };
+    if !blocks
+        .iter()
+        .any(|b| b.get("type").and_then(Value::as_str) == Some("image"))
+    {
+        return;
+    }
+    let parts: Vec<Value> = blocks
+        .iter()
+        .filter_map(|block| match block.get("type").and_then(Value::as_str) {
+            Some("text") => Some(serde_json::json!({
+                "type": "text",
+                "text": block.get("text").cloned().unwrap_or_default(),
+            })),
+            Some("image") => {
+                let source = block.get("source")?;
+                let url = match source.get("type").and_then(Value::as_str) {
+                    Some("base64") => format!(
+                        "data:{};base64,{}",
+                        source.get("media_type").and_then(Value::as_str)?,
+                        source.get("data").and_then(Value::as_str)?,
+                    ),
+                    _ => source.get("url").and_then(Value::as_str)?.to_string(),
+                };
+                Some(serde_json::json!({"type": "image_url", "image_url": {"url": url}}))
+            }
+            _ => None,
+        })
+        .collect();
+    if let Some(message) = openai.pointer_mut("/choices/0/message") {
+        message["content"] = Value::Array(parts);
+    }
+}
+
 /// Wraps a raw Claude SSE `ByteStream` and translates its events to
 /// `OpenAI` chat completion chunk SSE format line-by-line.
 ///
@@ -417,6 +672,21 @@ mod tests {
         ClaudeExecutor::builder().http(client).auth(auth).build()
     }
 
+    #[test]
+    fn test_beta_header_adds_long_output_for_flagged_model() {
+        let beta = beta_header("claude-3-7-sonnet-20250219");
+        assert!(beta.starts_with(ANTHROPIC_BETA));
+        assert!(beta.ends_with(&format!(",{LONG_OUTPUT_BETA}")));
+    }
+
+    #[test]
+    fn test_beta_header_omits_long_output_otherwise() {
+        // Opus 4.6 takes 128k output natively.
+        assert_eq!(beta_header("claude-opus-4-6"), ANTHROPIC_BETA);
+        assert_eq!(beta_header("claude-opus-4-5"), ANTHROPIC_BETA);
+        assert!(!beta_header("unknown-model").contains(LONG_OUTPUT_BETA));
+    }
+
     #[test]
     fn test_supported_models_non_empty() {
         let ex = make_executor();
@@ -435,4 +705,332 @@ mod tests {
             .build();
         assert!(!ex.supported_models().is_empty());
     }
+
+    fn tool_result_body(content: Value) -> Value {
+        serde_json::json!({
+            "messages": [{
+                "role": "user",
+                "content": [{"type": "tool_result", "tool_use_id": "t1", "content": content}]
+            }]
+        })
+    }
+
+    #[test]
+    fn test_tool_result_string_content_kept() {
+        let mut body = tool_result_body(serde_json::json!("72F and sunny"));
+        normalize_tool_result_content(&mut body);
+        assert_eq!(
+            body["messages"][0]["content"][0]["content"],
+            "72F and sunny"
+        );
+    }
+
+    #[test]
+    fn test_tool_result_array_parts_become_blocks() {
+        let mut body = tool_result_body(serde_json::json!([
+            {"type": "text", "text": "see chart"},
+            {"type": "image_url", "image_url": {"url": "data:image/png;base64,iVBOR"}},
+            {"type": "image_url", "image_url": {"url": "https://example.com/a.png"}},
+        ]));
+        normalize_tool_result_content(&mut body);
+        let blocks = &body["messages"][0]["content"][0]["content"];
+        assert_eq!(
+            blocks[0],
+            serde_json::json!({"type": "text", "text": "see chart"})
+        );
+        assert_eq!(blocks[1]["type"], "image");
+        assert_eq!(blocks[1]["source"]["type"], "base64");
+        assert_eq!(blocks[1]["source"]["media_type"], "image/png");
+        assert_eq!(blocks[1]["source"]["data"], "iVBOR");
+        assert_eq!(blocks[2]["source"]["type"], "url");
+        assert_eq!(blocks[2]["source"]["url"], "https://example.com/a.png");
+    }
+
+    fn cached_body(system: &str, user: &str) -> Value {
+        serde_json::json!({
+            "system": [{"type": "text", "text": system, "cache_control": {"type": "ephemeral"}}],
+            "messages": [{
+                "role": "user",
+                "content": [{"type": "text", "text": user, "cache_control": {"type": "ephemeral"}}]
+            }]
+        })
+    }
+
+    #[test]
+    fn test_cache_breakpoints_dropped_below_threshold() {
+        let mut body = cached_body("be brief", "hi");
+        drop_small_cache_breakpoints(&mut body, 1024);
+        assert!(body["system"][0].get("cache_control").is_none());
+        assert!(
+            body["messages"][0]["content"][0]
+                .get("cache_control")
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_cache_breakpoints_kept_above_threshold() {
+        let long = "x".repeat(2048);
+        let mut body = cached_body(&long, &long);
+        drop_small_cache_breakpoints(&mut body, 1024);
+        assert_eq!(body["system"][0]["cache_control"]["type"], "ephemeral");
+        assert_eq!(
+            body["messages"][0]["content"][0]["cache_control"]["type"],
+            "ephemeral"
+        );
+
+        // Each side is judged on its own size; zero disables the check.
+        let mut body = cached_body(&long, "hi");
+        drop_small_cache_breakpoints(&mut body, 1024);
+        assert!(body["system"][0].get("cache_control").is_some());
+        assert!(
+            body["messages"][0]["content"][0]
+                .get("cache_control")
+                .is_none()
+        );
+        let mut body = cached_body("a", "b");
+        drop_small_cache_breakpoints(&mut body, 0);
+        assert!(body["system"][0].get("cache_control").is_some());
+    }
+
+    #[test]
+    fn test_cache_breakpoints_judged_per_block() {
+        // A short last turn keeps the breakpoint on a large earlier block.
+        let long = "x".repeat(2048);
+        let mut body = serde_json::json!({
+            "messages": [
+                {"role": "user", "content": [
+                    {"type": "text", "text": long, "cache_control": {"type": "ephemeral"}}
+                ]},
+                {"role": "assistant", "content": "ok"},
+                {"role": "user", "content": [
+                    {"type": "text", "text": "hi", "cache_control": {"type": "ephemeral"}}
+                ]}
+            ]
+        });
+        drop_small_cache_breakpoints(&mut body, 1024);
+        assert!(
+            body["messages"][0]["content"][0]
+                .get("cache_control")
+                .is_some()
+        );
+        assert!(
+            body["messages"][2]["content"][0]
+                .get("cache_control")
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_has_cache_control_detects_client_breakpoints() {
+        let client = serde_json::json!({
+            "messages": [{"role": "user", "content": [
+                {"type": "text", "text": "hi", "cache_control": {"type": "ephemeral"}}
+            ]}]
+        });
+        assert!(has_cache_control(&client));
+        let plain = serde_json::json!({"messages": [{"role": "user", "content": "hi"}]});
+        assert!(!has_cache_control(&plain));
+    }
+
+    #[test]
+    fn test_message_data_uri_image_becomes_base64_block() {
+        let mut body = serde_json::json!({
+            "messages": [{
+                "role": "user",
+                "content": [
+                    {"type": "image_url", "image_url": {"url": "data:image/jpeg;base64,/9j/4AAQ"}}
+                ]
+            }]
+        });
+        normalize_message_image_parts(&mut body);
+        assert_eq!(
+            body["messages"][0]["content"][0],
+            serde_json::json!({
+                "type": "image",
+                "source": {"type": "base64", "media_type": "image/jpeg", "data": "/9j/4AAQ"}
+            })
+        );
+    }
+
+    #[test]
+    fn test_message_mixed_text_and_image_parts() {
+        let mut body = serde_json::json!({
+            "messages": [
+                {"role": "user", "content": [
+                    {"type": "text", "text": "what is this?"},
+                    {"type": "image_url", "image_url": {"url": "https://example.com/cat.png"}},
+                    {"type": "image_url", "image_url": {"url": "data:image/png;base64,iVBOR"}}
+                ]},
+                {"role": "assistant", "content": [
+                    {"type": "tool_use", "id": "t1", "name": "look", "input": {}}
+                ]}
+            ]
+        });
+        normalize_message_image_parts(&mut body);
+        let blocks = &body["messages"][0]["content"];
+        assert_eq!(
+            blocks[0],
+            serde_json::json!({"type": "text", "text": "what is this?"})
+        );
+        assert_eq!(
+            blocks[1],
+            serde_json::json!({
+                "type": "image",
+                "source": {"type": "url", "url": "https://example.com/cat.png"}
+            })
+        );
+        assert_eq!(blocks[2]["source"]["media_type"], "image/png");
+        assert_eq!(blocks[2]["source"]["data"], "iVBOR");
+        assert_eq!(body["messages"][1]["content"][0]["type"], "tool_use");
+    }
+
+    #[test]
+    fn test_response_image_blocks_become_image_url_parts() {
+        let claude = serde_json::json!({
+            "content": [
+                {"type": "thinking", "thinking": "hmm"},
+                {"type": "text", "text": "Here it is:"},
+                {"type": "image", "source": {"type": "base64", "media_type": "image/png", "data": "iVBOR"}},
+                {"type": "image", "source": {"type": "url", "url": "https://example.com/a.png"}}
+            ]
+        });
+        let mut openai = serde_json::json!({
+            "choices": [{"message": {"role": "assistant", "content": "Here it is:", "reasoning_content": "hmm"}}]
+        });
+        restore_image_content(&claude, &mut openai);
+        let message = &openai["choices"][0]["message"];
+        assert_eq!(
+            message["content"],
+            serde_json::json!([
+                {"type": "text", "text": "Here it is:"},
+                {"type": "image_url", "image_url": {"url": "data:image/png;base64,iVBOR"}},
+                {"type": "image_url", "image_url": {"url": "https://example.com/a.png"}}
+            ])
+        );
+        assert_eq!(message["reasoning_content"], "hmm");
+    }
+
+    #[test]
+    fn test_response_without_images_is_untouched() {
+        let claude = serde_json::json!({"content": [{"type": "text", "text": "hi"}]});
+        let mut openai =
+            serde_json::json!({"choices": [{"message": {"role": "assistant", "content": "hi"}}]});
+        restore_image_content(&claude, &mut openai);
+        assert_eq!(openai["choices"][0]["message"]["content"], "hi");
+    }
+
+    #[test]
+    fn test_tool_result_object_content_stringified() {
+        let mut body = tool_result_body(serde_json::json!({"temp": 72}));
+        normalize_tool_result_content(&mut body);
+        let content = &body["messages"][0]["content"][0]["content"];
+        assert_eq!(content, r#"{"temp":72}"#);
+
+        let mut openai = serde_json::json!({
+            "messages": [
+                {"role": "user", "content": "weather?"},
+                {"role": "tool", "tool_call_id": "t1", "content": {"temp": 72}}
+            ]
+        });
+        stringify_object_tool_content(&mut openai);
+        assert_eq!(openai["messages"][0]["content"], "weather?");
+        assert_eq!(openai["messages"][1]["content"], r#"{"temp":72}"#);
+    }
+
+    /// A recorded Claude stream: text, then a tool call whose JSON input
+    /// arrives in fragments, then `message_delta` with usage.
+    const CLAUDE_SSE_TRANSCRIPT: &str = r#"event: message_start
+data: {"type":"message_start","message":{"id":"msg_01","type":"message","role":"assistant","model":"claude-sonnet-4-20250514","content":[],"stop_reason":null,"usage":{"input_tokens":21,"output_tokens":1}}}
+
+event: content_block_start
+data: {"type":"content_block_start","index":0,"content_block":{"type":"text","text":""}}
+
+event: ping
+data: {"type":"ping"}
+
+event: content_block_delta
+data: {"type":"content_block_delta","index":0,"delta":{"type":"text_delta","text":"Hello"}}
+
+event: content_block_delta
+data: {"type":"content_block_delta","index":0,"delta":{"type":"text_delta","text":" world"}}
+
+event: content_block_stop
+data: {"type":"content_block_stop","index":0}
+
+event: content_block_start
+data: {"type":"content_block_start","index":1,"content_block":{"type":"tool_use","id":"toolu_01","name":"get_weather","input":{}}}
+
+event: content_block_delta
+data: {"type":"content_block_delta","index":1,"delta":{"type":"input_json_delta","partial_json":"{\"city\":"}}
+
+event: content_block_delta
+data: {"type":"content_block_delta","index":1,"delta":{"type":"input_json_delta","partial_json":" \"Paris\"}"}}
+
+event: content_block_stop
+data: {"type":"content_block_stop","index":1}
+
+event: message_delta
+data: {"type":"message_delta","delta":{"stop_reason":"tool_use","stop_sequence":null},"usage":{"output_tokens":12}}
+
+event: message_stop
+data: {"type":"message_stop"}
+
+"#;
+
+    #[tokio::test]
+    async fn test_translate_claude_sse_transcript() {
+        // Split at arbitrary points to exercise line buffering.
+        let inner: ByteStream = Box::pin(futures_util::stream::iter(
+            CLAUDE_SSE_TRANSCRIPT
+                .as_bytes()
+                .chunks(29)
+                .map(|c| Ok(Bytes::copy_from_slice(c)))
+                .collect::<Vec<_>>(),
+        ));
+        let out: Vec<u8> = translate_claude_sse(inner)
+            .map(|r| r.unwrap())
+            .collect::<Vec<_>>()
+            .await
+            .concat();
+        let out = String::from_utf8(out).unwrap();
+
+        let chunks: Vec<Value> = out
+            .lines()
+            .filter_map(|l| l.strip_prefix("data: "))
+            .filter(|d| *d != "[DONE]")
+            .map(|d| serde_json::from_str(d).unwrap())
+            .collect();
+        assert!(!chunks.is_empty());
+        for chunk in &chunks {
+            assert_eq!(chunk["object"], "chat.completion.chunk");
+            assert_eq!(chunk["id"], "chatcmpl-msg_01");
+        }
+
+        let delta = |field: &str| -> String {
+            chunks
+                .iter()
+                .filter_map(|c| c.pointer(&format!("/choices/0/delta/{field}")))
+                .filter_map(Value::as_str)
+                .collect()
+        };
+        assert_eq!(delta("content"), "Hello world");
+
+        let calls: Vec<&Value> = chunks
+            .iter()
+            .filter_map(|c| c.pointer("/choices/0/delta/tool_calls/0"))
+            .collect();
+        assert_eq!(calls[0]["id"], "toolu_01");
+        assert_eq!(calls[0]["function"]["name"], "get_weather");
+        assert!(calls.iter().all(|c| c["index"] == calls[0]["index"]));
+        let arguments: String = calls
+            .iter()
+            .filter_map(|c| c["function"]["arguments"].as_str())
+            .collect();
+        assert_eq!(arguments, r#"{"city": "Paris"}"#);
+
+        assert!(chunks.iter().any(
+            |c| c.pointer("/choices/0/finish_reason") == Some(&serde_json::json!("tool_calls"))
+        ));
+    }
 }
@@ -117,14 +117,21 @@ impl CodexExecutor {
     /// Translate a `ChatRequest` body `Value` to the Codex Responses API JSON
     /// body using [`aigw_openai::build_responses_create_request`] with the
     /// Codex preset config.
-    fn translate_body(body: Value) -> Result<Value> {
+    ///
+    /// Reasoning echoed back on prior assistant messages is replayed as
+    /// Responses API `reasoning` input items so multi-turn conversations keep
+    /// their reasoning state.
+    fn translate_body(mut body: Value) -> Result<Value> {
+        let reasoning = take_reasoning_items(&mut body);
         let aigw_request: aigw_core::model::ChatRequest = serde_json::from_value(body)
             .map_err(|e: serde_json::Error| ByokError::Translation(e.to_string()))?;
         let responses_req =
             build_responses_create_request(&aigw_request, &ResponsesRequestConfig::codex())
                 .map_err(|e| ByokError::Translation(e.to_string()))?;
-        serde_json::to_value(&responses_req)
-            .map_err(|e: serde_json::Error| ByokError::Translation(e.to_string()))
+        let mut value = serde_json::to_value(&responses_req)
+            .map_err(|e: serde_json::Error| ByokError::Translation(e.to_string()))?;
+        insert_reasoning_items(&mut value, reasoning);
+        Ok(value)
     }
 
     /// Translates an `OpenAI` Chat request, sends it to the Codex Responses
@@ -174,6 +181,15 @@ impl CodexExecutor {
                 if let Some(id) = value.get("id").and_then(Value::as_str) {
                     value["id"] = Value::String(format!("chatcmpl-{id}"));
                 }
+                // Surface the reasoning item so clients can echo it back on
+                // the next turn (see `take_reasoning_items`).
+                if let Some(item) = response["output"]
+                    .as_array()
+                    .and_then(|out| out.iter().find(|i| i["type"] == "reasoning"))
+                    && let Some(message) = value.pointer_mut("/choices/0/message")
+                {
+                    message["reasoning"] = item.clone();
+                }
                 return Ok(ProviderResponse::Complete(value));
             }
         }
@@ -184,6 +200,84 @@ impl CodexExecutor {
     }
 }
 
+/// Strips echoed reasoning from assistant messages, returning one entry per
+/// assistant message (in order) with the Responses API `reasoning` item to
+/// replay for it, if any.
+///
+/// Accepts either a raw `reasoning` item (as returned by non-streaming
+/// responses) or the `reasoning_content` / `reasoning_signature` pair emitted
+/// by streaming responses.
+fn take_reasoning_items(body: &mut Value) -> Vec<Option<Value>> {
+    let Some(messages) = body.get_mut("messages").and_then(Value::as_array_mut) else {
+        return Vec::new();
+    };
+    let mut items = Vec::new();
+    for msg in messages {
+        if msg.get("role").and_then(Value::as_str) != Some("assistant") {
+            continue;
+        }
+        let Some(obj) = msg.as_object_mut() else {
+            items.push(None);
+            continue;
+        };
+        let raw = obj
+            .remove("reasoning")
+            .filter(|r| r.get("type").and_then(Value::as_str) == Some("reasoning"));
+        let summary = obj.remove("reasoning_content");
+        let signature = obj.remove("reasoning_signature");
+        let item = raw.or_else(|| {
+            let encrypted = signature?.as_str()?.to_string();
+            let summary: Vec<Value> = summary
+                .as_ref()
+                .and_then(Value::as_str)
+                .filter(|t| !t.is_empty())
+                .map(|t| serde_json::json!({"type": "summary_text", "text": t}))
+                .into_iter()
+                .collect();
+            Some(serde_json::json!({
+                "type": "reasoning",
+                "summary": summary,
+                "encrypted_content": encrypted,
+            }))
+        });
+        items.push(item);
+    }
+    items
+}
+
+/// Inserts the reasoning items collected by [`take_reasoning_items`] into a
+/// Responses API body, each one directly before the input items of the
+/// assistant turn it belongs to.
+fn insert_reasoning_items(body: &mut Value, reasoning: Vec<Option<Value>>) {
+    if reasoning.iter().all(Option::is_none) {
+        return;
+    }
+    let Some(input) = body.get_mut("input").and_then(Value::as_array_mut) else {
+        return;
+    };
+    let is_assistant_item = |item: &Value| match item.get("type").and_then(Value::as_str) {
+        Some("function_call") => true,
+        Some("message") | None => item.get("role").and_then(Value::as_str) == Some("assistant"),
+        _ => false,
+    };
+
+    let mut pending = reasoning.into_iter();
+    let mut out = Vec::with_capacity(input.len());
+    let mut in_assistant_turn = false;
+    for item in input.drain(..) {
+        let assistant = is_assistant_item(&item);
+        if assistant
+            && !in_assistant_turn
+            && let Some(Some(reasoning)) = pending.next()
+        {
+            out.push(reasoning);
+        }
+        in_assistant_turn = assistant;
+        out.push(item);
+    }
+    *input = out;
+}
+
 /// Generates a deterministic prompt cache key from an API key using UUID v5.
 fn prompt_cache_key(api_key: &str) -> String {
     let seed = format!("cli-proxy-api:codex:prompt-cache:{api_key}");
@@ -298,6 +392,10 @@ impl ProviderExecutor for CodexExecutor {
     fn supported_models(&self) -> Vec<String> {
         registry::models_for_provider(&ProviderId::Codex)
     }
+
+    fn provider_id(&self) -> Option<ProviderId> {
+        Some(ProviderId::Codex)
+    }
 }
 
 #[cfg(test)]
@@ -315,6 +413,60 @@ mod tests {
         assert!(!ex.supported_models().is_empty());
     }
 
+    #[test]
+    fn test_translate_body_replays_prior_reasoning_item() {
+        let body = serde_json::json!({
+            "model": "gpt-5",
+            "stream": false,
+            "messages": [
+                {"role": "user", "content": "first"},
+                {
+                    "role": "assistant",
+                    "content": "answer",
+                    "reasoning": {
+                        "type": "reasoning",
+                        "id": "rs_1",
+                        "summary": [],
+                        "encrypted_content": "opaque"
+                    }
+                },
+                {"role": "user", "content": "second"}
+            ]
+        });
+        let out = CodexExecutor::translate_body(body).unwrap();
+        let input = out["input"].as_array().unwrap();
+        let pos = input
+            .iter()
+            .position(|i| i["type"] == "reasoning")
+            .expect("reasoning item replayed");
+        assert_eq!(input[pos]["encrypted_content"], "opaque");
+        assert_eq!(input[pos]["id"], "rs_1");
+        assert_eq!(input[pos + 1]["role"], "assistant");
+    }
+
+    #[test]
+    fn test_take_reasoning_items_from_signature() {
+        let mut body = serde_json::json!({
+            "messages": [
+                {"role": "user", "content": "q"},
+                {
+                    "role": "assistant",
+                    "content": "a",
+                    "reasoning_content": "thought",
+                    "reasoning_signature": "sig"
+                },
+                {"role": "assistant", "content": "b"}
+            ]
+        });
+        let items = take_reasoning_items(&mut body);
+        assert_eq!(items.len(), 2);
+        let first = items[0].as_ref().unwrap();
+        assert_eq!(first["encrypted_content"], "sig");
+        assert_eq!(first["summary"][0]["text"], "thought");
+        assert!(items[1].is_none());
+        assert!(body["messages"][1].get("reasoning_signature").is_none());
+    }
+
     #[test]
     fn test_supported_models_contains_o4_mini() {
         let ex = make_executor();
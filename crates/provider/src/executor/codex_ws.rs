@@ -134,6 +134,10 @@ impl ProviderExecutor for CodexWsExecutor {
     fn supported_models(&self) -> Vec<String> {
         registry::models_for_provider(&ProviderId::Codex)
     }
+
+    fn provider_id(&self) -> Option<ProviderId> {
+        Some(ProviderId::Codex)
+    }
 }
 
 #[cfg(test)]
@@ -553,6 +553,10 @@ impl ProviderExecutor for CopilotExecutor {
 
         let mut last_err = None;
         for attempt in 0..max_attempts {
+            if attempt > 0 && !crate::budget::allow_retry(Duration::ZERO) {
+                return Err(last_err
+                    .unwrap_or_else(|| ByokError::Auth("no copilot accounts available".into())));
+            }
             let creds = self.copilot_creds().await;
             let (token, endpoint) = match creds {
                 Ok(c) => c,
@@ -629,6 +633,7 @@ impl ProviderExecutor for CopilotExecutor {
                     }
                 };
                 let resp_bytes = resp.bytes().await.map_err(ByokError::from)?;
+                crate::http_util::log_response_body(&resp_bytes);
                 let aigw_response = OpenAIResponseTranslator
                     .translate_response(http::StatusCode::OK, &resp_bytes)
                     .map_err(|e: aigw_core::error::TranslateError| {
@@ -650,6 +655,15 @@ impl ProviderExecutor for CopilotExecutor {
     fn supported_models(&self) -> Vec<String> {
         registry::models_for_provider(&ProviderId::Copilot)
     }
+
+    fn provider_id(&self) -> Option<ProviderId> {
+        Some(ProviderId::Copilot)
+    }
+
+    /// Exchanges the GitHub token for a Copilot API token.
+    async fn health_check(&self) -> Option<Result<()>> {
+        Some(self.copilot_token().await.map(|_| ()))
+    }
 }
 
 #[cfg(test)]
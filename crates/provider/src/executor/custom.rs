@@ -0,0 +1,123 @@
+//! Custom executor — any OpenAI-compatible endpoint (vLLM, OpenRouter, Together, …).
+//!
+//! The request body is forwarded verbatim to `{base_url}/chat/completions` and
+//! the response is passed back untouched, so no per-vendor code is needed.
+//! Auth: `Authorization: Bearer {api_key}` when an API key is configured;
+//! local endpoints that need no auth can omit it.
+
+use crate::http_util::{ProviderHttp, accept_for_stream};
+use async_trait::async_trait;
+use byokey_types::{
+    ByokError, ChatRequest, ProviderId, RateLimitStore,
+    traits::{ProviderExecutor, ProviderResponse, Result},
+};
+use std::sync::Arc;
+
+/// Executor for a user-configured OpenAI-compatible endpoint.
+pub struct CustomExecutor {
+    ph: ProviderHttp,
+    api_key: Option<String>,
+    base_url: Option<String>,
+}
+
+#[bon::bon]
+impl CustomExecutor {
+    /// Creates a new custom executor. Requests fail until `base_url` is set.
+    #[builder]
+    pub fn new(
+        http: rquest::Client,
+        api_key: Option<String>,
+        base_url: Option<String>,
+        ratelimit: Option<Arc<RateLimitStore>>,
+    ) -> Self {
+        let mut ph = ProviderHttp::new(http);
+        if let Some(store) = ratelimit {
+            ph = ph.with_ratelimit(store, ProviderId::Custom);
+        }
+        Self {
+            ph,
+            api_key,
+            base_url: base_url.map(|u| u.trim_end_matches('/').to_owned()),
+        }
+    }
+
+    fn base_url(&self) -> Result<&str> {
+        self.base_url.as_deref().ok_or_else(|| {
+            ByokError::Config("the custom provider requires `base_url` to be set".into())
+        })
+    }
+
+    /// Full chat completions URL, e.g. `http://localhost:8000/v1/chat/completions`.
+    fn chat_url(&self) -> Result<String> {
+        Ok(format!("{}/chat/completions", self.base_url()?))
+    }
+}
+
+#[async_trait]
+impl ProviderExecutor for CustomExecutor {
+    async fn chat_completion(&self, request: ChatRequest) -> Result<ProviderResponse> {
+        let url = self.chat_url()?;
+        let stream = request.stream;
+        let mut builder = self
+            .ph
+            .client()
+            .post(&url)
+            .header("content-type", "application/json")
+            .header("accept", accept_for_stream(stream))
+            .json(&request.into_body());
+        if let Some(key) = &self.api_key {
+            builder = builder.header("authorization", format!("Bearer {key}"));
+        }
+        self.ph.send_passthrough(builder, stream).await
+    }
+
+    fn supported_models(&self) -> Vec<String> {
+        // The endpoint's models are not known ahead of time.
+        Vec::new()
+    }
+
+    fn provider_id(&self) -> Option<ProviderId> {
+        Some(ProviderId::Custom)
+    }
+
+    /// Lists the endpoint's models.
+    async fn health_check(&self) -> Option<Result<()>> {
+        let base_url = match self.base_url() {
+            Ok(url) => url,
+            Err(e) => return Some(Err(e)),
+        };
+        let mut builder = self.ph.client().get(format!("{base_url}/models"));
+        if let Some(key) = &self.api_key {
+            builder = builder.header("authorization", format!("Bearer {key}"));
+        }
+        Some(self.ph.send(builder).await.map(|_| ()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chat_url_appends_path() {
+        let ex = CustomExecutor::builder()
+            .http(rquest::Client::new())
+            .base_url("http://localhost:8000/v1/".into())
+            .build();
+        assert_eq!(
+            ex.chat_url().unwrap(),
+            "http://localhost:8000/v1/chat/completions"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_missing_base_url_is_config_error() {
+        let ex = CustomExecutor::builder()
+            .http(rquest::Client::new())
+            .build();
+        let request: ChatRequest =
+            serde_json::from_value(serde_json::json!({"model": "m", "messages": []})).unwrap();
+        let err = ex.chat_completion(request).await.err().unwrap();
+        assert!(matches!(err, ByokError::Config(_)));
+    }
+}
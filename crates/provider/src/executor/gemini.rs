@@ -155,6 +155,7 @@ impl ProviderExecutor for GeminiExecutor {
             Ok(ProviderResponse::Stream(byte_stream))
         } else {
             let resp_bytes = resp.bytes().await.map_err(byokey_types::ByokError::from)?;
+            crate::http_util::log_response_body(&resp_bytes);
             let aigw_response = OpenAIResponseTranslator
                 .translate_response(http::StatusCode::OK, &resp_bytes)
                 .map_err(|e: aigw_core::error::TranslateError| {
@@ -169,6 +170,24 @@ impl ProviderExecutor for GeminiExecutor {
     fn supported_models(&self) -> Vec<String> {
         registry::models_for_provider(&ProviderId::Gemini)
     }
+
+    fn provider_id(&self) -> Option<ProviderId> {
+        Some(ProviderId::Gemini)
+    }
+
+    /// Lists models through the OpenAI-compat shim.
+    async fn health_check(&self) -> Option<Result<()>> {
+        let token = match self.bearer_token().await {
+            Ok(token) => token,
+            Err(e) => return Some(Err(e)),
+        };
+        let builder = self
+            .ph
+            .client()
+            .get(format!("{}/models", self.base_url))
+            .header("authorization", format!("Bearer {token}"));
+        Some(self.ph.send(builder).await.map(|_| ()))
+    }
 }
 
 #[cfg(test)]
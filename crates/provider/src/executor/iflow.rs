@@ -120,6 +120,18 @@ fn create_signature(api_key: &str, session_id: &str, timestamp: u64) -> String {
     hex::encode(mac.finalize().into_bytes())
 }
 
+/// The signing headers iFlow requires on every chat request: a fresh
+/// `session-id`, the millisecond timestamp, and their HMAC signature.
+fn signing_headers(api_key: &str, timestamp: u64) -> [(&'static str, String); 3] {
+    let session_id = format!("session-{}", uuid::Uuid::new_v4());
+    let signature = create_signature(api_key, &session_id, timestamp);
+    [
+        ("session-id", session_id),
+        ("x-iflow-timestamp", timestamp.to_string()),
+        ("x-iflow-signature", signature),
+    ]
+}
+
 #[async_trait]
 impl ProviderExecutor for IFlowExecutor {
     async fn chat_completion(&self, request: ChatRequest) -> Result<ProviderResponse> {
@@ -146,14 +158,13 @@ impl ProviderExecutor for IFlowExecutor {
         };
 
         // Build per-request HMAC-SHA256 signing headers (dynamic — cannot be in default_headers).
-        let session_id = format!("session-{}", uuid::Uuid::new_v4());
         let timestamp = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
             .unwrap_or_default()
             .as_millis()
             .try_into()
             .unwrap_or(u64::MAX);
-        let signature = create_signature(&token, &session_id, timestamp);
+        let signing = signing_headers(&token, timestamp);
 
         // Build rquest from TranslatedRequest URL/headers + body, then append signing headers.
         let mut builder = self.ph.client().post(&translated.url);
@@ -162,12 +173,11 @@ impl ProviderExecutor for IFlowExecutor {
                 builder = builder.header(name.as_str(), v);
             }
         }
-        let builder = builder
-            .header("accept-encoding", "identity")
-            .header("session-id", &session_id)
-            .header("x-iflow-timestamp", timestamp.to_string())
-            .header("x-iflow-signature", &signature)
-            .body(translated.body.to_vec());
+        let mut builder = builder.header("accept-encoding", "identity");
+        for (name, value) in &signing {
+            builder = builder.header(*name, value.as_str());
+        }
+        let builder = builder.body(translated.body.to_vec());
 
         let resp = self.ph.send(builder).await?;
 
@@ -176,6 +186,7 @@ impl ProviderExecutor for IFlowExecutor {
             Ok(ProviderResponse::Stream(byte_stream))
         } else {
             let resp_bytes = resp.bytes().await.map_err(ByokError::from)?;
+            crate::http_util::log_response_body(&resp_bytes);
             let aigw_response = OpenAIResponseTranslator
                 .translate_response(http::StatusCode::OK, &resp_bytes)
                 .map_err(|e| ByokError::Translation(e.to_string()))?;
@@ -188,6 +199,10 @@ impl ProviderExecutor for IFlowExecutor {
     fn supported_models(&self) -> Vec<String> {
         registry::models_for_provider(&ProviderId::IFlow)
     }
+
+    fn provider_id(&self) -> Option<ProviderId> {
+        Some(ProviderId::IFlow)
+    }
 }
 
 #[cfg(test)]
@@ -215,6 +230,25 @@ mod tests {
         assert_eq!(sig1.len(), 64);
     }
 
+    #[test]
+    fn test_signing_headers_present_and_consistent() {
+        let headers = signing_headers("key123", 1_700_000_000);
+        let get = |name: &str| {
+            headers
+                .iter()
+                .find(|(n, _)| *n == name)
+                .map(|(_, v)| v.clone())
+                .unwrap()
+        };
+        let session_id = get("session-id");
+        assert!(session_id.starts_with("session-"));
+        assert_eq!(get("x-iflow-timestamp"), "1700000000");
+        assert_eq!(
+            get("x-iflow-signature"),
+            create_signature("key123", &session_id, 1_700_000_000)
+        );
+    }
+
     #[test]
     fn test_create_signature_differs_with_different_key() {
         let sig1 = create_signature("key1", "session-abc", 1_700_000_000);
@@ -29,8 +29,6 @@ use std::sync::Arc;
 /// Default Kimi API base URL (includes `/coding/v1`; aigw appends `/chat/completions`).
 const DEFAULT_BASE_URL: &str = "https://api.kimi.com/coding/v1";
 
-const DEFAULT_USER_AGENT: &str = "KimiCLI/1.10.6";
-
 /// Executor for the Moonshot AI (Kimi) API.
 pub struct KimiExecutor {
     ph: ProviderHttp,
@@ -69,7 +67,7 @@ impl KimiExecutor {
             base_url,
             auth,
             device_id: byokey_auth::provider::kimi::device_id(),
-            user_agent: user_agent.unwrap_or_else(|| DEFAULT_USER_AGENT.to_string()),
+            user_agent: user_agent.unwrap_or_else(byokey_auth::provider::kimi::user_agent),
         }
     }
 
@@ -83,24 +81,24 @@ impl KimiExecutor {
         .await
     }
 
+    /// Headers sent on every chat request: the user agent plus the same
+    /// `X-Msh-*` platform headers the login flow uses.
+    fn default_headers(&self) -> BTreeMap<String, String> {
+        let mut headers: BTreeMap<String, String> =
+            byokey_auth::provider::kimi::x_msh_headers_for(&self.device_id)
+                .into_iter()
+                .map(|(name, value)| (name.to_ascii_lowercase(), value))
+                .collect();
+        headers.insert("user-agent".to_owned(), self.user_agent.clone());
+        headers
+    }
+
     /// Builds an [`OpenAICompatProvider`] for a single request.
     ///
     /// Static Kimi-specific headers are placed in `default_headers` so aigw
     /// includes them in every request it builds.
     fn build_provider(&self, token: String) -> Result<OpenAICompatProvider> {
-        let mut default_headers = BTreeMap::new();
-        default_headers.insert("user-agent".to_owned(), self.user_agent.clone());
-        default_headers.insert("x-msh-platform".to_owned(), "kimi_cli".to_owned());
-        default_headers.insert("x-msh-version".to_owned(), "1.10.6".to_owned());
-        default_headers.insert(
-            "x-msh-device-name".to_owned(),
-            byokey_auth::provider::kimi::device_name().clone(),
-        );
-        default_headers.insert(
-            "x-msh-device-model".to_owned(),
-            byokey_auth::provider::kimi::DEVICE_MODEL.to_owned(),
-        );
-        default_headers.insert("x-msh-device-id".to_owned(), self.device_id.clone());
+        let default_headers = self.default_headers();
 
         OpenAICompatProvider::new(OpenAICompatConfig {
             name: "kimi".to_owned(),
@@ -173,6 +171,7 @@ impl ProviderExecutor for KimiExecutor {
             Ok(ProviderResponse::Stream(byte_stream))
         } else {
             let resp_bytes = resp.bytes().await.map_err(ByokError::from)?;
+            crate::http_util::log_response_body(&resp_bytes);
             let aigw_response = OpenAIResponseTranslator
                 .translate_response(http::StatusCode::OK, &resp_bytes)
                 .map_err(|e| ByokError::Translation(e.to_string()))?;
@@ -185,6 +184,10 @@ impl ProviderExecutor for KimiExecutor {
     fn supported_models(&self) -> Vec<String> {
         registry::models_for_provider(&ProviderId::Kimi)
     }
+
+    fn provider_id(&self) -> Option<ProviderId> {
+        Some(ProviderId::Kimi)
+    }
 }
 
 #[cfg(test)]
@@ -202,6 +205,21 @@ mod tests {
         assert!(!ex.supported_models().is_empty());
     }
 
+    #[test]
+    fn test_chat_headers_match_login_headers() {
+        use byokey_auth::provider::kimi;
+
+        let ex = make_executor();
+        let headers = ex.default_headers();
+        assert_eq!(headers["x-msh-platform"], kimi::PLATFORM);
+        assert_eq!(headers["x-msh-version"], kimi::VERSION);
+        assert_eq!(headers["x-msh-device-name"], kimi::device_name());
+        assert_eq!(headers["x-msh-device-model"], kimi::DEVICE_MODEL);
+        assert_eq!(headers["x-msh-device-id"], ex.device_id);
+        assert_eq!(headers["user-agent"], kimi::user_agent());
+        assert_eq!(headers["user-agent"], format!("KimiCLI/{}", kimi::VERSION));
+    }
+
     #[test]
     fn test_strip_kimi_prefix() {
         assert_eq!(strip_kimi_prefix("kimi-k2-0711"), "k2-0711");
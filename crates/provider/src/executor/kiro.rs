@@ -126,6 +126,7 @@ impl ProviderExecutor for KiroExecutor {
             ))
         } else {
             let resp_bytes = resp.bytes().await.map_err(byokey_types::ByokError::from)?;
+            crate::http_util::log_response_body(&resp_bytes);
             let aigw_response = AnthropicResponseTranslator
                 .translate_response(http::StatusCode::OK, &resp_bytes)
                 .map_err(|e| byokey_types::ByokError::Translation(e.to_string()))?;
@@ -138,6 +139,10 @@ impl ProviderExecutor for KiroExecutor {
     fn supported_models(&self) -> Vec<String> {
         registry::models_for_provider(&ProviderId::Kiro)
     }
+
+    fn provider_id(&self) -> Option<ProviderId> {
+        Some(ProviderId::Kiro)
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,146 @@
+//! Mistral executor — Mistral AI's OpenAI-compatible chat completions API.
+//!
+//! Mistral's request, response and tool-call formats match `OpenAI`'s, so the
+//! body is forwarded verbatim to `{base_url}/chat/completions` and the
+//! response is passed back untouched.
+//! Auth: `Authorization: Bearer {api_key}`. Mistral has no OAuth login, so an
+//! API key is required.
+
+use crate::http_util::{ProviderHttp, accept_for_stream};
+use crate::registry;
+use async_trait::async_trait;
+use byokey_types::{
+    ByokError, ChatRequest, ProviderId, RateLimitStore,
+    traits::{ProviderExecutor, ProviderResponse, Result},
+};
+use std::sync::Arc;
+
+/// Default Mistral API base URL.
+const DEFAULT_BASE_URL: &str = "https://api.mistral.ai/v1";
+
+/// Executor for the Mistral AI API.
+pub struct MistralExecutor {
+    ph: ProviderHttp,
+    api_key: Option<String>,
+    base_url: String,
+}
+
+#[bon::bon]
+impl MistralExecutor {
+    /// Creates a new Mistral executor. Requests fail until `api_key` is set.
+    #[builder]
+    pub fn new(
+        http: rquest::Client,
+        api_key: Option<String>,
+        base_url: Option<String>,
+        ratelimit: Option<Arc<RateLimitStore>>,
+    ) -> Self {
+        let mut ph = ProviderHttp::new(http);
+        if let Some(store) = ratelimit {
+            ph = ph.with_ratelimit(store, ProviderId::Mistral);
+        }
+        let base_url = base_url
+            .as_deref()
+            .unwrap_or(DEFAULT_BASE_URL)
+            .trim_end_matches('/')
+            .to_owned();
+        Self {
+            ph,
+            api_key,
+            base_url,
+        }
+    }
+
+    /// Full chat completions URL.
+    fn chat_url(&self) -> String {
+        format!("{}/chat/completions", self.base_url)
+    }
+
+    fn api_key(&self) -> Result<&str> {
+        self.api_key.as_deref().ok_or_else(|| {
+            ByokError::Auth("the mistral provider requires `api_key` to be set".into())
+        })
+    }
+}
+
+#[async_trait]
+impl ProviderExecutor for MistralExecutor {
+    async fn chat_completion(&self, request: ChatRequest) -> Result<ProviderResponse> {
+        let key = self.api_key()?;
+        let stream = request.stream;
+        let builder = self
+            .ph
+            .client()
+            .post(self.chat_url())
+            .header("authorization", format!("Bearer {key}"))
+            .header("content-type", "application/json")
+            .header("accept", accept_for_stream(stream))
+            .json(&request.into_body());
+        self.ph.send_passthrough(builder, stream).await
+    }
+
+    fn supported_models(&self) -> Vec<String> {
+        registry::models_for_provider(&ProviderId::Mistral)
+    }
+
+    fn provider_id(&self) -> Option<ProviderId> {
+        Some(ProviderId::Mistral)
+    }
+
+    /// Lists models, which needs a valid API key.
+    async fn health_check(&self) -> Option<Result<()>> {
+        let key = match self.api_key() {
+            Ok(key) => key,
+            Err(e) => return Some(Err(e)),
+        };
+        let builder = self
+            .ph
+            .client()
+            .get(format!("{}/models", self.base_url))
+            .header("authorization", format!("Bearer {key}"));
+        Some(self.ph.send(builder).await.map(|_| ()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chat_url_default_and_override() {
+        let ex = MistralExecutor::builder()
+            .http(rquest::Client::new())
+            .build();
+        assert_eq!(ex.chat_url(), "https://api.mistral.ai/v1/chat/completions");
+
+        let ex = MistralExecutor::builder()
+            .http(rquest::Client::new())
+            .base_url("http://localhost:9000/v1/".into())
+            .build();
+        assert_eq!(ex.chat_url(), "http://localhost:9000/v1/chat/completions");
+    }
+
+    #[test]
+    fn test_supported_models() {
+        let ex = MistralExecutor::builder()
+            .http(rquest::Client::new())
+            .build();
+        let models = ex.supported_models();
+        assert!(models.iter().any(|m| m == "mistral-large-latest"));
+        assert!(models.iter().any(|m| m == "codestral-latest"));
+        assert!(models.iter().any(|m| m == "magistral-medium-latest"));
+    }
+
+    #[tokio::test]
+    async fn test_missing_api_key_is_auth_error() {
+        let ex = MistralExecutor::builder()
+            .http(rquest::Client::new())
+            .build();
+        let request: ChatRequest = serde_json::from_value(
+            serde_json::json!({"model": "mistral-large-latest", "messages": []}),
+        )
+        .unwrap();
+        let err = ex.chat_completion(request).await.err().unwrap();
+        assert!(matches!(err, ByokError::Auth(_)));
+    }
+}
@@ -9,10 +9,12 @@ pub mod claude;
 pub mod codex;
 pub mod codex_ws;
 pub mod copilot;
+pub mod custom;
 pub mod gemini;
 pub mod iflow;
 pub mod kimi;
 pub mod kiro;
+pub mod mistral;
 pub mod qwen;
 
 pub use antigravity::AntigravityExecutor;
@@ -20,8 +22,10 @@ pub use claude::ClaudeExecutor;
 pub use codex::CodexExecutor;
 pub use codex_ws::CodexWsExecutor;
 pub use copilot::CopilotExecutor;
+pub use custom::CustomExecutor;
 pub use gemini::GeminiExecutor;
 pub use iflow::IFlowExecutor;
 pub use kimi::KimiExecutor;
 pub use kiro::KiroExecutor;
+pub use mistral::MistralExecutor;
 pub use qwen::QwenExecutor;
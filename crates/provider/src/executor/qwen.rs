@@ -74,8 +74,9 @@ impl QwenExecutor {
         .await
     }
 
-    /// Builds an [`OpenAICompatProvider`] for a single request.
-    fn build_provider(&self, token: String) -> Result<OpenAICompatProvider> {
+    /// DashScope headers the Qwen Code CLI sends on every chat request;
+    /// requests without them are rejected upstream.
+    fn default_headers(&self) -> BTreeMap<String, String> {
         let mut default_headers = BTreeMap::new();
         default_headers.insert("user-agent".to_owned(), self.user_agent.clone());
         default_headers.insert("x-dashscope-useragent".to_owned(), self.user_agent.clone());
@@ -94,6 +95,12 @@ impl QwenExecutor {
         default_headers.insert("x-stainless-retry-count".to_owned(), "0".to_owned());
         default_headers.insert("x-stainless-os".to_owned(), "MacOS".to_owned());
         default_headers.insert("x-stainless-runtime".to_owned(), "node".to_owned());
+        default_headers
+    }
+
+    /// Builds an [`OpenAICompatProvider`] for a single request.
+    fn build_provider(&self, token: String) -> Result<OpenAICompatProvider> {
+        let default_headers = self.default_headers();
 
         OpenAICompatProvider::new(OpenAICompatConfig {
             name: "qwen".to_owned(),
@@ -156,6 +163,7 @@ impl ProviderExecutor for QwenExecutor {
             Ok(ProviderResponse::Stream(byte_stream))
         } else {
             let resp_bytes = resp.bytes().await.map_err(ByokError::from)?;
+            crate::http_util::log_response_body(&resp_bytes);
             let aigw_response = OpenAIResponseTranslator
                 .translate_response(http::StatusCode::OK, &resp_bytes)
                 .map_err(|e| ByokError::Translation(e.to_string()))?;
@@ -168,6 +176,10 @@ impl ProviderExecutor for QwenExecutor {
     fn supported_models(&self) -> Vec<String> {
         registry::models_for_provider(&ProviderId::Qwen)
     }
+
+    fn provider_id(&self) -> Option<ProviderId> {
+        Some(ProviderId::Qwen)
+    }
 }
 
 #[cfg(test)]
@@ -184,4 +196,13 @@ mod tests {
         let ex = make_executor();
         assert!(!ex.supported_models().is_empty());
     }
+
+    #[test]
+    fn test_chat_headers_include_dashscope_headers() {
+        let headers = make_executor().default_headers();
+        assert_eq!(headers["x-dashscope-authtype"], "qwen-oauth");
+        assert_eq!(headers["x-dashscope-useragent"], DEFAULT_USER_AGENT);
+        assert_eq!(headers["x-dashscope-cachecontrol"], "enable");
+        assert_eq!(headers["user-agent"], DEFAULT_USER_AGENT);
+    }
 }
@@ -12,32 +12,209 @@ use byokey_types::{
     traits::{ProviderExecutor, ProviderResponse, Result as ProviderResult},
 };
 use rquest::Client;
+use std::cell::RefCell;
 use std::collections::HashSet;
 use std::hash::BuildHasher;
 use std::sync::Arc;
 
+use crate::breaker::CircuitBreaker;
 use crate::device_profile::DeviceProfileCache;
 use crate::executor::{
     AntigravityExecutor, ClaudeExecutor, CodexExecutor, CodexWsExecutor, CopilotExecutor,
-    GeminiExecutor, IFlowExecutor, KimiExecutor, KiroExecutor, QwenExecutor,
+    CustomExecutor, GeminiExecutor, IFlowExecutor, KimiExecutor, KiroExecutor, MistralExecutor,
+    QwenExecutor,
 };
+use crate::timeout::TimeoutExecutor;
 use crate::versions::VersionStore;
 use crate::{registry, retry};
 
+tokio::task_local! {
+    /// Route taken by the latest request, filled in inside [`capture_route`].
+    static ROUTE: RefCell<RouteInfo>;
+}
+
+/// Which tier of a fallback chain served a request: `0` is the primary
+/// provider, `1` the configured `fallback`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FallbackTier {
+    pub tier: usize,
+    pub provider: ProviderId,
+}
+
+/// How the executor layer routed a request.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RouteInfo {
+    /// Set by fallback-wrapped executors; `None` without a `fallback`.
+    pub fallback: Option<FallbackTier>,
+    /// Index into the provider's configured API keys of the key that
+    /// succeeded; set only by multi-key executors.
+    pub key_index: Option<usize>,
+}
+
+/// Runs `fut`, returning its output along with the route taken by the last
+/// request made through the executor layer while it ran.
+pub async fn capture_route<F: Future>(fut: F) -> (F::Output, RouteInfo) {
+    ROUTE
+        .scope(RefCell::new(RouteInfo::default()), async move {
+            let output = fut.await;
+            let route = ROUTE.with(|slot| slot.take());
+            (output, route)
+        })
+        .await
+}
+
+/// Records into the current [`capture_route`] scope, if any.
+pub(crate) fn record_route(f: impl FnOnce(&mut RouteInfo)) {
+    let _ = ROUTE.try_with(|slot| f(&mut slot.borrow_mut()));
+}
+
+/// Like [`make_executor`], also applying provider settings that executors
+/// take directly (currently Claude's `cache_min_chars`).
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn make_executor_with_settings(
+    provider: &ProviderId,
+    api_key: Option<String>,
+    base_url: Option<String>,
+    cache_min_chars: usize,
+    auth: Arc<AuthManager>,
+    http: Client,
+    ratelimit: Option<Arc<RateLimitStore>>,
+    versions: &VersionStore,
+) -> Option<Box<dyn ProviderExecutor>> {
+    if *provider == ProviderId::Claude {
+        let tracker = api_key.is_none().then(|| Arc::clone(&auth));
+        let executor = Box::new(
+            ClaudeExecutor::builder()
+                .http(http)
+                .auth(auth)
+                .maybe_api_key(api_key)
+                .maybe_base_url(base_url)
+                .maybe_ratelimit(ratelimit)
+                .cache_min_chars(cache_min_chars)
+                .build(),
+        );
+        return Some(track_last_used(executor, provider, tracker));
+    }
+    make_executor(provider, api_key, base_url, auth, http, ratelimit, versions)
+}
+
+/// Marks the OAuth account that served each successful request as used
+/// (see [`AuthManager::mark_used`]).
+struct LastUsedExecutor {
+    inner: Box<dyn ProviderExecutor>,
+    provider: ProviderId,
+    auth: Arc<AuthManager>,
+}
+
+#[async_trait]
+impl ProviderExecutor for LastUsedExecutor {
+    async fn chat_completion(&self, request: ChatRequest) -> ProviderResult<ProviderResponse> {
+        let (response, account) =
+            byokey_auth::track_served_account(self.inner.chat_completion(request)).await;
+        let response = response?;
+        if let Some(account) = account {
+            self.auth.mark_used(&self.provider, account);
+        }
+        Ok(response)
+    }
+
+    fn supported_models(&self) -> Vec<String> {
+        self.inner.supported_models()
+    }
+
+    fn provider_id(&self) -> Option<ProviderId> {
+        self.inner.provider_id()
+    }
+
+    async fn health_check(&self) -> Option<ProviderResult<()>> {
+        self.inner.health_check().await
+    }
+}
+
+/// Wraps `executor` in a [`LastUsedExecutor`] when it authenticates with a
+/// stored OAuth account, i.e. `auth` is set and the provider has accounts.
+fn track_last_used(
+    executor: Box<dyn ProviderExecutor>,
+    provider: &ProviderId,
+    auth: Option<Arc<AuthManager>>,
+) -> Box<dyn ProviderExecutor> {
+    match auth {
+        Some(auth)
+            if !matches!(
+                provider,
+                ProviderId::Mistral | ProviderId::Custom | ProviderId::Amp | ProviderId::Mock
+            ) =>
+        {
+            Box::new(LastUsedExecutor {
+                inner: executor,
+                provider: provider.clone(),
+                auth,
+            })
+        }
+        _ => executor,
+    }
+}
+
 /// Wraps a primary executor with a fallback: if the primary fails, the fallback is tried.
+///
+/// With a [`CircuitBreaker`], retryable primary failures are counted and the
+/// primary is skipped entirely while the breaker is open.
 struct FallbackExecutor {
     primary: Box<dyn ProviderExecutor>,
+    primary_provider: ProviderId,
     fallback: Box<dyn ProviderExecutor>,
+    fallback_provider: ProviderId,
+    breaker: Option<Arc<CircuitBreaker>>,
+}
+
+impl FallbackExecutor {
+    fn served(tier: usize, provider: &ProviderId) {
+        if tier == 0 {
+            tracing::debug!(tier, %provider, "primary provider served request");
+        } else {
+            tracing::info!(tier, %provider, "fallback provider served request");
+        }
+        record_route(|route| {
+            route.fallback = Some(FallbackTier {
+                tier,
+                provider: provider.clone(),
+            });
+        });
+    }
 }
 
 #[async_trait]
 impl ProviderExecutor for FallbackExecutor {
     async fn chat_completion(&self, request: ChatRequest) -> ProviderResult<ProviderResponse> {
+        if self.breaker.as_ref().is_some_and(|b| !b.allow_primary()) {
+            tracing::debug!(provider = %self.primary_provider, "circuit open, skipping primary");
+            let resp = self.fallback.chat_completion(request).await?;
+            Self::served(1, &self.fallback_provider);
+            return Ok(resp);
+        }
         match self.primary.chat_completion(request.clone()).await {
-            Ok(resp) => Ok(resp),
+            Ok(resp) => {
+                if let Some(breaker) = &self.breaker {
+                    breaker.record_success();
+                }
+                Self::served(0, &self.primary_provider);
+                Ok(resp)
+            }
             Err(err) => {
+                if let Some(breaker) = &self.breaker {
+                    if err.is_retryable() {
+                        breaker.record_failure();
+                    } else {
+                        breaker.release_probe();
+                    }
+                }
+                if !crate::budget::allow_retry(std::time::Duration::ZERO) {
+                    return Err(err);
+                }
                 tracing::warn!(error = %err, "primary provider failed, falling back");
-                self.fallback.chat_completion(request).await
+                let resp = self.fallback.chat_completion(request).await?;
+                Self::served(1, &self.fallback_provider);
+                Ok(resp)
             }
         }
     }
@@ -45,6 +222,14 @@ impl ProviderExecutor for FallbackExecutor {
     fn supported_models(&self) -> Vec<String> {
         self.primary.supported_models()
     }
+
+    fn provider_id(&self) -> Option<ProviderId> {
+        Some(self.primary_provider.clone())
+    }
+
+    async fn health_check(&self) -> Option<ProviderResult<()>> {
+        self.primary.health_check().await
+    }
 }
 
 /// Create a boxed executor for the given provider.
@@ -79,7 +264,8 @@ pub fn make_executor_with_cache(
     versions: &VersionStore,
 ) -> Option<Box<dyn ProviderExecutor>> {
     let ua = versions.get(provider).and_then(|v| v.user_agent.clone());
-    match provider {
+    let tracker = api_key.is_none().then(|| Arc::clone(&auth));
+    let executor: Option<Box<dyn ProviderExecutor>> = match provider {
         ProviderId::Claude => Some(Box::new(
             ClaudeExecutor::builder()
                 .http(http)
@@ -174,19 +360,84 @@ pub fn make_executor_with_cache(
                 .build(),
         )),
         ProviderId::Amp => None, // Amp is not a model provider
+        ProviderId::Mistral => Some(Box::new(
+            MistralExecutor::builder()
+                .http(http)
+                .maybe_api_key(api_key)
+                .maybe_base_url(base_url)
+                .maybe_ratelimit(ratelimit)
+                .build(),
+        )),
+        ProviderId::Custom => Some(Box::new(
+            CustomExecutor::builder()
+                .http(http)
+                .maybe_api_key(api_key)
+                .maybe_base_url(base_url)
+                .maybe_ratelimit(ratelimit)
+                .build(),
+        )),
+        #[cfg(feature = "testing")]
+        ProviderId::Mock => crate::mock::installed(),
+        #[cfg(not(feature = "testing"))]
+        ProviderId::Mock => None,
+    };
+    Some(track_last_used(executor?, provider, tracker))
+}
+
+/// Follows `backend` overrides starting at `provider` until reaching a
+/// provider without one, returning that provider and its config, or `None`
+/// when `provider` has no override.
+///
+/// # Errors
+///
+/// Returns [`ByokError::ProviderDisabled`] if any hop has `enabled: false`,
+/// and [`ByokError::Config`] if the chain loops back on itself.
+fn resolve_backend(
+    provider: &ProviderId,
+    config: &ProviderConfig,
+    config_fn: impl Fn(&ProviderId) -> Option<ProviderConfig>,
+) -> Result<Option<(ProviderId, ProviderConfig)>, ByokError> {
+    let Some(mut current) = config.backend.clone() else {
+        return Ok(None);
+    };
+    let mut chain = vec![provider.clone()];
+    loop {
+        if chain.contains(&current) {
+            chain.push(current);
+            let path: Vec<String> = chain.iter().map(ToString::to_string).collect();
+            return Err(ByokError::Config(format!(
+                "backend cycle: {}",
+                path.join(" -> ")
+            )));
+        }
+        let current_config = config_fn(&current).unwrap_or_default();
+        if !current_config.enabled {
+            return Err(ByokError::ProviderDisabled(current));
+        }
+        match current_config.backend.clone() {
+            Some(next) => {
+                chain.push(current);
+                current = next;
+            }
+            None => return Ok(Some((current, current_config))),
+        }
     }
 }
 
 /// Create an executor by resolving the model string to its provider.
 ///
-/// Respects `ProviderConfig::backend` (always route to another provider),
+/// Respects `ProviderConfig::backend` (always route to another provider,
+/// following chains of overrides),
 /// `ProviderConfig::fallback` (wrap with a fallback executor), and
 /// `ProviderConfig::api_keys` (multi-key retry with [`retry::RetryExecutor`]).
 ///
 /// # Errors
 ///
 /// Returns [`ByokError::UnsupportedModel`] if the model string is not recognised
-/// or if the resolved provider does not have an executor implemented yet.
+/// or if the resolved provider does not have an executor implemented yet, and
+/// [`ByokError::ProviderDisabled`] if the resolved provider (or its `backend`
+/// override) has `enabled: false`, and [`ByokError::Config`] if `backend`
+/// overrides form a cycle.
 #[allow(clippy::too_many_arguments)]
 pub fn make_executor_for_model<S: BuildHasher>(
     model: &str,
@@ -208,23 +459,34 @@ pub fn make_executor_for_model<S: BuildHasher>(
                 || oauth_providers.contains(p)
         })
         .or_else(|| registry::resolve_provider(model))
+        .or_else(|| {
+            // Unclaimed models go to the custom endpoint when one is configured.
+            config_fn(&ProviderId::Custom)
+                .is_some_and(|c| c.enabled && c.base_url.is_some())
+                .then_some(ProviderId::Custom)
+        })
         .ok_or_else(|| ByokError::UnsupportedModel(model.to_string()))?
     };
 
     let config = config_fn(&provider).unwrap_or_default();
+    if !config.enabled {
+        return Err(ByokError::ProviderDisabled(provider));
+    }
 
-    // If a backend override is set, route entirely to that provider.
-    if let Some(backend_id) = &config.backend {
-        let backend_config = config_fn(backend_id).unwrap_or_default();
-        return make_executor(
-            backend_id,
+    // If a backend override is set, route entirely to the end of that chain.
+    if let Some((backend_id, backend_config)) = resolve_backend(&provider, &config, &config_fn)? {
+        let timeout = backend_config.request_timeout();
+        return make_executor_with_settings(
+            &backend_id,
             backend_config.api_key,
             backend_config.base_url,
+            backend_config.cache_min_chars,
             auth,
             http,
             ratelimit,
             versions,
         )
+        .map(|executor| TimeoutExecutor::wrap(executor, timeout))
         .ok_or_else(|| ByokError::UnsupportedModel(model.to_string()));
     }
 
@@ -247,30 +509,45 @@ pub fn make_executor_for_model<S: BuildHasher>(
         )
         .map(|e| e.supported_models())
         .unwrap_or_default();
-        let primary: Box<dyn ProviderExecutor> = Box::new(retry::RetryExecutor::new(
-            provider.clone(),
-            credentials,
-            config.routing,
-            Arc::clone(&auth),
-            http.clone(),
-            models,
-            ratelimit.clone(),
-            versions.clone(),
-        ));
+        let primary: Box<dyn ProviderExecutor> = Box::new(
+            retry::RetryExecutor::new(
+                provider.clone(),
+                credentials,
+                config.routing,
+                Arc::clone(&auth),
+                http.clone(),
+                models,
+                ratelimit.clone(),
+                versions.clone(),
+            )
+            .with_retry_config(retry::RetryConfig::from(&config))
+            .with_weights(config.all_api_key_weights())
+            .with_cache_min_chars(config.cache_min_chars)
+            .with_timeout(config.request_timeout()),
+        );
 
         // Wrap with fallback if configured.
         if let Some(fallback_id) = &config.fallback {
             let fallback_config = config_fn(fallback_id).unwrap_or_default();
-            if let Some(fallback) = make_executor(
+            let fallback_timeout = fallback_config.request_timeout();
+            if let Some(fallback) = make_executor_with_settings(
                 fallback_id,
                 fallback_config.api_key,
                 fallback_config.base_url,
+                fallback_config.cache_min_chars,
                 auth,
                 http,
                 ratelimit,
                 versions,
             ) {
-                return Ok(Box::new(FallbackExecutor { primary, fallback }));
+                let fallback = TimeoutExecutor::wrap(fallback, fallback_timeout);
+                return Ok(Box::new(FallbackExecutor {
+                    primary,
+                    primary_provider: provider.clone(),
+                    fallback,
+                    fallback_provider: fallback_id.clone(),
+                    breaker: CircuitBreaker::shared(&provider, fallback_id, config.circuit_breaker),
+                }));
             }
         }
         return Ok(primary);
@@ -278,14 +555,20 @@ pub fn make_executor_for_model<S: BuildHasher>(
 
     // Build the primary executor (single key or OAuth).
     // For Codex with `websocket: true` and no API key, use WebSocket transport.
+    let timeout = config.request_timeout();
     let primary: Box<dyn ProviderExecutor> =
         if provider == ProviderId::Codex && config.websocket && config.api_key.is_none() {
-            Box::new(CodexWsExecutor::new(Arc::clone(&auth)))
+            track_last_used(
+                Box::new(CodexWsExecutor::new(Arc::clone(&auth))),
+                &provider,
+                Some(Arc::clone(&auth)),
+            )
         } else {
-            make_executor(
+            make_executor_with_settings(
                 &provider,
                 config.api_key,
                 config.base_url,
+                config.cache_min_chars,
                 Arc::clone(&auth),
                 http.clone(),
                 ratelimit.clone(),
@@ -293,20 +576,30 @@ pub fn make_executor_for_model<S: BuildHasher>(
             )
             .ok_or_else(|| ByokError::UnsupportedModel(model.to_string()))?
         };
+    let primary = TimeoutExecutor::wrap(primary, timeout);
 
     // If a fallback is configured, wrap in FallbackExecutor.
     if let Some(fallback_id) = &config.fallback {
         let fallback_config = config_fn(fallback_id).unwrap_or_default();
-        if let Some(fallback) = make_executor(
+        let fallback_timeout = fallback_config.request_timeout();
+        if let Some(fallback) = make_executor_with_settings(
             fallback_id,
             fallback_config.api_key,
             fallback_config.base_url,
+            fallback_config.cache_min_chars,
             auth,
             http,
             ratelimit,
             versions,
         ) {
-            return Ok(Box::new(FallbackExecutor { primary, fallback }));
+            let fallback = TimeoutExecutor::wrap(fallback, fallback_timeout);
+            return Ok(Box::new(FallbackExecutor {
+                primary,
+                primary_provider: provider.clone(),
+                fallback,
+                fallback_provider: fallback_id.clone(),
+                breaker: CircuitBreaker::shared(&provider, fallback_id, config.circuit_breaker),
+            }));
         }
     }
 
@@ -522,6 +815,114 @@ mod tests {
         assert!(ex.is_ok());
     }
 
+    #[test]
+    fn test_make_executor_for_model_backend_cycle() {
+        let result = make_executor_for_model(
+            "gemini-2.0-flash",
+            |p| match p {
+                ProviderId::Gemini => Some(ProviderConfig {
+                    backend: Some(ProviderId::Copilot),
+                    ..Default::default()
+                }),
+                ProviderId::Copilot => Some(ProviderConfig {
+                    backend: Some(ProviderId::Gemini),
+                    ..Default::default()
+                }),
+                _ => None,
+            },
+            &empty_oauth(),
+            None,
+            make_auth(),
+            make_http(),
+            None,
+            &ev(),
+        );
+        let Err(ByokError::Config(msg)) = result else {
+            panic!("expected a backend cycle error");
+        };
+        assert_eq!(msg, "backend cycle: gemini -> copilot -> gemini");
+    }
+
+    #[test]
+    fn test_make_executor_for_model_backend_chain() {
+        let ex = make_executor_for_model(
+            "gemini-2.0-flash",
+            |p| match p {
+                ProviderId::Gemini => Some(ProviderConfig {
+                    backend: Some(ProviderId::Copilot),
+                    ..Default::default()
+                }),
+                ProviderId::Copilot => Some(ProviderConfig {
+                    backend: Some(ProviderId::Claude),
+                    ..Default::default()
+                }),
+                _ => None,
+            },
+            &empty_oauth(),
+            None,
+            make_auth(),
+            make_http(),
+            None,
+            &ev(),
+        )
+        .unwrap();
+        assert_eq!(ex.provider_id(), Some(ProviderId::Claude));
+    }
+
+    #[test]
+    fn test_make_executor_for_model_disabled_provider() {
+        let auth = make_auth();
+        let result = make_executor_for_model(
+            "gemini-2.0-flash",
+            |p| match p {
+                ProviderId::Gemini => Some(ProviderConfig {
+                    enabled: false,
+                    ..Default::default()
+                }),
+                _ => None,
+            },
+            &empty_oauth(),
+            Some(&ProviderId::Gemini),
+            auth,
+            make_http(),
+            None,
+            &ev(),
+        );
+        assert!(matches!(
+            result,
+            Err(ByokError::ProviderDisabled(ProviderId::Gemini))
+        ));
+    }
+
+    #[test]
+    fn test_make_executor_for_model_disabled_backend() {
+        let auth = make_auth();
+        let result = make_executor_for_model(
+            "gemini-2.0-flash",
+            |p| match p {
+                ProviderId::Gemini => Some(ProviderConfig {
+                    backend: Some(ProviderId::Copilot),
+                    ..Default::default()
+                }),
+                ProviderId::Copilot => Some(ProviderConfig {
+                    enabled: false,
+                    ..Default::default()
+                }),
+                _ => None,
+            },
+            &empty_oauth(),
+            None,
+            auth,
+            make_http(),
+            None,
+            &ev(),
+        );
+        assert!(matches!(
+            result,
+            Err(ByokError::ProviderDisabled(ProviderId::Copilot))
+        ));
+    }
+
     #[test]
     fn test_make_executor_for_model_fallback() {
         let auth = make_auth();
@@ -562,11 +963,13 @@ mod tests {
                             api_key: "sk-key-1".into(),
                             label: None,
                             base_url: None,
+                            weight: 1,
                         },
                         ApiKeyEntry {
                             api_key: "sk-key-2".into(),
                             label: None,
                             base_url: None,
+                            weight: 1,
                         },
                     ],
                     ..Default::default()
@@ -608,4 +1011,108 @@ mod tests {
         );
         assert!(ex.is_ok());
     }
+
+    #[test]
+    fn test_make_executor_for_model_unknown_falls_back_to_custom() {
+        let custom = |p: &ProviderId| match p {
+            ProviderId::Custom => Some(ProviderConfig {
+                base_url: Some("http://localhost:8000/v1".into()),
+                ..Default::default()
+            }),
+            _ => None,
+        };
+        let ex = make_executor_for_model(
+            "meta-llama/llama-3.1-8b",
+            custom,
+            &empty_oauth(),
+            None,
+            make_auth(),
+            make_http(),
+            None,
+            &ev(),
+        );
+        assert!(ex.is_ok());
+
+        // Without a base_url the custom provider does not claim the model.
+        let result = make_executor_for_model(
+            "meta-llama/llama-3.1-8b",
+            |_| None,
+            &empty_oauth(),
+            None,
+            make_auth(),
+            make_http(),
+            None,
+            &ev(),
+        );
+        assert!(matches!(result, Err(ByokError::UnsupportedModel(_))));
+    }
+
+    struct StubExecutor(bool);
+
+    #[async_trait]
+    impl ProviderExecutor for StubExecutor {
+        async fn chat_completion(&self, _: ChatRequest) -> ProviderResult<ProviderResponse> {
+            if self.0 {
+                Ok(ProviderResponse::Complete(serde_json::json!({})))
+            } else {
+                Err(ByokError::Http("boom".into()))
+            }
+        }
+
+        fn supported_models(&self) -> Vec<String> {
+            Vec::new()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_circuit_breaker_opens_on_failing_primary() {
+        let breaker = Arc::new(CircuitBreaker::new(
+            ProviderId::Gemini,
+            byokey_config::CircuitBreakerConfig {
+                failure_threshold: 2,
+                window_secs: 60,
+                open_secs: 60,
+            },
+        ));
+        let ex = FallbackExecutor {
+            primary: Box::new(StubExecutor(false)),
+            primary_provider: ProviderId::Gemini,
+            fallback: Box::new(StubExecutor(true)),
+            fallback_provider: ProviderId::Copilot,
+            breaker: Some(Arc::clone(&breaker)),
+        };
+        let request: ChatRequest =
+            serde_json::from_value(serde_json::json!({"model": "m", "messages": []})).unwrap();
+        for _ in 0..2 {
+            assert!(breaker.allow_primary());
+            assert!(ex.chat_completion(request.clone()).await.is_ok());
+        }
+        assert!(!breaker.allow_primary());
+        // Served by the fallback without touching the primary.
+        let (result, route) = capture_route(ex.chat_completion(request)).await;
+        assert!(result.is_ok());
+        assert_eq!(route.fallback.map(|f| f.tier), Some(1));
+    }
+
+    #[tokio::test]
+    async fn test_fallback_tier_recorded_on_primary_failure() {
+        let ex = FallbackExecutor {
+            primary: Box::new(StubExecutor(false)),
+            primary_provider: ProviderId::Gemini,
+            fallback: Box::new(StubExecutor(true)),
+            fallback_provider: ProviderId::Copilot,
+            breaker: None,
+        };
+        let request: ChatRequest =
+            serde_json::from_value(serde_json::json!({"model": "m", "messages": []})).unwrap();
+        let (result, route) = capture_route(ex.chat_completion(request)).await;
+        assert!(result.is_ok());
+        assert_eq!(
+            route.fallback,
+            Some(FallbackTier {
+                tier: 1,
+                provider: ProviderId::Copilot,
+            })
+        );
+    }
 }
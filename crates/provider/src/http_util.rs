@@ -8,11 +8,136 @@ use byokey_types::{
     traits::{ByteStream, ProviderResponse, Result},
 };
 use futures_util::StreamExt as _;
-use rquest::{Client, RequestBuilder};
+use rquest::{Client, RequestBuilder, header::HeaderMap};
 use serde_json::Value;
+use std::cell::RefCell;
 use std::collections::HashMap;
 use std::sync::Arc;
 
+tokio::task_local! {
+    /// Headers of the latest upstream response, set inside
+    /// [`capture_response_headers`].
+    static RESPONSE_HEADERS: RefCell<Option<HeaderMap>>;
+}
+
+/// Runs `fut`, returning its output along with the headers of the last
+/// upstream response sent through [`ProviderHttp::send`] while it ran.
+pub async fn capture_response_headers<F: Future>(fut: F) -> (F::Output, Option<HeaderMap>) {
+    RESPONSE_HEADERS
+        .scope(RefCell::new(None), async move {
+            let output = fut.await;
+            let headers = RESPONSE_HEADERS.with(|slot| slot.borrow_mut().take());
+            (output, headers)
+        })
+        .await
+}
+
+tokio::task_local! {
+    /// Byte cap for upstream body logging, set inside [`log_upstream_bodies`].
+    static BODY_LOG_LIMIT: usize;
+}
+
+/// Runs `fut` with the bodies of upstream requests and non-streaming
+/// responses logged at DEBUG level, truncated to `max_bytes` (`0` logs them
+/// in full). `None` runs `fut` without body logging.
+pub async fn log_upstream_bodies<F: Future>(max_bytes: Option<usize>, fut: F) -> F::Output {
+    match max_bytes {
+        Some(max) => BODY_LOG_LIMIT.scope(max, fut).await,
+        None => fut.await,
+    }
+}
+
+/// The active body-log cap, if logging was requested and DEBUG is enabled.
+fn body_log_limit() -> Option<usize> {
+    if !tracing::enabled!(tracing::Level::DEBUG) {
+        return None;
+    }
+    BODY_LOG_LIMIT.try_with(|max| *max).ok()
+}
+
+/// Headers whose values are never written to logs.
+const SECRET_HEADERS: &[&str] = &[
+    "authorization",
+    "cookie",
+    "proxy-authorization",
+    "x-api-key",
+    "x-goog-api-key",
+];
+
+/// Renders `headers` for logging with credential values replaced.
+fn redacted_headers(headers: &HeaderMap) -> Value {
+    headers
+        .iter()
+        .map(|(name, value)| {
+            let value = if SECRET_HEADERS.contains(&name.as_str()) {
+                "[redacted]".to_string()
+            } else {
+                String::from_utf8_lossy(value.as_bytes()).into_owned()
+            };
+            (name.as_str().to_string(), Value::String(value))
+        })
+        .collect::<serde_json::Map<_, _>>()
+        .into()
+}
+
+/// Renders `url` for logging with a `key` query parameter (Gemini API keys)
+/// replaced.
+fn redacted_url(url: &rquest::Url) -> String {
+    if !url.query_pairs().any(|(k, _)| k == "key") {
+        return url.to_string();
+    }
+    let pairs: Vec<(String, String)> = url
+        .query_pairs()
+        .map(|(k, v)| {
+            let v = if k == "key" { "[redacted]".into() } else { v };
+            (k.into_owned(), v.into_owned())
+        })
+        .collect();
+    let mut url = url.clone();
+    url.query_pairs_mut().clear().extend_pairs(pairs);
+    url.to_string()
+}
+
+fn log_request(request: &rquest::Request, max: usize) {
+    let body = request
+        .body()
+        .and_then(rquest::Body::as_bytes)
+        .map(String::from_utf8_lossy)
+        .unwrap_or_default();
+    tracing::debug!(
+        method = %request.method(),
+        url = %redacted_url(request.url()),
+        headers = %redacted_headers(request.headers()),
+        body = %truncate_for_log(&body, max),
+        "upstream request"
+    );
+}
+
+/// Logs a raw upstream response body when body logging is active
+/// (see [`log_upstream_bodies`]).
+pub fn log_response_body(body: &[u8]) {
+    if let Some(max) = body_log_limit() {
+        tracing::debug!(
+            body = %truncate_for_log(&String::from_utf8_lossy(body), max),
+            "upstream response"
+        );
+    }
+}
+
+/// Shortens `body` to at most `max` bytes (on a char boundary) for logging,
+/// appending an ellipsis and the full length. `max == 0` disables truncation.
+#[must_use]
+pub fn truncate_for_log(body: &str, max: usize) -> std::borrow::Cow<'_, str> {
+    if max == 0 || body.len() <= max {
+        return body.into();
+    }
+    let mut end = max;
+    while !body.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}… ({} bytes total)", &body[..end], body.len()).into()
+}
+
 /// Optional rate-limit capture context attached to a `ProviderHttp`.
 #[derive(Clone)]
 struct RateLimitCtx {
@@ -102,15 +227,27 @@ impl ProviderHttp {
     /// Returns `ByokError::Upstream` on non-success HTTP status codes,
     /// or a transport error if the request fails to send.
     pub async fn send(&self, builder: RequestBuilder) -> Result<rquest::Response> {
-        let resp = builder.send().await?;
+        let log_limit = body_log_limit();
+        let resp = if log_limit.is_some() || crate::output_limit::in_scope() {
+            let mut request = builder.build()?;
+            crate::output_limit::clamp_request(&mut request);
+            if let Some(max) = log_limit {
+                log_request(&request, max);
+            }
+            self.http.execute(request).await?
+        } else {
+            builder.send().await?
+        };
         // Capture rate limit headers before consuming the body.
         self.capture_ratelimit_headers(resp.headers());
+        let _ = RESPONSE_HEADERS.try_with(|slot| *slot.borrow_mut() = Some(resp.headers().clone()));
         let status = resp.status();
         if status.is_success() {
             Ok(resp)
         } else {
             let retry_after = parse_retry_after_header(resp.headers());
             let text = resp.text().await.unwrap_or_default();
+            log_response_body(text.as_bytes());
             let retry_after = parse_retry_after_body(&text, status.as_u16()).or(retry_after);
             Err(ByokError::Upstream {
                 status: status.as_u16(),
@@ -137,7 +274,9 @@ impl ProviderHttp {
         if stream {
             Ok(ProviderResponse::Stream(Self::byte_stream(resp)))
         } else {
-            let json: Value = resp.json().await?;
+            let bytes = resp.bytes().await?;
+            log_response_body(&bytes);
+            let json: Value = serde_json::from_slice(&bytes)?;
             Ok(ProviderResponse::Complete(json))
         }
     }
@@ -293,6 +432,42 @@ mod tests {
         let _http2 = http.clone();
     }
 
+    #[test]
+    fn test_redacted_headers_hide_credentials() {
+        let mut headers = HeaderMap::new();
+        headers.insert("authorization", "Bearer sk-secret".parse().unwrap());
+        headers.insert("x-api-key", "sk-ant-secret".parse().unwrap());
+        headers.insert("content-type", "application/json".parse().unwrap());
+        let logged = redacted_headers(&headers);
+        assert_eq!(logged["authorization"], "[redacted]");
+        assert_eq!(logged["x-api-key"], "[redacted]");
+        assert_eq!(logged["content-type"], "application/json");
+        assert!(!logged.to_string().contains("secret"));
+    }
+
+    #[test]
+    fn test_redacted_url_hides_key_param() {
+        let url: rquest::Url = "https://example.com/v1/models?alt=sse&key=AIza-secret"
+            .parse()
+            .unwrap();
+        let logged = redacted_url(&url);
+        assert!(!logged.contains("AIza-secret"));
+        assert!(logged.contains("alt=sse"));
+    }
+
+    #[test]
+    fn test_truncate_for_log_limits_long_bodies() {
+        let body = "x".repeat(100);
+        assert_eq!(truncate_for_log(&body, 0), body);
+        assert_eq!(truncate_for_log(&body, 100), body);
+        assert_eq!(
+            truncate_for_log(&body, 10),
+            format!("{}… (100 bytes total)", "x".repeat(10))
+        );
+        // Never splits a multi-byte character.
+        assert_eq!(truncate_for_log("ééé", 3), "é… (6 bytes total)");
+    }
+
     #[test]
     fn test_with_ratelimit() {
         let store = Arc::new(RateLimitStore::new());
@@ -300,6 +475,13 @@ mod tests {
         assert!(http.rl_ctx.is_some());
     }
 
+    #[tokio::test]
+    async fn test_capture_response_headers_outside_send_is_none() {
+        let (value, headers) = capture_response_headers(async { 7 }).await;
+        assert_eq!(value, 7);
+        assert!(headers.is_none());
+    }
+
     #[test]
     fn test_parse_google_duration_seconds() {
         let d = parse_google_duration("0.847655010s").unwrap();
@@ -8,35 +8,57 @@
 //! - [`http_util`] — Shared HTTP send/stream helpers ([`ProviderHttp`]).
 //! - [`routing`]   — Round-robin API key selection ([`CredentialRouter`]).
 //! - [`retry`]     — Multi-key retry wrapper ([`RetryExecutor`]).
+//! - [`breaker`]   — Circuit breaker in front of fallback primaries.
+//! - [`budget`]    — Per-request cap on retries across all of the above.
+//! - [`output_limit`] — Per-request output token ceiling on upstream bodies.
+//! - [`timeout`]   — Request / first-byte timeout wrapper ([`TimeoutExecutor`]).
+//! - `mock`        — Offline [`ProviderId::Mock`] executor (`testing` feature).
+//!
+//! [`ProviderId::Mock`]: byokey_types::ProviderId::Mock
 
+pub mod breaker;
+pub mod budget;
 pub mod cloak;
 pub mod device_profile;
 pub mod executor;
 pub mod factory;
 pub mod http_util;
+#[cfg(feature = "testing")]
+pub mod mock;
+pub mod output_limit;
 pub mod registry;
 pub mod retry;
 pub mod routing;
 pub mod selector;
 pub mod stream_bridge;
 pub mod thinking;
+pub mod timeout;
 pub mod versions;
 
+pub use budget::{RetryBudget, propagate_retry_budget, with_retry_budget};
 pub use device_profile::DeviceProfileCache;
 pub use executor::{
     AntigravityExecutor, ClaudeExecutor, CodexExecutor, CodexWsExecutor, CopilotExecutor,
-    GeminiExecutor, IFlowExecutor, KimiExecutor, KiroExecutor, QwenExecutor,
+    CustomExecutor, GeminiExecutor, IFlowExecutor, KimiExecutor, KiroExecutor, MistralExecutor,
+    QwenExecutor,
+};
+pub use factory::{
+    FallbackTier, RouteInfo, capture_route, make_executor, make_executor_for_model,
+    make_executor_with_cache,
 };
-pub use factory::{make_executor, make_executor_for_model, make_executor_with_cache};
-pub use http_util::ProviderHttp;
+pub use http_util::{ProviderHttp, capture_response_headers, log_upstream_bodies};
+pub use output_limit::{clamp_output_tokens, with_output_token_ceiling};
 pub use registry::{
-    ModelEntry, ThinkingSupport, all_models, is_copilot_free_model, models_for_provider,
-    parse_qualified_model, resolve_provider, resolve_provider_with, thinking_capability,
+    ModelEntry, ThinkingSupport, all_models, is_copilot_free_model, is_known_model,
+    models_for_provider, parse_qualified_model, parse_qualified_model_with, resolve_provider,
+    resolve_provider_with, set_user_models, temperature_range, thinking_capability,
     thinking_support,
 };
+pub use retry::RetryConfig;
 pub use routing::{CredentialRouter, RoutingStrategy};
 pub use selector::{AccountNode, AccountSelector, RoutingPolicy, StrategyKind};
 pub use thinking::{ModelSuffix, parse_model_suffix};
+pub use timeout::{TimeoutExecutor, send_with_timeout};
 pub use versions::VersionStore;
 
 /// Claude fingerprint constants shared with the proxy crate's `/v1/messages` handler.
@@ -0,0 +1,187 @@
+//! Offline executor for tests (`testing` feature).
+//!
+//! A [`MockExecutor`] answers every request with a canned JSON completion or
+//! scripted SSE stream and records the requests it received. Install it with
+//! [`with_mock`] and address models as `mock/<name>`: inside the scope,
+//! [`make_executor_for_model`] resolves [`ProviderId::Mock`] to it, so router
+//! tests run end to end without network access.
+//!
+//! [`make_executor_for_model`]: crate::make_executor_for_model
+
+use std::cell::RefCell;
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use byokey_types::{
+    ChatRequest, ProviderId,
+    traits::{ProviderExecutor, ProviderResponse, Result},
+};
+use bytes::Bytes;
+use serde_json::Value;
+
+tokio::task_local! {
+    /// Executor returned for [`ProviderId::Mock`] inside [`with_mock`].
+    static MOCK: RefCell<Option<MockExecutor>>;
+}
+
+/// Runs `fut` with `mock` serving [`ProviderId::Mock`].
+pub async fn with_mock<F: Future>(mock: MockExecutor, fut: F) -> F::Output {
+    MOCK.scope(RefCell::new(Some(mock)), fut).await
+}
+
+/// The executor installed by the enclosing [`with_mock`], if any.
+pub(crate) fn installed() -> Option<Box<dyn ProviderExecutor>> {
+    MOCK.try_with(|slot| slot.borrow().clone())
+        .ok()
+        .flatten()
+        .map(|mock| Box::new(mock) as Box<dyn ProviderExecutor>)
+}
+
+#[derive(Debug, Clone)]
+enum Canned {
+    Json(Value),
+    Sse(Vec<String>),
+}
+
+/// Executor returning a canned response. Clones share the recorded requests.
+#[derive(Debug, Clone)]
+pub struct MockExecutor {
+    response: Canned,
+    requests: Arc<Mutex<Vec<ChatRequest>>>,
+}
+
+impl MockExecutor {
+    /// Answers every request with `completion` as a non-streaming response.
+    #[must_use]
+    pub fn json(completion: Value) -> Self {
+        Self {
+            response: Canned::Json(completion),
+            requests: Arc::default(),
+        }
+    }
+
+    /// Answers every request with a stream of `data:` events, one per entry
+    /// in `events` (e.g. `r#"{"choices":[]}"#` or `[DONE]`).
+    #[must_use]
+    pub fn sse<I, S>(events: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            response: Canned::Sse(events.into_iter().map(Into::into).collect()),
+            requests: Arc::default(),
+        }
+    }
+
+    /// Requests received so far, in order, as the executor saw them.
+    #[must_use]
+    pub fn requests(&self) -> Vec<ChatRequest> {
+        self.requests.lock().expect("mock lock poisoned").clone()
+    }
+}
+
+#[async_trait]
+impl ProviderExecutor for MockExecutor {
+    async fn chat_completion(&self, request: ChatRequest) -> Result<ProviderResponse> {
+        self.requests
+            .lock()
+            .expect("mock lock poisoned")
+            .push(request);
+        Ok(match &self.response {
+            Canned::Json(completion) => ProviderResponse::Complete(completion.clone()),
+            Canned::Sse(events) => {
+                let chunks: Vec<Result<Bytes>> = events
+                    .iter()
+                    .map(|event| Ok(Bytes::from(format!("data: {event}\n\n"))))
+                    .collect();
+                ProviderResponse::Stream(Box::pin(futures_util::stream::iter(chunks)))
+            }
+        })
+    }
+
+    fn supported_models(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    fn provider_id(&self) -> Option<ProviderId> {
+        Some(ProviderId::Mock)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{VersionStore, make_executor_for_model, parse_qualified_model};
+    use byokey_auth::AuthManager;
+    use byokey_store::InMemoryTokenStore;
+    use futures_util::TryStreamExt as _;
+    use std::collections::HashSet;
+
+    fn request(model: &str) -> ChatRequest {
+        serde_json::from_value(serde_json::json!({
+            "model": model,
+            "messages": [{"role": "user", "content": "hi"}],
+        }))
+        .unwrap()
+    }
+
+    fn resolve(
+        model: &str,
+    ) -> std::result::Result<Box<dyn ProviderExecutor>, byokey_types::ByokError> {
+        let (hint, model) = parse_qualified_model(model);
+        make_executor_for_model(
+            model,
+            |_| None,
+            &HashSet::<ProviderId>::new(),
+            hint.as_ref(),
+            Arc::new(AuthManager::new(
+                Arc::new(InMemoryTokenStore::new()),
+                rquest::Client::new(),
+            )),
+            rquest::Client::new(),
+            None,
+            &VersionStore::empty(),
+        )
+    }
+
+    #[tokio::test]
+    async fn factory_resolves_mock_only_inside_scope() {
+        assert!(resolve("mock/echo").is_err());
+
+        let mock = MockExecutor::json(serde_json::json!({"id": "chatcmpl-mock"}));
+        let response = with_mock(mock.clone(), async {
+            resolve("mock/echo")
+                .unwrap()
+                .chat_completion(request("echo"))
+                .await
+                .unwrap()
+        })
+        .await;
+
+        let ProviderResponse::Complete(json) = response else {
+            panic!("expected a complete response");
+        };
+        assert_eq!(json["id"], "chatcmpl-mock");
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].model, "echo");
+    }
+
+    #[tokio::test]
+    async fn sse_events_are_framed() {
+        let mock = MockExecutor::sse([r#"{"choices":[]}"#, "[DONE]"]);
+        let ProviderResponse::Stream(stream) = mock.chat_completion(request("m")).await.unwrap()
+        else {
+            panic!("expected a stream");
+        };
+        let chunks: Vec<Bytes> = stream.try_collect().await.unwrap();
+        assert_eq!(
+            chunks,
+            [
+                Bytes::from_static(b"data: {\"choices\":[]}\n\n"),
+                Bytes::from_static(b"data: [DONE]\n\n"),
+            ]
+        );
+    }
+}
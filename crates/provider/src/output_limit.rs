@@ -0,0 +1,195 @@
+//! Per-request ceiling on output tokens, enforced on the upstream body.
+//!
+//! Clients name their limit in their own wire format, and translators may
+//! fill in a default when the client names none, so clamping the incoming
+//! request is not enough. Inside [`with_output_token_ceiling`], every request
+//! sent through [`ProviderHttp`] has its final, translated body clamped.
+//!
+//! [`ProviderHttp`]: crate::http_util::ProviderHttp
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use serde_json::{Map, Value};
+
+tokio::task_local! {
+    /// Ceiling of the request being handled, set inside [`with_output_token_ceiling`].
+    static CEILING: Arc<OutputCeiling>;
+}
+
+#[derive(Debug)]
+struct OutputCeiling {
+    ceiling: u64,
+    /// Whether any upstream body had to be clamped.
+    clamped: AtomicBool,
+}
+
+/// Top-level limit fields: `OpenAI` chat, Anthropic messages, `OpenAI` Responses.
+const TOP_LEVEL_FIELDS: &[&str] = &["max_tokens", "max_completion_tokens", "max_output_tokens"];
+
+/// Clamps every output token limit in `body` down to `ceiling`, in any wire
+/// format byokey sends: the top-level fields above, Gemini's
+/// `generationConfig.maxOutputTokens`, and the same inside a Code Assist
+/// `request` envelope. A missing limit is left alone. Returns whether a
+/// value changed.
+pub fn clamp_output_tokens(body: &mut Map<String, Value>, ceiling: u64) -> bool {
+    let mut clamped = false;
+    for field in TOP_LEVEL_FIELDS {
+        clamped |= clamp_field(body, field, ceiling);
+    }
+    if let Some(config) = body
+        .get_mut("generationConfig")
+        .and_then(Value::as_object_mut)
+    {
+        clamped |= clamp_field(config, "maxOutputTokens", ceiling);
+    }
+    if let Some(config) = body
+        .get_mut("request")
+        .and_then(|r| r.get_mut("generationConfig"))
+        .and_then(Value::as_object_mut)
+    {
+        clamped |= clamp_field(config, "maxOutputTokens", ceiling);
+    }
+    clamped
+}
+
+fn clamp_field(obj: &mut Map<String, Value>, field: &str, ceiling: u64) -> bool {
+    let Some(requested) = obj.get(field).and_then(Value::as_u64) else {
+        return false;
+    };
+    if requested <= ceiling {
+        return false;
+    }
+    tracing::warn!(field, requested, ceiling, "clamping output token limit");
+    obj.insert(field.to_string(), Value::from(ceiling));
+    true
+}
+
+/// Runs `fut` with upstream output token limits clamped to `ceiling`.
+/// Returns the output and whether an upstream body had to be clamped.
+pub async fn with_output_token_ceiling<F: Future>(
+    ceiling: Option<u64>,
+    fut: F,
+) -> (F::Output, bool) {
+    let Some(ceiling) = ceiling else {
+        return (fut.await, false);
+    };
+    let state = Arc::new(OutputCeiling {
+        ceiling,
+        clamped: AtomicBool::new(false),
+    });
+    let output = CEILING.scope(Arc::clone(&state), fut).await;
+    (output, state.clamped.load(Ordering::Relaxed))
+}
+
+/// Whether a ceiling is in scope, i.e. request bodies must be inspected.
+pub(crate) fn in_scope() -> bool {
+    CEILING.try_with(|_| ()).is_ok()
+}
+
+/// Clamps the JSON body of `request` to the ceiling in scope, if any.
+pub(crate) fn clamp_request(request: &mut rquest::Request) {
+    let Ok(state) = CEILING.try_with(Arc::clone) else {
+        return;
+    };
+    let Some(Ok(Value::Object(mut body))) = request
+        .body()
+        .and_then(rquest::Body::as_bytes)
+        .map(serde_json::from_slice::<Value>)
+    else {
+        return;
+    };
+    if !clamp_output_tokens(&mut body, state.ceiling) {
+        return;
+    }
+    if let Ok(bytes) = serde_json::to_vec(&body) {
+        *request.body_mut() = Some(bytes.into());
+        state.clamped.store(true, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use byokey_types::{ChatRequest, traits::ProviderExecutor as _};
+    use serde_json::json;
+    use tokio::io::{AsyncReadExt as _, AsyncWriteExt as _};
+
+    #[test]
+    fn clamps_every_wire_format() {
+        let mut body = json!({
+            "max_tokens": 9000,
+            "max_completion_tokens": 10,
+            "max_output_tokens": 9000,
+            "generationConfig": {"maxOutputTokens": 9000},
+            "request": {"generationConfig": {"maxOutputTokens": 9000}},
+        });
+        assert!(clamp_output_tokens(body.as_object_mut().unwrap(), 100));
+        assert_eq!(
+            body,
+            json!({
+                "max_tokens": 100,
+                "max_completion_tokens": 10,
+                "max_output_tokens": 100,
+                "generationConfig": {"maxOutputTokens": 100},
+                "request": {"generationConfig": {"maxOutputTokens": 100}},
+            })
+        );
+        assert!(!clamp_output_tokens(body.as_object_mut().unwrap(), 100));
+    }
+
+    /// Accepts one HTTP request, answers 500 and returns its JSON body.
+    async fn capture_body(listener: tokio::net::TcpListener) -> Value {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut buf = Vec::new();
+        let mut chunk = [0u8; 4096];
+        let body_start = loop {
+            let n = socket.read(&mut chunk).await.unwrap();
+            buf.extend_from_slice(&chunk[..n]);
+            if let Some(pos) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+                break pos + 4;
+            }
+        };
+        let head = String::from_utf8_lossy(&buf[..body_start]).to_ascii_lowercase();
+        let length: usize = head
+            .lines()
+            .find_map(|l| l.strip_prefix("content-length:"))
+            .map_or(0, |v| v.trim().parse().unwrap());
+        while buf.len() < body_start + length {
+            let n = socket.read(&mut chunk).await.unwrap();
+            buf.extend_from_slice(&chunk[..n]);
+        }
+        socket
+            .write_all(b"HTTP/1.1 500 Internal Server Error\r\ncontent-length: 0\r\n\r\n")
+            .await
+            .unwrap();
+        serde_json::from_slice(&buf[body_start..body_start + length]).unwrap()
+    }
+
+    #[tokio::test]
+    async fn translated_default_is_clamped_through_executor() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let server = tokio::spawn(capture_body(listener));
+
+        let (client, auth) = crate::http_util::test_auth();
+        let executor = crate::ClaudeExecutor::builder()
+            .http(client)
+            .auth(auth)
+            .api_key("sk-test".into())
+            .base_url(base_url)
+            .build();
+        // No limit from the client: the Anthropic translation supplies one.
+        let request: ChatRequest = serde_json::from_value(json!({
+            "model": "claude-opus-4-5",
+            "messages": [{"role": "user", "content": "hi"}],
+        }))
+        .unwrap();
+
+        let (result, clamped) =
+            with_output_token_ceiling(Some(16), executor.chat_completion(request)).await;
+        assert!(result.is_err());
+        assert!(clamped);
+        assert_eq!(server.await.unwrap()["max_tokens"], 16);
+    }
+}
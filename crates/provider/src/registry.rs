@@ -1,6 +1,30 @@
 //! Model registry: static model lists and provider resolution.
+//!
+//! A user model file (`models_file` in the config) installed with
+//! [`set_user_models`] is consulted before the compiled-in tables.
 
+use arc_swap::ArcSwapOption;
+use byokey_config::ModelRegistryFile;
 use byokey_types::{ProviderId, ThinkingCapability};
+use std::sync::{Arc, LazyLock, RwLock};
+
+static USER_MODELS: LazyLock<RwLock<Arc<ArcSwapOption<ModelRegistryFile>>>> =
+    LazyLock::new(RwLock::default);
+
+/// Makes resolution follow `models`, a live user model registry (typically
+/// `ConfigWatcher::models`, which is swapped on hot reload) merged over the
+/// built-in one.
+///
+/// # Panics
+///
+/// Panics if the registry lock is poisoned.
+pub fn set_user_models(models: Arc<ArcSwapOption<ModelRegistryFile>>) {
+    *USER_MODELS.write().expect("user models lock") = models;
+}
+
+fn user_models() -> Option<Arc<ModelRegistryFile>> {
+    USER_MODELS.read().expect("user models lock").load_full()
+}
 
 /// Per-model thinking configuration support metadata.
 pub struct ThinkingSupport {
@@ -22,6 +46,9 @@ pub struct ModelEntry {
     pub providers: &'static [ProviderId],
     /// Thinking support metadata, if the model supports extended thinking.
     pub thinking: Option<&'static ThinkingSupport>,
+    /// Whether the model needs the long-output (128k) `anthropic-beta` flag
+    /// to accept large `max_tokens` values.
+    pub long_output: bool,
 }
 
 /// Unified model registry. Provider order within each entry determines
@@ -32,123 +59,147 @@ const REGISTRY: &[ModelEntry] = &[
         id: "o3",
         providers: &[ProviderId::Codex],
         thinking: None,
+        long_output: false,
     },
     ModelEntry {
         id: "o4-mini",
         providers: &[ProviderId::Codex],
         thinking: None,
+        long_output: false,
     },
     ModelEntry {
         id: "gpt-4-turbo",
         providers: &[ProviderId::Codex],
         thinking: None,
+        long_output: false,
     },
     ModelEntry {
         id: "gpt-4",
         providers: &[ProviderId::Codex],
         thinking: None,
+        long_output: false,
     },
     // Codex-primary, also on Copilot
     ModelEntry {
         id: "gpt-5.4",
         providers: &[ProviderId::Codex, ProviderId::Copilot],
         thinking: None,
+        long_output: false,
     },
     ModelEntry {
         id: "gpt-5.4-mini",
         providers: &[ProviderId::Codex, ProviderId::Copilot],
         thinking: None,
+        long_output: false,
     },
     ModelEntry {
         id: "gpt-5.4-nano",
         providers: &[ProviderId::Codex],
         thinking: None,
+        long_output: false,
     },
     ModelEntry {
         id: "gpt-5.3-codex",
         providers: &[ProviderId::Codex, ProviderId::Copilot],
         thinking: None,
+        long_output: false,
     },
     ModelEntry {
         id: "gpt-5.3-codex-spark",
         providers: &[ProviderId::Codex],
         thinking: None,
+        long_output: false,
     },
     ModelEntry {
         id: "gpt-5.2-codex",
         providers: &[ProviderId::Codex, ProviderId::Copilot],
         thinking: None,
+        long_output: false,
     },
     ModelEntry {
         id: "gpt-5.2",
         providers: &[ProviderId::Codex, ProviderId::Copilot],
         thinking: None,
+        long_output: false,
     },
     ModelEntry {
         id: "gpt-5.1-codex-max",
         providers: &[ProviderId::Codex, ProviderId::Copilot],
         thinking: None,
+        long_output: false,
     },
     ModelEntry {
         id: "gpt-5.1-codex",
         providers: &[ProviderId::Codex, ProviderId::Copilot],
         thinking: None,
+        long_output: false,
     },
     ModelEntry {
         id: "gpt-5.1-codex-mini",
         providers: &[ProviderId::Codex, ProviderId::Copilot],
         thinking: None,
+        long_output: false,
     },
     ModelEntry {
         id: "gpt-5.1",
         providers: &[ProviderId::Codex, ProviderId::Copilot],
         thinking: None,
+        long_output: false,
     },
     ModelEntry {
         id: "gpt-5-codex",
         providers: &[ProviderId::Codex],
         thinking: None,
+        long_output: false,
     },
     ModelEntry {
         id: "gpt-5-codex-mini",
         providers: &[ProviderId::Codex],
         thinking: None,
+        long_output: false,
     },
     ModelEntry {
         id: "gpt-5",
         providers: &[ProviderId::Codex, ProviderId::Copilot],
         thinking: None,
+        long_output: false,
     },
     // Copilot-only
     ModelEntry {
         id: "gpt-4o",
         providers: &[ProviderId::Copilot],
         thinking: None,
+        long_output: false,
     },
     ModelEntry {
         id: "gpt-4.1",
         providers: &[ProviderId::Copilot],
         thinking: None,
+        long_output: false,
     },
     ModelEntry {
         id: "gpt-5-mini",
         providers: &[ProviderId::Copilot],
         thinking: None,
+        long_output: false,
     },
     ModelEntry {
         id: "raptor-mini",
         providers: &[ProviderId::Copilot],
         thinking: None,
+        long_output: false,
     },
     ModelEntry {
         id: "goldeneye",
         providers: &[ProviderId::Copilot],
         thinking: None,
+        long_output: false,
     },
     ModelEntry {
         id: "grok-code-fast-1",
         providers: &[ProviderId::Copilot],
         thinking: None,
+        long_output: false,
     },
     // Claude (Anthropic — dashes)
     ModelEntry {
@@ -160,187 +211,275 @@ const REGISTRY: &[ModelEntry] = &[
             levels: &["low", "medium", "high", "max"],
             zero_allowed: false,
         }),
+        long_output: false,
     },
     ModelEntry {
         id: "claude-opus-4-5",
         providers: &[ProviderId::Claude],
         thinking: None,
+        long_output: false,
     },
     ModelEntry {
         id: "claude-sonnet-4-5",
         providers: &[ProviderId::Claude, ProviderId::Antigravity],
         thinking: None,
+        long_output: false,
     },
     ModelEntry {
         id: "claude-haiku-4-5-20251001",
         providers: &[ProviderId::Claude],
         thinking: None,
+        long_output: false,
+    },
+    // 128k output only behind `output-128k-2025-02-19`.
+    ModelEntry {
+        id: "claude-3-7-sonnet-20250219",
+        providers: &[ProviderId::Claude],
+        thinking: None,
+        long_output: true,
     },
     // Copilot Claude (GitHub — dots)
     ModelEntry {
         id: "claude-opus-4.6",
         providers: &[ProviderId::Copilot],
         thinking: None,
+        long_output: false,
     },
     ModelEntry {
         id: "claude-opus-4.5",
         providers: &[ProviderId::Copilot],
         thinking: None,
+        long_output: false,
     },
     ModelEntry {
         id: "claude-sonnet-4.6",
         providers: &[ProviderId::Copilot],
         thinking: None,
+        long_output: false,
     },
     ModelEntry {
         id: "claude-sonnet-4.5",
         providers: &[ProviderId::Copilot],
         thinking: None,
+        long_output: false,
     },
     ModelEntry {
         id: "claude-sonnet-4",
         providers: &[ProviderId::Copilot],
         thinking: None,
+        long_output: false,
     },
     ModelEntry {
         id: "claude-haiku-4.5",
         providers: &[ProviderId::Copilot],
         thinking: None,
+        long_output: false,
     },
     // Gemini (Google AI)
     ModelEntry {
         id: "gemini-2.0-flash",
         providers: &[ProviderId::Gemini],
         thinking: None,
+        long_output: false,
     },
     ModelEntry {
         id: "gemini-2.0-flash-lite",
         providers: &[ProviderId::Gemini],
         thinking: None,
+        long_output: false,
     },
     ModelEntry {
         id: "gemini-1.5-pro",
         providers: &[ProviderId::Gemini],
         thinking: None,
+        long_output: false,
     },
     ModelEntry {
         id: "gemini-1.5-flash",
         providers: &[ProviderId::Gemini],
         thinking: None,
+        long_output: false,
     },
     // Copilot Gemini (GitHub)
     ModelEntry {
         id: "gemini-2.5-pro",
         providers: &[ProviderId::Copilot],
         thinking: None,
+        long_output: false,
     },
     ModelEntry {
         id: "gemini-3-flash",
         providers: &[ProviderId::Copilot],
         thinking: None,
+        long_output: false,
     },
     ModelEntry {
         id: "gemini-3-pro",
         providers: &[ProviderId::Copilot],
         thinking: None,
+        long_output: false,
     },
     ModelEntry {
         id: "gemini-3.1-pro",
         providers: &[ProviderId::Copilot],
         thinking: None,
+        long_output: false,
     },
     // Kiro
     ModelEntry {
         id: "kiro-default",
         providers: &[ProviderId::Kiro],
         thinking: None,
+        long_output: false,
     },
     // Antigravity
     ModelEntry {
         id: "ag-gemini-2.5-flash",
         providers: &[ProviderId::Antigravity],
         thinking: None,
+        long_output: false,
     },
     ModelEntry {
         id: "ag-gemini-2.5-pro",
         providers: &[ProviderId::Antigravity],
         thinking: None,
+        long_output: false,
     },
     ModelEntry {
         id: "ag-claude-sonnet-4-5",
         providers: &[ProviderId::Antigravity],
         thinking: None,
+        long_output: false,
     },
     // Qwen
     ModelEntry {
         id: "qwen3-coder-plus",
         providers: &[ProviderId::Qwen],
         thinking: None,
+        long_output: false,
     },
     ModelEntry {
         id: "qwen3-235b-a22b",
         providers: &[ProviderId::Qwen],
         thinking: None,
+        long_output: false,
     },
     ModelEntry {
         id: "qwen3-32b",
         providers: &[ProviderId::Qwen],
         thinking: None,
+        long_output: false,
     },
     ModelEntry {
         id: "qwen3-14b",
         providers: &[ProviderId::Qwen],
         thinking: None,
+        long_output: false,
     },
     ModelEntry {
         id: "qwen3-8b",
         providers: &[ProviderId::Qwen],
         thinking: None,
+        long_output: false,
     },
     ModelEntry {
         id: "qwen3-max",
         providers: &[ProviderId::Qwen],
         thinking: None,
+        long_output: false,
     },
     ModelEntry {
         id: "qwen-plus",
         providers: &[ProviderId::Qwen],
         thinking: None,
+        long_output: false,
     },
     ModelEntry {
         id: "qwen-turbo",
         providers: &[ProviderId::Qwen],
         thinking: None,
+        long_output: false,
     },
     // Kimi
     ModelEntry {
         id: "kimi-k2-0711",
         providers: &[ProviderId::Kimi],
         thinking: None,
+        long_output: false,
     },
     // iFlow
     ModelEntry {
         id: "glm-4.5",
         providers: &[ProviderId::IFlow],
         thinking: None,
+        long_output: false,
     },
     ModelEntry {
         id: "glm-4.5-air",
         providers: &[ProviderId::IFlow],
         thinking: None,
+        long_output: false,
     },
     ModelEntry {
         id: "glm-z1-flash",
         providers: &[ProviderId::IFlow],
         thinking: None,
+        long_output: false,
     },
     ModelEntry {
         id: "kimi-k2",
         providers: &[ProviderId::IFlow],
         thinking: None,
+        long_output: false,
+    },
+    // Mistral
+    ModelEntry {
+        id: "mistral-large-latest",
+        providers: &[ProviderId::Mistral],
+        thinking: None,
+        long_output: false,
+    },
+    ModelEntry {
+        id: "mistral-medium-latest",
+        providers: &[ProviderId::Mistral],
+        thinking: None,
+        long_output: false,
+    },
+    ModelEntry {
+        id: "mistral-small-latest",
+        providers: &[ProviderId::Mistral],
+        thinking: None,
+        long_output: false,
+    },
+    ModelEntry {
+        id: "codestral-latest",
+        providers: &[ProviderId::Mistral],
+        thinking: None,
+        long_output: false,
+    },
+    ModelEntry {
+        id: "magistral-medium-latest",
+        providers: &[ProviderId::Mistral],
+        thinking: None,
+        long_output: false,
+    },
+    ModelEntry {
+        id: "magistral-small-latest",
+        providers: &[ProviderId::Mistral],
+        thinking: None,
+        long_output: false,
     },
 ];
 
+/// Model-name prefixes that route to a provider when the exact id is not in
+/// [`REGISTRY`], for vendors whose dated snapshots (e.g.
+/// `mistral-large-2411`) outpace the static list.
+const PREFIX_ROUTES: &[(&str, ProviderId)] = &[
+    ("mistral-", ProviderId::Mistral),
+    ("codestral-", ProviderId::Mistral),
+    ("magistral-", ProviderId::Mistral),
+];
+
 /// Returns the full model registry.
 #[must_use]
 pub fn all_models() -> &'static [ModelEntry] {
@@ -361,13 +500,35 @@ pub fn parse_qualified_model(model: &str) -> (Option<ProviderId>, &str) {
     (None, model)
 }
 
+/// Like [`parse_qualified_model`], but also routes models starting with
+/// `custom_prefix` (the custom provider's `model_prefix`) to
+/// [`ProviderId::Custom`], stripping the prefix.
+#[must_use]
+pub fn parse_qualified_model_with<'a>(
+    model: &'a str,
+    custom_prefix: Option<&str>,
+) -> (Option<ProviderId>, &'a str) {
+    if let Some(rest) = custom_prefix
+        .filter(|p| !p.is_empty())
+        .and_then(|p| model.strip_prefix(p))
+        && !rest.is_empty()
+    {
+        return (Some(ProviderId::Custom), rest);
+    }
+    parse_qualified_model(model)
+}
+
 /// Resolve a model string to its backing provider, considering only providers
-/// for which `filter` returns `true`. Uses REGISTRY order (first match wins).
+/// for which `filter` returns `true`. The user model registry wins, then
+/// REGISTRY order (first match wins), then [`PREFIX_ROUTES`].
 #[must_use]
 pub fn resolve_provider_with<F>(model: &str, filter: F) -> Option<ProviderId>
 where
     F: Fn(&ProviderId) -> bool,
 {
+    if let Some(provider) = user_models().and_then(|m| m.resolve_provider_with(model, &filter)) {
+        return Some(provider);
+    }
     for entry in REGISTRY {
         if entry.id == model {
             for provider in entry.providers {
@@ -377,7 +538,10 @@ where
             }
         }
     }
-    None
+    PREFIX_ROUTES
+        .iter()
+        .find(|(prefix, provider)| model.starts_with(prefix) && filter(provider))
+        .map(|(_, provider)| provider.clone())
 }
 
 /// Map a model string to its backing provider.
@@ -387,6 +551,14 @@ pub fn resolve_provider(model: &str) -> Option<ProviderId> {
     resolve_provider_with(model, |_| true)
 }
 
+/// Returns `true` if `model` is a registry model id: built in or advertised
+/// by the user model registry. Pattern routes do not count.
+#[must_use]
+pub fn is_known_model(model: &str) -> bool {
+    REGISTRY.iter().any(|e| e.id == model)
+        || user_models().is_some_and(|m| m.models.values().flatten().any(|id| id == model))
+}
+
 /// Returns the thinking support metadata for a model, if it supports extended thinking.
 #[must_use]
 pub fn thinking_support(model: &str) -> Option<&'static ThinkingSupport> {
@@ -396,6 +568,12 @@ pub fn thinking_support(model: &str) -> Option<&'static ThinkingSupport> {
         .and_then(|e| e.thinking)
 }
 
+/// Returns `true` if the model needs the long-output `anthropic-beta` flag.
+#[must_use]
+pub fn needs_long_output_beta(model: &str) -> bool {
+    REGISTRY.iter().any(|e| e.id == model && e.long_output)
+}
+
 /// Returns the thinking capability classification for a model.
 #[must_use]
 pub fn thinking_capability(model: &str) -> Option<ThinkingCapability> {
@@ -421,13 +599,40 @@ pub fn is_copilot_free_model(model: &str) -> bool {
 /// Returns the model list for a given provider.
 ///
 /// Models served by multiple providers will appear in each provider's list.
+/// Models the user model registry lists for `provider` are appended.
 #[must_use]
 pub fn models_for_provider(provider: &ProviderId) -> Vec<String> {
-    REGISTRY
+    let mut models: Vec<String> = REGISTRY
         .iter()
         .filter(|entry| entry.providers.contains(provider))
         .map(|entry| entry.id.to_string())
-        .collect()
+        .collect();
+    if let Some(extra) = user_models().as_ref().and_then(|m| m.models.get(provider)) {
+        for model in extra {
+            if !models.contains(model) {
+                models.push(model.clone());
+            }
+        }
+    }
+    models
+}
+
+/// Returns the `temperature` range `(min, max)` the provider's API accepts,
+/// or `None` if it is unknown (e.g. custom endpoints).
+#[must_use]
+pub fn temperature_range(provider: &ProviderId) -> Option<(f64, f64)> {
+    match provider {
+        ProviderId::Claude | ProviderId::Kiro | ProviderId::Kimi | ProviderId::IFlow => {
+            Some((0.0, 1.0))
+        }
+        ProviderId::Codex
+        | ProviderId::Copilot
+        | ProviderId::Gemini
+        | ProviderId::Antigravity
+        | ProviderId::Qwen => Some((0.0, 2.0)),
+        ProviderId::Mistral => Some((0.0, 1.5)),
+        ProviderId::Amp | ProviderId::Custom | ProviderId::Mock => None,
+    }
 }
 
 /// Returns model entries that are served by more than one provider.
@@ -464,6 +669,34 @@ mod tests {
         assert_eq!(resolve_provider("gemini-1.5-pro"), Some(ProviderId::Gemini));
     }
 
+    #[test]
+    fn test_user_models_route_before_builtin() {
+        let file = ModelRegistryFile {
+            routes: vec![byokey_config::ModelRoute {
+                pattern: "zz-frontier-*".into(),
+                provider: ProviderId::Claude,
+            }],
+            ..Default::default()
+        };
+        assert_eq!(resolve_provider("zz-frontier-1"), None);
+        let live = Arc::new(ArcSwapOption::from_pointee(file));
+        set_user_models(Arc::clone(&live));
+        assert_eq!(resolve_provider("zz-frontier-1"), Some(ProviderId::Claude));
+        assert_eq!(
+            resolve_provider_with("zz-frontier-1", |p| *p != ProviderId::Claude),
+            None
+        );
+        live.store(None);
+        assert_eq!(resolve_provider("zz-frontier-1"), None);
+    }
+
+    #[test]
+    fn test_temperature_range() {
+        assert_eq!(temperature_range(&ProviderId::Claude), Some((0.0, 1.0)));
+        assert_eq!(temperature_range(&ProviderId::Codex), Some((0.0, 2.0)));
+        assert_eq!(temperature_range(&ProviderId::Custom), None);
+    }
+
     #[test]
     fn test_resolve_kiro() {
         assert_eq!(resolve_provider("kiro-default"), Some(ProviderId::Kiro));
@@ -554,6 +787,31 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_resolve_mistral() {
+        assert_eq!(
+            resolve_provider("mistral-large-latest"),
+            Some(ProviderId::Mistral)
+        );
+        // Snapshots not in the registry resolve by prefix.
+        assert_eq!(
+            resolve_provider("mistral-large-2411"),
+            Some(ProviderId::Mistral)
+        );
+        assert_eq!(
+            resolve_provider("codestral-2501"),
+            Some(ProviderId::Mistral)
+        );
+        assert_eq!(
+            resolve_provider("magistral-small-2509"),
+            Some(ProviderId::Mistral)
+        );
+        assert_eq!(
+            resolve_provider_with("mistral-large-2411", |p| *p != ProviderId::Mistral),
+            None
+        );
+    }
+
     #[test]
     fn test_resolve_unknown() {
         assert_eq!(resolve_provider("unknown-model"), None);
@@ -563,8 +821,8 @@ mod tests {
     #[test]
     fn test_model_lists_non_empty() {
         for provider in ProviderId::all() {
-            if *provider == ProviderId::Amp {
-                continue; // Amp is not a model provider
+            if matches!(provider, ProviderId::Amp | ProviderId::Custom) {
+                continue; // Amp is not a model provider; Custom models are user-defined
             }
             let models = models_for_provider(provider);
             assert!(
@@ -671,6 +929,21 @@ mod tests {
         assert_eq!(m, "copilot/");
     }
 
+    #[test]
+    fn test_parse_qualified_model_with_custom_prefix() {
+        let (p, m) = parse_qualified_model_with("or:meta-llama/llama-3", Some("or:"));
+        assert_eq!(p, Some(ProviderId::Custom));
+        assert_eq!(m, "meta-llama/llama-3");
+
+        let (p, m) = parse_qualified_model_with("custom/qwen3-8b", None);
+        assert_eq!(p, Some(ProviderId::Custom));
+        assert_eq!(m, "qwen3-8b");
+
+        let (p, m) = parse_qualified_model_with("codex/gpt-5.1", Some("or:"));
+        assert_eq!(p, Some(ProviderId::Codex));
+        assert_eq!(m, "gpt-5.1");
+    }
+
     #[test]
     fn test_all_models_non_empty() {
         assert!(!all_models().is_empty());
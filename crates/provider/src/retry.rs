@@ -2,17 +2,20 @@
 //!
 //! When a provider has multiple API keys configured, the `RetryExecutor`
 //! tries each key in round-robin order (using [`CredentialRouter`]) until
-//! a request succeeds or all keys are exhausted / in cooldown.
+//! a request succeeds, all keys are exhausted / in cooldown, or the
+//! request's [retry budget](crate::budget) runs out.
 
 use crate::routing::{CredentialRouter, RoutingStrategy};
+use crate::timeout::TimeoutExecutor;
 use crate::versions::VersionStore;
 use async_trait::async_trait;
 use byokey_auth::AuthManager;
-use byokey_config::KeyRoutingStrategy;
+use byokey_config::{KeyRoutingStrategy, ProviderConfig};
 use byokey_types::{
     ChatRequest, ProviderId, RateLimitStore,
     traits::{ProviderExecutor, ProviderResponse, Result},
 };
+use rand::Rng;
 use rquest::Client;
 use std::collections::HashMap;
 use std::{sync::Arc, time::Duration};
@@ -20,6 +23,88 @@ use std::{sync::Arc, time::Duration};
 /// Default cooldown duration for a key after a retryable error.
 const COOLDOWN_DURATION: Duration = Duration::from_secs(30);
 
+/// Backoff between key rotations in [`RetryExecutor`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryConfig {
+    /// Delay before the first retry.
+    pub base_delay: Duration,
+    /// Upper bound on the computed delay.
+    pub max_delay: Duration,
+    /// Factor applied to the delay on each further retry.
+    pub multiplier: f64,
+    /// Use full jitter: a uniformly random delay in `[0, computed]`.
+    pub jitter: bool,
+    /// Longest upstream `Retry-After` worth waiting for; a longer one ends
+    /// the retries instead.
+    pub max_retry_after: Duration,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            base_delay: Duration::from_millis(250),
+            max_delay: Duration::from_secs(8),
+            multiplier: 2.0,
+            jitter: true,
+            max_retry_after: Duration::from_secs(10),
+        }
+    }
+}
+
+impl From<&ProviderConfig> for RetryConfig {
+    fn from(config: &ProviderConfig) -> Self {
+        let backoff = &config.retry_backoff;
+        Self {
+            base_delay: Duration::from_millis(backoff.base_delay_ms),
+            max_delay: Duration::from_millis(backoff.max_delay_ms),
+            multiplier: backoff.multiplier,
+            jitter: config.retry_jitter,
+            max_retry_after: Duration::from_secs(backoff.max_retry_after_secs),
+        }
+    }
+}
+
+impl RetryConfig {
+    /// Computes the delay before retry number `attempt` (0-based).
+    ///
+    /// Without jitter this is `base_delay * multiplier^attempt`, capped at
+    /// `max_delay`; with full jitter it is a uniformly random duration in
+    /// `[0, that]`.
+    fn backoff_delay<R: Rng + ?Sized>(&self, attempt: u32, rng: &mut R) -> Duration {
+        let factor = self
+            .multiplier
+            .max(1.0)
+            .powi(i32::try_from(attempt).unwrap_or(i32::MAX));
+        let ceiling = Duration::try_from_secs_f64(self.base_delay.as_secs_f64() * factor)
+            .unwrap_or(self.max_delay)
+            .min(self.max_delay);
+        if self.jitter {
+            ceiling.mul_f64(rng.gen_range(0.0..=1.0))
+        } else {
+            ceiling
+        }
+    }
+
+    /// Delay before retry number `attempt`, or `None` to stop retrying.
+    ///
+    /// The upstream's `Retry-After` only concerns the key that failed, so it
+    /// is waited out only when no other key is ready; past
+    /// `max_retry_after` it ends the retries. Otherwise this is the computed
+    /// backoff.
+    fn retry_delay<R: Rng + ?Sized>(
+        &self,
+        attempt: u32,
+        retry_after: Option<Duration>,
+        other_key_ready: bool,
+        rng: &mut R,
+    ) -> Option<Duration> {
+        match retry_after {
+            Some(delay) if !other_key_ready => (delay <= self.max_retry_after).then_some(delay),
+            _ => Some(self.backoff_delay(attempt, rng)),
+        }
+    }
+}
+
 /// Wraps a provider with multi-key retry: on retryable errors, marks the
 /// current key as cooled down and retries with the next available key.
 ///
@@ -27,7 +112,11 @@ const COOLDOWN_DURATION: Duration = Duration::from_secs(30);
 /// official and third-party endpoints for the same provider type.
 pub struct RetryExecutor {
     provider: ProviderId,
-    router: Arc<CredentialRouter>,
+    strategy: RoutingStrategy,
+    /// Per-key round-robin weights, parallel to `keys`.
+    weights: Vec<u32>,
+    /// Keys in configured order, for reporting which one served a request.
+    keys: Vec<String>,
     /// Per-key base URL overrides.
     base_urls: HashMap<String, Option<String>>,
     auth: Arc<AuthManager>,
@@ -35,6 +124,10 @@ pub struct RetryExecutor {
     models: Vec<String>,
     ratelimit: Option<Arc<RateLimitStore>>,
     versions: VersionStore,
+    retry: RetryConfig,
+    cache_min_chars: usize,
+    /// Limit on each attempt (see [`TimeoutExecutor`]).
+    timeout: Option<Duration>,
 }
 
 impl RetryExecutor {
@@ -47,6 +140,9 @@ impl RetryExecutor {
     /// - `RoundRobin`: rotate evenly across all keys.
     /// - `Priority`: always prefer the first ready key, only try later keys on failure.
     ///
+    /// Key selection state is shared by every executor built for the same
+    /// keys (see [`CredentialRouter::shared`]).
+    ///
     /// # Panics
     ///
     /// Panics if `credentials` is empty (propagated from [`CredentialRouter::new`]).
@@ -62,6 +158,10 @@ impl RetryExecutor {
         versions: VersionStore,
     ) -> Self {
         let keys: Vec<String> = credentials.iter().map(|(k, _)| k.clone()).collect();
+        assert!(
+            !keys.is_empty(),
+            "CredentialRouter requires at least one key"
+        );
         let base_urls: HashMap<String, Option<String>> = credentials.into_iter().collect();
         let routing_strategy = match strategy {
             KeyRoutingStrategy::RoundRobin => RoutingStrategy::RoundRobin,
@@ -69,39 +169,111 @@ impl RetryExecutor {
         };
         Self {
             provider,
-            router: Arc::new(
-                CredentialRouter::new(keys, COOLDOWN_DURATION).with_strategy(routing_strategy),
-            ),
+            strategy: routing_strategy,
+            weights: vec![1; keys.len()],
+            keys,
             base_urls,
             auth,
             http,
             models,
             ratelimit,
             versions,
+            retry: RetryConfig::default(),
+            cache_min_chars: byokey_config::DEFAULT_CACHE_MIN_CHARS,
+            timeout: None,
         }
     }
+
+    /// Enables or disables full jitter on the backoff between retries.
+    #[must_use]
+    pub fn with_jitter(mut self, jitter: bool) -> Self {
+        self.retry.jitter = jitter;
+        self
+    }
+
+    /// Sets per-key round-robin weights, in the same order as `credentials`.
+    #[must_use]
+    pub fn with_weights(mut self, weights: Vec<u32>) -> Self {
+        self.weights = (0..self.keys.len())
+            .map(|i| weights.get(i).copied().unwrap_or(1))
+            .collect();
+        self
+    }
+
+    /// The process-wide router for this executor's keys.
+    fn router(&self) -> Arc<CredentialRouter> {
+        CredentialRouter::shared(
+            &self.provider,
+            &self.keys,
+            COOLDOWN_DURATION,
+            self.strategy,
+            &self.weights,
+        )
+    }
+
+    /// Sets the prompt-caching threshold passed to Claude executors
+    /// (see `ProviderConfig::cache_min_chars`).
+    #[must_use]
+    pub fn with_cache_min_chars(mut self, cache_min_chars: usize) -> Self {
+        self.cache_min_chars = cache_min_chars;
+        self
+    }
+
+    /// Bounds each attempt by `timeout`; a timed-out attempt rotates to the
+    /// next key like any retryable error.
+    #[must_use]
+    pub fn with_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Replaces the backoff between retries (see
+    /// [`ProviderConfig::retry_backoff`]).
+    ///
+    /// [`ProviderConfig::retry_backoff`]: byokey_config::ProviderConfig::retry_backoff
+    #[must_use]
+    pub fn with_retry_config(mut self, retry: RetryConfig) -> Self {
+        self.retry = retry;
+        self
+    }
 }
 
 #[async_trait]
 impl ProviderExecutor for RetryExecutor {
     async fn chat_completion(&self, request: ChatRequest) -> Result<ProviderResponse> {
-        let max_attempts = self
-            .router
-            .max_retry()
-            .unwrap_or_else(|| self.router.len().min(3));
-        let mut last_err = None;
-
-        for _ in 0..max_attempts {
-            let key = match self.router.next_key() {
+        let router = self.router();
+        let max_attempts = router.max_retry().unwrap_or_else(|| router.len().min(3));
+        let mut last_err: Option<byokey_types::ByokError> = None;
+
+        for attempt in 0..max_attempts {
+            if attempt > 0 {
+                let Some(delay) = self.retry.retry_delay(
+                    u32::try_from(attempt - 1).unwrap_or(u32::MAX),
+                    last_err
+                        .as_ref()
+                        .and_then(byokey_types::ByokError::retry_after),
+                    router.has_ready_key(),
+                    &mut rand::thread_rng(),
+                ) else {
+                    break;
+                };
+                if !crate::budget::allow_retry(delay) {
+                    break;
+                }
+                tokio::time::sleep(delay).await;
+            }
+
+            let key = match router.next_key() {
                 Some(k) => k.to_string(),
                 None => break, // all keys in cooldown
             };
 
             let base_url = self.base_urls.get(&key).cloned().flatten();
-            let executor = crate::factory::make_executor(
+            let executor = crate::factory::make_executor_with_settings(
                 &self.provider,
                 Some(key.clone()),
                 base_url,
+                self.cache_min_chars,
                 Arc::clone(&self.auth),
                 self.http.clone(),
                 self.ratelimit.clone(),
@@ -111,9 +283,14 @@ impl ProviderExecutor for RetryExecutor {
             let Some(executor) = executor else {
                 break;
             };
+            let executor = TimeoutExecutor::wrap(executor, self.timeout);
 
             match executor.chat_completion(request.clone()).await {
-                Ok(resp) => return Ok(resp),
+                Ok(resp) => {
+                    let index = self.keys.iter().position(|k| *k == key);
+                    crate::factory::record_route(|route| route.key_index = index);
+                    return Ok(resp);
+                }
                 Err(e) if e.is_retryable() => {
                     tracing::warn!(
                         provider = %self.provider,
@@ -121,9 +298,9 @@ impl ProviderExecutor for RetryExecutor {
                         "retryable error, rotating key"
                     );
                     if let Some(delay) = e.retry_after() {
-                        self.router.mark_error_with_delay(&key, delay);
+                        router.mark_error_with_delay(&key, delay);
                     } else {
-                        self.router.mark_error(&key);
+                        router.mark_error(&key);
                     }
                     last_err = Some(e);
                 }
@@ -142,6 +319,10 @@ impl ProviderExecutor for RetryExecutor {
     fn supported_models(&self) -> Vec<String> {
         self.models.clone()
     }
+
+    fn provider_id(&self) -> Option<ProviderId> {
+        Some(self.provider.clone())
+    }
 }
 
 #[cfg(test)]
@@ -189,4 +370,187 @@ mod tests {
         );
         assert_eq!(exec.supported_models().len(), 1);
     }
+
+    #[test]
+    fn test_weights_spread_across_executors() {
+        // Executors are rebuilt per request; the weighted rotation must carry
+        // over between them rather than restart at the first key.
+        let build = || {
+            RetryExecutor::new(
+                ProviderId::Claude,
+                vec![("spread-a".into(), None), ("spread-b".into(), None)],
+                KeyRoutingStrategy::RoundRobin,
+                make_auth(),
+                Client::new(),
+                Vec::new(),
+                None,
+                VersionStore::empty(),
+            )
+            .with_weights(vec![3, 1])
+        };
+        let picks: Vec<String> = (0..8)
+            .map(|_| build().router().next_key().unwrap().to_string())
+            .collect();
+        assert_eq!(picks.iter().filter(|k| *k == "spread-a").count(), 6);
+        assert_eq!(picks.iter().filter(|k| *k == "spread-b").count(), 2);
+    }
+
+    fn fixed(base: Duration) -> RetryConfig {
+        RetryConfig {
+            base_delay: base,
+            max_delay: base * 32,
+            multiplier: 2.0,
+            jitter: false,
+            max_retry_after: Duration::from_secs(10),
+        }
+    }
+
+    #[test]
+    fn test_backoff_without_jitter_is_exponential() {
+        let mut rng = rand::thread_rng();
+        let base = Duration::from_millis(100);
+        let config = fixed(base);
+        assert_eq!(config.backoff_delay(0, &mut rng), base);
+        assert_eq!(config.backoff_delay(3, &mut rng), base * 8);
+        assert_eq!(config.backoff_delay(40, &mut rng), base * 32);
+        assert_eq!(config.backoff_delay(u32::MAX, &mut rng), base * 32);
+    }
+
+    #[test]
+    fn test_backoff_custom_multiplier() {
+        let mut rng = rand::thread_rng();
+        let config = RetryConfig {
+            multiplier: 3.0,
+            ..fixed(Duration::from_millis(100))
+        };
+        assert_eq!(
+            config.backoff_delay(2, &mut rng),
+            Duration::from_millis(900)
+        );
+    }
+
+    #[test]
+    fn test_backoff_jitter_within_bounds() {
+        use rand::SeedableRng as _;
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        let base = Duration::from_millis(100);
+        let config = RetryConfig {
+            jitter: true,
+            ..fixed(base)
+        };
+        for attempt in 0..=5 {
+            let ceiling = base * (1 << attempt);
+            let delays: Vec<Duration> = (0..200)
+                .map(|_| config.backoff_delay(attempt, &mut rng))
+                .collect();
+            assert!(delays.iter().all(|d| *d <= ceiling));
+            // Full jitter spreads delays rather than pinning them to the ceiling.
+            assert!(delays.iter().any(|d| *d < ceiling / 2));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retry_budget_caps_attempts_with_keys_left() {
+        use crate::budget::{RetryBudget, with_retry_budget};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        // Every connection is dropped unanswered, a retryable transport error.
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}/v1", listener.local_addr().unwrap());
+        let connections = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&connections);
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                counter.fetch_add(1, Ordering::SeqCst);
+                drop(stream);
+            }
+        });
+
+        let exec = RetryExecutor::new(
+            ProviderId::Custom,
+            (1..=5)
+                .map(|i| (format!("key-{i}"), Some(base_url.clone())))
+                .collect(),
+            KeyRoutingStrategy::default(),
+            make_auth(),
+            Client::new(),
+            Vec::new(),
+            None,
+            VersionStore::empty(),
+        )
+        .with_retry_config(fixed(Duration::from_millis(1)));
+        let request: ChatRequest = serde_json::from_value(serde_json::json!({
+            "model": "m",
+            "messages": [{"role": "user", "content": "hi"}],
+        }))
+        .unwrap();
+
+        let result = with_retry_budget(
+            RetryBudget::new(2, Duration::ZERO),
+            exec.chat_completion(request),
+        )
+        .await;
+
+        assert!(result.is_err());
+        assert_eq!(connections.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_retry_after_overrides_backoff() {
+        let mut rng = rand::thread_rng();
+        let config = fixed(Duration::from_millis(100));
+        assert_eq!(
+            config.retry_delay(0, Some(Duration::from_secs(7)), false, &mut rng),
+            Some(Duration::from_secs(7))
+        );
+        assert_eq!(
+            config.retry_delay(1, None, false, &mut rng),
+            Some(Duration::from_millis(200))
+        );
+    }
+
+    #[test]
+    fn test_retry_after_skipped_when_another_key_is_ready() {
+        let mut rng = rand::thread_rng();
+        let config = fixed(Duration::from_millis(100));
+        assert_eq!(
+            config.retry_delay(0, Some(Duration::from_secs(7)), true, &mut rng),
+            Some(Duration::from_millis(100))
+        );
+    }
+
+    #[test]
+    fn test_retry_after_beyond_cap_stops_retrying() {
+        let mut rng = rand::thread_rng();
+        let config = fixed(Duration::from_millis(100));
+        assert_eq!(
+            config.retry_delay(0, Some(Duration::from_secs(600)), false, &mut rng),
+            None
+        );
+        // The cap does not matter when another key can take the retry.
+        assert!(
+            config
+                .retry_delay(0, Some(Duration::from_secs(600)), true, &mut rng)
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn test_retry_config_from_provider_config() {
+        let mut pc = ProviderConfig {
+            retry_jitter: false,
+            ..Default::default()
+        };
+        pc.retry_backoff.base_delay_ms = 40;
+        pc.retry_backoff.max_retry_after_secs = 2;
+        let config = RetryConfig::from(&pc);
+        assert_eq!(config.base_delay, Duration::from_millis(40));
+        assert_eq!(config.max_retry_after, Duration::from_secs(2));
+        assert!(!config.jitter);
+        assert_eq!(
+            RetryConfig::from(&ProviderConfig::default()),
+            RetryConfig::default()
+        );
+    }
 }
@@ -1,11 +1,23 @@
 //! Credential routing — API key selection with configurable strategy and per-key state machine.
+//!
+//! Executors are built per request, so [`CredentialRouter::shared`] keeps
+//! routers in a process-wide registry: the round-robin position and key
+//! cooldowns carry over between requests.
 
-use std::sync::Mutex;
+use byokey_types::ProviderId;
+use std::collections::HashMap;
 use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, LazyLock, Mutex};
 use std::time::{Duration, Instant};
 
+/// Identifies one key set: provider, keys, strategy and weights.
+type RouterKey = (ProviderId, Vec<String>, RoutingStrategy, Vec<u32>);
+
+static ROUTERS: LazyLock<Mutex<HashMap<RouterKey, Arc<CredentialRouter>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
 /// Strategy used to select the next available key.
-#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
 pub enum RoutingStrategy {
     /// Rotate through keys evenly (default).
     #[default]
@@ -34,6 +46,8 @@ pub struct CredentialRouter {
     keys: Vec<String>,
     /// Per-key state, indexed in parallel with `keys`.
     states: Mutex<Vec<KeyState>>,
+    /// Per-key share of round-robin traffic, parallel to `keys`.
+    weights: Vec<u32>,
     /// Atomic counter for round-robin selection.
     index: AtomicUsize,
     /// How long a key stays in cooldown after an error.
@@ -62,6 +76,7 @@ impl CredentialRouter {
         Self {
             keys,
             states: Mutex::new(vec![KeyState::Ready; len]),
+            weights: vec![1; len],
             index: AtomicUsize::new(0),
             cooldown_duration,
             strategy: RoutingStrategy::default(),
@@ -69,6 +84,34 @@ impl CredentialRouter {
         }
     }
 
+    /// Returns the shared router for `provider`'s `keys` with the given
+    /// strategy and weights, creating it on first use. A config change that
+    /// alters any of them starts a fresh router.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `keys` is empty or the registry mutex is poisoned.
+    #[must_use]
+    pub fn shared(
+        provider: &ProviderId,
+        keys: &[String],
+        cooldown_duration: Duration,
+        strategy: RoutingStrategy,
+        weights: &[u32],
+    ) -> Arc<Self> {
+        let mut routers = ROUTERS.lock().expect("routers lock");
+        let entry = routers
+            .entry((provider.clone(), keys.to_vec(), strategy, weights.to_vec()))
+            .or_insert_with(|| {
+                Arc::new(
+                    Self::new(keys.to_vec(), cooldown_duration)
+                        .with_strategy(strategy)
+                        .with_weights(weights.to_vec()),
+                )
+            });
+        Arc::clone(entry)
+    }
+
     /// Builder: set the routing strategy.
     #[must_use]
     pub fn with_strategy(mut self, strategy: RoutingStrategy) -> Self {
@@ -76,6 +119,35 @@ impl CredentialRouter {
         self
     }
 
+    /// Builder: set per-key round-robin weights, in the same order as the keys.
+    ///
+    /// A key with weight 3 is picked first three times as often as a key with
+    /// weight 1. Missing entries default to 1; if every weight is 0 the keys
+    /// are rotated evenly.
+    #[must_use]
+    pub fn with_weights(mut self, weights: Vec<u32>) -> Self {
+        self.weights = (0..self.keys.len())
+            .map(|i| weights.get(i).copied().unwrap_or(1))
+            .collect();
+        self
+    }
+
+    /// Maps a round-robin tick to the key index that should be tried first.
+    fn weighted_start(&self, tick: usize) -> usize {
+        let total: u64 = self.weights.iter().map(|&w| u64::from(w)).sum();
+        if total == 0 {
+            return tick % self.keys.len();
+        }
+        let mut slot = u64::try_from(tick).unwrap_or_default() % total;
+        for (idx, &w) in self.weights.iter().enumerate() {
+            if slot < u64::from(w) {
+                return idx;
+            }
+            slot -= u64::from(w);
+        }
+        0
+    }
+
     /// Builder: set the maximum number of keys to try before giving up.
     #[must_use]
     pub fn with_max_retry(mut self, max: usize) -> Self {
@@ -107,7 +179,8 @@ impl CredentialRouter {
     ///    [`KeyState::Ready`].
     /// 2. Filters to `Ready` entries only.
     /// 3. Applies the strategy:
-    ///    - [`RoundRobin`](RoutingStrategy::RoundRobin): uses the atomic index.
+    ///    - [`RoundRobin`](RoutingStrategy::RoundRobin): uses the atomic index,
+    ///      spread across keys in proportion to their weights.
     ///    - [`FillFirst`](RoutingStrategy::FillFirst): returns the first ready
     ///      key.
     ///
@@ -133,7 +206,7 @@ impl CredentialRouter {
         // Step 2+3: select based on strategy.
         match self.strategy {
             RoutingStrategy::RoundRobin => {
-                let start = self.index.fetch_add(1, Ordering::Relaxed) % len;
+                let start = self.weighted_start(self.index.fetch_add(1, Ordering::Relaxed));
                 for i in 0..len {
                     let idx = (start + i) % len;
                     if states[idx] == KeyState::Ready {
@@ -153,6 +226,26 @@ impl CredentialRouter {
         }
     }
 
+    /// Returns `true` if some key is ready now (or its cooldown has expired),
+    /// without advancing the rotation.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the internal mutex is poisoned.
+    #[must_use]
+    pub fn has_ready_key(&self) -> bool {
+        let now = Instant::now();
+        self.states
+            .lock()
+            .expect("states lock")
+            .iter()
+            .any(|state| match state {
+                KeyState::Ready => true,
+                KeyState::Cooldown { until } => now >= *until,
+                KeyState::Blocked | KeyState::Disabled => false,
+            })
+    }
+
     /// Marks a key as having encountered an error, placing it in cooldown
     /// for the default duration.
     ///
@@ -273,6 +366,17 @@ mod tests {
         assert!(router.next_key().is_none());
     }
 
+    #[test]
+    fn test_has_ready_key() {
+        let router = CredentialRouter::new(vec!["a".into(), "b".into()], Duration::from_secs(60));
+        router.mark_error("a");
+        assert!(router.has_ready_key());
+        router.mark_blocked("b");
+        assert!(!router.has_ready_key());
+        router.mark_error_with_delay("a", Duration::ZERO);
+        assert!(router.has_ready_key());
+    }
+
     #[test]
     fn test_clear_cooldown() {
         let router = CredentialRouter::new(vec!["key-a".into()], Duration::from_mins(1));
@@ -419,6 +523,48 @@ mod tests {
         assert_eq!(router.next_key().unwrap(), "a");
     }
 
+    #[test]
+    fn test_weighted_round_robin() {
+        let router =
+            CredentialRouter::new(vec!["key-a".into(), "key-b".into()], Duration::from_mins(1))
+                .with_weights(vec![3, 1]);
+        let picks: Vec<String> = (0..8)
+            .map(|_| router.next_key().unwrap().to_string())
+            .collect();
+        assert_eq!(
+            picks,
+            [
+                "key-a", "key-a", "key-a", "key-b", "key-a", "key-a", "key-a", "key-b"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_shared_router_is_reused() {
+        let keys = vec!["shared-a".to_string(), "shared-b".to_string()];
+        let get = |weights: &[u32]| {
+            CredentialRouter::shared(
+                &ProviderId::Claude,
+                &keys,
+                Duration::from_mins(1),
+                RoutingStrategy::RoundRobin,
+                weights,
+            )
+        };
+        assert!(Arc::ptr_eq(&get(&[1, 1]), &get(&[1, 1])));
+        assert!(!Arc::ptr_eq(&get(&[1, 1]), &get(&[2, 1])));
+    }
+
+    #[test]
+    fn test_weighted_round_robin_skips_cooled() {
+        let router =
+            CredentialRouter::new(vec!["key-a".into(), "key-b".into()], Duration::from_mins(1))
+                .with_weights(vec![3, 1]);
+        router.mark_error("key-a");
+        assert_eq!(router.next_key().unwrap(), "key-b");
+        assert_eq!(router.next_key().unwrap(), "key-b");
+    }
+
     #[test]
     fn test_all_blocked_returns_none() {
         let router =
@@ -0,0 +1,203 @@
+//! Timeout executor — bounds how long an upstream call may hold a worker.
+//!
+//! Non-streaming calls must complete within the request timeout. Streaming
+//! calls get a separate first-byte deadline covering connection, response
+//! headers and the first body chunk; once data flows the stream is not cut.
+//!
+//! The factory applies it to each upstream attempt (every key of a
+//! [`RetryExecutor`](crate::retry::RetryExecutor) and the fallback), so a
+//! stalled attempt fails over instead of aborting the whole request.
+//! Handlers that call upstreams directly use [`send_with_timeout`].
+
+use async_trait::async_trait;
+use byokey_types::{
+    ByokError, ChatRequest, ProviderId,
+    traits::{ProviderExecutor, ProviderResponse, Result},
+};
+use futures_util::{StreamExt as _, stream};
+use std::time::Duration;
+use tokio::time::{Instant, timeout, timeout_at};
+
+/// Wraps an executor with request and first-byte timeouts.
+pub struct TimeoutExecutor {
+    inner: Box<dyn ProviderExecutor>,
+    timeout: Duration,
+    first_byte_timeout: Duration,
+}
+
+impl TimeoutExecutor {
+    /// Wraps `inner`: non-streaming calls fail after `timeout`, streaming
+    /// calls if no data arrives within `first_byte_timeout`.
+    #[must_use]
+    pub fn new(
+        inner: Box<dyn ProviderExecutor>,
+        timeout: Duration,
+        first_byte_timeout: Duration,
+    ) -> Self {
+        Self {
+            inner,
+            timeout,
+            first_byte_timeout,
+        }
+    }
+
+    /// Wraps `inner` with `timeout` (see `ProviderConfig::request_timeout`)
+    /// as both the request and first-byte limit, or returns it unchanged
+    /// when `None`.
+    #[must_use]
+    pub fn wrap(
+        inner: Box<dyn ProviderExecutor>,
+        timeout: Option<Duration>,
+    ) -> Box<dyn ProviderExecutor> {
+        match timeout {
+            Some(timeout) => Box::new(Self::new(inner, timeout, timeout)),
+            None => inner,
+        }
+    }
+}
+
+/// Sends `builder`, failing with [`ByokError::Timeout`] if the response
+/// headers do not arrive within `timeout`. Non-streaming requests must also
+/// finish reading their body in time; streaming bodies are not cut.
+///
+/// # Errors
+///
+/// Returns [`ByokError::Timeout`] on expiry and [`ByokError::Http`] on
+/// transport failures.
+pub async fn send_with_timeout(
+    builder: rquest::RequestBuilder,
+    timeout: Option<Duration>,
+    stream: bool,
+) -> Result<rquest::Response> {
+    let Some(limit) = timeout else {
+        return Ok(builder.send().await?);
+    };
+    let builder = if stream {
+        builder
+    } else {
+        builder.timeout(limit)
+    };
+    match tokio::time::timeout(limit, builder.send()).await {
+        Ok(Ok(response)) => Ok(response),
+        Ok(Err(e)) if e.is_timeout() => Err(ByokError::Timeout(limit)),
+        Ok(Err(e)) => Err(e.into()),
+        Err(_) => Err(ByokError::Timeout(limit)),
+    }
+}
+
+#[async_trait]
+impl ProviderExecutor for TimeoutExecutor {
+    async fn chat_completion(&self, request: ChatRequest) -> Result<ProviderResponse> {
+        if !request.stream {
+            return timeout(self.timeout, self.inner.chat_completion(request))
+                .await
+                .map_err(|_| ByokError::Timeout(self.timeout))?;
+        }
+
+        let expired = || ByokError::Timeout(self.first_byte_timeout);
+        let deadline = Instant::now() + self.first_byte_timeout;
+        let response = timeout_at(deadline, self.inner.chat_completion(request))
+            .await
+            .map_err(|_| expired())??;
+        let ProviderResponse::Stream(mut inner) = response else {
+            return Ok(response);
+        };
+        let first = timeout_at(deadline, inner.next())
+            .await
+            .map_err(|_| expired())?;
+        Ok(ProviderResponse::Stream(Box::pin(
+            stream::iter(first).chain(inner),
+        )))
+    }
+
+    fn supported_models(&self) -> Vec<String> {
+        self.inner.supported_models()
+    }
+
+    fn provider_id(&self) -> Option<ProviderId> {
+        self.inner.provider_id()
+    }
+
+    async fn health_check(&self) -> Option<Result<()>> {
+        timeout(self.timeout, self.inner.health_check())
+            .await
+            .unwrap_or(Some(Err(ByokError::Timeout(self.timeout))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::Bytes;
+
+    /// Never answers non-streaming calls; streams respond at once but stall
+    /// before the first chunk unless `chunk` is set.
+    struct Stalled {
+        chunk: bool,
+    }
+
+    #[async_trait]
+    impl ProviderExecutor for Stalled {
+        async fn chat_completion(&self, request: ChatRequest) -> Result<ProviderResponse> {
+            if !request.stream {
+                std::future::pending::<()>().await;
+            }
+            let first = self.chunk.then(|| Ok(Bytes::from_static(b"data: {}\n\n")));
+            Ok(ProviderResponse::Stream(Box::pin(
+                stream::iter(first).chain(stream::pending()),
+            )))
+        }
+
+        fn supported_models(&self) -> Vec<String> {
+            Vec::new()
+        }
+    }
+
+    fn request(stream: bool) -> ChatRequest {
+        serde_json::from_value(serde_json::json!({
+            "model": "m",
+            "messages": [],
+            "stream": stream,
+        }))
+        .unwrap()
+    }
+
+    fn wrap(chunk: bool) -> TimeoutExecutor {
+        TimeoutExecutor::new(
+            Box::new(Stalled { chunk }),
+            Duration::from_secs(5),
+            Duration::from_secs(15),
+        )
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn stalled_request_times_out() {
+        let err = wrap(false)
+            .chat_completion(request(false))
+            .await
+            .err()
+            .unwrap();
+        assert!(matches!(err, ByokError::Timeout(d) if d == Duration::from_secs(5)));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn stream_without_first_byte_times_out() {
+        let err = wrap(false)
+            .chat_completion(request(true))
+            .await
+            .err()
+            .unwrap();
+        assert!(matches!(err, ByokError::Timeout(d) if d == Duration::from_secs(15)));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn stream_keeps_first_chunk() {
+        let Ok(ProviderResponse::Stream(mut stream)) =
+            wrap(true).chat_completion(request(true)).await
+        else {
+            panic!("expected a stream");
+        };
+        let chunk = stream.next().await.unwrap().unwrap();
+        assert_eq!(&chunk[..], b"data: {}\n\n");
+    }
+}
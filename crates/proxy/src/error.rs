@@ -2,7 +2,7 @@
 
 use axum::{
     Json,
-    http::StatusCode,
+    http::{HeaderValue, StatusCode, header},
     response::{IntoResponse, Response},
 };
 use byokey_types::ByokError;
@@ -35,6 +35,11 @@ impl ApiError {
                 "invalid_request_error",
                 "provider_not_found",
             ),
+            ByokError::ProviderDisabled(_) => (
+                StatusCode::FORBIDDEN,
+                "permission_error",
+                "provider_disabled",
+            ),
             ByokError::Translation(_) => (
                 StatusCode::BAD_REQUEST,
                 "invalid_request_error",
@@ -42,6 +47,16 @@ impl ApiError {
             ),
             ByokError::Upstream { status, .. } => classify_upstream(*status),
             ByokError::Http(_) => (StatusCode::BAD_GATEWAY, "server_error", "upstream_error"),
+            ByokError::Timeout(_) => (
+                StatusCode::GATEWAY_TIMEOUT,
+                "server_error",
+                "upstream_timeout",
+            ),
+            ByokError::ProviderBusy(_) => (
+                StatusCode::SERVICE_UNAVAILABLE,
+                "server_error",
+                "provider_busy",
+            ),
             _ => (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 "server_error",
@@ -49,6 +64,23 @@ impl ApiError {
             ),
         }
     }
+
+    /// Converts into a response, copying `retry-after` and `x-ratelimit-*`
+    /// from the `upstream` response headers when the upstream returned 429,
+    /// so clients can run their own backoff.
+    pub(crate) fn into_response_with_upstream(
+        self,
+        upstream: Option<&rquest::header::HeaderMap>,
+    ) -> Response {
+        let rate_limited = matches!(self.0, ByokError::Upstream { status: 429, .. });
+        let mut response = self.into_response();
+        if rate_limited && let Some(upstream) = upstream {
+            crate::util::forward_upstream_headers(upstream, &mut response, |name| {
+                name == "retry-after" || name.starts_with("x-ratelimit-")
+            });
+        }
+        response
+    }
 }
 
 fn classify_upstream(status: u16) -> (StatusCode, &'static str, &'static str) {
@@ -86,17 +118,37 @@ impl IntoResponse for ApiError {
             }
             other => other.to_string(),
         };
-        (
-            status,
-            Json(json!({
-                "error": {
-                    "message": msg,
-                    "type": error_type,
-                    "code": error_code,
-                }
-            })),
-        )
-            .into_response()
+        // Whole seconds, rounded up so clients never retry early.
+        let retry_after_secs = self
+            .0
+            .retry_after()
+            .map(|d| d.as_secs() + u64::from(d.subsec_nanos() > 0));
+        let mut error = json!({
+            "message": msg,
+            "type": error_type,
+            "code": error_code,
+        });
+        if let Some(secs) = retry_after_secs {
+            error["retry_after_seconds"] = secs.into();
+        }
+        let upstream_failure = match &self.0 {
+            ByokError::Upstream { status, .. } => Some(status.to_string()),
+            ByokError::Timeout(_) => Some("timeout".to_string()),
+            ByokError::Http(_) => Some("connection".to_string()),
+            _ => None,
+        };
+        let mut response = (status, Json(json!({ "error": error }))).into_response();
+        if let Some(kind) = upstream_failure {
+            response
+                .extensions_mut()
+                .insert(crate::metrics::UpstreamFailure(kind));
+        }
+        if let Some(secs) = retry_after_secs {
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, HeaderValue::from(secs));
+        }
+        response
     }
 }
 
@@ -169,6 +221,46 @@ mod tests {
         assert_eq!(body["error"]["code"], "rate_limit_exceeded");
     }
 
+    #[tokio::test]
+    async fn test_upstream_429_surfaces_retry_after() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/v1/chat", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            use tokio::io::{AsyncReadExt as _, AsyncWriteExt as _};
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 4096];
+            let _ = socket.read(&mut buf).await.unwrap();
+            let body = r#"{"error":{"message":"slow down"}}"#;
+            let response = format!(
+                "HTTP/1.1 429 Too Many Requests\r\nretry-after: 12\r\n\
+                 x-ratelimit-remaining: 0\r\nx-ratelimit-reset: 12s\r\n\
+                 x-request-id: req-1\r\ncontent-type: application/json\r\n\
+                 content-length: {}\r\nconnection: close\r\n\r\n{body}",
+                body.len()
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+        });
+
+        let http = byokey_provider::ProviderHttp::new(rquest::Client::new());
+        let (result, upstream) = byokey_provider::capture_response_headers(async {
+            http.send(http.client().post(url)).await
+        })
+        .await;
+        let err = ApiError(result.err().unwrap());
+        let resp = err.into_response_with_upstream(upstream.as_ref());
+
+        assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
+        let headers = resp.headers();
+        assert_eq!(headers["retry-after"], "12");
+        assert_eq!(headers["x-ratelimit-remaining"], "0");
+        assert_eq!(headers["x-ratelimit-reset"], "12s");
+        assert!(headers.get("x-request-id").is_none());
+        let bytes = resp.into_body().collect().await.unwrap().to_bytes();
+        let body: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(body["error"]["retry_after_seconds"], 12);
+        assert_eq!(body["error"]["code"], "rate_limit_exceeded");
+    }
+
     #[tokio::test]
     async fn test_upstream_401_error() {
         let (status, body) = extract_error_body(ApiError(ByokError::Upstream {
@@ -217,6 +309,37 @@ mod tests {
         assert_eq!(body["error"]["code"], "upstream_error");
     }
 
+    #[tokio::test]
+    async fn test_timeout_error() {
+        let (status, body) = extract_error_body(ApiError(ByokError::Timeout(
+            std::time::Duration::from_secs(30),
+        )))
+        .await;
+        assert_eq!(status, StatusCode::GATEWAY_TIMEOUT);
+        assert_eq!(body["error"]["code"], "upstream_timeout");
+    }
+
+    #[tokio::test]
+    async fn test_provider_busy_error() {
+        let resp = ApiError(ByokError::ProviderBusy(ProviderId::Claude)).into_response();
+        assert_eq!(resp.headers()["retry-after"], "1");
+        let (status, body) =
+            extract_error_body(ApiError(ByokError::ProviderBusy(ProviderId::Claude))).await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body["error"]["code"], "provider_busy");
+        assert_eq!(body["error"]["retry_after_seconds"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_provider_disabled_error() {
+        let (status, body) =
+            extract_error_body(ApiError(ByokError::ProviderDisabled(ProviderId::Codex))).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert_eq!(body["error"]["type"], "permission_error");
+        assert_eq!(body["error"]["code"], "provider_disabled");
+        assert_eq!(body["error"]["message"], "provider disabled: codex");
+    }
+
     #[tokio::test]
     async fn test_internal_error() {
         let (status, body) =
@@ -26,9 +26,11 @@ use std::{collections::HashMap, sync::Arc};
 
 use crate::middleware::forward::ForwardedHeaders;
 use crate::util::stream::{
-    CodexParser, GeminiParser, OpenAIParser, response_to_stream, tap_usage_stream,
+    CodexParser, GeminiParser, OpenAIParser, keepalive_stream, response_to_stream,
+    tap_json_usage_stream, tap_usage_stream,
 };
-use crate::util::{bad_gateway, extract_usage, sse_response, upstream_error};
+use crate::util::warnings::{Warnings, clamp_max_tokens};
+use crate::util::{bad_gateway, extract_usage, sse_response, truncate_for_log, upstream_error};
 use crate::{AppState, error::ApiError};
 
 const CODEX_RESPONSES_URL: &str = "https://chatgpt.com/backend-api/codex/responses";
@@ -37,6 +39,8 @@ const CODEX_VERSION: &str = "0.120.0";
 const CODEX_USER_AGENT: &str = "codex-tui/0.120.0 (Mac OS 26.0.1; arm64) Apple_Terminal/464";
 const GEMINI_MODELS_BASE: &str = "https://generativelanguage.googleapis.com/v1beta/models";
 const AMP_BACKEND: &str = "https://ampcode.com";
+/// Non-SSE Codex bodies larger than this are streamed instead of buffered.
+const STREAM_JSON_THRESHOLD: u64 = 1024 * 1024;
 
 /// Handles `POST /api/provider/openai/v1/responses`.
 ///
@@ -116,9 +120,16 @@ pub async fn codex_responses_passthrough(
         "codex responses: sending request to upstream"
     );
 
+    let timeout = config
+        .providers
+        .get(&ProviderId::Codex)
+        .and_then(byokey_config::ProviderConfig::request_timeout);
+    // The OAuth endpoint always answers with SSE.
+    let is_stream = is_oauth || body.get("stream").and_then(Value::as_bool) == Some(true);
+
     let start = std::time::Instant::now();
 
-    let resp = if is_oauth {
+    let request = if is_oauth {
         state
             .http
             .post(CODEX_RESPONSES_URL)
@@ -129,8 +140,6 @@ pub async fn codex_responses_passthrough(
             .header("Originator", "codex_cli_rs")
             .header("Accept", "text/event-stream")
             .json(&body)
-            .send()
-            .await
     } else {
         state
             .http
@@ -138,9 +147,8 @@ pub async fn codex_responses_passthrough(
             .header("authorization", format!("Bearer {token}"))
             .header("content-type", "application/json")
             .json(&body)
-            .send()
-            .await
     };
+    let resp = byokey_provider::send_with_timeout(request, timeout, is_stream).await;
 
     let elapsed = start.elapsed();
 
@@ -153,7 +161,7 @@ pub async fn codex_responses_passthrough(
             error = %e,
             "codex responses: transport error (DNS/TLS/connection)"
         );
-        ApiError(ByokError::from(e))
+        ApiError(e)
     })?;
 
     let provider = "codex";
@@ -170,7 +178,7 @@ pub async fn codex_responses_passthrough(
             upstream_status,
             ?elapsed,
             response_headers = %headers_dbg,
-            response_body = %text,
+            response_body = %truncate_for_log(&text, state.config.load().log.max_body_bytes),
             "codex responses: upstream returned non-2xx"
         );
         return Err(upstream_error(
@@ -213,6 +221,7 @@ pub async fn codex_responses_passthrough(
             account_id.clone(),
             CodexParser::new(),
         );
+        let tapped = keepalive_stream(tapped, config.streaming.keepalive_seconds);
         let stream_model = model_name;
         let mapped = tapped.map_err(move |e| {
             tracing::error!(
@@ -223,6 +232,26 @@ pub async fn codex_responses_passthrough(
             std::io::Error::other(e.to_string())
         });
         Ok(sse_response(status, mapped))
+    } else if resp
+        .content_length()
+        .is_some_and(|len| len > STREAM_JSON_THRESHOLD)
+    {
+        let tapped = tap_json_usage_stream(
+            response_to_stream(resp),
+            state.usage.clone(),
+            model_name,
+            provider.to_string(),
+            account_id,
+            ("input_tokens", "output_tokens"),
+        );
+        Response::builder()
+            .status(status)
+            .header("content-type", content_type)
+            .body(axum::body::Body::from_stream(tapped.map_err(|e| {
+                tracing::error!(error = %e, "codex responses: JSON body stream error mid-transfer");
+                std::io::Error::other(e.to_string())
+            })))
+            .map_err(|e| ApiError(ByokError::Http(e.to_string())))
     } else {
         let json: Value = resp
             .json()
@@ -265,7 +294,7 @@ pub async fn gemini_native_passthrough(
     Path(action): Path<String>,
     Query(query_params): Query<HashMap<String, String>>,
     uri: Uri,
-    axum::extract::Json(body): axum::extract::Json<Value>,
+    axum::extract::Json(mut body): axum::extract::Json<Value>,
 ) -> Result<Response, ApiError> {
     let config = state.config.load();
     let gemini_config = config
@@ -279,17 +308,29 @@ pub async fn gemini_native_passthrough(
         .split_once(':')
         .map_or(action.as_str(), |(model, _)| model);
 
+    // Gemini-native clients name their limit in `generationConfig.maxOutputTokens`.
+    let mut warnings = Warnings::default();
+    let output_ceiling = config.max_output_tokens(&ProviderId::Gemini, model_name);
+    if let Some(ceiling) = output_ceiling
+        && let Some(obj) = body.as_object_mut()
+    {
+        clamp_max_tokens(obj, ceiling, &mut warnings);
+    }
+
     // If a backend override is configured, translate and route through it.
     if let Some(backend_id) = &gemini_config.backend {
-        return gemini_native_via_backend(
+        let mut response = gemini_native_via_backend(
             &state,
             &action,
             &query_params,
             body,
             model_name,
             backend_id,
+            output_ceiling,
         )
-        .await;
+        .await?;
+        warnings.apply(&mut response);
+        return Ok(response);
     }
 
     // Direct passthrough to Gemini API.
@@ -320,15 +361,18 @@ pub async fn gemini_native_passthrough(
             )
         };
 
-    let resp = state
+    let is_stream = action.contains("streamGenerateContent")
+        || query_params.get("alt").is_some_and(|v| v == "sse");
+    let request = state
         .http
         .post(&url)
         .header(auth_name, auth_value)
         .header("content-type", "application/json")
-        .json(&body)
-        .send()
-        .await
-        .map_err(|e| ApiError(ByokError::from(e)))?;
+        .json(&body);
+    let resp =
+        byokey_provider::send_with_timeout(request, gemini_config.request_timeout(), is_stream)
+            .await
+            .map_err(ApiError)?;
 
     let provider = "gemini";
     let status = StatusCode::from_u16(resp.status().as_u16()).unwrap_or(StatusCode::BAD_GATEWAY);
@@ -350,7 +394,7 @@ pub async fn gemini_native_passthrough(
         .and_then(|v| v.to_str().ok())
         .is_some_and(|ct| ct.contains("text/event-stream"));
 
-    if is_sse {
+    let mut response = if is_sse {
         let tapped = tap_usage_stream(
             response_to_stream(resp),
             state.usage.clone(),
@@ -359,8 +403,10 @@ pub async fn gemini_native_passthrough(
             account_id,
             GeminiParser::new(),
         );
-        let mapped = tapped.map_err(|e| std::io::Error::other(e.to_string()));
-        Ok(sse_response(status, mapped))
+        let keepalive = state.config.load().streaming.keepalive_seconds;
+        let mapped =
+            keepalive_stream(tapped, keepalive).map_err(|e| std::io::Error::other(e.to_string()));
+        sse_response(status, mapped)
     } else {
         let json: Value = resp
             .json()
@@ -374,13 +420,16 @@ pub async fn gemini_native_passthrough(
         state
             .usage
             .record_success_for(model_name, provider, &account_id, input, output);
-        Ok((status, axum::Json(json)).into_response())
-    }
+        (status, axum::Json(json)).into_response()
+    };
+    warnings.apply(&mut response);
+    Ok(response)
 }
 
 /// Route a Gemini native request through an `OpenAI`-compatible backend provider.
 ///
 /// Translates: Google native → `OpenAI` → backend → `OpenAI` response → Google native.
+/// The backend's translated body is held to `output_ceiling` as well.
 async fn gemini_native_via_backend(
     state: &Arc<AppState>,
     action: &str,
@@ -388,6 +437,7 @@ async fn gemini_native_via_backend(
     body: Value,
     model: &str,
     backend_id: &ProviderId,
+    output_ceiling: Option<u64>,
 ) -> Result<Response, ApiError> {
     let is_stream = action.contains("streamGenerateContent")
         || query_params.get("alt").is_some_and(|v| v == "sse");
@@ -399,6 +449,7 @@ async fn gemini_native_via_backend(
         .get(backend_id)
         .cloned()
         .unwrap_or_default();
+    let timeout = backend_config.request_timeout();
     let executor = byokey_provider::make_executor(
         backend_id,
         backend_config.api_key,
@@ -413,6 +464,7 @@ async fn gemini_native_via_backend(
             "backend {backend_id:?} has no executor"
         )))
     })?;
+    let executor = byokey_provider::TimeoutExecutor::wrap(executor, timeout);
 
     // Translate Gemini-native request → canonical (OpenAI shape) via aigw.
     let mut native: aigw_gemini::GenerateContentRequest = serde_json::from_value(body.clone())
@@ -444,7 +496,13 @@ async fn gemini_native_via_backend(
     let account_id = byokey_types::DEFAULT_ACCOUNT;
 
     // Send through the backend executor.
-    let provider_resp = match executor.chat_completion(chat_request).await {
+    let body_log = state.config.load().log.body_log_limit();
+    let (result, _) = byokey_provider::with_output_token_ceiling(
+        output_ceiling,
+        byokey_provider::log_upstream_bodies(body_log, executor.chat_completion(chat_request)),
+    )
+    .await;
+    let provider_resp = match result {
         Ok(r) => r,
         Err(e) => {
             state
@@ -495,7 +553,9 @@ async fn gemini_native_via_backend(
             );
             let model_owned = model.to_string();
             let translated = byte_stream_to_gemini_sse(tapped, model_owned);
-            let mapped = translated.map_err(|e| std::io::Error::other(e.to_string()));
+            let keepalive = state.config.load().streaming.keepalive_seconds;
+            let mapped = keepalive_stream(Box::pin(translated), keepalive)
+                .map_err(|e| std::io::Error::other(e.to_string()));
             Ok(sse_response(StatusCode::OK, mapped))
         }
     }
@@ -645,6 +705,7 @@ pub async fn ampcode_proxy(
     };
 
     let debug = path.ends_with("/internal") && tracing::enabled!(tracing::Level::DEBUG);
+    let max_log_body = state.config.load().log.max_body_bytes;
     if debug {
         let req_body = std::str::from_utf8(&body)
             .ok()
@@ -653,7 +714,12 @@ pub async fn ampcode_proxy(
                 || format!("{body:?}"),
                 |v| serde_json::to_string_pretty(&v).unwrap_or_default(),
             );
-        tracing::debug!(%method, %url, body = %req_body, "ampcode proxy request");
+        tracing::debug!(
+            %method,
+            %url,
+            body = %truncate_for_log(&req_body, max_log_body),
+            "ampcode proxy request"
+        );
     }
 
     let resp = match state
@@ -690,7 +756,11 @@ pub async fn ampcode_proxy(
                 || format!("{body_bytes:?}"),
                 |v| serde_json::to_string_pretty(&v).unwrap_or_default(),
             );
-        tracing::debug!(%status, body = %resp_body, "ampcode proxy response");
+        tracing::debug!(
+            %status,
+            body = %truncate_for_log(&resp_body, max_log_body),
+            "ampcode proxy response"
+        );
     }
 
     (status, resp_headers, body_bytes).into_response()
@@ -21,8 +21,9 @@ use std::{
 /// Amp CLI uses `~/.local/share/amp/threads/` on both macOS and Linux
 /// (XDG data dir, not `~/Library`).
 pub(crate) fn threads_dir() -> PathBuf {
-    let home = std::env::var("HOME").unwrap_or_else(|_| String::from("/tmp"));
-    PathBuf::from(home).join(".local/share/amp/threads")
+    dirs::home_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join(".local/share/amp/threads")
 }
 
 /// Validate a thread ID to prevent path traversal.
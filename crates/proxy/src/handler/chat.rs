@@ -3,24 +3,50 @@
 use axum::{
     Json,
     extract::State,
-    http::StatusCode,
+    http::{HeaderMap, StatusCode},
     response::{IntoResponse, Response},
 };
-use byokey_provider::{make_executor_for_model, parse_model_suffix, parse_qualified_model};
-use byokey_types::{ChatRequest, ProviderId, traits::ProviderResponse};
+use byokey_provider::{
+    RetryBudget, capture_response_headers, capture_route, log_upstream_bodies,
+    make_executor_for_model, parse_model_suffix, parse_qualified_model_with,
+    with_output_token_ceiling, with_retry_budget,
+};
+use byokey_types::{
+    ChatRequest, ProviderId,
+    traits::{ProviderExecutor, ProviderResponse},
+};
 use futures_util::TryStreamExt as _;
+use serde_json::Value;
 use std::collections::HashSet;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
 
-use crate::util::stream::{OpenAIParser, tap_usage_stream};
-use crate::util::{extract_usage, sse_response};
+use crate::util::coalesce::{Coalescer, unshare_error};
+use crate::util::route::RouteHeader;
+use crate::util::stream::{
+    OpenAIParser, keepalive_stream, rewrite_sse_chunks, sse_to_ndjson, tap_usage_stream,
+    time_stream,
+};
+use crate::util::trailers::{StreamSummary, append_trailers, summarize_stream};
+use crate::util::warnings::{
+    Warnings, clamp_max_tokens, clamp_temperature, drop_unsupported_params,
+};
+use crate::util::{
+    completion_to_chunk, extract_usage, forward_upstream_headers, ndjson_response, sse_response,
+    wants_ndjson,
+};
 use crate::{AppState, error::ApiError};
 
 /// Handles `POST /v1/chat/completions` requests.
 ///
 /// Resolves the model to a provider via config (`provider.backend`),
 /// forwards the request, and returns either a complete JSON response
-/// or an SSE stream.
+/// or an SSE stream. Streams are sent as newline-delimited JSON instead
+/// when the client sends `Accept: application/x-ndjson`.
+///
+/// Parameters the provider cannot honour (e.g. `seed` on Claude, or unknown
+/// fields on providers that need format translation) are dropped and
+/// reported in the `x-byokey-warnings` response header.
 #[tracing::instrument(skip_all, fields(
     model = %request.model,
     provider = tracing::field::Empty,
@@ -28,9 +54,17 @@ use crate::{AppState, error::ApiError};
 ))]
 pub async fn chat_completions(
     State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
     Json(mut request): Json<ChatRequest>,
 ) -> Result<Response, ApiError> {
+    let started = Instant::now();
+    request.apply_legacy_prompt().map_err(ApiError::from)?;
     let config = state.config.load();
+    let callback_url = take_callback_url(&mut request, config.allow_callback_url)?;
+    if callback_url.is_some() {
+        // Callback jobs deliver one final result.
+        force_nonstream(&mut request);
+    }
 
     // Pre-compute which providers have OAuth tokens (async → sync bridge).
     let mut oauth_providers = HashSet::new();
@@ -40,11 +74,15 @@ pub async fn chat_completions(
         }
     }
 
-    // Resolve model alias before anything else.
-    let resolved_model = config.resolve_alias(&request.model);
+    // Pick a backend for weighted routes, then resolve model aliases.
+    let routed_model = config
+        .resolve_weighted_route(&request.model, &mut rand::thread_rng())
+        .unwrap_or(&request.model);
+    let resolved_model = config.resolve_alias(routed_model);
 
     // Strip provider qualifier (e.g. "codex/gpt-5.4" → "gpt-5.4").
-    let (provider_hint, bare_model) = parse_qualified_model(&resolved_model);
+    let (provider_hint, bare_model) =
+        parse_qualified_model_with(&resolved_model, config.custom_model_prefix());
 
     // Parse thinking suffix from (possibly alias-resolved) model name.
     let suffix = parse_model_suffix(bare_model);
@@ -62,17 +100,36 @@ pub async fn chat_completions(
         &state.versions,
     )
     .map_err(ApiError::from)?;
+    let max_concurrency = executor
+        .provider_id()
+        .and_then(|p| config.providers.get(&p))
+        .and_then(|pc| pc.max_concurrency);
+    let executor = state.concurrency.wrap(executor, max_concurrency);
 
     let provider = byokey_provider::resolve_provider(&suffix.model)
         .map_or_else(|| "unknown".to_string(), |p| p.to_string());
     let span = tracing::Span::current();
     span.record("provider", provider.as_str());
     span.record("bare_model", bare_model);
+    crate::metrics::label_request(&provider, &suffix.model);
     tracing::info!(stream = request.stream, "chat completion request");
 
     // Replace model name with the clean version (suffix stripped)
     request.model.clone_from(&suffix.model);
 
+    // Some models stream poorly; call them non-streaming and re-wrap below.
+    let client_stream = request.stream;
+    if config.is_nonstream_forced(&suffix.model) {
+        force_nonstream(&mut request);
+    }
+
+    // Drop parameters the target provider cannot honour and tell the client.
+    let mut warnings = Warnings::default();
+    let target_provider = provider_hint
+        .clone()
+        .or_else(|| byokey_provider::resolve_provider(&suffix.model));
+    drop_unsupported_params(&mut request, target_provider.as_ref(), &mut warnings);
+
     // Apply thinking config if a model suffix was parsed.
     //
     // The canonical [`aigw_core::model::ThinkingRequest`] is set on the
@@ -93,53 +150,433 @@ pub async fn chat_completions(
     }
 
     // Apply payload rules (default/override/filter) based on model name.
-    if !config.payload.default.is_empty()
-        || !config.payload.r#override.is_empty()
-        || !config.payload.filter.is_empty()
-    {
+    if !config.payload.is_empty() {
         let mut body = request.into_body();
         body = config.apply_payload_rules(body, &suffix.model);
         request = serde_json::from_value(body)
             .map_err(|e| ApiError::from(byokey_types::ByokError::Translation(e.to_string())))?;
     }
 
+    // Clamp last so payload-rule overrides are covered too.
+    if config.clamp_temperature {
+        clamp_temperature(&mut request, target_provider.as_ref(), &mut warnings);
+    }
+    // Translation may add a limit of its own, so executors clamp the
+    // upstream body again under `with_output_token_ceiling` below.
+    let output_ceiling = target_provider
+        .as_ref()
+        .and_then(|p| config.max_output_tokens(p, &suffix.model));
+    if let Some(ceiling) = output_ceiling {
+        clamp_max_tokens(&mut request.extra, ceiling, &mut warnings);
+    }
+
     let model_name = suffix.model.clone();
     // Executor-based chat path currently does its own account rotation;
     // the specific account isn't surfaced back, so attribute to
     // DEFAULT_ACCOUNT until we plumb it through the executor trait.
     let account_id = byokey_types::DEFAULT_ACCOUNT;
-    match executor.chat_completion(request).await {
-        Ok(ProviderResponse::Complete(json)) => {
-            let (input_tok, output_tok) =
-                extract_usage(&json, "/usage/prompt_tokens", "/usage/completion_tokens");
-            state.usage.record_success_for(
-                &model_name,
-                &provider,
-                account_id,
-                input_tok,
-                output_tok,
-            );
+
+    if let Some(url) = callback_url {
+        let job_id = format!("chatjob-{}", uuid::Uuid::new_v4());
+        let job = job_id.clone();
+        let state = Arc::clone(&state);
+        tokio::spawn(async move {
+            let config = state.config.load_full();
+            let budget = RetryBudget::from_config(&config.retry_budget);
+            let (result, _) = with_output_token_ceiling(
+                output_ceiling,
+                with_retry_budget(
+                    budget,
+                    log_upstream_bodies(
+                        config.log.body_log_limit(),
+                        run_callback_job(
+                            executor.as_ref(),
+                            request,
+                            &state.http,
+                            &url,
+                            &job,
+                            |json| {
+                                if config.response_payload.rules.is_empty() {
+                                    json
+                                } else {
+                                    config.apply_response_payload_rules(json, &model_name)
+                                }
+                            },
+                        ),
+                    ),
+                ),
+            )
+            .await;
+            match result {
+                Ok(json) => {
+                    let (input_tok, output_tok) =
+                        extract_usage(&json, "/usage/prompt_tokens", "/usage/completion_tokens");
+                    state.usage.record_success_for(
+                        &model_name,
+                        &provider,
+                        account_id,
+                        input_tok,
+                        output_tok,
+                    );
+                }
+                Err(_) => state
+                    .usage
+                    .record_failure_for(&model_name, &provider, account_id),
+            }
+        });
+        let mut response = (
+            StatusCode::ACCEPTED,
+            Json(serde_json::json!({
+                "id": job_id,
+                "object": "chat.completion.job",
+                "status": "accepted",
+            })),
+        )
+            .into_response();
+        warnings.apply(&mut response);
+        return Ok(response);
+    }
+
+    // Identical concurrent non-streaming requests share one upstream call;
+    // only the caller that made it records usage.
+    let mut record_usage = true;
+    let body_log = config.log.body_log_limit();
+    let routed_provider = executor.provider_id();
+    // The request's retry budget is set by the `retry_budget` middleware.
+    let (((response, route), upstream_headers), output_clamped) = with_output_token_ceiling(
+        output_ceiling,
+        capture_response_headers(capture_route(log_upstream_bodies(body_log, async {
+            if config.coalesce_requests && !request.stream {
+                let key = Coalescer::key(&model_name, &request.to_body());
+                let (result, leader) = state
+                    .coalescer
+                    .run(key, async move {
+                        match executor.chat_completion(request).await? {
+                            ProviderResponse::Complete(json) => Ok(json),
+                            ProviderResponse::Stream(_) => {
+                                Err(byokey_types::ByokError::Translation(
+                                    "unexpected streaming response to non-streaming request".into(),
+                                ))
+                            }
+                        }
+                    })
+                    .await;
+                record_usage = leader;
+                result
+                    .map(ProviderResponse::Complete)
+                    .map_err(unshare_error)
+            } else {
+                executor.chat_completion(request).await
+            }
+        }))),
+    )
+    .await;
+    if let Some(ceiling) = output_ceiling.filter(|_| output_clamped) {
+        warnings.max_tokens_clamped(ceiling);
+    }
+    if record_usage
+        && response.is_ok()
+        && let Some(served) = route.fallback.as_ref().filter(|s| s.tier > 0)
+    {
+        state
+            .usage
+            .record_fallback_hit(&model_name, &served.provider.to_string());
+    }
+    let route_header = if config.route_header {
+        Some(
+            RouteHeader::resolve(
+                &config,
+                &state.auth,
+                target_provider.clone(),
+                routed_provider,
+                route,
+            )
+            .await,
+        )
+    } else {
+        None
+    };
+
+    let result = match response {
+        Ok(ProviderResponse::Complete(mut json)) => {
+            if !config.response_payload.rules.is_empty() {
+                json = config.apply_response_payload_rules(json, &model_name);
+            }
+            if record_usage {
+                let (input_tok, output_tok) =
+                    extract_usage(&json, "/usage/prompt_tokens", "/usage/completion_tokens");
+                state.usage.record_success_for(
+                    &model_name,
+                    &provider,
+                    account_id,
+                    input_tok,
+                    output_tok,
+                );
+            }
             tracing::debug!(model = %model_name, "chat completion complete");
-            Ok(Json(json).into_response())
+            if client_stream {
+                let mut chunk = completion_to_chunk(&json);
+                config.apply_chunk_rules(&mut chunk, &model_name);
+                let ndjson = wants_ndjson(&headers);
+                let body = if ndjson {
+                    format!("{chunk}\n")
+                } else {
+                    format!("data: {chunk}\n\ndata: [DONE]\n\n")
+                };
+                let stream = futures_util::stream::once(async move {
+                    Ok::<_, std::io::Error>(bytes::Bytes::from(body))
+                });
+                Ok(if ndjson {
+                    ndjson_response(StatusCode::OK, stream)
+                } else {
+                    sse_response(StatusCode::OK, stream)
+                })
+            } else {
+                Ok(Json(json).into_response())
+            }
         }
         Ok(ProviderResponse::Stream(byte_stream)) => {
             tracing::debug!(model = %model_name, "streaming chat completion");
+            let rewrite_response =
+                config.response_payload.streaming && !config.response_payload.rules.is_empty();
+            let byte_stream = if config.streaming.chunk_rules.is_empty() && !rewrite_response {
+                byte_stream
+            } else {
+                let config = state.config.load_full();
+                let model = model_name.clone();
+                rewrite_sse_chunks(byte_stream, move |chunk| {
+                    if rewrite_response {
+                        *chunk = config.apply_response_payload_rules(chunk.take(), &model);
+                    }
+                    config.apply_chunk_rules(chunk, &model);
+                })
+            };
             let tapped = tap_usage_stream(
                 byte_stream,
                 state.usage.clone(),
-                model_name,
+                model_name.clone(),
                 provider.clone(),
                 account_id.to_string(),
                 OpenAIParser::new(),
             );
-            let mapped = tapped.map_err(|e| std::io::Error::other(e.to_string()));
-            Ok(sse_response(StatusCode::OK, mapped))
+            let tapped = time_stream(tapped, state.usage.clone(), model_name, started);
+            let summary = config
+                .streaming
+                .trailers
+                .then(|| Arc::new(Mutex::new(StreamSummary::default())));
+            let tapped = match &summary {
+                Some(summary) => summarize_stream(tapped, Arc::clone(summary)),
+                None => tapped,
+            };
+            let response = if wants_ndjson(&headers) {
+                let mapped =
+                    sse_to_ndjson(tapped).map_err(|e| std::io::Error::other(e.to_string()));
+                ndjson_response(StatusCode::OK, mapped)
+            } else {
+                let mapped = keepalive_stream(tapped, config.streaming.keepalive_seconds)
+                    .map_err(|e| std::io::Error::other(e.to_string()));
+                sse_response(StatusCode::OK, mapped)
+            };
+            Ok(match summary {
+                Some(summary) => append_trailers(response, summary),
+                None => response,
+            })
         }
         Err(e) => {
-            state
-                .usage
-                .record_failure_for(&model_name, &provider, account_id);
-            Err(ApiError::from(e))
+            if record_usage {
+                state
+                    .usage
+                    .record_failure_for(&model_name, &provider, account_id);
+            }
+            return Ok(ApiError::from(e).into_response_with_upstream(upstream_headers.as_ref()));
+        }
+    };
+    result.map(|mut response| {
+        warnings.apply(&mut response);
+        if let Some(route_header) = &route_header {
+            route_header.apply(&mut response);
+        }
+        if let Some(upstream) = &upstream_headers {
+            forward_upstream_headers(upstream, &mut response, |name| {
+                config.forwards_response_header(name)
+            });
         }
+        response
+    })
+}
+
+/// Removes `callback_url` from the request, returning it when callbacks are
+/// allowed by config.
+fn take_callback_url(request: &mut ChatRequest, allowed: bool) -> Result<Option<String>, ApiError> {
+    let Some(value) = request.extra.shift_remove("callback_url") else {
+        return Ok(None);
+    };
+    let invalid = |msg: &str| ApiError::from(byokey_types::ByokError::Translation(msg.into()));
+    if !allowed {
+        return Err(invalid(
+            "`callback_url` is disabled; set allow_callback_url in the config",
+        ));
+    }
+    match value {
+        Value::String(url) if url.starts_with("http://") || url.starts_with("https://") => {
+            Ok(Some(url))
+        }
+        _ => Err(invalid("`callback_url` must be an http(s) URL")),
+    }
+}
+
+/// Executes `request` and POSTs the outcome to `callback_url` as
+/// `{"id", "status": "completed", "result"}` or
+/// `{"id", "status": "failed", "error"}`. `rewrite` is applied to a
+/// successful completion before delivery. Delivery failures are logged,
+/// not retried.
+async fn run_callback_job(
+    executor: &dyn ProviderExecutor,
+    request: ChatRequest,
+    http: &rquest::Client,
+    callback_url: &str,
+    job_id: &str,
+    rewrite: impl FnOnce(Value) -> Value,
+) -> byokey_types::Result<Value> {
+    let result = match executor.chat_completion(request).await {
+        Ok(ProviderResponse::Complete(json)) => Ok(rewrite(json)),
+        Ok(ProviderResponse::Stream(_)) => Err(byokey_types::ByokError::Translation(
+            "unexpected streaming response to non-streaming request".into(),
+        )),
+        Err(e) => Err(e),
+    };
+    let payload = match &result {
+        Ok(json) => serde_json::json!({"id": job_id, "status": "completed", "result": json}),
+        Err(e) => serde_json::json!({
+            "id": job_id,
+            "status": "failed",
+            "error": {"message": e.to_string()},
+        }),
+    };
+    match http.post(callback_url).json(&payload).send().await {
+        Ok(resp) if resp.status().is_success() => {}
+        Ok(resp) => {
+            tracing::warn!(
+                job_id,
+                status = resp.status().as_u16(),
+                "callback rejected result"
+            );
+        }
+        Err(e) => tracing::warn!(job_id, error = %e, "callback delivery failed"),
+    }
+    result
+}
+
+/// Turns a streaming request into a non-streaming one, dropping
+/// `stream_options`, which upstreams reject when `stream` is false.
+fn force_nonstream(request: &mut ChatRequest) {
+    request.stream = false;
+    request.extra.shift_remove("stream_options");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_forced_model_is_requested_non_streaming() {
+        let config = byokey_config::Config::from_yaml(
+            r#"
+force_nonstream_models: ["gemini-*-preview"]
+"#,
+        )
+        .unwrap();
+        let mut request: ChatRequest = serde_json::from_value(serde_json::json!({
+            "model": "gemini-3-pro-preview",
+            "stream": true,
+            "stream_options": {"include_usage": true},
+            "messages": [{"role": "user", "content": "hi"}]
+        }))
+        .unwrap();
+
+        assert!(config.is_nonstream_forced(&request.model));
+        force_nonstream(&mut request);
+
+        let body = request.into_body();
+        assert_eq!(body["stream"], false);
+        assert!(body.get("stream_options").is_none());
+    }
+
+    struct StubExecutor;
+
+    #[async_trait::async_trait]
+    impl ProviderExecutor for StubExecutor {
+        async fn chat_completion(
+            &self,
+            request: ChatRequest,
+        ) -> byokey_types::Result<ProviderResponse> {
+            assert!(!request.stream);
+            Ok(ProviderResponse::Complete(serde_json::json!({
+                "object": "chat.completion",
+                "choices": [{"message": {"role": "assistant", "content": "done"}}]
+            })))
+        }
+
+        fn supported_models(&self) -> Vec<String> {
+            vec!["stub".into()]
+        }
+    }
+
+    #[test]
+    fn test_callback_url_requires_config() {
+        let mut request: ChatRequest = serde_json::from_value(serde_json::json!({
+            "model": "m",
+            "callback_url": "http://127.0.0.1:9/hook",
+            "messages": []
+        }))
+        .unwrap();
+        assert!(take_callback_url(&mut request.clone(), false).is_err());
+        assert_eq!(
+            take_callback_url(&mut request, true).unwrap().as_deref(),
+            Some("http://127.0.0.1:9/hook")
+        );
+        assert!(!request.extra.contains_key("callback_url"));
+    }
+
+    #[tokio::test]
+    async fn test_callback_receives_completion() {
+        use axum::{Router, routing::post};
+
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<Value>();
+        let app = Router::new().route(
+            "/hook",
+            post(move |Json(body): Json<Value>| async move {
+                tx.send(body).unwrap();
+                StatusCode::NO_CONTENT
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let request: ChatRequest = serde_json::from_value(serde_json::json!({
+            "model": "stub",
+            "messages": [{"role": "user", "content": "hi"}]
+        }))
+        .unwrap();
+        let result = run_callback_job(
+            &StubExecutor,
+            request,
+            &rquest::Client::new(),
+            &url,
+            "chatjob-1",
+            |json| json,
+        )
+        .await
+        .unwrap();
+
+        let delivered = rx.recv().await.unwrap();
+        assert_eq!(delivered["id"], "chatjob-1");
+        assert_eq!(delivered["status"], "completed");
+        assert_eq!(delivered["result"], result);
+        assert_eq!(
+            delivered["result"]["choices"][0]["message"]["content"],
+            "done"
+        );
     }
 }
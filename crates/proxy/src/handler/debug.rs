@@ -0,0 +1,50 @@
+//! Debug handlers — dry-run views of request rewriting without calling upstream.
+
+use axum::{Json, extract::State};
+use byokey_config::AppliedPayloadRule;
+use byokey_provider::{parse_model_suffix, parse_qualified_model_with};
+use byokey_types::ChatRequest;
+use serde::Serialize;
+use serde_json::Value;
+use std::sync::Arc;
+
+use crate::AppState;
+
+/// Result of a payload-rule dry run.
+#[derive(Serialize)]
+pub struct PayloadDryRun {
+    /// Model name the rules were matched against (alias resolved, suffix stripped).
+    pub model: String,
+    /// Rules whose model patterns matched, in application order.
+    pub applied_rules: Vec<AppliedPayloadRule>,
+    /// Request body before payload rules.
+    pub before: Value,
+    /// Request body after payload rules.
+    pub after: Value,
+}
+
+/// Handles `POST /v1/debug/payload` requests.
+///
+/// Resolves the model the same way the chat handler does and reports which
+/// `payload` default/override/filter rules fire, together with the body
+/// before and after they are applied. Nothing is sent upstream.
+pub async fn payload_dry_run(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<ChatRequest>,
+) -> Json<PayloadDryRun> {
+    let config = state.config.load();
+
+    let resolved_model = config.resolve_alias(&request.model);
+    let (_, bare_model) = parse_qualified_model_with(&resolved_model, config.custom_model_prefix());
+    let model = parse_model_suffix(bare_model).model;
+
+    let before = request.into_body();
+    let (after, applied_rules) = config.apply_payload_rules_traced(before.clone(), &model);
+
+    Json(PayloadDryRun {
+        model,
+        applied_rules,
+        before,
+        after,
+    })
+}
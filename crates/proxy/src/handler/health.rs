@@ -0,0 +1,159 @@
+//! Liveness and readiness probes for container orchestration, plus the
+//! per-provider credential check.
+//!
+//! The probes are unauthenticated and never touch an upstream provider;
+//! [`provider_health`] does, once per configured provider. It is served by
+//! the management `StatusService`, not as a public route.
+
+use axum::{Json, extract::State, http::StatusCode};
+use byokey_types::ProviderId;
+use serde_json::{Value, json};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::AppState;
+
+/// How long the token store gets to answer the readiness query.
+const READY_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How long each provider's health check may take.
+const PROVIDER_CHECK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long a provider health report is served from cache.
+const PROVIDER_HEALTH_TTL: Duration = Duration::from_secs(30);
+
+/// Handles `GET /healthz`: the process is up and serving requests.
+pub async fn healthz() -> Json<Value> {
+    Json(json!({"status": "ok"}))
+}
+
+/// Handles `GET /readyz`.
+///
+/// The config is loaded before [`AppState`] exists, so readiness comes down
+/// to the token store answering a trivial query within [`READY_TIMEOUT`].
+pub async fn readyz(State(state): State<Arc<AppState>>) -> (StatusCode, Json<Value>) {
+    let probe = state.auth.list_accounts(&ProviderId::Claude);
+    match tokio::time::timeout(READY_TIMEOUT, probe).await {
+        Ok(Ok(_)) => (StatusCode::OK, Json(json!({"status": "ready"}))),
+        Ok(Err(e)) => {
+            tracing::warn!(error = %e, "readiness probe: token store query failed");
+            (
+                StatusCode::SERVICE_UNAVAILABLE,
+                Json(json!({"status": "unavailable", "reason": "token store error"})),
+            )
+        }
+        Err(_) => {
+            tracing::warn!("readiness probe: token store query timed out");
+            (
+                StatusCode::SERVICE_UNAVAILABLE,
+                Json(json!({"status": "unavailable", "reason": "token store timeout"})),
+            )
+        }
+    }
+}
+
+/// Outcome of one provider's [`health_check`](byokey_types::ProviderExecutor::health_check).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProviderHealth {
+    /// The authenticated call succeeded.
+    Ok,
+    /// The provider has no health check, so its credentials were not tried.
+    Unchecked,
+    /// The call did not finish within [`PROVIDER_CHECK_TIMEOUT`].
+    Timeout,
+    /// The call failed (or the check panicked).
+    Error(String),
+}
+
+/// One round of provider health checks.
+#[derive(Debug)]
+pub struct HealthReport {
+    /// Unix timestamp (seconds) at which the checks ran.
+    pub checked_at: u64,
+    /// Each configured, enabled provider with its outcome.
+    pub providers: Vec<(ProviderId, ProviderHealth)>,
+}
+
+/// The last [`HealthReport`], reused for [`PROVIDER_HEALTH_TTL`] so that
+/// polling dashboards do not hit every upstream on each refresh.
+#[derive(Default)]
+pub struct HealthCache(tokio::sync::Mutex<Option<(Instant, Arc<HealthReport>)>>);
+
+/// Returns the cached provider health report, running the checks when it
+/// is older than [`PROVIDER_HEALTH_TTL`] or `refresh` is set.
+///
+/// Concurrent callers wait for the same round instead of starting their own.
+pub async fn provider_health(state: &Arc<AppState>, refresh: bool) -> Arc<HealthReport> {
+    let mut cached = state.provider_health.0.lock().await;
+    if !refresh
+        && let Some((at, report)) = cached.as_ref()
+        && at.elapsed() < PROVIDER_HEALTH_TTL
+    {
+        return Arc::clone(report);
+    }
+    let report = Arc::new(check_providers(state).await);
+    *cached = Some((Instant::now(), Arc::clone(&report)));
+    report
+}
+
+/// Runs [`health_check`](byokey_types::ProviderExecutor::health_check)
+/// concurrently for every enabled provider that has credentials (an API key,
+/// a stored login, or for `custom` a `base_url`), each bounded by
+/// [`PROVIDER_CHECK_TIMEOUT`].
+async fn check_providers(state: &Arc<AppState>) -> HealthReport {
+    let config = state.config.load_full();
+    let mut checks = Vec::new();
+    for provider in ProviderId::all() {
+        let pc = config.providers.get(provider).cloned().unwrap_or_default();
+        let configured = !pc.all_api_keys_with_base_url().is_empty()
+            || (*provider == ProviderId::Custom && pc.base_url.is_some())
+            || state.auth.is_authenticated(provider).await;
+        if !pc.enabled || !configured {
+            continue;
+        }
+        let (api_key, base_url) = pc
+            .all_api_keys_with_base_url()
+            .first()
+            .map_or((None, pc.base_url.clone()), |(key, url)| {
+                (Some((*key).to_owned()), url.map(str::to_owned))
+            });
+        let Some(executor) = byokey_provider::make_executor(
+            provider,
+            api_key,
+            base_url,
+            state.auth.clone(),
+            state.http.clone(),
+            Some(state.ratelimits.clone()),
+            &state.versions,
+        ) else {
+            continue;
+        };
+        // Spawned so a panicking check only fails its own entry.
+        let check = tokio::spawn(async move {
+            tokio::time::timeout(PROVIDER_CHECK_TIMEOUT, executor.health_check()).await
+        });
+        checks.push((provider.clone(), check));
+    }
+
+    let checked_at = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    let mut providers = Vec::with_capacity(checks.len());
+    for (provider, check) in checks {
+        let health = match check.await {
+            Ok(Ok(Some(Ok(())))) => ProviderHealth::Ok,
+            Ok(Ok(None)) => ProviderHealth::Unchecked,
+            Ok(Ok(Some(Err(e)))) => ProviderHealth::Error(e.to_string()),
+            Ok(Err(_)) => ProviderHealth::Timeout,
+            Err(e) => {
+                tracing::error!(%provider, error = %e, "provider health check panicked");
+                ProviderHealth::Error("health check panicked".to_owned())
+            }
+        };
+        providers.push((provider, health));
+    }
+    HealthReport {
+        checked_at,
+        providers,
+    }
+}
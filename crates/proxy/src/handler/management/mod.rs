@@ -49,11 +49,50 @@ fn byok_to_connect_error(e: &byokey_types::ByokError) -> ConnectError {
         ByokError::UnsupportedProvider(_) | ByokError::Translation(_) => {
             ConnectError::invalid_argument(msg)
         }
-        ByokError::ProviderUnavailable(_) => ConnectError::unavailable(msg),
+        ByokError::ProviderUnavailable(_) | ByokError::ProviderBusy(_) | ByokError::Timeout(_) => {
+            ConnectError::unavailable(msg)
+        }
+        ByokError::ProviderDisabled(_) => ConnectError::failed_precondition(msg),
         _ => ConnectError::internal(msg),
     }
 }
 
+fn model_stats_to_pb(m: crate::usage::ModelStats) -> stat::ModelStats {
+    stat::ModelStats {
+        requests: m.requests,
+        success: m.success,
+        failure: m.failure,
+        input_tokens: m.input_tokens,
+        output_tokens: m.output_tokens,
+        ttfb_samples: m.ttfb_samples,
+        ttfb_ms_total: m.ttfb_ms_total,
+        stream_bytes: m.stream_bytes,
+        fallback_hits: m.fallback_hits.into_iter().collect(),
+        estimated_output: m.estimated_output,
+        ..Default::default()
+    }
+}
+
+/// Sums one provider's per-model counters, keeping the per-model detail
+/// only when `per_model` is set.
+fn provider_usage_to_pb(
+    models: std::collections::HashMap<String, crate::usage::ModelStats>,
+    per_model: bool,
+) -> stat::ProviderUsage {
+    let mut usage = stat::ProviderUsage::default();
+    for (model, m) in models {
+        usage.requests += m.requests;
+        usage.success += m.success;
+        usage.failure += m.failure;
+        usage.input_tokens += m.input_tokens;
+        usage.output_tokens += m.output_tokens;
+        if per_model {
+            usage.models.insert(model, model_stats_to_pb(m));
+        }
+    }
+    usage
+}
+
 fn json_to_pb_value(v: JsonValue) -> Value {
     let kind = match v {
         JsonValue::Null => Kind::NullValue(NullValue::NULL_VALUE.into()),
@@ -144,11 +183,15 @@ impl stat::StatusService for StatusServiceImpl {
             ..Default::default()
         };
 
+        let token_states = self.0.auth.token_states_all().await;
         let mut providers = Vec::new();
         for pid in byokey_types::ProviderId::all() {
             let cfg = snapshot.providers.get(pid);
             let has_key = cfg.is_some_and(|c| c.api_key.is_some() || !c.api_keys.is_empty());
-            let auth = if has_key || self.0.auth.is_authenticated(pid).await {
+            let authenticated = token_states
+                .get(pid)
+                .is_some_and(|s| *s != byokey_types::TokenState::Invalid);
+            let auth = if has_key || authenticated {
                 stat::AuthStatus::AUTH_STATUS_VALID
             } else {
                 let accts = self.0.auth.list_accounts(pid).await.unwrap_or_default();
@@ -180,26 +223,33 @@ impl stat::StatusService for StatusServiceImpl {
     async fn get_usage(
         &self,
         ctx: Context,
-        _: OwnedView<stat::GetUsageRequestView<'static>>,
+        request: OwnedView<stat::GetUsageRequestView<'static>>,
     ) -> Result<(stat::GetUsageResponse, Context), ConnectError> {
+        let req = request.to_owned_message();
+        let per_model = match req.group_by.as_deref() {
+            None => None,
+            Some("model") => Some(true),
+            Some("provider") => Some(false),
+            Some(other) => {
+                return Err(ConnectError::invalid_argument(format!(
+                    "group_by must be \"model\" or \"provider\", got \"{other}\""
+                )));
+            }
+        };
         let s = self.0.usage.snapshot();
         let models = s
             .models
             .into_iter()
-            .map(|(k, m)| {
-                (
-                    k,
-                    stat::ModelStats {
-                        requests: m.requests,
-                        success: m.success,
-                        failure: m.failure,
-                        input_tokens: m.input_tokens,
-                        output_tokens: m.output_tokens,
-                        ..Default::default()
-                    },
-                )
-            })
+            .map(|(k, m)| (k, model_stats_to_pb(m)))
             .collect();
+        let providers = match per_model {
+            Some(per_model) => s
+                .providers
+                .into_iter()
+                .map(|(p, models)| (p, provider_usage_to_pb(models, per_model)))
+                .collect(),
+            None => Default::default(),
+        };
         Ok((
             stat::GetUsageResponse {
                 total_requests: s.total_requests,
@@ -208,6 +258,7 @@ impl stat::StatusService for StatusServiceImpl {
                 input_tokens: s.input_tokens,
                 output_tokens: s.output_tokens,
                 models,
+                providers,
                 ..Default::default()
             },
             ctx,
@@ -414,6 +465,47 @@ impl stat::StatusService for StatusServiceImpl {
             ctx,
         ))
     }
+
+    async fn get_provider_health(
+        &self,
+        ctx: Context,
+        request: OwnedView<stat::GetProviderHealthRequestView<'static>>,
+    ) -> Result<(stat::GetProviderHealthResponse, Context), ConnectError> {
+        let refresh = request.to_owned_message().refresh;
+        let report = crate::handler::health::provider_health(&self.0, refresh).await;
+        let providers = report
+            .providers
+            .iter()
+            .map(|(pid, health)| provider_health_to_pb(pid, health))
+            .collect();
+        Ok((
+            stat::GetProviderHealthResponse {
+                providers,
+                checked_at: report.checked_at,
+                ..Default::default()
+            },
+            ctx,
+        ))
+    }
+}
+
+fn provider_health_to_pb(
+    pid: &byokey_types::ProviderId,
+    health: &crate::handler::health::ProviderHealth,
+) -> stat::ProviderHealth {
+    use crate::handler::health::ProviderHealth;
+    let (state, error) = match health {
+        ProviderHealth::Ok => (stat::HealthState::HEALTH_STATE_OK, None),
+        ProviderHealth::Unchecked => (stat::HealthState::HEALTH_STATE_UNCHECKED, None),
+        ProviderHealth::Timeout => (stat::HealthState::HEALTH_STATE_TIMEOUT, None),
+        ProviderHealth::Error(e) => (stat::HealthState::HEALTH_STATE_ERROR, Some(e.clone())),
+    };
+    stat::ProviderHealth {
+        id: pid.to_string(),
+        state: state.into(),
+        error,
+        ..Default::default()
+    }
 }
 
 // ═══════════════════════ AccountsService ══════════════════════════
@@ -456,6 +548,7 @@ impl acct::AccountsService for AccountsServiceImpl {
                         is_active: info.is_active,
                         token_state: ts.into(),
                         expires_at: exp,
+                        last_used_at: info.last_used_at.and_then(|t| u64::try_from(t).ok()),
                         ..Default::default()
                     }
                 })
@@ -616,12 +709,21 @@ impl acct::AccountsService for AccountsServiceImpl {
             tokio::sync::mpsc::channel::<Result<acct::LoginEvent, ConnectError>>(16);
 
         let auth = self.0.auth.clone();
+        let proxy_url = self.0.config.load().proxy_url.clone();
         let event_tx_drive = event_tx.clone();
         tokio::spawn(async move {
+            let cancel = tokio_util::sync::CancellationToken::new();
             let mut progress_rx = progress_rx;
             let account_ref = account.as_deref();
-            let login_fut =
-                byokey_auth::flow::login_with_events(&pid, &auth, account_ref, Some(progress_tx));
+            let login_fut = byokey_auth::flow::login_with_events(
+                &pid,
+                &auth,
+                account_ref,
+                None,
+                proxy_url.as_deref(),
+                Some(progress_tx),
+                &cancel,
+            );
             tokio::pin!(login_fut);
 
             loop {
@@ -631,6 +733,12 @@ impl acct::AccountsService for AccountsServiceImpl {
                         let ev = progress_to_pb(&p);
                         if event_tx_drive.send(Ok(ev)).await.is_err() { return; }
                     }
+                    // Client hung up (e.g. "Cancel login"): stop device-code
+                    // polling instead of running until the code expires.
+                    () = event_tx_drive.closed() => {
+                        cancel.cancel();
+                        return;
+                    }
                     res = &mut login_fut => {
                         // Drain any remaining progress events before emitting terminal.
                         while let Ok(p) = progress_rx.try_recv() {
@@ -897,6 +1005,25 @@ mod tests {
     use super::*;
     use byokey_auth::flow::LoginProgress;
 
+    #[test]
+    fn provider_usage_sums_models() {
+        let stats = crate::UsageStats::new();
+        stats.record_success_for("claude-opus-4-5", "claude", 100, 200);
+        stats.record_success_for("claude-haiku-4-5", "claude", 10, 20);
+        stats.record_failure_for("claude-haiku-4-5", "claude");
+        let models = stats.snapshot().providers.remove("claude").unwrap();
+
+        let totals = provider_usage_to_pb(models.clone(), false);
+        assert_eq!(totals.requests, 3);
+        assert_eq!(totals.failure, 1);
+        assert_eq!(totals.input_tokens, 110);
+        assert!(totals.models.is_empty());
+
+        let detailed = provider_usage_to_pb(models, true);
+        assert_eq!(detailed.models["claude-haiku-4-5"].requests, 2);
+        assert_eq!(detailed.models["claude-opus-4-5"].output_tokens, 200);
+    }
+
     #[test]
     fn progress_to_pb_started() {
         let ev = progress_to_pb(&LoginProgress::Started);
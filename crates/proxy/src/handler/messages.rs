@@ -24,9 +24,13 @@ use serde_json::Value;
 use std::fmt::Write as _;
 use std::sync::Arc;
 
-use crate::util::stream::{AnthropicParser, response_to_stream, tap_usage_stream};
-use crate::util::{extract_usage, sse_response, strip_gateway_headers};
-use crate::{AppState, UsageRecorder, error::ApiError};
+use crate::util::concurrency::hold_permit;
+use crate::util::stream::{
+    AnthropicParser, keepalive_stream, response_to_stream, tap_usage_stream,
+};
+use crate::util::warnings::{Warnings, clamp_max_tokens};
+use crate::util::{extract_usage, sse_response, strip_gateway_headers, truncate_for_log};
+use crate::{AppState, error::ApiError};
 
 /// Default thinking budget (tokens) for `Auto` mode on legacy Claude models
 /// that require an explicit `budget_tokens` value with `thinking.type: "enabled"`.
@@ -307,8 +311,43 @@ pub async fn anthropic_messages(
         .cloned()
         .unwrap_or_default();
 
-    if claude_config.backend.as_ref() == Some(&ProviderId::Copilot) {
-        return copilot_messages(&state, body, stream, &beta).await;
+    // Enforce the configured output token ceiling on the Anthropic body.
+    let mut warnings = Warnings::default();
+    let ceiling = body
+        .get("model")
+        .and_then(Value::as_str)
+        .and_then(|model| config.max_output_tokens(&ProviderId::Claude, model));
+    if let Some(ceiling) = ceiling
+        && let Some(obj) = body.as_object_mut()
+    {
+        clamp_max_tokens(obj, ceiling, &mut warnings);
+    }
+
+    // Hold a `max_concurrency` slot on whichever provider serves the request.
+    let upstream = match claude_config.backend.as_ref() {
+        Some(ProviderId::Copilot) => ProviderId::Copilot,
+        _ => ProviderId::Claude,
+    };
+    let max_concurrency = config
+        .providers
+        .get(&upstream)
+        .and_then(|pc| pc.max_concurrency);
+    crate::metrics::label_request(
+        &upstream.to_string(),
+        body.get("model")
+            .and_then(Value::as_str)
+            .unwrap_or("unknown"),
+    );
+    let permit = state
+        .concurrency
+        .acquire(&upstream, max_concurrency)
+        .await
+        .map_err(ApiError::from)?;
+
+    if upstream == ProviderId::Copilot {
+        let mut response = copilot_messages(&state, body, stream, &beta).await?;
+        warnings.apply(&mut response);
+        return Ok(hold_permit(response, permit));
     }
 
     // Default: passthrough to Anthropic API.
@@ -407,22 +446,23 @@ pub async fn anthropic_messages(
         .unwrap_or("unknown")
         .to_string();
 
-    let resp = builder
-        .json(&body)
-        .send()
+    let timeout = provider_cfg.and_then(byokey_config::ProviderConfig::request_timeout);
+    let resp = byokey_provider::send_with_timeout(builder.json(&body), timeout, stream)
         .await
-        .map_err(|e| ApiError(ByokError::from(e)))?;
+        .map_err(ApiError)?;
 
-    forward_response(
+    let mut response = forward_response(
         resp,
         stream,
-        &state.usage,
+        &state,
         &model_name,
         "claude",
         &account_id,
         is_oauth,
     )
-    .await
+    .await?;
+    warnings.apply(&mut response);
+    Ok(hold_permit(response, permit))
 }
 
 /// Build a Copilot Messages API request with standard headers.
@@ -478,6 +518,7 @@ async fn copilot_messages(
         .get(&ProviderId::Copilot)
         .cloned()
         .unwrap_or_default();
+    let timeout = copilot_config.request_timeout();
 
     let executor = CopilotExecutor::builder()
         .http(state.http.clone())
@@ -534,7 +575,7 @@ async fn copilot_messages(
             "routing Anthropic messages through Copilot"
         );
 
-        let resp = build_copilot_messages_request(
+        let request = build_copilot_messages_request(
             &state.http,
             &url,
             &token,
@@ -542,9 +583,8 @@ async fn copilot_messages(
             accept,
             initiator,
             &body,
-        )
-        .send()
-        .await;
+        );
+        let resp = byokey_provider::send_with_timeout(request, timeout, stream).await;
 
         match resp {
             Ok(r) if r.status().is_success() => {
@@ -554,7 +594,7 @@ async fn copilot_messages(
                 return forward_response(
                     r,
                     stream,
-                    &state.usage,
+                    &state,
                     &model_name,
                     "copilot",
                     byokey_types::DEFAULT_ACCOUNT,
@@ -581,8 +621,7 @@ async fn copilot_messages(
                 CopilotExecutor::invalidate_current_account();
                 last_err = Some(ApiError(err));
             }
-            Err(e) => {
-                let err = ByokError::from(e);
+            Err(err) => {
                 if !err.is_retryable() || attempt + 1 >= max_attempts {
                     return Err(ApiError(err));
                 }
@@ -608,7 +647,7 @@ async fn copilot_messages(
 async fn forward_response(
     resp: rquest::Response,
     stream: bool,
-    usage: &Arc<UsageRecorder>,
+    state: &AppState,
     model: &str,
     provider: &str,
     account_id: &str,
@@ -619,10 +658,10 @@ async fn forward_response(
         let text = resp.text().await.unwrap_or_default();
         tracing::error!(
             status = status.as_u16(),
-            body = %text,
+            body = %truncate_for_log(&text, state.config.load().log.max_body_bytes),
             "anthropic upstream error (non-retryable)"
         );
-        usage.record_failure_for(model, provider, account_id);
+        state.usage.record_failure_for(model, provider, account_id);
         return Err(ApiError::from(ByokError::Upstream {
             status: status.as_u16(),
             body: text,
@@ -668,13 +707,15 @@ async fn forward_response(
         };
         let tapped = tap_usage_stream(
             remapped,
-            usage.clone(),
+            state.usage.clone(),
             model.to_string(),
             provider.to_string(),
             account_id.to_string(),
             AnthropicParser::new(),
         );
-        let mapped = tapped.map_err(|e| std::io::Error::other(e.to_string()));
+        let keepalive = state.config.load().streaming.keepalive_seconds;
+        let mapped =
+            keepalive_stream(tapped, keepalive).map_err(|e| std::io::Error::other(e.to_string()));
         let mut sse = sse_response(upstream_status, mapped);
         // Merge upstream headers (gateway-stripped) into the SSE response.
         // We do not overwrite the SSE-specific headers set by sse_response.
@@ -693,7 +734,9 @@ async fn forward_response(
             byokey_provider::cloak::reverse_remap_tool_names_response(&mut json);
         }
         let (input, output) = extract_usage(&json, "/usage/input_tokens", "/usage/output_tokens");
-        usage.record_success_for(model, provider, account_id, input, output);
+        state
+            .usage
+            .record_success_for(model, provider, account_id, input, output);
         let mut response = (upstream_status, axum::Json(json)).into_response();
         // Merge upstream headers (gateway-stripped) into the JSON response.
         for (name, value) in &upstream_headers {
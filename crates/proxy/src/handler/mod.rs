@@ -2,10 +2,14 @@
 //!
 //! - [`chat`] / [`messages`] / [`models`] — `OpenAI`-compatible API.
 //! - [`amp`]                              — Amp CLI / `AmpCode` proxy.
+//! - [`debug`]                            — Dry-run views of request rewriting.
+//! - [`health`]                           — `/healthz` and `/readyz` probes, provider health.
 //! - [`management`]                       — BYOKEY management API (`/v0/management/*`).
 
 pub mod amp;
 pub(crate) mod chat;
+pub(crate) mod debug;
+pub(crate) mod health;
 pub mod management;
 pub(crate) mod messages;
 pub(crate) mod models;
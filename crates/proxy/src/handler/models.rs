@@ -1,10 +1,18 @@
 //! Models listing handler — returns available models in `OpenAI` format.
 
-use axum::{Json, extract::State};
+use axum::{
+    Json,
+    extract::{Query, State},
+    http::{HeaderMap, HeaderValue, StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use byokey_config::Config;
 use byokey_provider::all_models;
-use serde::Serialize;
+use byokey_types::ProviderId;
+use serde::{Deserialize, Serialize};
+use std::hash::{DefaultHasher, Hash as _, Hasher as _};
 use std::sync::Arc;
-use utoipa::ToSchema;
+use utoipa::{IntoParams, ToSchema};
 
 use crate::AppState;
 
@@ -16,7 +24,7 @@ pub struct ModelsResponse {
 }
 
 /// A single model entry.
-#[derive(Serialize, ToSchema)]
+#[derive(Serialize, ToSchema, Hash)]
 pub struct ModelEntry {
     pub id: String,
     pub object: String,
@@ -24,20 +32,86 @@ pub struct ModelEntry {
     pub owned_by: String,
 }
 
+/// Optional filters for `GET /v1/models`.
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ModelsQuery {
+    /// Only list models served by this provider (name or alias). An unknown
+    /// provider yields an empty list.
+    pub provider: Option<String>,
+    /// Only list model ids starting with this prefix.
+    pub prefix: Option<String>,
+}
+
+impl ModelsQuery {
+    /// Keeps only the entries matching every given filter.
+    fn retain(&self, data: &mut Vec<ModelEntry>) {
+        if let Some(provider) = &self.provider {
+            match provider.parse::<ProviderId>() {
+                Ok(p) => {
+                    let owner = p.to_string();
+                    data.retain(|m| m.owned_by == owner);
+                }
+                Err(_) => data.clear(),
+            }
+        }
+        if let Some(prefix) = &self.prefix {
+            data.retain(|m| m.id.starts_with(prefix.as_str()));
+        }
+    }
+}
+
 /// Handles `GET /v1/models` requests.
 ///
 /// Returns an OpenAI-compatible model list from the unified registry.
 /// For models available on multiple providers, both unqualified (primary)
 /// and qualified (`provider/model`) forms are listed.
+///
+/// `?provider=` and `?prefix=` narrow the listing to one provider's models
+/// or to ids with a given prefix.
+///
+/// The response carries an `ETag` derived from the listed entries; a request
+/// whose `If-None-Match` matches it gets `304 Not Modified` with no body.
 #[utoipa::path(
     get,
     path = "/v1/models",
-    responses((status = 200, body = ModelsResponse)),
+    params(ModelsQuery),
+    responses(
+        (status = 200, body = ModelsResponse),
+        (status = 304, description = "Listing unchanged since the given ETag"),
+    ),
     tag = "management"
 )]
-pub async fn list_models(State(state): State<Arc<AppState>>) -> Json<ModelsResponse> {
+pub async fn list_models(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<ModelsQuery>,
+    headers: HeaderMap,
+) -> Response {
+    let mut data = advertised_models(&state.config.load());
+    query.retain(&mut data);
+
+    let etag = models_etag(&data);
+    let not_modified = headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.split(',').any(|t| t.trim() == etag || t.trim() == "*"));
+    if not_modified {
+        return (StatusCode::NOT_MODIFIED, [(header::ETAG, etag)]).into_response();
+    }
+
+    (
+        [(header::ETAG, etag)],
+        Json(ModelsResponse {
+            object: "list".into(),
+            data,
+        }),
+    )
+        .into_response()
+}
+
+/// Builds the `/v1/models` listing for `config`.
+fn advertised_models(config: &Config) -> Vec<ModelEntry> {
     let mut data: Vec<ModelEntry> = Vec::new();
-    let config = state.config.load();
 
     for entry in all_models() {
         let Some(primary_provider) = entry.providers.first() else {
@@ -52,27 +126,26 @@ pub async fn list_models(State(state): State<Arc<AppState>>) -> Json<ModelsRespo
         let primary_enabled =
             primary_pc.enabled && !config.is_model_excluded(primary_provider, entry.id);
 
-        // List the unqualified model under its primary provider if enabled.
+        // List the unqualified model under its primary provider if enabled:
+        // every alias of it, plus the original name unless all of those
+        // aliases replace it (`fork: false`).
         if primary_enabled {
-            let aliases = config.model_alias.get(primary_provider);
-            let alias_entry = aliases.and_then(|a| a.iter().find(|ae| ae.name == entry.id));
-
-            if let Some(ae) = alias_entry {
+            let aliases: Vec<_> = config
+                .model_alias
+                .get(primary_provider)
+                .into_iter()
+                .flatten()
+                .filter(|ae| ae.name == entry.id)
+                .collect();
+            for ae in &aliases {
                 data.push(ModelEntry {
                     id: ae.alias.clone(),
                     object: "model".into(),
                     created: 0,
                     owned_by: primary_provider.to_string(),
                 });
-                if ae.fork {
-                    data.push(ModelEntry {
-                        id: entry.id.to_string(),
-                        object: "model".into(),
-                        created: 0,
-                        owned_by: primary_provider.to_string(),
-                    });
-                }
-            } else {
+            }
+            if aliases.is_empty() || aliases.iter().any(|ae| ae.fork) {
                 data.push(ModelEntry {
                     id: entry.id.to_string(),
                     object: "model".into(),
@@ -107,8 +180,111 @@ pub async fn list_models(State(state): State<Arc<AppState>>) -> Json<ModelsRespo
         }
     }
 
-    Json(ModelsResponse {
-        object: "list".into(),
-        data,
-    })
+    // Weighted routes are public names of their own.
+    let mut routes: Vec<&String> = config.model_routing.keys().collect();
+    routes.sort();
+    for name in routes {
+        data.push(ModelEntry {
+            id: name.clone(),
+            object: "model".into(),
+            created: 0,
+            owned_by: "byokey".into(),
+        });
+    }
+    data
+}
+
+/// Computes a strong `ETag` over the model listing.
+fn models_etag(data: &[ModelEntry]) -> HeaderValue {
+    let mut hasher = DefaultHasher::new();
+    data.hash(&mut hasher);
+    HeaderValue::from_str(&format!("\"{:016x}\"", hasher.finish()))
+        .expect("hex etag is a valid header value")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(yaml: &str) -> Vec<String> {
+        let config = Config::from_yaml(yaml).unwrap();
+        advertised_models(&config)
+            .into_iter()
+            .map(|m| m.id)
+            .collect()
+    }
+
+    #[test]
+    fn non_forked_alias_hides_original() {
+        let ids = ids(r#"
+model_alias:
+  claude:
+    - name: "claude-opus-4-5"
+      alias: "opus"
+"#);
+        assert!(ids.iter().any(|id| id == "opus"));
+        assert!(!ids.iter().any(|id| id == "claude-opus-4-5"));
+    }
+
+    #[test]
+    fn forked_alias_lists_both_names() {
+        let ids = ids(r#"
+model_alias:
+  claude:
+    - name: "claude-opus-4-5"
+      alias: "opus"
+    - name: "claude-opus-4-5"
+      alias: "big"
+      fork: true
+"#);
+        assert!(ids.iter().any(|id| id == "opus"));
+        assert!(ids.iter().any(|id| id == "big"));
+        assert_eq!(ids.iter().filter(|id| *id == "claude-opus-4-5").count(), 1);
+    }
+
+    fn filtered(query: &ModelsQuery) -> Vec<ModelEntry> {
+        let mut data = advertised_models(&Config::default());
+        query.retain(&mut data);
+        data
+    }
+
+    #[test]
+    fn provider_filter_keeps_one_catalog() {
+        let query = ModelsQuery {
+            provider: Some("anthropic".into()),
+            ..Default::default()
+        };
+        let data = filtered(&query);
+        assert!(!data.is_empty());
+        assert!(data.iter().all(|m| m.owned_by == "claude"));
+        assert!(data.iter().any(|m| m.id == "claude-opus-4-5"));
+
+        let unknown = ModelsQuery {
+            provider: Some("nope".into()),
+            ..Default::default()
+        };
+        assert!(filtered(&unknown).is_empty());
+    }
+
+    #[test]
+    fn prefix_filter_matches_ids() {
+        let query = ModelsQuery {
+            prefix: Some("gpt-".into()),
+            ..Default::default()
+        };
+        let data = filtered(&query);
+        assert!(!data.is_empty());
+        assert!(data.iter().all(|m| m.id.starts_with("gpt-")));
+
+        let both = ModelsQuery {
+            provider: Some("codex".into()),
+            prefix: Some("gpt-".into()),
+        };
+        let data = filtered(&both);
+        assert!(!data.is_empty());
+        assert!(
+            data.iter()
+                .all(|m| m.owned_by == "codex" && m.id.starts_with("gpt-"))
+        );
+    }
 }
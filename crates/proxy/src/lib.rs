@@ -5,11 +5,13 @@
 //! - [`handler`]  — HTTP route handlers (API, Amp, management).
 //! - [`router`]   — Axum router construction and route registration.
 //! - [`error`]    — [`ApiError`] type for OpenAI-compatible error responses.
+//! - [`metrics`]  — Prometheus metrics served at `/metrics`.
 //! - [`openapi`]  — `OpenAPI` specification generation.
 //! - [`usage`]    — In-memory request/token usage tracking.
 
 pub mod error;
 pub mod handler;
+pub mod metrics;
 pub mod middleware;
 #[allow(clippy::needless_for_each)]
 pub mod openapi;
@@ -20,12 +22,14 @@ pub(crate) mod util;
 pub use byokey_provider::VersionStore;
 pub use error::ApiError;
 pub use handler::amp::threads::AmpThreadIndex;
+pub use middleware::inflight::InFlight;
 pub use openapi::ApiDoc;
 pub use router::make_router;
-pub use usage::{UsageRecorder, UsageStats};
+pub use usage::{UsageRecorder, UsageSnapshot, UsageStats};
 
 use arc_swap::ArcSwap;
 use byokey_auth::AuthManager;
+use byokey_config::UpstreamHttpVersion;
 use byokey_provider::DeviceProfileCache;
 use byokey_types::{RateLimitStore, UsageStore};
 use std::sync::Arc;
@@ -49,6 +53,16 @@ pub struct AppState {
     pub amp_threads: Arc<AmpThreadIndex>,
     /// Remote version/fingerprint info fetched from assets.byokey.io at startup.
     pub versions: VersionStore,
+    /// Requests whose responses are still being served, for graceful shutdown.
+    pub in_flight: InFlight,
+    /// Per-provider request metrics exported at `/metrics`.
+    pub metrics: Arc<metrics::Metrics>,
+    /// In-flight identical requests shared when `coalesce_requests` is on.
+    coalescer: util::coalesce::Coalescer,
+    /// Per-provider slots enforcing `max_concurrency`.
+    concurrency: Arc<util::concurrency::ConcurrencyLimits>,
+    /// Last provider health report, served by the management API.
+    provider_health: handler::health::HealthCache,
 }
 
 impl AppState {
@@ -78,7 +92,10 @@ impl AppState {
         amp_threads: Arc<AmpThreadIndex>,
     ) -> Arc<Self> {
         let snapshot = config.load();
-        let http = build_http_client(snapshot.proxy_url.as_deref());
+        let http = build_http_client(
+            snapshot.proxy_url.as_deref(),
+            snapshot.upstream_http_version,
+        );
         Arc::new(Self {
             config,
             auth,
@@ -88,24 +105,108 @@ impl AppState {
             device_profiles: Arc::new(DeviceProfileCache::new()),
             amp_threads,
             versions,
+            in_flight: InFlight::default(),
+            metrics: Arc::default(),
+            coalescer: util::coalesce::Coalescer::default(),
+            concurrency: Arc::default(),
+            provider_health: handler::health::HealthCache::default(),
         })
     }
 }
 
-/// Build an HTTP client, optionally configured with a proxy URL.
-fn build_http_client(proxy_url: Option<&str>) -> rquest::Client {
+/// Build an HTTP client, optionally configured with a proxy URL and a
+/// forced upstream HTTP version.
+fn build_http_client(proxy_url: Option<&str>, version: UpstreamHttpVersion) -> rquest::Client {
+    configure_client(rquest::Client::builder(), proxy_url, version)
+        .build()
+        .unwrap_or_else(|_| rquest::Client::new())
+}
+
+/// The subset of client-builder options set from configuration; lets tests
+/// observe what [`configure_client`] applies.
+trait ClientOptions: Sized {
+    fn proxy(self, proxy: rquest::Proxy) -> Self;
+    fn http1_only(self) -> Self;
+    fn http2_only(self) -> Self;
+}
+
+impl ClientOptions for rquest::ClientBuilder {
+    fn proxy(self, proxy: rquest::Proxy) -> Self {
+        self.proxy(proxy)
+    }
+
+    fn http1_only(self) -> Self {
+        self.http1_only()
+    }
+
+    fn http2_only(self) -> Self {
+        self.http2_only()
+    }
+}
+
+fn configure_client<B: ClientOptions>(
+    mut builder: B,
+    proxy_url: Option<&str>,
+    version: UpstreamHttpVersion,
+) -> B {
     if let Some(url) = proxy_url {
         match rquest::Proxy::all(url) {
-            Ok(proxy) => {
-                return rquest::Client::builder()
-                    .proxy(proxy)
-                    .build()
-                    .unwrap_or_else(|_| rquest::Client::new());
-            }
+            Ok(proxy) => builder = builder.proxy(proxy),
             Err(e) => {
                 tracing::warn!(url = url, error = %e, "invalid proxy_url, using direct connection");
             }
         }
     }
-    rquest::Client::new()
+    match version {
+        UpstreamHttpVersion::Auto => builder,
+        UpstreamHttpVersion::Http1 => builder.http1_only(),
+        UpstreamHttpVersion::Http2 => builder.http2_only(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Records which options `configure_client` applied.
+    #[derive(Default)]
+    struct Recorder {
+        proxied: bool,
+        http1_only: bool,
+        http2_only: bool,
+    }
+
+    impl ClientOptions for Recorder {
+        fn proxy(mut self, _: rquest::Proxy) -> Self {
+            self.proxied = true;
+            self
+        }
+
+        fn http1_only(mut self) -> Self {
+            self.http1_only = true;
+            self
+        }
+
+        fn http2_only(mut self) -> Self {
+            self.http2_only = true;
+            self
+        }
+    }
+
+    #[test]
+    fn configure_client_applies_requested_http_version() {
+        let auto = configure_client(Recorder::default(), None, UpstreamHttpVersion::Auto);
+        assert!(!auto.http1_only && !auto.http2_only);
+
+        let h1 = configure_client(Recorder::default(), None, UpstreamHttpVersion::Http1);
+        assert!(h1.http1_only && !h1.http2_only);
+
+        let h2 = configure_client(
+            Recorder::default(),
+            Some("http://127.0.0.1:8080"),
+            UpstreamHttpVersion::Http2,
+        );
+        assert!(h2.http2_only && !h2.http1_only);
+        assert!(h2.proxied);
+    }
 }
@@ -0,0 +1,416 @@
+//! Prometheus metrics for `GET /metrics`.
+//!
+//! Handlers call [`label_request`] once they know the provider and model of
+//! a request; [`track_requests`] then records its status, latency, upstream
+//! failures and, for SSE responses, how long the stream stays open.
+//! Unlabelled requests (health probes, the amp proxy) are not recorded.
+//! Token-refresh and usage counters are read from [`AuthManager`] and
+//! [`UsageRecorder`] at scrape time.
+//!
+//! [`AuthManager`]: byokey_auth::AuthManager
+//! [`UsageRecorder`]: crate::UsageRecorder
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use axum::{
+    extract::{Request, State},
+    http::{HeaderValue, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::AppState;
+use crate::middleware::inflight::hold_until_sent;
+
+/// Upper bounds, in seconds, of the request latency histogram buckets.
+const LATENCY_BUCKETS: [f64; 10] = [0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0, 120.0];
+
+/// Content type of the Prometheus text exposition format.
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+tokio::task_local! {
+    /// Labels of the request being handled, set inside [`track_requests`].
+    static LABELS: RefCell<Option<RequestLabels>>;
+}
+
+#[derive(Debug, Clone)]
+struct RequestLabels {
+    provider: String,
+    model: String,
+}
+
+/// Model label for models outside the registry.
+const OTHER_MODEL: &str = "other";
+
+/// Attributes the current request to `provider` and the resolved `model`
+/// (alias, qualifier and suffix stripped) in metrics. Models missing from
+/// the registry are labelled `other`, so client-chosen names cannot grow
+/// the series count. A no-op outside [`track_requests`].
+pub(crate) fn label_request(provider: &str, model: &str) {
+    let model = if byokey_provider::is_known_model(model) {
+        model
+    } else {
+        OTHER_MODEL
+    };
+    let _ = LABELS.try_with(|slot| {
+        *slot.borrow_mut() = Some(RequestLabels {
+            provider: provider.to_string(),
+            model: model.to_string(),
+        });
+    });
+}
+
+/// Response extension marking an error caused by the upstream provider.
+/// Holds the upstream status code, `timeout` or `connection`.
+#[derive(Debug, Clone)]
+pub(crate) struct UpstreamFailure(pub(crate) String);
+
+#[derive(Default)]
+struct Histogram {
+    /// Non-cumulative counts per bucket in [`LATENCY_BUCKETS`].
+    buckets: [u64; LATENCY_BUCKETS.len()],
+    count: u64,
+    sum: f64,
+}
+
+impl Histogram {
+    fn observe(&mut self, secs: f64) {
+        if let Some(i) = LATENCY_BUCKETS.iter().position(|&le| secs <= le) {
+            self.buckets[i] += 1;
+        }
+        self.count += 1;
+        self.sum += secs;
+    }
+}
+
+#[derive(Default)]
+struct Recorded {
+    /// Keyed by (provider, model, status).
+    requests: BTreeMap<(String, String, u16), u64>,
+    /// Keyed by (provider, model).
+    latency: BTreeMap<(String, String), Histogram>,
+    /// Keyed by (provider, kind).
+    upstream_errors: BTreeMap<(String, String), u64>,
+}
+
+/// Request metrics recorded by [`track_requests`].
+#[derive(Default)]
+pub struct Metrics {
+    recorded: Mutex<Recorded>,
+    active_streams: Arc<AtomicUsize>,
+}
+
+impl Metrics {
+    /// Number of SSE responses currently being streamed.
+    #[must_use]
+    pub fn active_streams(&self) -> usize {
+        self.active_streams.load(Ordering::Relaxed)
+    }
+
+    fn record(&self, labels: RequestLabels, response: &Response, elapsed: f64) {
+        let mut recorded = self.recorded.lock().expect("metrics lock poisoned");
+        if let Some(UpstreamFailure(kind)) = response.extensions().get() {
+            *recorded
+                .upstream_errors
+                .entry((labels.provider.clone(), kind.clone()))
+                .or_default() += 1;
+        }
+        *recorded
+            .requests
+            .entry((
+                labels.provider.clone(),
+                labels.model.clone(),
+                response.status().as_u16(),
+            ))
+            .or_default() += 1;
+        recorded
+            .latency
+            .entry((labels.provider, labels.model))
+            .or_default()
+            .observe(elapsed);
+    }
+
+    fn render(&self, out: &mut String) {
+        let recorded = self.recorded.lock().expect("metrics lock poisoned");
+
+        header(
+            out,
+            "byokey_requests_total",
+            "counter",
+            "Proxied requests by provider, model and response status.",
+        );
+        for ((provider, model, status), n) in &recorded.requests {
+            let _ = writeln!(
+                out,
+                "byokey_requests_total{{provider=\"{}\",model=\"{}\",status=\"{status}\"}} {n}",
+                escape(provider),
+                escape(model),
+            );
+        }
+
+        header(
+            out,
+            "byokey_request_duration_seconds",
+            "histogram",
+            "Time until response headers, by provider and model.",
+        );
+        for ((provider, model), h) in &recorded.latency {
+            let labels = format!(
+                "provider=\"{}\",model=\"{}\"",
+                escape(provider),
+                escape(model)
+            );
+            let mut cumulative = 0;
+            for (le, n) in LATENCY_BUCKETS.iter().zip(h.buckets) {
+                cumulative += n;
+                let _ = writeln!(
+                    out,
+                    "byokey_request_duration_seconds_bucket{{{labels},le=\"{le}\"}} {cumulative}"
+                );
+            }
+            let _ = writeln!(
+                out,
+                "byokey_request_duration_seconds_bucket{{{labels},le=\"+Inf\"}} {}",
+                h.count
+            );
+            let _ = writeln!(
+                out,
+                "byokey_request_duration_seconds_sum{{{labels}}} {}",
+                h.sum
+            );
+            let _ = writeln!(
+                out,
+                "byokey_request_duration_seconds_count{{{labels}}} {}",
+                h.count
+            );
+        }
+
+        header(
+            out,
+            "byokey_upstream_errors_total",
+            "counter",
+            "Failed upstream calls by provider and upstream status, timeout or connection.",
+        );
+        for ((provider, kind), n) in &recorded.upstream_errors {
+            let _ = writeln!(
+                out,
+                "byokey_upstream_errors_total{{provider=\"{}\",kind=\"{}\"}} {n}",
+                escape(provider),
+                escape(kind),
+            );
+        }
+
+        header(
+            out,
+            "byokey_active_streams",
+            "gauge",
+            "Streaming responses currently open.",
+        );
+        let _ = writeln!(out, "byokey_active_streams {}", self.active_streams());
+    }
+}
+
+/// Decrements the active stream gauge when the stream body is dropped.
+struct StreamGuard(Arc<AtomicUsize>);
+
+impl Drop for StreamGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Axum middleware recording requests labelled via [`label_request`].
+pub async fn track_requests(
+    State(metrics): State<Arc<Metrics>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let start = Instant::now();
+    let (response, labels) = LABELS
+        .scope(RefCell::new(None), async move {
+            let response = next.run(request).await;
+            (response, LABELS.with(RefCell::take))
+        })
+        .await;
+    let Some(labels) = labels else {
+        return response;
+    };
+    metrics.record(labels, &response, start.elapsed().as_secs_f64());
+
+    let is_stream = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|ct| ct.starts_with("text/event-stream"));
+    if !is_stream {
+        return response;
+    }
+    metrics.active_streams.fetch_add(1, Ordering::Relaxed);
+    hold_until_sent(response, StreamGuard(Arc::clone(&metrics.active_streams)))
+}
+
+/// `GET /metrics` — Prometheus text exposition of proxy metrics.
+pub async fn metrics(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let mut out = String::new();
+    state.metrics.render(&mut out);
+
+    header(
+        &mut out,
+        "byokey_token_refresh_total",
+        "counter",
+        "OAuth token refresh round-trips by provider and result.",
+    );
+    let mut refreshes: Vec<_> = state.auth.refresh_counts().into_iter().collect();
+    refreshes.sort_by_key(|(provider, _)| provider.to_string());
+    for (provider, counts) in refreshes {
+        for (result, n) in [("success", counts.succeeded), ("failure", counts.failed)] {
+            let _ = writeln!(
+                out,
+                "byokey_token_refresh_total{{provider=\"{provider}\",result=\"{result}\"}} {n}"
+            );
+        }
+    }
+
+    let usage = state.usage.snapshot();
+    header(
+        &mut out,
+        "byokey_tokens_total",
+        "counter",
+        "Tokens reported by upstream usage, by model and direction.",
+    );
+    let models: BTreeMap<_, _> = usage.models.iter().collect();
+    for (model, stats) in models {
+        for (direction, n) in [
+            ("input", stats.input_tokens),
+            ("output", stats.output_tokens),
+        ] {
+            let _ = writeln!(
+                out,
+                "byokey_tokens_total{{model=\"{}\",direction=\"{direction}\"}} {n}",
+                escape(model),
+            );
+        }
+    }
+
+    header(
+        &mut out,
+        "byokey_in_flight_requests",
+        "gauge",
+        "Requests whose responses are still being served.",
+    );
+    let _ = writeln!(out, "byokey_in_flight_requests {}", state.in_flight.count());
+
+    (
+        [(header::CONTENT_TYPE, HeaderValue::from_static(CONTENT_TYPE))],
+        out,
+    )
+}
+
+fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} {kind}");
+}
+
+/// Escapes a label value per the exposition format.
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', r"\\")
+        .replace('"', "\\\"")
+        .replace('\n', r"\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{Router, body::Body, middleware, routing::get};
+    use http_body_util::BodyExt as _;
+    use tower::ServiceExt as _;
+
+    fn app(metrics: &Arc<Metrics>) -> Router {
+        Router::new()
+            .route(
+                "/chat",
+                get(|| async {
+                    label_request("claude", "claude-sonnet-4");
+                    "ok"
+                }),
+            )
+            .route(
+                "/stream",
+                get(|| async {
+                    label_request("codex", "gpt-5");
+                    (
+                        [(header::CONTENT_TYPE, "text/event-stream")],
+                        "data: {}\n\n",
+                    )
+                }),
+            )
+            .route(
+                "/unknown",
+                get(|| async {
+                    label_request("claude", "claude-made-up-1");
+                    "ok"
+                }),
+            )
+            .route("/healthz", get(|| async { "ok" }))
+            .layer(middleware::from_fn_with_state(
+                Arc::clone(metrics),
+                track_requests,
+            ))
+    }
+
+    fn get_req(path: &str) -> Request {
+        Request::builder().uri(path).body(Body::empty()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn labelled_requests_are_recorded() {
+        let metrics = Arc::new(Metrics::default());
+        let app = app(&metrics);
+        app.clone().oneshot(get_req("/chat")).await.unwrap();
+        app.oneshot(get_req("/healthz")).await.unwrap();
+
+        let mut out = String::new();
+        metrics.render(&mut out);
+        assert!(out.contains(
+            "byokey_requests_total{provider=\"claude\",model=\"claude-sonnet-4\",status=\"200\"} 1"
+        ));
+        assert!(out.contains(
+            "byokey_request_duration_seconds_count{provider=\"claude\",model=\"claude-sonnet-4\"} 1"
+        ));
+        assert!(!out.contains("healthz"));
+    }
+
+    #[tokio::test]
+    async fn unknown_models_are_labelled_other() {
+        let metrics = Arc::new(Metrics::default());
+        app(&metrics).oneshot(get_req("/unknown")).await.unwrap();
+
+        let mut out = String::new();
+        metrics.render(&mut out);
+        assert!(out.contains(
+            "byokey_requests_total{provider=\"claude\",model=\"other\",status=\"200\"} 1"
+        ));
+        assert!(!out.contains("claude-made-up-1"));
+    }
+
+    #[tokio::test]
+    async fn streams_count_as_active_until_consumed() {
+        let metrics = Arc::new(Metrics::default());
+        let response = app(&metrics).oneshot(get_req("/stream")).await.unwrap();
+        assert_eq!(metrics.active_streams(), 1);
+
+        response.into_body().collect().await.unwrap();
+        assert_eq!(metrics.active_streams(), 0);
+    }
+
+    #[test]
+    fn label_values_are_escaped() {
+        assert_eq!(escape("a\"b\\c\nd"), r#"a\"b\\c\nd"#);
+    }
+}
@@ -0,0 +1,24 @@
+//! Retry budget for every request that may call into provider executors.
+//!
+//! Handlers run inside the budget, so key rotation, fallbacks and
+//! provider-internal retries on any route draw on one shared allowance.
+
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::Response,
+};
+use byokey_provider::{RetryBudget, with_retry_budget};
+use std::sync::Arc;
+
+use crate::AppState;
+
+/// Runs the rest of the stack inside a fresh budget from `retry_budget`.
+pub async fn retry_budget(
+    State(state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let budget = RetryBudget::from_config(&state.config.load().retry_budget);
+    with_retry_budget(budget, next.run(request)).await
+}
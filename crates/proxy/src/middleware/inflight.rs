@@ -0,0 +1,116 @@
+//! In-flight request tracking, used to report what graceful shutdown cut off.
+//!
+//! A request counts as in flight until its response body has been fully
+//! sent (or dropped), so long-lived SSE streams stay counted while open.
+
+use axum::{
+    body::{Body, Bytes},
+    extract::{Request, State},
+    middleware::Next,
+    response::Response,
+};
+use http_body::{Frame, SizeHint};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::task::{Context, Poll};
+
+/// Shared counter of requests whose responses are still being served.
+#[derive(Debug, Clone, Default)]
+pub struct InFlight(Arc<AtomicUsize>);
+
+impl InFlight {
+    /// Number of requests currently in flight.
+    #[must_use]
+    pub fn count(&self) -> usize {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    fn enter(&self) -> InFlightGuard {
+        self.0.fetch_add(1, Ordering::Relaxed);
+        InFlightGuard(Arc::clone(&self.0))
+    }
+}
+
+/// Decrements the counter when dropped.
+struct InFlightGuard(Arc<AtomicUsize>);
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Response body that holds a guard until it is finished.
+struct TrackedBody<G> {
+    inner: Body,
+    _guard: G,
+}
+
+impl<G: Unpin> http_body::Body for TrackedBody<G> {
+    type Data = Bytes;
+    type Error = axum::Error;
+
+    fn poll_frame(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Bytes>, axum::Error>>> {
+        Pin::new(&mut self.get_mut().inner).poll_frame(cx)
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.inner.size_hint()
+    }
+}
+
+/// Axum middleware counting each request in `in_flight` until its response
+/// body completes.
+pub async fn track_in_flight(
+    State(in_flight): State<InFlight>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let guard = in_flight.enter();
+    hold_until_sent(next.run(request).await, guard)
+}
+
+/// Keeps `guard` alive until `response`'s body has been fully sent or
+/// dropped. Frames, including trailers, pass through unchanged.
+pub(crate) fn hold_until_sent<G>(response: Response, guard: G) -> Response
+where
+    G: Send + Unpin + 'static,
+{
+    response.map(|inner| {
+        Body::new(TrackedBody {
+            inner,
+            _guard: guard,
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{Router, middleware, routing::get};
+    use http_body_util::BodyExt as _;
+    use tower::ServiceExt as _;
+
+    #[tokio::test]
+    async fn request_counted_until_body_consumed() {
+        let in_flight = InFlight::default();
+        let app = Router::new().route("/", get(|| async { "hello" })).layer(
+            middleware::from_fn_with_state(in_flight.clone(), track_in_flight),
+        );
+
+        let response = app.oneshot(Request::new(Body::empty())).await.unwrap();
+        assert_eq!(in_flight.count(), 1);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        assert_eq!(body, "hello");
+        assert_eq!(in_flight.count(), 0);
+    }
+}
@@ -1,4 +1,6 @@
 //! Axum middleware layers for the proxy.
 
+pub mod budget;
 pub mod dump;
 pub mod forward;
+pub mod inflight;
@@ -23,7 +23,7 @@ use tower_http::request_id::{
 use tower_http::trace::TraceLayer;
 use tracing::{Span, info_span};
 
-use crate::handler::{amp, chat, management, messages, models};
+use crate::handler::{amp, chat, debug, health, management, messages, models};
 use crate::{AppState, openapi};
 
 fn common_layers(router: Router) -> Router {
@@ -85,6 +85,9 @@ fn common_layers(router: Router) -> Router {
 /// Routes served:
 /// - `/v1/chat/completions`, `/v1/responses`, `/v1/messages`, `/v1/models`
 ///   — `OpenAI` / Anthropic compatible REST AI.
+/// - `/v1/debug/payload` — dry run of `payload` rules for a request body.
+/// - `/healthz`, `/readyz` — liveness and readiness probes (no auth).
+/// - `/metrics` — Prometheus metrics (no auth).
 /// - `/openapi.json` — REST `OpenAPI` spec (AI endpoints only).
 /// - `/auth/cli-login`, `/v1/login` — amp CLI login redirects to
 ///   `ampcode.com`.
@@ -128,8 +131,12 @@ pub fn make_router(state: Arc<AppState>) -> Router {
             crate::middleware::forward::forward_headers_middleware,
         ));
 
-    // REST AI proxy routes.
+    // REST AI proxy routes, plus the health probes. These are merged ahead
+    // of the amp catch-all so they never reach the amp proxy.
     let rest_routes = Router::new()
+        .route("/healthz", get(health::healthz))
+        .route("/readyz", get(health::readyz))
+        .route("/metrics", get(crate::metrics::metrics))
         .route("/v1/chat/completions", post(chat::chat_completions))
         .route(
             "/v1/responses",
@@ -137,15 +144,30 @@ pub fn make_router(state: Arc<AppState>) -> Router {
         )
         .route("/v1/messages", post(messages::anthropic_messages))
         .route("/v1/models", get(models::list_models))
+        .route("/v1/debug/payload", post(debug::payload_dry_run))
         .route("/openapi.json", get(openapi::openapi_json));
 
     // `ConnectRPC` management service (served as the fallback).
     let connect_service = management::build_router(state.clone()).into_axum_service();
 
+    let in_flight = state.in_flight.clone();
+    let metrics = state.metrics.clone();
     let router = rest_routes
         .merge(amp_routes)
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            crate::middleware::budget::retry_budget,
+        ))
         .with_state(state)
-        .fallback_service(connect_service);
+        .fallback_service(connect_service)
+        .layer(middleware::from_fn_with_state(
+            metrics,
+            crate::metrics::track_requests,
+        ))
+        .layer(middleware::from_fn_with_state(
+            in_flight,
+            crate::middleware::inflight::track_in_flight,
+        ));
 
     common_layers(router)
 }
@@ -156,6 +178,9 @@ mod tests {
     use axum::{body::Body, http::Request};
     use byokey_auth::AuthManager;
     use byokey_store::InMemoryTokenStore;
+    use byokey_types::{
+        AccountInfo, ByokError, OAuthToken, ProviderId, Result as ByokResult, TokenStore,
+    };
     use http_body_util::BodyExt as _;
     use serde_json::Value;
     use tower::ServiceExt as _;
@@ -201,6 +226,51 @@ mod tests {
         assert!(!json["data"].as_array().unwrap().is_empty());
     }
 
+    #[tokio::test]
+    async fn test_list_models_if_none_match() {
+        let state = make_state();
+        let resp = make_router(state.clone())
+            .oneshot(
+                Request::builder()
+                    .uri("/v1/models")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let etag = resp.headers().get("etag").unwrap().clone();
+
+        // Matching tag → 304 without a body.
+        let resp = make_router(state.clone())
+            .oneshot(
+                Request::builder()
+                    .uri("/v1/models")
+                    .header("if-none-match", etag.clone())
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), axum::http::StatusCode::NOT_MODIFIED);
+        assert_eq!(resp.headers().get("etag"), Some(&etag));
+        let bytes = resp.into_body().collect().await.unwrap().to_bytes();
+        assert!(bytes.is_empty());
+
+        // Stale tag → full listing.
+        let resp = make_router(state)
+            .oneshot(
+                Request::builder()
+                    .uri("/v1/models")
+                    .header("if-none-match", "\"stale\"")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), axum::http::StatusCode::OK);
+        assert_eq!(body_json(resp).await["object"], "list");
+    }
+
     #[tokio::test]
     async fn test_amp_login_redirect() {
         let app = make_router(make_state());
@@ -291,6 +361,238 @@ mod tests {
         assert_eq!(resp.status(), axum::http::StatusCode::UNPROCESSABLE_ENTITY);
     }
 
+    #[tokio::test]
+    async fn test_chat_mock_provider_end_to_end() {
+        use byokey_provider::mock::{MockExecutor, with_mock};
+        use serde_json::json;
+
+        let mock = MockExecutor::json(json!({
+            "id": "chatcmpl-mock",
+            "object": "chat.completion",
+            "choices": [{"index": 0, "message": {"role": "assistant", "content": "pong"}}]
+        }));
+        let app = make_router(make_state());
+        let body = json!({
+            "model": "mock/echo-1",
+            "messages": [{"role": "user", "content": "ping"}],
+            "temperature": 0.3
+        });
+        let resp = with_mock(
+            mock.clone(),
+            app.oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/v1/chat/completions")
+                    .header("content-type", "application/json")
+                    .body(Body::from(serde_json::to_vec(&body).unwrap()))
+                    .unwrap(),
+            ),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(resp.status(), axum::http::StatusCode::OK);
+        let json = body_json(resp).await;
+        assert_eq!(json["choices"][0]["message"]["content"], "pong");
+
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 1);
+        let sent = requests[0].clone().into_body();
+        assert_eq!(sent["model"], "echo-1");
+        assert_eq!(sent["messages"][0]["content"], "ping");
+        assert_eq!(sent["temperature"], 0.3);
+    }
+
+    #[tokio::test]
+    async fn test_debug_payload_reports_applied_rules() {
+        use serde_json::json;
+
+        let config = byokey_config::Config::from_yaml(
+            r#"
+payload:
+  override:
+    - models: ["claude-*"]
+      params:
+        "temperature": 0.1
+"#,
+        )
+        .unwrap();
+        let state = make_state();
+        state.config.store(Arc::new(config));
+        let app = make_router(state);
+        let body = json!({"model": "claude-opus-4-5", "messages": [], "temperature": 1.0});
+        let resp = app
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/v1/debug/payload")
+                    .header("content-type", "application/json")
+                    .body(Body::from(serde_json::to_vec(&body).unwrap()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(resp.status(), axum::http::StatusCode::OK);
+        let json = body_json(resp).await;
+        assert_eq!(json["before"]["temperature"], 1.0);
+        assert_eq!(json["after"]["temperature"], 0.1);
+        assert_eq!(json["applied_rules"][0]["kind"], "override");
+        assert_eq!(json["applied_rules"][0]["pattern"], "claude-*");
+    }
+
+    #[tokio::test]
+    async fn test_healthz_ok() {
+        let resp = make_router(make_state())
+            .oneshot(
+                Request::builder()
+                    .uri("/healthz")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), axum::http::StatusCode::OK);
+        assert_eq!(body_json(resp).await["status"], "ok");
+    }
+
+    #[tokio::test]
+    async fn test_readyz_ready_with_responsive_store() {
+        let resp = make_router(make_state())
+            .oneshot(
+                Request::builder()
+                    .uri("/readyz")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), axum::http::StatusCode::OK);
+        assert_eq!(body_json(resp).await["status"], "ready");
+    }
+
+    /// Token store whose every query fails, for the readiness probe.
+    struct BrokenStore;
+
+    #[async_trait::async_trait]
+    impl TokenStore for BrokenStore {
+        async fn load(&self, _: &ProviderId) -> ByokResult<Option<OAuthToken>> {
+            Err(ByokError::Storage("down".into()))
+        }
+        async fn save(&self, _: &ProviderId, _: &OAuthToken) -> ByokResult<()> {
+            Err(ByokError::Storage("down".into()))
+        }
+        async fn remove(&self, _: &ProviderId) -> ByokResult<()> {
+            Err(ByokError::Storage("down".into()))
+        }
+        async fn list_accounts(&self, _: &ProviderId) -> ByokResult<Vec<AccountInfo>> {
+            Err(ByokError::Storage("down".into()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_readyz_unavailable_when_store_fails() {
+        let auth = Arc::new(AuthManager::new(
+            Arc::new(BrokenStore),
+            rquest::Client::new(),
+        ));
+        let config = Arc::new(arc_swap::ArcSwap::from_pointee(
+            byokey_config::Config::default(),
+        ));
+        let state = AppState::with_thread_index(
+            config,
+            auth,
+            None,
+            byokey_provider::VersionStore::empty(),
+            Arc::new(crate::AmpThreadIndex::empty()),
+        );
+        let resp = make_router(state)
+            .oneshot(
+                Request::builder()
+                    .uri("/readyz")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), axum::http::StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    #[tokio::test]
+    async fn test_provider_health_reports_each_provider() {
+        use crate::handler::health::{ProviderHealth, provider_health};
+        use axum::routing::get;
+
+        // `/ok/models` answers, `/bad/models` rejects the key.
+        let upstream = Router::new()
+            .route(
+                "/ok/models",
+                get(|| async { axum::Json(serde_json::json!({"data": []})) }),
+            )
+            .route(
+                "/bad/models",
+                get(|| async { axum::http::StatusCode::UNAUTHORIZED }),
+            );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, upstream).await.unwrap() });
+
+        let config = byokey_config::Config::from_yaml(&format!(
+            r#"
+providers:
+  custom:
+    base_url: "{base}/ok"
+  mistral:
+    api_key: "bad"
+    base_url: "{base}/bad"
+  claude:
+    api_key: "sk-ant-test"
+"#
+        ))
+        .unwrap();
+        let state = make_state();
+        state.config.store(Arc::new(config));
+
+        let report = provider_health(&state, false).await;
+        let status = |id: ProviderId| {
+            report
+                .providers
+                .iter()
+                .find(|(p, _)| *p == id)
+                .map(|(_, h)| h.clone())
+        };
+        assert_eq!(status(ProviderId::Custom), Some(ProviderHealth::Ok));
+        assert!(matches!(
+            status(ProviderId::Mistral),
+            Some(ProviderHealth::Error(_))
+        ));
+        // Claude has no credential check.
+        assert_eq!(status(ProviderId::Claude), Some(ProviderHealth::Unchecked));
+        // No credentials: not checked.
+        assert_eq!(status(ProviderId::Gemini), None);
+
+        // Served from cache until a refresh is requested.
+        assert!(Arc::ptr_eq(&report, &provider_health(&state, false).await));
+        assert!(!Arc::ptr_eq(&report, &provider_health(&state, true).await));
+    }
+
+    #[tokio::test]
+    async fn test_provider_health_served_by_management_service() {
+        let app = make_router(make_state());
+        let resp = app
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/byokey.status.StatusService/GetProviderHealth")
+                    .header("content-type", "application/json")
+                    .body(Body::from("{}"))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), axum::http::StatusCode::OK);
+    }
+
     /// Basic sanity check that the `ConnectRPC` management service is
     /// reachable at the expected fallback path.
     #[tokio::test]
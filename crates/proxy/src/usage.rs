@@ -2,10 +2,12 @@
 //! persistent backing via [`UsageStore`].
 
 use byokey_types::{DEFAULT_ACCOUNT, UsageRecord, UsageStore};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use tokio::sync::mpsc;
 
 /// Global request/token counters.
@@ -23,20 +25,59 @@ pub struct UsageStats {
     pub output_tokens: AtomicU64,
     /// Per-model request counts.
     model_counts: Mutex<HashMap<String, ModelStats>>,
+    /// Request and token counts per provider, then per model.
+    provider_counts: Mutex<HashMap<String, HashMap<String, ModelStats>>>,
 }
 
 /// Per-model usage counters.
-#[derive(Default, Clone, Serialize)]
+#[derive(Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ModelStats {
     pub requests: u64,
     pub success: u64,
     pub failure: u64,
     pub input_tokens: u64,
     pub output_tokens: u64,
+    /// Streams that produced at least one byte.
+    pub ttfb_samples: u64,
+    /// Sum of time-to-first-byte across `ttfb_samples`, in milliseconds.
+    pub ttfb_ms_total: u64,
+    /// Total bytes forwarded to clients across streamed responses.
+    pub stream_bytes: u64,
+    /// Requests served by a fallback provider, keyed by that provider.
+    pub fallback_hits: HashMap<String, u64>,
+    /// Streamed requests whose `output_tokens` were estimated from the
+    /// streamed text because upstream reported no usage.
+    pub estimated_output: u64,
+}
+
+impl ModelStats {
+    /// Mean time-to-first-byte in milliseconds, or `None` without samples.
+    #[must_use]
+    pub fn avg_ttfb_ms(&self) -> Option<u64> {
+        self.ttfb_ms_total.checked_div(self.ttfb_samples)
+    }
+
+    /// Adds `other`'s counters to these.
+    fn merge(&mut self, other: Self) {
+        self.requests += other.requests;
+        self.success += other.success;
+        self.failure += other.failure;
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+        self.ttfb_samples += other.ttfb_samples;
+        self.ttfb_ms_total = self.ttfb_ms_total.saturating_add(other.ttfb_ms_total);
+        self.stream_bytes += other.stream_bytes;
+        for (provider, hits) in other.fallback_hits {
+            *self.fallback_hits.entry(provider).or_default() += hits;
+        }
+        self.estimated_output += other.estimated_output;
+    }
 }
 
 /// JSON-serializable snapshot of current usage.
-#[derive(Serialize)]
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct UsageSnapshot {
     pub total_requests: u64,
     pub success_requests: u64,
@@ -44,6 +85,42 @@ pub struct UsageSnapshot {
     pub input_tokens: u64,
     pub output_tokens: u64,
     pub models: HashMap<String, ModelStats>,
+    /// Request and token counts per provider, then per model.
+    pub providers: HashMap<String, HashMap<String, ModelStats>>,
+}
+
+impl UsageSnapshot {
+    /// Reads a snapshot written by [`save`](Self::save), or `None` if
+    /// `path` does not exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or is not a snapshot.
+    pub fn load(path: &Path) -> std::io::Result<Option<Self>> {
+        match std::fs::read(path) {
+            Ok(bytes) => serde_json::from_slice(&bytes)
+                .map(Some)
+                .map_err(std::io::Error::other),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Writes the snapshot to `path` via a temporary file and rename, so a
+    /// crash mid-write never leaves a truncated snapshot behind.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be written.
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let json = serde_json::to_vec_pretty(self).map_err(std::io::Error::other)?;
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, json)?;
+        std::fs::rename(&tmp, path)
+    }
 }
 
 impl UsageStats {
@@ -53,6 +130,42 @@ impl UsageStats {
         Self::default()
     }
 
+    /// Creates a stats tracker seeded from a saved snapshot.
+    #[must_use]
+    pub fn from_snapshot(snapshot: UsageSnapshot) -> Self {
+        let stats = Self::new();
+        stats.restore(snapshot);
+        stats
+    }
+
+    /// Adds a saved snapshot's counters to the current ones.
+    pub fn restore(&self, snapshot: UsageSnapshot) {
+        self.total_requests
+            .fetch_add(snapshot.total_requests, Ordering::Relaxed);
+        self.success_requests
+            .fetch_add(snapshot.success_requests, Ordering::Relaxed);
+        self.failure_requests
+            .fetch_add(snapshot.failure_requests, Ordering::Relaxed);
+        self.input_tokens
+            .fetch_add(snapshot.input_tokens, Ordering::Relaxed);
+        self.output_tokens
+            .fetch_add(snapshot.output_tokens, Ordering::Relaxed);
+
+        if let Ok(mut map) = self.model_counts.lock() {
+            for (model, stats) in snapshot.models {
+                map.entry(model).or_default().merge(stats);
+            }
+        }
+        if let Ok(mut map) = self.provider_counts.lock() {
+            for (provider, models) in snapshot.providers {
+                let per_model = map.entry(provider).or_default();
+                for (model, stats) in models {
+                    per_model.entry(model).or_default().merge(stats);
+                }
+            }
+        }
+    }
+
     /// Record a successful request with optional token counts.
     pub fn record_success(&self, model: &str, input_tokens: u64, output_tokens: u64) {
         self.total_requests.fetch_add(1, Ordering::Relaxed);
@@ -82,6 +195,78 @@ impl UsageStats {
         }
     }
 
+    /// Like [`record_success`](Self::record_success), also counting the
+    /// request towards `provider`'s breakdown.
+    pub fn record_success_for(
+        &self,
+        model: &str,
+        provider: &str,
+        input_tokens: u64,
+        output_tokens: u64,
+    ) {
+        self.record_success(model, input_tokens, output_tokens);
+        self.with_provider_entry(model, provider, |entry| {
+            entry.requests += 1;
+            entry.success += 1;
+            entry.input_tokens += input_tokens;
+            entry.output_tokens += output_tokens;
+        });
+    }
+
+    /// Like [`record_failure`](Self::record_failure), also counting the
+    /// request towards `provider`'s breakdown.
+    pub fn record_failure_for(&self, model: &str, provider: &str) {
+        self.record_failure(model);
+        self.with_provider_entry(model, provider, |entry| {
+            entry.requests += 1;
+            entry.failure += 1;
+        });
+    }
+
+    fn with_provider_entry(&self, model: &str, provider: &str, f: impl FnOnce(&mut ModelStats)) {
+        if let Ok(mut map) = self.provider_counts.lock() {
+            f(map
+                .entry(provider.to_string())
+                .or_default()
+                .entry(model.to_string())
+                .or_default());
+        }
+    }
+
+    /// Record the time-to-first-byte of a streamed response.
+    pub fn record_first_byte(&self, model: &str, ttfb: Duration) {
+        let ms = u64::try_from(ttfb.as_millis()).unwrap_or(u64::MAX);
+        if let Ok(mut map) = self.model_counts.lock() {
+            let entry = map.entry(model.to_string()).or_default();
+            entry.ttfb_samples += 1;
+            entry.ttfb_ms_total = entry.ttfb_ms_total.saturating_add(ms);
+        }
+    }
+
+    /// Record the total bytes forwarded for a streamed response.
+    pub fn record_stream_bytes(&self, model: &str, bytes: u64) {
+        if let Ok(mut map) = self.model_counts.lock() {
+            let entry = map.entry(model.to_string()).or_default();
+            entry.stream_bytes += bytes;
+        }
+    }
+
+    /// Record that the output tokens of a request were estimated.
+    pub fn record_estimated_output(&self, model: &str, provider: &str) {
+        if let Ok(mut map) = self.model_counts.lock() {
+            map.entry(model.to_string()).or_default().estimated_output += 1;
+        }
+        self.with_provider_entry(model, provider, |entry| entry.estimated_output += 1);
+    }
+
+    /// Record that a fallback `provider` served a request for `model`.
+    pub fn record_fallback_hit(&self, model: &str, provider: &str) {
+        if let Ok(mut map) = self.model_counts.lock() {
+            let entry = map.entry(model.to_string()).or_default();
+            *entry.fallback_hits.entry(provider.to_string()).or_default() += 1;
+        }
+    }
+
     /// Take a JSON-serializable snapshot of current stats.
     #[must_use]
     pub fn snapshot(&self) -> UsageSnapshot {
@@ -90,6 +275,11 @@ impl UsageStats {
             .lock()
             .map(|m| m.clone())
             .unwrap_or_default();
+        let providers = self
+            .provider_counts
+            .lock()
+            .map(|m| m.clone())
+            .unwrap_or_default();
         UsageSnapshot {
             total_requests: self.total_requests.load(Ordering::Relaxed),
             success_requests: self.success_requests.load(Ordering::Relaxed),
@@ -97,6 +287,7 @@ impl UsageStats {
             input_tokens: self.input_tokens.load(Ordering::Relaxed),
             output_tokens: self.output_tokens.load(Ordering::Relaxed),
             models,
+            providers,
         }
     }
 }
@@ -110,6 +301,8 @@ pub struct UsageRecorder {
     stats: UsageStats,
     store: Option<Arc<dyn UsageStore>>,
     sender: Option<mpsc::UnboundedSender<UsageRecord>>,
+    /// Serialises snapshot file writes; never held while taking a snapshot.
+    snapshot_lock: tokio::sync::Mutex<()>,
 }
 
 impl UsageRecorder {
@@ -129,6 +322,7 @@ impl UsageRecorder {
             stats: UsageStats::new(),
             store,
             sender,
+            snapshot_lock: tokio::sync::Mutex::new(()),
         }
     }
 
@@ -188,7 +382,7 @@ impl UsageRecorder {
         output_tokens: u64,
     ) {
         self.stats
-            .record_success(model, input_tokens, output_tokens);
+            .record_success_for(model, provider, input_tokens, output_tokens);
         self.persist(
             model,
             provider,
@@ -206,16 +400,80 @@ impl UsageRecorder {
 
     /// Record a failed request, attributing it to a specific account.
     pub fn record_failure_for(&self, model: &str, provider: &str, account_id: &str) {
-        self.stats.record_failure(model);
+        self.stats.record_failure_for(model, provider);
         self.persist(model, provider, account_id, 0, 0, false);
     }
 
+    /// Record the time-to-first-byte of a streamed response.
+    pub fn record_first_byte(&self, model: &str, ttfb: Duration) {
+        self.stats.record_first_byte(model, ttfb);
+    }
+
+    /// Record the total bytes forwarded for a streamed response.
+    pub fn record_stream_bytes(&self, model: &str, bytes: u64) {
+        self.stats.record_stream_bytes(model, bytes);
+    }
+
+    /// Record that the output tokens of a request were estimated rather
+    /// than reported by upstream.
+    pub fn record_estimated_output(&self, model: &str, provider: &str) {
+        self.stats.record_estimated_output(model, provider);
+    }
+
+    /// Record that a fallback `provider` served a request for `model`.
+    pub fn record_fallback_hit(&self, model: &str, provider: &str) {
+        self.stats.record_fallback_hit(model, provider);
+    }
+
     /// Take a snapshot of in-memory stats.
     #[must_use]
     pub fn snapshot(&self) -> UsageSnapshot {
         self.stats.snapshot()
     }
 
+    /// Adds a snapshot saved by a previous run to the in-memory counters.
+    pub fn restore(&self, snapshot: UsageSnapshot) {
+        self.stats.restore(snapshot);
+    }
+
+    /// Writes the current snapshot to `path`.
+    ///
+    /// The snapshot is taken before any file I/O starts, so concurrent
+    /// [`snapshot`](Self::snapshot) calls are never blocked by a slow disk.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be written.
+    pub async fn save_snapshot(&self, path: &Path) -> std::io::Result<()> {
+        let _guard = self.snapshot_lock.lock().await;
+        let snapshot = self.snapshot();
+        let path = path.to_path_buf();
+        tokio::task::spawn_blocking(move || snapshot.save(&path))
+            .await
+            .map_err(std::io::Error::other)?
+    }
+
+    /// Spawns a task writing the snapshot to `path` every `interval`.
+    pub fn spawn_snapshot_writer(
+        self: &Arc<Self>,
+        path: PathBuf,
+        interval: Duration,
+    ) -> tokio::task::JoinHandle<()> {
+        let recorder = Arc::clone(self);
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            // The first tick fires immediately; nothing has changed yet.
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                if let Err(e) = recorder.save_snapshot(&path).await {
+                    tracing::warn!(error = %e, path = %path.display(), "failed to write usage snapshot");
+                }
+            }
+        })
+    }
+
     /// Pre-load cumulative counters from historical totals (e.g. on startup).
     pub fn preload(&self, model: &str, requests: u64, input_tokens: u64, output_tokens: u64) {
         self.stats
@@ -293,6 +551,16 @@ mod tests {
         assert_eq!(claude.output_tokens, 300);
     }
 
+    #[test]
+    fn test_record_fallback_hit() {
+        let stats = UsageStats::new();
+        stats.record_fallback_hit("gemini-2.0-flash", "copilot");
+        stats.record_fallback_hit("gemini-2.0-flash", "copilot");
+
+        let snap = stats.snapshot();
+        assert_eq!(snap.models["gemini-2.0-flash"].fallback_hits["copilot"], 2);
+    }
+
     #[test]
     fn test_record_failure() {
         let stats = UsageStats::new();
@@ -310,6 +578,95 @@ mod tests {
         assert_eq!(model.success, 1);
     }
 
+    #[test]
+    fn test_record_stream_timing() {
+        let stats = UsageStats::new();
+        stats.record_first_byte("gpt-4o", Duration::from_millis(100));
+        stats.record_first_byte("gpt-4o", Duration::from_millis(300));
+        stats.record_stream_bytes("gpt-4o", 512);
+
+        let model = &stats.snapshot().models["gpt-4o"];
+        assert_eq!(model.ttfb_samples, 2);
+        assert_eq!(model.avg_ttfb_ms(), Some(200));
+        assert_eq!(model.stream_bytes, 512);
+        assert_eq!(model.requests, 0);
+    }
+
+    #[test]
+    fn test_snapshot_roundtrip_restores_counters() {
+        let stats = UsageStats::new();
+        stats.record_success("gpt-4o", 10, 20);
+        stats.record_failure("gpt-4o");
+        stats.record_fallback_hit("gpt-4o", "copilot");
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested").join("usage.json");
+        stats.snapshot().save(&path).unwrap();
+
+        let restored = UsageStats::from_snapshot(UsageSnapshot::load(&path).unwrap().unwrap());
+        restored.record_success("gpt-4o", 1, 2);
+        let snap = restored.snapshot();
+        assert_eq!(snap.total_requests, 3);
+        assert_eq!(snap.failure_requests, 1);
+        assert_eq!(snap.input_tokens, 11);
+        assert_eq!(snap.models["gpt-4o"].requests, 3);
+        assert_eq!(snap.models["gpt-4o"].fallback_hits["copilot"], 1);
+    }
+
+    #[test]
+    fn test_load_missing_snapshot_is_none() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(
+            UsageSnapshot::load(&dir.path().join("usage.json"))
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_not_blocked_by_concurrent_saves() {
+        let recorder = Arc::new(UsageRecorder::new(None));
+        recorder.record_success("gpt-4o", "codex", 5, 5);
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("usage.json");
+
+        let saves: Vec<_> = (0..4)
+            .map(|_| {
+                let recorder = Arc::clone(&recorder);
+                let path = path.clone();
+                tokio::spawn(async move { recorder.save_snapshot(&path).await })
+            })
+            .collect();
+        for _ in 0..100 {
+            assert_eq!(recorder.snapshot().total_requests, 1);
+        }
+        for save in saves {
+            save.await.unwrap().unwrap();
+        }
+        let saved = UsageSnapshot::load(&path).unwrap().unwrap();
+        assert_eq!(saved.total_requests, 1);
+    }
+
+    #[test]
+    fn test_provider_breakdown() {
+        let stats = UsageStats::new();
+        stats.record_success_for("claude-opus-4-5", "claude", 100, 200);
+        stats.record_success_for("claude-opus-4-5", "copilot", 10, 20);
+        stats.record_failure_for("claude-opus-4-5", "claude");
+
+        let snap = stats.snapshot();
+        assert_eq!(snap.total_requests, 3);
+        assert_eq!(snap.models["claude-opus-4-5"].requests, 3);
+        let claude = &snap.providers["claude"]["claude-opus-4-5"];
+        assert_eq!(claude.requests, 2);
+        assert_eq!(claude.failure, 1);
+        assert_eq!(claude.input_tokens, 100);
+        assert_eq!(
+            snap.providers["copilot"]["claude-opus-4-5"].output_tokens,
+            20
+        );
+    }
+
     #[test]
     fn test_snapshot_empty() {
         let stats = UsageStats::new();
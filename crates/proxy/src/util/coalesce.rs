@@ -0,0 +1,197 @@
+//! Single-flight coalescing of identical concurrent non-streaming requests.
+
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash as _, Hasher as _};
+use std::sync::{Arc, Mutex};
+
+use byokey_provider::propagate_retry_budget;
+use byokey_types::ByokError;
+use futures_util::future::{BoxFuture, FutureExt as _, Shared};
+use serde_json::Value;
+
+type SharedResult = Result<Value, Arc<ByokError>>;
+
+/// Runs at most one upstream call per request key at a time; concurrent
+/// callers with the same key await and share the first call's result.
+#[derive(Default)]
+pub(crate) struct Coalescer {
+    inflight: Mutex<HashMap<u64, Shared<BoxFuture<'static, SharedResult>>>>,
+}
+
+impl Coalescer {
+    /// Hashes the resolved model and the full request body into a key.
+    ///
+    /// Object keys are hashed in sorted order so field order doesn't matter.
+    pub(crate) fn key(model: &str, body: &Value) -> u64 {
+        let mut h = DefaultHasher::new();
+        model.hash(&mut h);
+        hash_value(body, &mut h);
+        h.finish()
+    }
+
+    /// Awaits the in-flight call for `key`, or starts `fut` if there is none.
+    ///
+    /// Returns the shared result and whether this caller started the call
+    /// (the leader), so side effects such as usage accounting run once.
+    /// The call draws on the leader's retry budget whichever caller polls it.
+    pub(crate) async fn run<F>(&self, key: u64, fut: F) -> (SharedResult, bool)
+    where
+        F: Future<Output = Result<Value, ByokError>> + Send + 'static,
+    {
+        let (shared, leader) = {
+            let mut inflight = self.inflight.lock().expect("coalescer lock poisoned");
+            if let Some(existing) = inflight.get(&key) {
+                (existing.clone(), false)
+            } else {
+                let shared = propagate_retry_budget(fut)
+                    .map(|r| r.map_err(Arc::new))
+                    .boxed()
+                    .shared();
+                inflight.insert(key, shared.clone());
+                (shared, true)
+            }
+        };
+
+        let result = shared.await;
+
+        // Any finisher clears the finished flight (the leader may have been
+        // cancelled), so later identical requests start a fresh call.
+        let mut inflight = self.inflight.lock().expect("coalescer lock poisoned");
+        if inflight.get(&key).is_some_and(|f| f.peek().is_some()) {
+            inflight.remove(&key);
+        }
+        drop(inflight);
+        (result, leader)
+    }
+}
+
+fn hash_value(value: &Value, h: &mut DefaultHasher) {
+    match value {
+        Value::Object(map) => {
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_unstable_by_key(|(k, _)| *k);
+            h.write_u8(b'{');
+            for (k, v) in entries {
+                k.hash(h);
+                hash_value(v, h);
+            }
+        }
+        Value::Array(items) => {
+            h.write_u8(b'[');
+            h.write_usize(items.len());
+            for v in items {
+                hash_value(v, h);
+            }
+        }
+        scalar => scalar.to_string().hash(h),
+    }
+}
+
+/// Recovers an owned [`ByokError`] from a shared result.
+///
+/// The last holder gets the original error back; other callers get an
+/// equivalent copy that maps to the same HTTP status.
+pub(crate) fn unshare_error(e: Arc<ByokError>) -> ByokError {
+    Arc::try_unwrap(e).unwrap_or_else(|e| match &*e {
+        ByokError::Auth(m) => ByokError::Auth(m.clone()),
+        ByokError::TokenNotFound(p) => ByokError::TokenNotFound(p.clone()),
+        ByokError::TokenExpired(p) => ByokError::TokenExpired(p.clone()),
+        ByokError::ProviderUnavailable(p) => ByokError::ProviderUnavailable(p.clone()),
+        ByokError::ProviderDisabled(p) => ByokError::ProviderDisabled(p.clone()),
+        ByokError::ProviderBusy(p) => ByokError::ProviderBusy(p.clone()),
+        ByokError::Translation(m) => ByokError::Translation(m.clone()),
+        ByokError::Http(m) => ByokError::Http(m.clone()),
+        ByokError::Timeout(d) => ByokError::Timeout(*d),
+        ByokError::UnsupportedModel(m) => ByokError::UnsupportedModel(m.clone()),
+        ByokError::UnsupportedProvider(m) => ByokError::UnsupportedProvider(m.clone()),
+        ByokError::Upstream {
+            status,
+            body,
+            retry_after,
+        } => ByokError::Upstream {
+            status: *status,
+            body: body.clone(),
+            retry_after: *retry_after,
+        },
+        other => ByokError::Storage(other.to_string()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    fn stub_upstream(calls: &Arc<AtomicUsize>) -> impl Future<Output = Result<Value, ByokError>> {
+        let calls = Arc::clone(calls);
+        async move {
+            calls.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            Ok(serde_json::json!({"id": "chatcmpl-1"}))
+        }
+    }
+
+    #[tokio::test]
+    async fn identical_concurrent_requests_hit_upstream_once() {
+        let coalescer = Coalescer::default();
+        let calls = Arc::new(AtomicUsize::new(0));
+        let body =
+            serde_json::json!({"model": "m", "messages": [{"role": "user", "content": "hi"}]});
+        let key = Coalescer::key("m", &body);
+
+        let ((a, a_leader), (b, b_leader)) = tokio::join!(
+            coalescer.run(key, stub_upstream(&calls)),
+            coalescer.run(key, stub_upstream(&calls)),
+        );
+
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(a.unwrap()["id"], "chatcmpl-1");
+        assert_eq!(b.unwrap()["id"], "chatcmpl-1");
+        assert!(a_leader ^ b_leader);
+        assert!(coalescer.inflight.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn different_requests_are_not_coalesced() {
+        let coalescer = Coalescer::default();
+        let calls = Arc::new(AtomicUsize::new(0));
+        let a = Coalescer::key("m", &serde_json::json!({"messages": ["a"]}));
+        let b = Coalescer::key("m", &serde_json::json!({"messages": ["b"]}));
+        assert_ne!(a, b);
+        assert_ne!(
+            a,
+            Coalescer::key("n", &serde_json::json!({"messages": ["a"]}))
+        );
+
+        let _ = tokio::join!(
+            coalescer.run(a, stub_upstream(&calls)),
+            coalescer.run(b, stub_upstream(&calls)),
+        );
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn unshare_error_keeps_timeout() {
+        let shared = Arc::new(ByokError::Timeout(Duration::from_secs(5)));
+        let _other = Arc::clone(&shared);
+        assert!(matches!(
+            unshare_error(shared),
+            ByokError::Timeout(d) if d == Duration::from_secs(5)
+        ));
+    }
+
+    #[test]
+    fn unshare_error_keeps_upstream_status() {
+        let shared = Arc::new(ByokError::Upstream {
+            status: 429,
+            body: "slow down".into(),
+            retry_after: None,
+        });
+        let _other = Arc::clone(&shared);
+        assert!(matches!(
+            unshare_error(shared),
+            ByokError::Upstream { status: 429, .. }
+        ));
+    }
+}
@@ -0,0 +1,250 @@
+//! Per-provider concurrency limits (`max_concurrency`).
+//!
+//! Each limited provider gets a semaphore sized to its configured limit.
+//! Requests over the limit queue for up to [`QUEUE_TIMEOUT`], then fail
+//! with [`ByokError::ProviderBusy`]. Streaming responses keep their permit
+//! until the body stream is dropped.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use axum::response::Response;
+use byokey_types::{
+    ByokError, ChatRequest, ProviderId,
+    traits::{ProviderExecutor, ProviderResponse, Result},
+};
+use futures_util::StreamExt as _;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use crate::middleware::inflight::hold_until_sent;
+
+/// How long a request waits for a free slot before giving up.
+const QUEUE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Semaphores for providers with a `max_concurrency` limit.
+#[derive(Default)]
+pub(crate) struct ConcurrencyLimits {
+    semaphores: Mutex<HashMap<ProviderId, (usize, Arc<Semaphore>)>>,
+}
+
+impl ConcurrencyLimits {
+    /// Returns the semaphore for `provider`, replacing it when the configured
+    /// limit changed since it was created (e.g. after a config reload).
+    fn semaphore(&self, provider: &ProviderId, limit: usize) -> Arc<Semaphore> {
+        let mut semaphores = self.semaphores.lock().expect("concurrency lock poisoned");
+        match semaphores.get(provider) {
+            Some((current, sem)) if *current == limit => Arc::clone(sem),
+            _ => {
+                let sem = Arc::new(Semaphore::new(limit));
+                semaphores.insert(provider.clone(), (limit, Arc::clone(&sem)));
+                sem
+            }
+        }
+    }
+
+    /// Waits for a slot on `provider`. Returns `None` when unlimited.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ByokError::ProviderBusy`] if no slot frees up within
+    /// [`QUEUE_TIMEOUT`].
+    pub(crate) async fn acquire(
+        &self,
+        provider: &ProviderId,
+        limit: Option<usize>,
+    ) -> Result<Option<OwnedSemaphorePermit>> {
+        let Some(limit) = limit.filter(|&n| n > 0) else {
+            return Ok(None);
+        };
+        let sem = self.semaphore(provider, limit);
+        match tokio::time::timeout(QUEUE_TIMEOUT, sem.acquire_owned()).await {
+            Ok(Ok(permit)) => Ok(Some(permit)),
+            _ => Err(ByokError::ProviderBusy(provider.clone())),
+        }
+    }
+
+    /// Wraps `inner` so each call holds a slot on its provider, or returns it
+    /// unchanged when there is no limit.
+    pub(crate) fn wrap(
+        self: &Arc<Self>,
+        inner: Box<dyn ProviderExecutor>,
+        limit: Option<usize>,
+    ) -> Box<dyn ProviderExecutor> {
+        match (inner.provider_id(), limit.filter(|&n| n > 0)) {
+            (Some(provider), Some(limit)) => Box::new(LimitedExecutor {
+                inner,
+                limits: Arc::clone(self),
+                provider,
+                limit,
+            }),
+            _ => inner,
+        }
+    }
+}
+
+/// Executor that holds a provider slot for the duration of each call.
+struct LimitedExecutor {
+    inner: Box<dyn ProviderExecutor>,
+    limits: Arc<ConcurrencyLimits>,
+    provider: ProviderId,
+    limit: usize,
+}
+
+#[async_trait]
+impl ProviderExecutor for LimitedExecutor {
+    async fn chat_completion(&self, request: ChatRequest) -> Result<ProviderResponse> {
+        let permit = self
+            .limits
+            .acquire(&self.provider, Some(self.limit))
+            .await?;
+        match self.inner.chat_completion(request).await? {
+            ProviderResponse::Stream(inner) => Ok(ProviderResponse::Stream(Box::pin(inner.map(
+                move |chunk| {
+                    let _held = &permit;
+                    chunk
+                },
+            )))),
+            complete => Ok(complete),
+        }
+    }
+
+    fn supported_models(&self) -> Vec<String> {
+        self.inner.supported_models()
+    }
+
+    fn provider_id(&self) -> Option<ProviderId> {
+        self.inner.provider_id()
+    }
+
+    async fn health_check(&self) -> Option<Result<()>> {
+        self.inner.health_check().await
+    }
+}
+
+/// Keeps `permit` alive until the response body has been fully sent.
+pub(crate) fn hold_permit(response: Response, permit: Option<OwnedSemaphorePermit>) -> Response {
+    match permit {
+        Some(permit) => hold_until_sent(response, permit),
+        None => response,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::Bytes;
+    use futures_util::stream;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Records the peak number of calls running at once.
+    struct Tracked {
+        running: Arc<AtomicUsize>,
+        peak: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl ProviderExecutor for Tracked {
+        async fn chat_completion(&self, _request: ChatRequest) -> Result<ProviderResponse> {
+            let now = self.running.fetch_add(1, Ordering::SeqCst) + 1;
+            self.peak.fetch_max(now, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            self.running.fetch_sub(1, Ordering::SeqCst);
+            Ok(ProviderResponse::Complete(serde_json::json!({})))
+        }
+
+        fn supported_models(&self) -> Vec<String> {
+            Vec::new()
+        }
+
+        fn provider_id(&self) -> Option<ProviderId> {
+            Some(ProviderId::Claude)
+        }
+    }
+
+    fn request(stream: bool) -> ChatRequest {
+        serde_json::from_value(serde_json::json!({
+            "model": "m",
+            "messages": [],
+            "stream": stream,
+        }))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn requests_over_the_limit_serialize() {
+        let limits = Arc::new(ConcurrencyLimits::default());
+        let peak = Arc::new(AtomicUsize::new(0));
+        let executor = limits.wrap(
+            Box::new(Tracked {
+                running: Arc::new(AtomicUsize::new(0)),
+                peak: Arc::clone(&peak),
+            }),
+            Some(1),
+        );
+
+        let (a, b, c) = tokio::join!(
+            executor.chat_completion(request(false)),
+            executor.chat_completion(request(false)),
+            executor.chat_completion(request(false)),
+        );
+
+        assert!(a.is_ok() && b.is_ok() && c.is_ok());
+        assert_eq!(peak.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn open_stream_holds_its_slot_until_dropped() {
+        struct Streaming;
+
+        #[async_trait]
+        impl ProviderExecutor for Streaming {
+            async fn chat_completion(&self, _request: ChatRequest) -> Result<ProviderResponse> {
+                Ok(ProviderResponse::Stream(Box::pin(stream::iter([Ok(
+                    Bytes::from_static(b"data: {}\n\n"),
+                )]))))
+            }
+
+            fn supported_models(&self) -> Vec<String> {
+                Vec::new()
+            }
+
+            fn provider_id(&self) -> Option<ProviderId> {
+                Some(ProviderId::Claude)
+            }
+        }
+
+        let limits = Arc::new(ConcurrencyLimits::default());
+        let executor = limits.wrap(Box::new(Streaming), Some(1));
+
+        let open = executor.chat_completion(request(true)).await.unwrap();
+        let busy = executor.chat_completion(request(true)).await;
+        assert!(matches!(
+            busy,
+            Err(ByokError::ProviderBusy(ProviderId::Claude))
+        ));
+
+        drop(open);
+        assert!(executor.chat_completion(request(true)).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn zero_or_unset_limit_is_unlimited() {
+        let limits = ConcurrencyLimits::default();
+        assert!(
+            limits
+                .acquire(&ProviderId::Claude, None)
+                .await
+                .unwrap()
+                .is_none()
+        );
+        assert!(
+            limits
+                .acquire(&ProviderId::Claude, Some(0))
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+}
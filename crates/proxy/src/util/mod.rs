@@ -1,6 +1,11 @@
 //! Shared proxy utilities — response builders, usage extraction, SSE stream tapping.
 
+pub(crate) mod coalesce;
+pub(crate) mod concurrency;
+pub(crate) mod route;
 pub(crate) mod stream;
+pub(crate) mod trailers;
+pub(crate) mod warnings;
 
 use axum::{
     body::Body,
@@ -12,6 +17,8 @@ use serde_json::Value;
 
 use crate::{UsageRecorder, error::ApiError};
 
+pub(crate) use byokey_provider::http_util::truncate_for_log;
+
 /// Prefixes whose presence in a response header name indicates a third-party
 /// API gateway fingerprint.  Names are case-insensitive.
 static GATEWAY_HEADER_PREFIXES: &[&str] = &[
@@ -51,6 +58,58 @@ pub(crate) fn strip_gateway_headers(headers: &mut HeaderMap) {
     }
 }
 
+/// Upstream response headers that are never forwarded to clients: hop-by-hop
+/// and framing headers (the proxy re-frames the body) and headers carrying
+/// credentials or upstream security policy.
+static BLOCKED_FORWARD_HEADERS: &[&str] = &[
+    "connection",
+    "keep-alive",
+    "proxy-authenticate",
+    "proxy-authorization",
+    "te",
+    "trailer",
+    "transfer-encoding",
+    "upgrade",
+    "content-length",
+    "content-encoding",
+    "content-type",
+    "authorization",
+    "www-authenticate",
+    "set-cookie",
+    "strict-transport-security",
+    "content-security-policy",
+    "alt-svc",
+];
+
+/// Copies upstream headers accepted by `allow` onto `response`.
+///
+/// Headers in [`BLOCKED_FORWARD_HEADERS`], gateway fingerprints and headers
+/// already set on `response` are always skipped.
+pub(crate) fn forward_upstream_headers(
+    upstream: &rquest::header::HeaderMap,
+    response: &mut Response,
+    allow: impl Fn(&str) -> bool,
+) {
+    let mut forwarded = HeaderMap::new();
+    for (name, value) in upstream {
+        let lower = name.as_str().to_ascii_lowercase();
+        if BLOCKED_FORWARD_HEADERS.contains(&lower.as_str())
+            || response.headers().contains_key(lower.as_str())
+            || !allow(&lower)
+        {
+            continue;
+        }
+        if let (Ok(n), Ok(v)) = (
+            axum::http::HeaderName::from_bytes(lower.as_bytes()),
+            axum::http::HeaderValue::from_bytes(value.as_bytes()),
+        ) {
+            forwarded.append(n, v);
+        }
+    }
+    strip_gateway_headers(&mut forwarded);
+    response.headers_mut().extend(forwarded);
+}
+
 pub(crate) fn extract_usage(json: &Value, input_ptr: &str, output_ptr: &str) -> (u64, u64) {
     (
         json.pointer(input_ptr).and_then(Value::as_u64).unwrap_or(0),
@@ -81,6 +140,68 @@ pub(crate) fn sse_response(
         .expect("valid response")
 }
 
+pub(crate) fn ndjson_response(
+    status: StatusCode,
+    stream: impl futures_util::Stream<Item = Result<bytes::Bytes, std::io::Error>> + Send + 'static,
+) -> Response {
+    Response::builder()
+        .status(status)
+        .header("content-type", "application/x-ndjson")
+        .header("cache-control", "no-cache")
+        .header("x-accel-buffering", "no")
+        .body(Body::from_stream(stream))
+        .expect("valid response")
+}
+
+/// Re-shapes a complete `chat.completion` response into a single
+/// `chat.completion.chunk`, for streaming clients served from a
+/// non-streaming upstream call.
+pub(crate) fn completion_to_chunk(completion: &Value) -> Value {
+    let choices: Vec<Value> = completion
+        .get("choices")
+        .and_then(Value::as_array)
+        .map(|choices| {
+            choices
+                .iter()
+                .enumerate()
+                .map(|(i, c)| {
+                    serde_json::json!({
+                        "index": c.get("index").cloned().unwrap_or_else(|| i.into()),
+                        "delta": c.get("message").cloned().unwrap_or_else(|| serde_json::json!({})),
+                        "finish_reason": c.get("finish_reason").cloned().unwrap_or(Value::Null),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    let mut chunk = serde_json::json!({
+        "id": completion.get("id").cloned().unwrap_or(Value::Null),
+        "object": "chat.completion.chunk",
+        "created": completion.get("created").cloned().unwrap_or(Value::Null),
+        "model": completion.get("model").cloned().unwrap_or(Value::Null),
+        "choices": choices,
+    });
+    if let Some(usage) = completion.get("usage") {
+        chunk["usage"] = usage.clone();
+    }
+    chunk
+}
+
+/// Returns `true` if the client asked for newline-delimited JSON streaming
+/// via `Accept: application/x-ndjson`.
+pub(crate) fn wants_ndjson(headers: &HeaderMap) -> bool {
+    headers
+        .get_all(axum::http::header::ACCEPT)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .any(|v| {
+            v.split(';')
+                .next()
+                .is_some_and(|mime| mime.trim().eq_ignore_ascii_case("application/x-ndjson"))
+        })
+}
+
 pub(crate) fn upstream_error(
     status: StatusCode,
     body: String,
@@ -141,4 +262,89 @@ mod tests {
         assert!(map.get("x-request-id").is_some());
         assert!(map.get(axum::http::header::CONTENT_TYPE).is_some());
     }
+
+    #[test]
+    fn forward_upstream_headers_only_forwards_allowlisted() {
+        use rquest::header::HeaderValue as UpstreamValue;
+
+        let mut upstream = rquest::header::HeaderMap::new();
+        for (name, value) in [
+            ("x-ratelimit-remaining-requests", "42"),
+            ("x-request-id", "req-1"),
+            ("server", "upstream"),
+            ("set-cookie", "session=secret"),
+            ("transfer-encoding", "chunked"),
+            ("cf-ray", "abc123"),
+        ] {
+            upstream.insert(name, UpstreamValue::from_static(value));
+        }
+        let mut response = axum::Json(serde_json::json!({})).into_response();
+        let allow = [
+            "x-ratelimit-*",
+            "x-request-id",
+            "set-cookie",
+            "transfer-encoding",
+            "cf-*",
+        ];
+
+        forward_upstream_headers(&upstream, &mut response, |name| {
+            allow.iter().any(|p| {
+                p.strip_suffix('*')
+                    .map_or(*p == name, |pre| name.starts_with(pre))
+            })
+        });
+
+        let headers = response.headers();
+        assert_eq!(headers["x-ratelimit-remaining-requests"], "42");
+        assert_eq!(headers["x-request-id"], "req-1");
+        assert!(headers.get("server").is_none());
+        // Blocked and gateway headers are dropped even when allowlisted.
+        assert!(headers.get("set-cookie").is_none());
+        assert!(headers.get("transfer-encoding").is_none());
+        assert!(headers.get("cf-ray").is_none());
+        assert_eq!(
+            headers[axum::http::header::CONTENT_TYPE],
+            "application/json"
+        );
+    }
+
+    #[test]
+    fn completion_to_chunk_moves_message_into_delta() {
+        let completion = serde_json::json!({
+            "id": "chatcmpl-1",
+            "object": "chat.completion",
+            "created": 1,
+            "model": "gpt-test",
+            "choices": [{
+                "index": 0,
+                "message": {"role": "assistant", "content": "hi"},
+                "finish_reason": "stop"
+            }],
+            "usage": {"prompt_tokens": 3, "completion_tokens": 1}
+        });
+        let chunk = completion_to_chunk(&completion);
+        assert_eq!(chunk["object"], "chat.completion.chunk");
+        assert_eq!(chunk["id"], "chatcmpl-1");
+        assert_eq!(chunk["choices"][0]["delta"]["content"], "hi");
+        assert_eq!(chunk["choices"][0]["finish_reason"], "stop");
+        assert_eq!(chunk["usage"]["prompt_tokens"], 3);
+    }
+
+    #[test]
+    fn wants_ndjson_matches_accept_header() {
+        let mut map = HeaderMap::new();
+        assert!(!wants_ndjson(&map));
+        map.insert(
+            axum::http::header::ACCEPT,
+            "text/event-stream".parse().unwrap(),
+        );
+        assert!(!wants_ndjson(&map));
+        map.insert(
+            axum::http::header::ACCEPT,
+            "application/json, Application/X-NDJSON;q=0.9"
+                .parse()
+                .unwrap(),
+        );
+        assert!(wants_ndjson(&map));
+    }
 }
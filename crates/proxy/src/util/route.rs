@@ -0,0 +1,191 @@
+//! Optional `x-byokey-route` response header describing which provider,
+//! credential and fallback tier served a chat request.
+
+use axum::{http::HeaderValue, response::Response};
+use byokey_auth::AuthManager;
+use byokey_config::Config;
+use byokey_provider::RouteInfo;
+use byokey_types::{DEFAULT_ACCOUNT, ProviderId};
+
+/// Response header carrying the routing decision.
+pub(crate) const ROUTE_HEADER: &str = "x-byokey-route";
+
+/// Routing decision for one request, rendered as `key=value` pairs.
+#[derive(Debug)]
+pub(crate) struct RouteHeader {
+    /// Provider the model resolved to.
+    requested: Option<ProviderId>,
+    /// Provider that actually served the request.
+    served: Option<ProviderId>,
+    /// Set when `backend` sent the request somewhere other than `requested`.
+    backend: Option<ProviderId>,
+    /// API key used, by position in the provider's configured keys.
+    key_index: Option<usize>,
+    /// OAuth account used when no API key was.
+    account: Option<String>,
+    fallback: bool,
+}
+
+impl RouteHeader {
+    /// Assembles the header from the resolved provider, the executor's
+    /// [`provider_id`](byokey_types::traits::ProviderExecutor::provider_id)
+    /// and the captured [`RouteInfo`], looking up the active OAuth account
+    /// when the served provider has no API key configured.
+    pub(crate) async fn resolve(
+        config: &Config,
+        auth: &AuthManager,
+        requested: Option<ProviderId>,
+        routed: Option<ProviderId>,
+        route: RouteInfo,
+    ) -> Self {
+        let backend = routed.clone().filter(|r| requested.as_ref() != Some(r));
+        let fallback = route.fallback.as_ref().is_some_and(|f| f.tier > 0);
+        let served = route.fallback.map(|f| f.provider).or(routed);
+
+        let has_keys = served.as_ref().is_some_and(|p| {
+            config
+                .providers
+                .get(p)
+                .is_some_and(|c| !c.all_api_keys().is_empty())
+        });
+        let key_index = route.key_index.or(has_keys.then_some(0));
+        let account = match &served {
+            Some(p) if key_index.is_none() => Some(
+                auth.list_accounts(p)
+                    .await
+                    .ok()
+                    .and_then(|accounts| accounts.into_iter().find(|a| a.is_active))
+                    .map_or_else(|| DEFAULT_ACCOUNT.to_string(), |a| a.account_id),
+            ),
+            _ => None,
+        };
+
+        Self {
+            requested,
+            served,
+            backend,
+            key_index,
+            account,
+            fallback,
+        }
+    }
+
+    fn value(&self) -> String {
+        let mut parts = Vec::with_capacity(6);
+        if let Some(p) = &self.served {
+            parts.push(format!("provider={p}"));
+        }
+        if let Some(p) = &self.requested {
+            parts.push(format!("requested={p}"));
+        }
+        if let Some(p) = &self.backend {
+            parts.push(format!("backend={p}"));
+        }
+        if let Some(i) = self.key_index {
+            parts.push(format!("key_index={i}"));
+        }
+        if let Some(a) = &self.account {
+            parts.push(format!("account={a}"));
+        }
+        parts.push(format!("fallback={}", self.fallback));
+        parts.join("; ")
+    }
+
+    /// Sets [`ROUTE_HEADER`] on `response`.
+    pub(crate) fn apply(&self, response: &mut Response) {
+        if let Ok(value) = HeaderValue::from_str(&self.value()) {
+            response.headers_mut().insert(ROUTE_HEADER, value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::response::IntoResponse as _;
+    use byokey_provider::{FallbackTier, VersionStore, make_executor_for_model};
+    use byokey_store::InMemoryTokenStore;
+    use std::collections::HashSet;
+    use std::sync::Arc;
+
+    fn make_auth() -> Arc<AuthManager> {
+        Arc::new(AuthManager::new(
+            Arc::new(InMemoryTokenStore::new()),
+            rquest::Client::new(),
+        ))
+    }
+
+    #[tokio::test]
+    async fn header_reflects_backend_routed_request() {
+        let config = Config::from_yaml(
+            r"
+route_header: true
+providers:
+  claude:
+    backend: copilot
+",
+        )
+        .unwrap();
+        let auth = make_auth();
+        let executor = make_executor_for_model(
+            "claude-opus-4-5",
+            |p| Some(config.providers.get(p).cloned().unwrap_or_default()),
+            &HashSet::<ProviderId>::new(),
+            None,
+            Arc::clone(&auth),
+            rquest::Client::new(),
+            None,
+            &VersionStore::empty(),
+        )
+        .unwrap();
+
+        let header = RouteHeader::resolve(
+            &config,
+            &auth,
+            Some(ProviderId::Claude),
+            executor.provider_id(),
+            RouteInfo::default(),
+        )
+        .await;
+        let mut response = "ok".into_response();
+        header.apply(&mut response);
+        assert_eq!(
+            response.headers()[ROUTE_HEADER],
+            "provider=copilot; requested=claude; backend=copilot; account=default; fallback=false"
+        );
+    }
+
+    #[tokio::test]
+    async fn header_reports_fallback_and_key_index() {
+        let config = Config::from_yaml(
+            r#"
+providers:
+  gemini:
+    api_keys:
+      - api_key: "k1"
+      - api_key: "k2"
+    fallback: copilot
+"#,
+        )
+        .unwrap();
+        let route = RouteInfo {
+            fallback: Some(FallbackTier {
+                tier: 0,
+                provider: ProviderId::Gemini,
+            }),
+            key_index: Some(1),
+        };
+        let header = RouteHeader::resolve(
+            &config,
+            &make_auth(),
+            Some(ProviderId::Gemini),
+            Some(ProviderId::Gemini),
+            route,
+        )
+        .await;
+        assert_eq!(
+            header.value(),
+            "provider=gemini; requested=gemini; key_index=1; fallback=false"
+        );
+    }
+}
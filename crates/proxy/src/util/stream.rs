@@ -1,9 +1,11 @@
 //! Generic SSE stream tapping for token usage extraction.
 
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use byokey_types::ByokError;
 use byokey_types::traits::ByteStream;
+use bytes::Bytes;
 use futures_util::{StreamExt as _, stream::try_unfold};
 use serde_json::Value;
 
@@ -12,12 +14,18 @@ use crate::UsageRecorder;
 /// Implemented per-provider to extract (`input_tokens`, `output_tokens`) from SSE data lines.
 pub(crate) trait UsageParser: Send + 'static {
     fn parse_line(&mut self, data: &Value);
+    /// Whether [`finish`](Self::finish) reports an estimated output count
+    /// instead of one taken from upstream usage.
+    fn output_estimated(&self) -> bool {
+        false
+    }
     fn finish(self) -> (u64, u64);
 }
 
 /// Wraps a [`ByteStream`], scanning each SSE `data:` line through `parser`
 /// and recording usage via [`UsageRecorder`] when the stream ends.
-/// All bytes are forwarded unchanged.
+/// All bytes are forwarded unchanged, so same-format passthrough providers
+/// (e.g. Copilot) keep every chunk field — `logprobs` included — intact.
 pub(crate) fn tap_usage_stream<P: UsageParser>(
     inner: ByteStream,
     usage: Arc<UsageRecorder>,
@@ -73,9 +81,13 @@ pub(crate) fn tap_usage_stream<P: UsageParser>(
                         let line = std::mem::take(&mut s.buf);
                         parse_usage_sse_line(&mut s.parser, &line);
                     }
+                    let estimated = s.parser.output_estimated();
                     let (input, output) = s.parser.finish();
                     s.usage
                         .record_success_for(&s.model, &s.provider, &s.account_id, input, output);
+                    if estimated {
+                        s.usage.record_estimated_output(&s.model, &s.provider);
+                    }
                     Ok(None)
                 }
             }
@@ -94,6 +106,287 @@ fn parse_usage_sse_line<P: UsageParser>(parser: &mut P, line: &[u8]) {
     }
 }
 
+/// Bytes of a streamed JSON body kept for usage extraction.
+const JSON_USAGE_TAIL: usize = 16 * 1024;
+
+/// Wraps a [`ByteStream`] carrying one large JSON object, forwarding bytes
+/// unbuffered. Only the last [`JSON_USAGE_TAIL`] bytes are kept; the final
+/// `input_field` / `output_field` counts found there are recorded when the
+/// stream ends, which suits APIs that put `usage` at the end of the object.
+pub(crate) fn tap_json_usage_stream(
+    inner: ByteStream,
+    usage: Arc<UsageRecorder>,
+    model: String,
+    provider: String,
+    account_id: String,
+    fields: (&'static str, &'static str),
+) -> ByteStream {
+    struct State {
+        inner: ByteStream,
+        tail: Vec<u8>,
+        usage: Arc<UsageRecorder>,
+        model: String,
+        provider: String,
+        account_id: String,
+        fields: (&'static str, &'static str),
+    }
+
+    Box::pin(try_unfold(
+        State {
+            inner,
+            tail: Vec::new(),
+            usage,
+            model,
+            provider,
+            account_id,
+            fields,
+        },
+        |mut s| async move {
+            match s.inner.next().await {
+                Some(Ok(bytes)) => {
+                    s.tail.extend_from_slice(&bytes);
+                    if s.tail.len() > JSON_USAGE_TAIL {
+                        s.tail.drain(..s.tail.len() - JSON_USAGE_TAIL);
+                    }
+                    Ok(Some((bytes, s)))
+                }
+                Some(Err(e)) => {
+                    s.usage
+                        .record_failure_for(&s.model, &s.provider, &s.account_id);
+                    Err(e)
+                }
+                None => {
+                    let tail = String::from_utf8_lossy(&s.tail);
+                    let input = last_u64_field(&tail, s.fields.0).unwrap_or(0);
+                    let output = last_u64_field(&tail, s.fields.1).unwrap_or(0);
+                    s.usage
+                        .record_success_for(&s.model, &s.provider, &s.account_id, input, output);
+                    Ok(None)
+                }
+            }
+        },
+    ))
+}
+
+/// Finds the last `"field": <integer>` in a JSON fragment.
+fn last_u64_field(json: &str, field: &str) -> Option<u64> {
+    let key = format!("\"{field}\"");
+    let rest = &json[json.rfind(&key)? + key.len()..];
+    let rest = rest.trim_start().strip_prefix(':')?.trim_start();
+    let end = rest
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(rest.len());
+    rest[..end].parse().ok()
+}
+
+/// Wraps a [`ByteStream`], passing each SSE `data:` JSON payload through
+/// `rewrite` and re-serializing it. Bytes are re-emitted on line boundaries;
+/// non-JSON lines (including `[DONE]`) are forwarded unchanged.
+pub(crate) fn rewrite_sse_chunks<F>(inner: ByteStream, rewrite: F) -> ByteStream
+where
+    F: Fn(&mut Value) + Send + 'static,
+{
+    map_sse_lines(inner, move |line, out| {
+        rewrite_sse_line(&rewrite, line, out)
+    })
+}
+
+/// Converts an OpenAI SSE stream into newline-delimited JSON
+/// (`application/x-ndjson`): each `data:` chunk object becomes one line.
+/// The `[DONE]` sentinel, blank separators and SSE comments are dropped.
+pub(crate) fn sse_to_ndjson(inner: ByteStream) -> ByteStream {
+    map_sse_lines(inner, |line, out| {
+        let text = String::from_utf8_lossy(line);
+        if let Some(data) = text.trim().strip_prefix("data:").map(str::trim_start)
+            && data != "[DONE]"
+            && !data.is_empty()
+        {
+            out.extend_from_slice(data.as_bytes());
+            out.push(b'\n');
+        }
+    })
+}
+
+/// Wraps a [`ByteStream`], recording time-to-first-byte (measured from
+/// `started`) and total forwarded bytes for `model` in [`UsageRecorder`].
+/// Chunks pass through unbuffered; the byte total is recorded when the
+/// stream ends or is dropped, so client disconnects are still counted.
+pub(crate) fn time_stream(
+    inner: ByteStream,
+    usage: Arc<UsageRecorder>,
+    model: String,
+    started: Instant,
+) -> ByteStream {
+    struct State {
+        inner: ByteStream,
+        usage: Arc<UsageRecorder>,
+        model: String,
+        started: Instant,
+        first_byte: bool,
+        bytes: u64,
+    }
+
+    impl Drop for State {
+        fn drop(&mut self) {
+            if self.first_byte {
+                self.usage.record_stream_bytes(&self.model, self.bytes);
+            }
+        }
+    }
+
+    Box::pin(try_unfold(
+        State {
+            inner,
+            usage,
+            model,
+            started,
+            first_byte: false,
+            bytes: 0,
+        },
+        |mut s| async move {
+            match s.inner.next().await {
+                Some(Ok(bytes)) => {
+                    if !s.first_byte && !bytes.is_empty() {
+                        s.first_byte = true;
+                        s.usage.record_first_byte(&s.model, s.started.elapsed());
+                    }
+                    s.bytes += bytes.len() as u64;
+                    Ok(Some((bytes, s)))
+                }
+                Some(Err(e)) => Err(e),
+                None => Ok(None),
+            }
+        },
+    ))
+}
+
+/// SSE comment emitted by [`keepalive_stream`].
+const KEEPALIVE_COMMENT: &[u8] = b": keepalive\n\n";
+
+/// Wraps an SSE [`ByteStream`], emitting a [`KEEPALIVE_COMMENT`] whenever
+/// nothing has been forwarded for `seconds`, so idle-timeout proxies between
+/// byokey and the client keep the connection open. Comments are only
+/// inserted between events, never inside a partially forwarded one.
+/// `seconds == 0` returns the stream unchanged.
+pub(crate) fn keepalive_stream(inner: ByteStream, seconds: u64) -> ByteStream {
+    struct State {
+        inner: ByteStream,
+        /// Whether the bytes forwarded so far end on an event boundary.
+        at_boundary: bool,
+    }
+
+    if seconds == 0 {
+        return inner;
+    }
+    let interval = Duration::from_secs(seconds);
+
+    Box::pin(try_unfold(
+        State {
+            inner,
+            at_boundary: true,
+        },
+        move |mut s| async move {
+            let next = if s.at_boundary {
+                match tokio::time::timeout(interval, s.inner.next()).await {
+                    Ok(next) => next,
+                    Err(_) => return Ok(Some((Bytes::from_static(KEEPALIVE_COMMENT), s))),
+                }
+            } else {
+                s.inner.next().await
+            };
+            match next {
+                Some(Ok(bytes)) => {
+                    s.at_boundary = ends_event(&bytes, s.at_boundary);
+                    Ok(Some((bytes, s)))
+                }
+                Some(Err(e)) => Err(e),
+                None => Ok(None),
+            }
+        },
+    ))
+}
+
+/// Whether the stream sits on an SSE event boundary after forwarding `bytes`.
+fn ends_event(bytes: &[u8], was_at_boundary: bool) -> bool {
+    if bytes.is_empty() {
+        was_at_boundary
+    } else {
+        bytes.ends_with(b"\n\n") || bytes.ends_with(b"\r\n\r\n")
+    }
+}
+
+/// Re-chunks a [`ByteStream`] on line boundaries, feeding each complete line
+/// (including its newline) to `map`, which appends the bytes to emit.
+fn map_sse_lines<F>(inner: ByteStream, map: F) -> ByteStream
+where
+    F: FnMut(&[u8], &mut Vec<u8>) + Send + 'static,
+{
+    struct State<F> {
+        inner: ByteStream,
+        buf: Vec<u8>,
+        map: F,
+        done: bool,
+    }
+
+    Box::pin(try_unfold(
+        State {
+            inner,
+            buf: Vec::new(),
+            map,
+            done: false,
+        },
+        |mut s| async move {
+            loop {
+                if s.done {
+                    return Ok(None);
+                }
+                match s.inner.next().await {
+                    Some(Ok(bytes)) => {
+                        s.buf.extend_from_slice(&bytes);
+                        let mut out = Vec::new();
+                        while let Some(nl) = s.buf.iter().position(|&b| b == b'\n') {
+                            let line: Vec<u8> = s.buf.drain(..=nl).collect();
+                            (s.map)(&line, &mut out);
+                        }
+                        if !out.is_empty() {
+                            return Ok(Some((Bytes::from(out), s)));
+                        }
+                    }
+                    Some(Err(e)) => return Err(e),
+                    None => {
+                        s.done = true;
+                        let line = std::mem::take(&mut s.buf);
+                        let mut out = Vec::new();
+                        if !line.is_empty() {
+                            (s.map)(&line, &mut out);
+                        }
+                        if out.is_empty() {
+                            return Ok(None);
+                        }
+                        return Ok(Some((Bytes::from(out), s)));
+                    }
+                }
+            }
+        },
+    ))
+}
+
+fn rewrite_sse_line<F: Fn(&mut Value)>(rewrite: &F, line: &[u8], out: &mut Vec<u8>) {
+    let text = String::from_utf8_lossy(line);
+    let trimmed = text.trim_end_matches(['\r', '\n']);
+    if let Some(data) = trimmed.strip_prefix("data:").map(str::trim_start)
+        && data != "[DONE]"
+        && let Ok(mut chunk) = serde_json::from_str::<Value>(data)
+    {
+        rewrite(&mut chunk);
+        out.extend_from_slice(b"data: ");
+        out.extend_from_slice(chunk.to_string().as_bytes());
+        out.extend_from_slice(&line[trimmed.len()..]);
+    } else {
+        out.extend_from_slice(line);
+    }
+}
+
 /// Converts an `rquest::Response` into a [`ByteStream`].
 pub(crate) fn response_to_stream(resp: rquest::Response) -> ByteStream {
     Box::pin(resp.bytes_stream().map(|r| {
@@ -106,9 +399,17 @@ pub(crate) fn response_to_stream(resp: rquest::Response) -> ByteStream {
 
 // ── Parser implementations ──────────────────────────────────────────
 
+/// Rough characters-per-token ratio for estimating streamed output.
+const CHARS_PER_TOKEN: u64 = 4;
+
+/// Reads `usage` from the terminal chunk. Streams without one (the client
+/// did not set `stream_options.include_usage`) get an output estimate from
+/// the streamed delta text instead.
 pub(crate) struct OpenAIParser {
     input: u64,
     output: u64,
+    saw_usage: bool,
+    delta_chars: u64,
 }
 
 impl OpenAIParser {
@@ -116,13 +417,16 @@ impl OpenAIParser {
         Self {
             input: 0,
             output: 0,
+            saw_usage: false,
+            delta_chars: 0,
         }
     }
 }
 
 impl UsageParser for OpenAIParser {
     fn parse_line(&mut self, ev: &Value) {
-        if let Some(usage) = ev.get("usage") {
+        if let Some(usage) = ev.get("usage").filter(|u| u.is_object()) {
+            self.saw_usage = true;
             if let Some(v) = usage.get("prompt_tokens").and_then(Value::as_u64) {
                 self.input = v;
             }
@@ -130,8 +434,34 @@ impl UsageParser for OpenAIParser {
                 self.output = v;
             }
         }
+        let deltas = ev
+            .get("choices")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|choice| choice.get("delta"));
+        for delta in deltas {
+            let text = ["content", "reasoning_content"]
+                .into_iter()
+                .filter_map(|field| delta.get(field).and_then(Value::as_str));
+            let arguments = delta
+                .get("tool_calls")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(|call| call.pointer("/function/arguments").and_then(Value::as_str));
+            for s in text.chain(arguments) {
+                self.delta_chars += u64::try_from(s.chars().count()).unwrap_or(u64::MAX);
+            }
+        }
+    }
+    fn output_estimated(&self) -> bool {
+        !self.saw_usage && self.delta_chars > 0
     }
     fn finish(self) -> (u64, u64) {
+        if self.output_estimated() {
+            return (self.input, self.delta_chars.div_ceil(CHARS_PER_TOKEN));
+        }
         (self.input, self.output)
     }
 }
@@ -249,9 +579,167 @@ impl UsageParser for GeminiParser {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use bytes::Bytes;
+    use crate::usage::UsageSnapshot;
     use futures_util::stream;
 
+    #[tokio::test(start_paused = true)]
+    async fn keepalive_stream_fills_stalls_between_events() {
+        let inner: ByteStream = Box::pin(
+            stream::iter([Ok(Bytes::from_static(b"data: {\"a\":1}\n\n"))]).chain(stream::once(
+                async {
+                    tokio::time::sleep(Duration::from_secs(35)).await;
+                    Ok(Bytes::from_static(b"data: [DONE]\n\n"))
+                },
+            )),
+        );
+        let chunks: Vec<Bytes> = keepalive_stream(inner, 15)
+            .map(Result::unwrap)
+            .collect()
+            .await;
+        assert_eq!(
+            chunks,
+            [
+                Bytes::from_static(b"data: {\"a\":1}\n\n"),
+                Bytes::from_static(KEEPALIVE_COMMENT),
+                Bytes::from_static(KEEPALIVE_COMMENT),
+                Bytes::from_static(b"data: [DONE]\n\n"),
+            ]
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn keepalive_stream_waits_inside_partial_event() {
+        let inner: ByteStream = Box::pin(
+            stream::iter([Ok(Bytes::from_static(b"data: {\"a\""))]).chain(stream::once(async {
+                tokio::time::sleep(Duration::from_secs(20)).await;
+                Ok(Bytes::from_static(b":1}\n\n"))
+            })),
+        );
+        let chunks: Vec<Bytes> = keepalive_stream(inner, 15)
+            .map(Result::unwrap)
+            .collect()
+            .await;
+        assert_eq!(chunks.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn tap_json_usage_stream_reads_usage_from_tail() {
+        let usage = Arc::new(UsageRecorder::new(None));
+        let inner: ByteStream = Box::pin(stream::iter([
+            Ok(Bytes::from_static(
+                br#"{"output":[{"text":"hi"}],"usage":{"input_tokens""#,
+            )),
+            Ok(Bytes::from_static(
+                br#": 12,"input_tokens_details":{"cached_tokens":3},"output_tokens":7}}"#,
+            )),
+        ]));
+
+        let chunks: Vec<_> = tap_json_usage_stream(
+            inner,
+            Arc::clone(&usage),
+            "gpt-test".to_owned(),
+            "codex".to_owned(),
+            "default".to_owned(),
+            ("input_tokens", "output_tokens"),
+        )
+        .collect()
+        .await;
+        assert_eq!(chunks.len(), 2);
+
+        let model = &usage.snapshot().models["gpt-test"];
+        assert_eq!(model.success, 1);
+        assert_eq!(model.input_tokens, 12);
+        assert_eq!(model.output_tokens, 7);
+    }
+
+    #[tokio::test]
+    async fn time_stream_records_ttfb_after_delayed_first_chunk() {
+        let usage = Arc::new(UsageRecorder::new(None));
+        let inner: ByteStream = Box::pin(
+            stream::once(async {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                Ok(Bytes::from_static(b"data: {}\n\n"))
+            })
+            .chain(stream::iter([Ok(Bytes::from_static(b"data: [DONE]\n\n"))])),
+        );
+
+        let chunks: Vec<_> = time_stream(
+            inner,
+            Arc::clone(&usage),
+            "gpt-test".to_owned(),
+            Instant::now(),
+        )
+        .collect()
+        .await;
+        assert_eq!(chunks.len(), 2);
+
+        let model = &usage.snapshot().models["gpt-test"];
+        assert_eq!(model.ttfb_samples, 1);
+        assert!(model.ttfb_ms_total >= 50);
+        assert_eq!(model.stream_bytes, 24);
+    }
+
+    #[tokio::test]
+    async fn rewrite_sse_chunks_sets_model_on_every_chunk() {
+        let inner: ByteStream = Box::pin(stream::iter([
+            Ok(Bytes::from_static(
+                b"data: {\"object\":\"chat.completion.chunk\"}\n\ndata: {\"obj",
+            )),
+            Ok(Bytes::from_static(
+                b"ect\":\"chat.completion.chunk\",\"model\":\"x\"}\n\ndata: [DONE]\n\n",
+            )),
+        ]));
+
+        let chunks: Vec<_> = rewrite_sse_chunks(inner, |chunk| {
+            chunk["model"] = Value::String("gpt-test".to_owned());
+        })
+        .collect()
+        .await;
+
+        let text: String = chunks
+            .into_iter()
+            .map(|c| String::from_utf8(c.unwrap().to_vec()).unwrap())
+            .collect();
+        let data: Vec<&str> = text
+            .lines()
+            .filter_map(|l| l.strip_prefix("data: "))
+            .collect();
+        assert_eq!(data.len(), 3);
+        for line in &data[..2] {
+            let v: Value = serde_json::from_str(line).unwrap();
+            assert_eq!(v["model"], "gpt-test");
+        }
+        assert_eq!(data[2], "[DONE]");
+    }
+
+    #[tokio::test]
+    async fn sse_to_ndjson_strips_data_prefix_and_done() {
+        let inner: ByteStream = Box::pin(stream::iter([
+            Ok(Bytes::from_static(
+                b": keepalive\n\ndata: {\"id\":\"a\",\"object\":\"chat.completion.chunk\"}\n\nda",
+            )),
+            Ok(Bytes::from_static(
+                b"ta: {\"id\":\"b\",\"object\":\"chat.completion.chunk\"}\n\ndata: [DONE]\n\n",
+            )),
+        ]));
+
+        let chunks: Vec<_> = sse_to_ndjson(inner).collect().await;
+        let text: String = chunks
+            .into_iter()
+            .map(|c| String::from_utf8(c.unwrap().to_vec()).unwrap())
+            .collect();
+
+        assert!(!text.contains("data:"));
+        assert!(!text.contains("[DONE]"));
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 2);
+        for (line, id) in lines.iter().zip(["a", "b"]) {
+            let v: Value = serde_json::from_str(line).unwrap();
+            assert_eq!(v["id"], id);
+        }
+        assert!(text.ends_with('\n'));
+    }
+
     #[tokio::test]
     async fn tap_usage_stream_parses_final_line_without_newline() {
         let usage = Arc::new(UsageRecorder::new(None));
@@ -277,4 +765,117 @@ mod tests {
         assert_eq!(snapshot.input_tokens, 12);
         assert_eq!(snapshot.output_tokens, 7);
     }
+
+    async fn tap_openai_transcript(transcript: &'static [u8]) -> UsageSnapshot {
+        let usage = Arc::new(UsageRecorder::new(None));
+        let inner: ByteStream = Box::pin(stream::iter(
+            transcript
+                .chunks(37)
+                .map(|c| Ok(Bytes::copy_from_slice(c)))
+                .collect::<Vec<_>>(),
+        ));
+        let out: Vec<u8> = tap_usage_stream(
+            inner,
+            Arc::clone(&usage),
+            "gpt-4o".to_owned(),
+            "codex".to_owned(),
+            "default".to_owned(),
+            OpenAIParser::new(),
+        )
+        .map(|r| r.unwrap())
+        .collect::<Vec<_>>()
+        .await
+        .concat();
+        assert_eq!(out, transcript);
+        usage.snapshot()
+    }
+
+    #[tokio::test]
+    async fn tap_usage_stream_records_terminal_usage_chunk() {
+        let snapshot = tap_openai_transcript(
+            br#"data: {"choices":[{"index":0,"delta":{"role":"assistant","content":""}}],"usage":null}
+
+data: {"choices":[{"index":0,"delta":{"content":"Hello there"}}],"usage":null}
+
+data: {"choices":[{"index":0,"delta":{},"finish_reason":"stop"}],"usage":null}
+
+data: {"choices":[],"usage":{"prompt_tokens":9,"completion_tokens":2,"total_tokens":11}}
+
+data: [DONE]
+
+"#,
+        )
+        .await;
+        assert_eq!(snapshot.input_tokens, 9);
+        assert_eq!(snapshot.output_tokens, 2);
+        assert_eq!(snapshot.models["gpt-4o"].estimated_output, 0);
+    }
+
+    #[tokio::test]
+    async fn tap_usage_stream_estimates_output_without_usage_chunk() {
+        let snapshot = tap_openai_transcript(
+            br#"data: {"choices":[{"index":0,"delta":{"content":"Hello, "}}]}
+
+data: {"choices":[{"index":0,"delta":{"content":"world!"}}]}
+
+data: {"choices":[{"index":0,"delta":{"tool_calls":[{"index":0,"function":{"arguments":"{}"}}]}}]}
+
+data: [DONE]
+
+"#,
+        )
+        .await;
+        // 15 characters at ~4 characters per token.
+        assert_eq!(snapshot.input_tokens, 0);
+        assert_eq!(snapshot.output_tokens, 4);
+        assert_eq!(snapshot.models["gpt-4o"].estimated_output, 1);
+        assert_eq!(snapshot.providers["codex"]["gpt-4o"].estimated_output, 1);
+    }
+
+    #[tokio::test]
+    async fn tap_usage_stream_preserves_logprobs_in_passthrough_chunks() {
+        // A Copilot-style `chat.completion.chunk` carrying per-token logprobs.
+        let chunk: &[u8] = br#"data: {"id":"chatcmpl-1","object":"chat.completion.chunk","model":"gpt-4o","choices":[{"index":0,"delta":{"content":"Hi"},"logprobs":{"content":[{"token":"Hi","logprob":-0.01,"bytes":[72,105],"top_logprobs":[{"token":"Hi","logprob":-0.01,"bytes":[72,105]}]}]},"finish_reason":null}]}
+
+data: {"id":"chatcmpl-1","object":"chat.completion.chunk","model":"gpt-4o","choices":[],"usage":{"prompt_tokens":3,"completion_tokens":1}}
+
+data: [DONE]
+
+"#;
+        let usage = Arc::new(UsageRecorder::new(None));
+        // Split mid-line to exercise buffering across chunk boundaries.
+        let (a, b) = chunk.split_at(64);
+        let inner: ByteStream = Box::pin(stream::iter([
+            Ok(Bytes::copy_from_slice(a)),
+            Ok(Bytes::copy_from_slice(b)),
+        ]));
+
+        let out: Vec<u8> = tap_usage_stream(
+            inner,
+            Arc::clone(&usage),
+            "gpt-4o".to_owned(),
+            "copilot".to_owned(),
+            "default".to_owned(),
+            OpenAIParser::new(),
+        )
+        .map(|r| r.unwrap())
+        .collect::<Vec<_>>()
+        .await
+        .concat();
+
+        assert_eq!(out, chunk);
+        let first = String::from_utf8_lossy(&out);
+        let first = first
+            .lines()
+            .next()
+            .unwrap()
+            .strip_prefix("data: ")
+            .unwrap();
+        let ev: Value = serde_json::from_str(first).unwrap();
+        assert_eq!(
+            ev.pointer("/choices/0/logprobs/content/0/top_logprobs/0/logprob"),
+            Some(&serde_json::json!(-0.01))
+        );
+        assert_eq!(usage.snapshot().input_tokens, 3);
+    }
 }
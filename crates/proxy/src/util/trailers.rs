@@ -0,0 +1,148 @@
+//! Optional HTTP trailers summarising a streamed chat completion, for
+//! clients that read the outcome from trailers instead of the last chunk.
+
+use std::sync::{Arc, Mutex};
+
+use axum::{
+    body::Body,
+    http::{HeaderMap, HeaderValue, header::TRAILER},
+    response::Response,
+};
+use byokey_types::traits::ByteStream;
+use futures_util::{StreamExt as _, TryStreamExt as _};
+use http_body::Frame;
+use http_body_util::StreamBody;
+use serde_json::Value;
+
+/// Trailer carrying the final `finish_reason`.
+pub(crate) const FINISH_REASON_TRAILER: &str = "x-byokey-finish-reason";
+/// Trailer carrying `usage.prompt_tokens`.
+pub(crate) const PROMPT_TOKENS_TRAILER: &str = "x-byokey-prompt-tokens";
+/// Trailer carrying `usage.completion_tokens`.
+pub(crate) const COMPLETION_TOKENS_TRAILER: &str = "x-byokey-completion-tokens";
+
+/// What an OpenAI-format chunk stream reported by the time it ended.
+#[derive(Debug, Default)]
+pub(crate) struct StreamSummary {
+    finish_reason: Option<String>,
+    prompt_tokens: u64,
+    completion_tokens: u64,
+}
+
+impl StreamSummary {
+    fn observe_line(&mut self, line: &[u8]) {
+        let line = String::from_utf8_lossy(line);
+        let Some(data) = line.trim().strip_prefix("data:").map(str::trim_start) else {
+            return;
+        };
+        let Ok(chunk) = serde_json::from_str::<Value>(data) else {
+            return;
+        };
+        if let Some(reason) = chunk
+            .pointer("/choices/0/finish_reason")
+            .and_then(Value::as_str)
+        {
+            self.finish_reason = Some(reason.to_string());
+        }
+        if let Some(n) = chunk
+            .pointer("/usage/prompt_tokens")
+            .and_then(Value::as_u64)
+        {
+            self.prompt_tokens = n;
+        }
+        if let Some(n) = chunk
+            .pointer("/usage/completion_tokens")
+            .and_then(Value::as_u64)
+        {
+            self.completion_tokens = n;
+        }
+    }
+
+    fn trailers(&self) -> HeaderMap {
+        let mut trailers = HeaderMap::new();
+        if let Some(value) = self
+            .finish_reason
+            .as_deref()
+            .and_then(|r| HeaderValue::from_str(r).ok())
+        {
+            trailers.insert(FINISH_REASON_TRAILER, value);
+        }
+        trailers.insert(PROMPT_TOKENS_TRAILER, self.prompt_tokens.into());
+        trailers.insert(COMPLETION_TOKENS_TRAILER, self.completion_tokens.into());
+        trailers
+    }
+}
+
+/// Forwards `inner` unchanged while recording its finish reason and usage
+/// into `summary`.
+pub(crate) fn summarize_stream(
+    inner: ByteStream,
+    summary: Arc<Mutex<StreamSummary>>,
+) -> ByteStream {
+    let mut buf = Vec::new();
+    Box::pin(inner.inspect_ok(move |bytes| {
+        buf.extend_from_slice(bytes);
+        let mut summary = summary.lock().unwrap();
+        while let Some(nl) = buf.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = buf.drain(..=nl).collect();
+            summary.observe_line(&line);
+        }
+    }))
+}
+
+/// Declares the summary trailers on `response` and sends them once its body
+/// has been fully written.
+///
+/// HTTP/1.1 clients only receive trailers when they send `TE: trailers`.
+pub(crate) fn append_trailers(response: Response, summary: Arc<Mutex<StreamSummary>>) -> Response {
+    let (mut parts, body) = response.into_parts();
+    parts.headers.insert(
+        TRAILER,
+        HeaderValue::from_static(
+            "x-byokey-finish-reason, x-byokey-prompt-tokens, x-byokey-completion-tokens",
+        ),
+    );
+    let data = body.into_data_stream().map_ok(Frame::data);
+    let trailers = futures_util::stream::once(async move {
+        Ok::<_, axum::Error>(Frame::trailers(summary.lock().unwrap().trailers()))
+    });
+    Response::from_parts(parts, Body::new(StreamBody::new(data.chain(trailers))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::sse_response;
+    use axum::http::StatusCode;
+    use bytes::Bytes;
+    use http_body_util::BodyExt as _;
+
+    #[tokio::test]
+    async fn trailers_carry_finish_reason_and_usage() {
+        let chunks = [
+            "data: {\"choices\":[{\"delta\":{\"content\":\"hi\"},\"finish_reason\":null}]}\n\n",
+            "data: {\"choices\":[{\"delta\":{},\"finish_reason\":\"length\"}],",
+            "\"usage\":{\"prompt_tokens\":3,\"completion_tokens\":16}}\n\ndata: [DONE]\n\n",
+        ];
+        let inner: ByteStream = Box::pin(futures_util::stream::iter(
+            chunks.map(|c| Ok(Bytes::from_static(c.as_bytes()))),
+        ));
+        let summary = Arc::default();
+        let stream = summarize_stream(inner, Arc::clone(&summary))
+            .map_err(|e| std::io::Error::other(e.to_string()));
+        let response = append_trailers(sse_response(StatusCode::OK, stream), summary);
+        assert!(
+            response.headers()[TRAILER]
+                .to_str()
+                .unwrap()
+                .contains(FINISH_REASON_TRAILER)
+        );
+
+        let collected = response.into_body().collect().await.unwrap();
+        let trailers = collected.trailers().cloned().unwrap();
+        assert_eq!(trailers[FINISH_REASON_TRAILER], "length");
+        assert_eq!(trailers[PROMPT_TOKENS_TRAILER], "3");
+        assert_eq!(trailers[COMPLETION_TOKENS_TRAILER], "16");
+        assert!(collected.to_bytes().ends_with(b"data: [DONE]\n\n"));
+    }
+}
@@ -0,0 +1,334 @@
+//! Request-scoped compatibility warnings, surfaced to clients through the
+//! `x-byokey-warnings` response header.
+
+use axum::{http::HeaderValue, response::Response};
+use byokey_types::{ChatRequest, ProviderId};
+use serde_json::Value;
+
+/// Response header listing warning codes, comma-separated.
+pub(crate) const WARNINGS_HEADER: &str = "x-byokey-warnings";
+
+/// Response header carrying the output token limit a request was clamped to.
+pub(crate) const MAX_TOKENS_CLAMPED_HEADER: &str = "x-byokey-max-tokens-clamped";
+
+/// Warning codes collected while preparing a request.
+#[derive(Debug, Default)]
+pub(crate) struct Warnings {
+    codes: Vec<&'static str>,
+    max_tokens_clamped: Option<u64>,
+}
+
+impl Warnings {
+    /// Records a warning code once.
+    pub(crate) fn push(&mut self, code: &'static str) {
+        if !self.codes.contains(&code) {
+            self.codes.push(code);
+        }
+    }
+
+    /// Records that the output token limit was clamped to `ceiling`.
+    pub(crate) fn max_tokens_clamped(&mut self, ceiling: u64) {
+        self.push("max-tokens-clamped");
+        self.max_tokens_clamped = Some(ceiling);
+    }
+
+    /// Sets [`WARNINGS_HEADER`] on `response` if any warning was recorded,
+    /// and [`MAX_TOKENS_CLAMPED_HEADER`] if the output token limit was clamped.
+    pub(crate) fn apply(&self, response: &mut Response) {
+        if let Some(ceiling) = self.max_tokens_clamped {
+            response
+                .headers_mut()
+                .insert(MAX_TOKENS_CLAMPED_HEADER, HeaderValue::from(ceiling));
+        }
+        if self.codes.is_empty() {
+            return;
+        }
+        if let Ok(value) = HeaderValue::from_str(&self.codes.join(", ")) {
+            response.headers_mut().insert(WARNINGS_HEADER, value);
+        }
+    }
+}
+
+/// Providers whose upstream APIs have no equivalent of `seed`.
+fn supports_seed(provider: &ProviderId) -> bool {
+    !matches!(
+        provider,
+        ProviderId::Claude | ProviderId::Gemini | ProviderId::Antigravity | ProviderId::Kiro
+    )
+}
+
+/// Providers whose requests are translated out of the `OpenAI` format.
+fn translates_request(provider: &ProviderId) -> bool {
+    matches!(
+        provider,
+        ProviderId::Claude | ProviderId::Gemini | ProviderId::Antigravity | ProviderId::Kiro
+    )
+}
+
+/// Top-level chat-completion fields the request translators understand.
+/// Anything else (e.g. `extra_body` from the `OpenAI` SDK) is dropped before
+/// translation; passthrough providers still receive it.
+const TRANSLATED_FIELDS: &[&str] = &[
+    "frequency_penalty",
+    "max_completion_tokens",
+    "max_tokens",
+    "metadata",
+    "n",
+    "parallel_tool_calls",
+    "presence_penalty",
+    "reasoning_effort",
+    "response_format",
+    "seed",
+    "stop",
+    "stream_options",
+    "temperature",
+    "thinking",
+    "tool_choice",
+    "tools",
+    "top_k",
+    "top_p",
+    "user",
+];
+
+/// Removes request parameters the resolved provider cannot honour, logging
+/// and recording a warning for each one dropped.
+pub(crate) fn drop_unsupported_params(
+    request: &mut ChatRequest,
+    provider: Option<&ProviderId>,
+    warnings: &mut Warnings,
+) {
+    let Some(provider) = provider else {
+        return;
+    };
+    if !supports_seed(provider) && request.extra.shift_remove("seed").is_some() {
+        tracing::warn!(%provider, model = %request.model, "dropping unsupported `seed`");
+        warnings.push("seed-unsupported");
+    }
+    if translates_request(provider) {
+        let before = request.extra.len();
+        request.extra.retain(|key, _| {
+            let known = TRANSLATED_FIELDS.contains(&key.as_str());
+            if !known {
+                tracing::warn!(%provider, field = %key, "dropping unknown request field");
+            }
+            known
+        });
+        if request.extra.len() < before {
+            warnings.push("unknown-fields-dropped");
+        }
+    }
+}
+
+/// Clamps `temperature` into the provider's accepted range, recording a
+/// warning when the value had to change. Unknown ranges pass through.
+pub(crate) fn clamp_temperature(
+    request: &mut ChatRequest,
+    provider: Option<&ProviderId>,
+    warnings: &mut Warnings,
+) {
+    let Some((min, max)) = provider.and_then(byokey_provider::temperature_range) else {
+        return;
+    };
+    let Some(temperature) = request.extra.get("temperature").and_then(Value::as_f64) else {
+        return;
+    };
+    if temperature < min || temperature > max {
+        let clamped = temperature.clamp(min, max);
+        tracing::warn!(
+            model = %request.model,
+            temperature,
+            clamped,
+            "clamping out-of-range `temperature`"
+        );
+        request
+            .extra
+            .insert("temperature".to_string(), Value::from(clamped));
+        warnings.push("temperature-clamped");
+    }
+}
+
+/// Clamps the requested output token limit down to `ceiling` in whichever
+/// wire format `body` uses (see [`byokey_provider::clamp_output_tokens`]),
+/// recording a warning when a value had to change. A missing limit is left
+/// alone; limits added by translation are clamped by the executors inside
+/// [`byokey_provider::with_output_token_ceiling`].
+pub(crate) fn clamp_max_tokens(
+    body: &mut serde_json::Map<String, Value>,
+    ceiling: u64,
+    warnings: &mut Warnings,
+) {
+    if byokey_provider::clamp_output_tokens(body, ceiling) {
+        warnings.max_tokens_clamped(ceiling);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::response::IntoResponse as _;
+
+    fn request_with_seed() -> ChatRequest {
+        serde_json::from_value(serde_json::json!({
+            "model": "claude-opus-4-5",
+            "seed": 42,
+            "messages": [{"role": "user", "content": "hi"}]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn seed_dropped_for_claude_sets_warning_header() {
+        let mut request = request_with_seed();
+        let mut warnings = Warnings::default();
+        drop_unsupported_params(&mut request, Some(&ProviderId::Claude), &mut warnings);
+        assert!(!request.extra.contains_key("seed"));
+
+        let mut response = "ok".into_response();
+        warnings.apply(&mut response);
+        assert_eq!(response.headers()[WARNINGS_HEADER], "seed-unsupported");
+    }
+
+    #[test]
+    fn seed_kept_for_codex_without_warning() {
+        let mut request = request_with_seed();
+        let mut warnings = Warnings::default();
+        drop_unsupported_params(&mut request, Some(&ProviderId::Codex), &mut warnings);
+        assert_eq!(request.extra["seed"], 42);
+
+        let mut response = "ok".into_response();
+        warnings.apply(&mut response);
+        assert!(response.headers().get(WARNINGS_HEADER).is_none());
+    }
+
+    fn request_with_extra_body() -> ChatRequest {
+        serde_json::from_value(serde_json::json!({
+            "model": "m",
+            "temperature": 0.2,
+            "vendor_option": {"mode": "fast"},
+            "messages": [{"role": "user", "content": "hi"}]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn unknown_field_dropped_for_claude() {
+        let mut request = request_with_extra_body();
+        let mut warnings = Warnings::default();
+        drop_unsupported_params(&mut request, Some(&ProviderId::Claude), &mut warnings);
+        assert!(!request.extra.contains_key("vendor_option"));
+        assert_eq!(request.extra["temperature"], 0.2);
+
+        let mut response = "ok".into_response();
+        warnings.apply(&mut response);
+        assert_eq!(
+            response.headers()[WARNINGS_HEADER],
+            "unknown-fields-dropped"
+        );
+    }
+
+    #[test]
+    fn unknown_field_kept_for_copilot_passthrough() {
+        let mut request = request_with_extra_body();
+        let mut warnings = Warnings::default();
+        drop_unsupported_params(&mut request, Some(&ProviderId::Copilot), &mut warnings);
+        assert_eq!(request.extra["vendor_option"]["mode"], "fast");
+
+        let mut response = "ok".into_response();
+        warnings.apply(&mut response);
+        assert!(response.headers().get(WARNINGS_HEADER).is_none());
+    }
+
+    fn request_with_temperature(temperature: f64) -> ChatRequest {
+        serde_json::from_value(serde_json::json!({
+            "model": "claude-opus-4-5",
+            "temperature": temperature,
+            "messages": [{"role": "user", "content": "hi"}]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn too_high_temperature_clamped_for_claude() {
+        let mut request = request_with_temperature(2.5);
+        let mut warnings = Warnings::default();
+        clamp_temperature(&mut request, Some(&ProviderId::Claude), &mut warnings);
+        assert_eq!(request.extra["temperature"], 1.0);
+
+        let mut response = "ok".into_response();
+        warnings.apply(&mut response);
+        assert_eq!(response.headers()[WARNINGS_HEADER], "temperature-clamped");
+    }
+
+    #[test]
+    fn in_range_temperature_untouched() {
+        let mut request = request_with_temperature(1.5);
+        let mut warnings = Warnings::default();
+        clamp_temperature(&mut request, Some(&ProviderId::Codex), &mut warnings);
+        assert_eq!(request.extra["temperature"], 1.5);
+
+        let mut response = "ok".into_response();
+        warnings.apply(&mut response);
+        assert!(response.headers().get(WARNINGS_HEADER).is_none());
+    }
+
+    fn chat_request(body: Value) -> ChatRequest {
+        serde_json::from_value(body).unwrap()
+    }
+
+    #[test]
+    fn max_tokens_over_ceiling_clamped_for_claude() {
+        let mut request = chat_request(serde_json::json!({
+            "model": "claude-opus-4-5",
+            "max_tokens": 64000,
+            "messages": [{"role": "user", "content": "hi"}]
+        }));
+        let mut warnings = Warnings::default();
+        clamp_max_tokens(&mut request.extra, 8192, &mut warnings);
+        assert_eq!(request.extra["max_tokens"], 8192);
+
+        let mut response = "ok".into_response();
+        warnings.apply(&mut response);
+        assert_eq!(response.headers()[WARNINGS_HEADER], "max-tokens-clamped");
+        assert_eq!(response.headers()[MAX_TOKENS_CLAMPED_HEADER], "8192");
+    }
+
+    #[test]
+    fn max_completion_tokens_over_ceiling_clamped_for_gemini() {
+        let mut request = chat_request(serde_json::json!({
+            "model": "gemini-2.5-pro",
+            "max_completion_tokens": 65536,
+            "messages": [{"role": "user", "content": "hi"}]
+        }));
+        let mut warnings = Warnings::default();
+        clamp_max_tokens(&mut request.extra, 4096, &mut warnings);
+        assert_eq!(request.extra["max_completion_tokens"], 4096);
+        assert_eq!(warnings.codes, ["max-tokens-clamped"]);
+        assert_eq!(warnings.max_tokens_clamped, Some(4096));
+    }
+
+    #[test]
+    fn gemini_native_max_output_tokens_clamped() {
+        let mut body = serde_json::json!({"generationConfig": {"maxOutputTokens": 65536}});
+        let mut warnings = Warnings::default();
+        clamp_max_tokens(body.as_object_mut().unwrap(), 4096, &mut warnings);
+        assert_eq!(body["generationConfig"]["maxOutputTokens"], 4096);
+        assert_eq!(warnings.max_tokens_clamped, Some(4096));
+    }
+
+    #[test]
+    fn max_tokens_within_ceiling_or_absent_untouched() {
+        let mut body = serde_json::json!({"max_tokens": 1024});
+        let mut warnings = Warnings::default();
+        clamp_max_tokens(body.as_object_mut().unwrap(), 8192, &mut warnings);
+        assert_eq!(body["max_tokens"], 1024);
+
+        let mut body = serde_json::json!({"model": "claude-opus-4-5"});
+        clamp_max_tokens(body.as_object_mut().unwrap(), 8192, &mut warnings);
+        assert!(body.get("max_tokens").is_none());
+        assert!(warnings.codes.is_empty());
+
+        let mut response = "ok".into_response();
+        warnings.apply(&mut response);
+        assert!(response.headers().get(MAX_TOKENS_CLAMPED_HEADER).is_none());
+    }
+}
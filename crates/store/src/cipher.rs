@@ -0,0 +1,182 @@
+//! Encryption at rest for stored OAuth tokens.
+//!
+//! Tokens are sealed with XChaCha20-Poly1305 under a 256-bit key, usually
+//! derived from a passphrase with Argon2id and a random salt kept in the
+//! database's `store_meta` table. The provider and account id are bound as
+//! associated data, so a ciphertext copied to another row fails to decrypt.
+//! Stored form: base64 of `nonce || ciphertext`.
+
+use argon2::Argon2;
+use base64::Engine as _;
+use base64::engine::general_purpose::STANDARD;
+use byokey_types::{ByokError, Result};
+use chacha20poly1305::aead::rand_core::RngCore as _;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+
+/// Environment variable holding the passphrase for [`TokenKey::from_env`].
+pub const DB_KEY_ENV: &str = "BYOKEY_DB_KEY";
+
+/// Secret the token cipher is built from.
+pub enum TokenKey {
+    /// A raw 256-bit key, used as is.
+    Raw([u8; 32]),
+    /// A passphrase, stretched with Argon2id and the database's salt.
+    Passphrase(String),
+}
+
+impl std::fmt::Debug for TokenKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TokenKey").finish_non_exhaustive()
+    }
+}
+
+impl TokenKey {
+    /// Reads the passphrase from [`DB_KEY_ENV`], or `None` when it is unset.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ByokError::Config`] if the variable is set but empty.
+    pub fn from_env() -> Result<Option<Self>> {
+        match std::env::var(DB_KEY_ENV) {
+            Ok(p) if p.is_empty() => Err(ByokError::Config(format!("{DB_KEY_ENV} is empty"))),
+            Ok(p) => Ok(Some(Self::Passphrase(p))),
+            Err(_) => Ok(None),
+        }
+    }
+
+    /// Builds the cipher, deriving the key with `salt` for a passphrase.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ByokError::Config`] if key derivation fails.
+    pub fn cipher(&self, salt: &[u8]) -> Result<TokenCipher> {
+        match self {
+            Self::Raw(key) => Ok(TokenCipher::new(key)),
+            Self::Passphrase(p) => TokenCipher::from_passphrase(p, salt),
+        }
+    }
+}
+
+/// Returns a fresh random salt for passphrase derivation.
+pub(crate) fn generate_salt() -> [u8; argon2::RECOMMENDED_SALT_LEN] {
+    let mut salt = [0u8; argon2::RECOMMENDED_SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    salt
+}
+
+/// XChaCha20-Poly1305 nonce length in bytes.
+const NONCE_LEN: usize = 24;
+
+/// Symmetric cipher for `accounts.token_json`.
+pub struct TokenCipher {
+    aead: XChaCha20Poly1305,
+}
+
+impl std::fmt::Debug for TokenCipher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TokenCipher").finish_non_exhaustive()
+    }
+}
+
+impl TokenCipher {
+    /// Creates a cipher from a raw 256-bit key.
+    #[must_use]
+    pub fn new(key: &[u8; 32]) -> Self {
+        Self {
+            aead: XChaCha20Poly1305::new(key.into()),
+        }
+    }
+
+    /// Derives the key from `passphrase` and `salt` with Argon2id.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ByokError::Config`] if the passphrase is empty or key
+    /// derivation fails.
+    pub fn from_passphrase(passphrase: &str, salt: &[u8]) -> Result<Self> {
+        if passphrase.is_empty() {
+            return Err(ByokError::Config(format!("{DB_KEY_ENV} is empty")));
+        }
+        let mut key = [0u8; 32];
+        Argon2::default()
+            .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+            .map_err(|e| ByokError::Config(format!("token key derivation failed: {e}")))?;
+        Ok(Self::new(&key))
+    }
+
+    /// Seals `plaintext`, binding it to `aad`.
+    pub(crate) fn encrypt(&self, plaintext: &str, aad: &str) -> Result<String> {
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .aead
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: plaintext.as_bytes(),
+                    aad: aad.as_bytes(),
+                },
+            )
+            .map_err(|_| ByokError::Storage("token encryption failed".into()))?;
+        let mut sealed = nonce.to_vec();
+        sealed.extend_from_slice(&ciphertext);
+        Ok(STANDARD.encode(sealed))
+    }
+
+    /// Opens a value produced by [`encrypt`](Self::encrypt) with the same `aad`.
+    pub(crate) fn decrypt(&self, sealed: &str, aad: &str) -> Result<String> {
+        let undecryptable = || ByokError::Storage(format!("cannot decrypt token for {aad}"));
+        let bytes = STANDARD.decode(sealed).map_err(|_| undecryptable())?;
+        if bytes.len() < NONCE_LEN {
+            return Err(undecryptable());
+        }
+        let (nonce, ciphertext) = bytes.split_at(NONCE_LEN);
+        let plaintext = self
+            .aead
+            .decrypt(
+                XNonce::from_slice(nonce),
+                Payload {
+                    msg: ciphertext,
+                    aad: aad.as_bytes(),
+                },
+            )
+            .map_err(|_| undecryptable())?;
+        String::from_utf8(plaintext).map_err(|_| undecryptable())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip_is_bound_to_aad() {
+        let cipher = TokenCipher::new(&[7; 32]);
+        let sealed = cipher
+            .encrypt("{\"access_token\":\"a\"}", "claude/work")
+            .unwrap();
+        assert!(!sealed.contains("access_token"));
+        assert_eq!(
+            cipher.decrypt(&sealed, "claude/work").unwrap(),
+            "{\"access_token\":\"a\"}"
+        );
+        assert!(cipher.decrypt(&sealed, "claude/home").is_err());
+        assert!(
+            TokenCipher::new(&[8; 32])
+                .decrypt(&sealed, "claude/work")
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn passphrase_derivation_depends_on_salt() {
+        let salt = generate_salt();
+        let a = TokenCipher::from_passphrase("hunter2", &salt).unwrap();
+        let b = TokenCipher::from_passphrase("hunter2", &salt).unwrap();
+        let sealed = a.encrypt("x", "codex/default").unwrap();
+        assert_eq!(b.decrypt(&sealed, "codex/default").unwrap(), "x");
+        let other = TokenCipher::from_passphrase("hunter2", &generate_salt()).unwrap();
+        assert!(other.decrypt(&sealed, "codex/default").is_err());
+        assert!(TokenCipher::from_passphrase("", &salt).is_err());
+    }
+}
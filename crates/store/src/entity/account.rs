@@ -13,6 +13,10 @@ pub struct Model {
     pub is_active: bool,
     #[sea_orm(column_type = "Text")]
     pub token_json: String,
+    /// Whether `token_json` holds a [`crate::TokenCipher`] ciphertext.
+    pub encrypted: bool,
+    /// Unix seconds of the last request this account served, if any.
+    pub last_used_at: Option<i64>,
     pub created_at: i64,
     pub updated_at: i64,
 }
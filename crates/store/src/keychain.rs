@@ -0,0 +1,326 @@
+//! OS keychain token store (macOS Keychain, Windows Credential Manager,
+//! Secret Service on Linux), enabled by the `keychain` feature.
+//!
+//! Keychains cannot enumerate their entries, so each provider gets a single
+//! entry (service `byokey`, user = provider id) holding a JSON document with
+//! all of that provider's accounts and the active-account pointer.
+
+use async_trait::async_trait;
+use byokey_types::{AccountInfo, ByokError, OAuthToken, ProviderId, Result, TokenStore};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use tokio::sync::Mutex;
+
+/// Default keychain service name.
+pub const DEFAULT_SERVICE: &str = "byokey";
+
+/// A [`TokenStore`] that keeps tokens in the platform credential vault.
+pub struct KeychainTokenStore {
+    service: String,
+    /// Serialises read-modify-write cycles on the per-provider documents.
+    lock: Mutex<()>,
+}
+
+/// Per-account record inside a provider's document.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct AccountRecord {
+    token: OAuthToken,
+    #[serde(default)]
+    label: Option<String>,
+}
+
+/// Everything stored for one provider.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Vault {
+    #[serde(default)]
+    active: Option<String>,
+    #[serde(default)]
+    accounts: BTreeMap<String, AccountRecord>,
+}
+
+impl Vault {
+    /// The active account id, falling back to the first account
+    /// (alphabetically) if the pointer is missing or dangling.
+    fn active_id(&self) -> Option<&str> {
+        self.active
+            .as_deref()
+            .filter(|id| self.accounts.contains_key(*id))
+            .or_else(|| self.accounts.keys().next().map(String::as_str))
+    }
+
+    fn save(&mut self, account_id: &str, label: Option<&str>, token: &OAuthToken) {
+        let label = label
+            .map(String::from)
+            .or_else(|| self.accounts.get(account_id).and_then(|r| r.label.clone()));
+        self.accounts.insert(
+            account_id.to_string(),
+            AccountRecord {
+                token: token.clone(),
+                label,
+            },
+        );
+        // The first account saved for a provider becomes active.
+        if self.active.is_none() {
+            self.active = Some(account_id.to_string());
+        }
+    }
+
+    /// Accounts with their active flag, active first then alphabetical.
+    fn records(&self) -> Vec<(&str, &AccountRecord, bool)> {
+        let active = self.active_id();
+        let mut records: Vec<_> = self
+            .accounts
+            .iter()
+            .map(|(id, r)| (id.as_str(), r, Some(id.as_str()) == active))
+            .collect();
+        records.sort_by(|a, b| b.2.cmp(&a.2).then(a.0.cmp(b.0)));
+        records
+    }
+}
+
+fn storage_err(e: impl std::fmt::Display) -> ByokError {
+    ByokError::Storage(e.to_string())
+}
+
+impl KeychainTokenStore {
+    /// Creates a store using the [`DEFAULT_SERVICE`] service name.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_service(DEFAULT_SERVICE)
+    }
+
+    /// Creates a store whose entries are filed under `service`, so several
+    /// installs (or tests) can share one keychain without clashing.
+    #[must_use]
+    pub fn with_service(service: impl Into<String>) -> Self {
+        Self {
+            service: service.into(),
+            lock: Mutex::new(()),
+        }
+    }
+
+    fn entry(&self, provider: &ProviderId) -> Result<keyring::Entry> {
+        keyring::Entry::new(&self.service, &provider.to_string()).map_err(storage_err)
+    }
+
+    async fn read(&self, provider: &ProviderId) -> Result<Vault> {
+        let entry = self.entry(provider)?;
+        let secret = tokio::task::spawn_blocking(move || entry.get_password())
+            .await
+            .map_err(storage_err)?;
+        match secret {
+            Ok(json) => serde_json::from_str(&json).map_err(storage_err),
+            Err(keyring::Error::NoEntry) => Ok(Vault::default()),
+            Err(e) => Err(storage_err(e)),
+        }
+    }
+
+    async fn write(&self, provider: &ProviderId, vault: &Vault) -> Result<()> {
+        let entry = self.entry(provider)?;
+        if vault.accounts.is_empty() {
+            return tokio::task::spawn_blocking(move || match entry.delete_credential() {
+                Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+                Err(e) => Err(storage_err(e)),
+            })
+            .await
+            .map_err(storage_err)?;
+        }
+        let json = serde_json::to_string(vault).map_err(storage_err)?;
+        tokio::task::spawn_blocking(move || entry.set_password(&json))
+            .await
+            .map_err(storage_err)?
+            .map_err(storage_err)
+    }
+
+    /// Applies `f` to the provider's document and writes it back.
+    async fn update<T>(&self, provider: &ProviderId, f: impl FnOnce(&mut Vault) -> T) -> Result<T> {
+        let _guard = self.lock.lock().await;
+        let mut vault = self.read(provider).await?;
+        let out = f(&mut vault);
+        self.write(provider, &vault).await?;
+        Ok(out)
+    }
+}
+
+impl Default for KeychainTokenStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl TokenStore for KeychainTokenStore {
+    // ── Active-account shortcuts ──────────────────────────────────────────
+
+    async fn load(&self, provider: &ProviderId) -> Result<Option<OAuthToken>> {
+        let vault = self.read(provider).await?;
+        Ok(vault
+            .active_id()
+            .and_then(|id| vault.accounts.get(id))
+            .map(|r| r.token.clone()))
+    }
+
+    async fn save(&self, provider: &ProviderId, token: &OAuthToken) -> Result<()> {
+        self.update(provider, |vault| {
+            let account_id = vault.active_id().unwrap_or("default").to_string();
+            vault.save(&account_id, None, token);
+        })
+        .await
+    }
+
+    async fn remove(&self, provider: &ProviderId) -> Result<()> {
+        self.update(provider, |vault| {
+            if let Some(id) = vault.active_id().map(String::from) {
+                vault.accounts.remove(&id);
+                vault.active = None;
+            }
+        })
+        .await
+    }
+
+    // ── Multi-account operations ──────────────────────────────────────────
+
+    async fn load_account(
+        &self,
+        provider: &ProviderId,
+        account_id: &str,
+    ) -> Result<Option<OAuthToken>> {
+        let vault = self.read(provider).await?;
+        Ok(vault.accounts.get(account_id).map(|r| r.token.clone()))
+    }
+
+    async fn save_account(
+        &self,
+        provider: &ProviderId,
+        account_id: &str,
+        label: Option<&str>,
+        token: &OAuthToken,
+    ) -> Result<()> {
+        tracing::debug!(%provider, %account_id, "saving account token to keychain");
+        self.update(provider, |vault| vault.save(account_id, label, token))
+            .await
+    }
+
+    async fn remove_account(&self, provider: &ProviderId, account_id: &str) -> Result<()> {
+        self.update(provider, |vault| {
+            vault.accounts.remove(account_id);
+        })
+        .await
+    }
+
+    async fn list_accounts(&self, provider: &ProviderId) -> Result<Vec<AccountInfo>> {
+        let vault = self.read(provider).await?;
+        Ok(vault
+            .records()
+            .into_iter()
+            .map(|(id, record, is_active)| AccountInfo {
+                account_id: id.to_string(),
+                label: record.label.clone(),
+                is_active,
+                last_used_at: None,
+            })
+            .collect())
+    }
+
+    async fn set_active(&self, provider: &ProviderId, account_id: &str) -> Result<()> {
+        tracing::debug!(%provider, %account_id, "setting active account");
+        self.update(provider, |vault| {
+            if !vault.accounts.contains_key(account_id) {
+                return Err(ByokError::Storage(format!(
+                    "account '{account_id}' not found for provider {provider}"
+                )));
+            }
+            vault.active = Some(account_id.to_string());
+            Ok(())
+        })
+        .await?
+    }
+
+    async fn load_all_tokens(&self, provider: &ProviderId) -> Result<Vec<(String, OAuthToken)>> {
+        let vault = self.read(provider).await?;
+        Ok(vault
+            .records()
+            .into_iter()
+            .map(|(id, record, _)| (id.to_string(), record.token.clone()))
+            .collect())
+    }
+
+    async fn clear_all(&self) -> Result<()> {
+        let _guard = self.lock.lock().await;
+        for provider in ProviderId::all() {
+            self.write(provider, &Vault::default()).await?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_first_account_becomes_active() {
+        let mut vault = Vault::default();
+        vault.save("work", Some("Work"), &OAuthToken::new("w"));
+        vault.save("personal", None, &OAuthToken::new("p"));
+        assert_eq!(vault.active_id(), Some("work"));
+
+        vault.save("work", None, &OAuthToken::new("w2"));
+        let records = vault.records();
+        assert_eq!(records[0].0, "work");
+        assert!(records[0].2);
+        assert_eq!(records[0].1.token.access_token, "w2");
+        assert_eq!(records[0].1.label.as_deref(), Some("Work"));
+    }
+
+    #[test]
+    fn test_dangling_active_falls_back_to_first() {
+        let mut vault = Vault::default();
+        vault.save("b", None, &OAuthToken::new("b"));
+        vault.save("a", None, &OAuthToken::new("a"));
+        vault.accounts.remove("b");
+        assert_eq!(vault.active_id(), Some("a"));
+    }
+
+    /// Talks to the real OS vault, so it only runs where one is always
+    /// available to the logged-in user.
+    #[cfg(any(target_os = "macos", target_os = "windows"))]
+    #[tokio::test]
+    async fn test_keychain_roundtrip() {
+        let store = KeychainTokenStore::with_service("byokey-test");
+        let provider = ProviderId::Claude;
+        store.clear_all().await.unwrap();
+
+        store
+            .save_account(&provider, "work", Some("Work"), &OAuthToken::new("w"))
+            .await
+            .unwrap();
+        store
+            .save_account(&provider, "personal", None, &OAuthToken::new("p"))
+            .await
+            .unwrap();
+        assert_eq!(
+            store.load(&provider).await.unwrap().unwrap().access_token,
+            "w"
+        );
+
+        store.set_active(&provider, "personal").await.unwrap();
+        store
+            .save(&provider, &OAuthToken::new("refreshed"))
+            .await
+            .unwrap();
+        let accounts = store.list_accounts(&provider).await.unwrap();
+        assert_eq!(accounts.len(), 2);
+        assert!(accounts[0].is_active);
+        assert_eq!(accounts[0].account_id, "personal");
+        assert!(store.set_active(&provider, "missing").await.is_err());
+
+        store.remove(&provider).await.unwrap();
+        assert_eq!(
+            store.load(&provider).await.unwrap().unwrap().access_token,
+            "w"
+        );
+        store.clear_all().await.unwrap();
+        assert!(store.load(&provider).await.unwrap().is_none());
+    }
+}
@@ -1,11 +1,23 @@
 //! Token storage backends for persisting OAuth tokens.
 //!
-//! Provides an in-memory store for testing and a SQLite-backed store for production.
+//! Provides an in-memory store for testing, a SQLite-backed store for production
+//! and, behind features, a Redis-backed store for sharing accounts between
+//! instances (`redis`) and an OS keychain store (`keychain`).
 
+pub mod cipher;
 pub mod entity;
+#[cfg(feature = "keychain")]
+pub mod keychain;
 pub mod memory;
 pub mod migration;
 pub mod persistent;
+#[cfg(feature = "redis")]
+pub mod redis;
 
+#[cfg(feature = "redis")]
+pub use crate::redis::RedisTokenStore;
+pub use cipher::{DB_KEY_ENV, TokenCipher, TokenKey};
+#[cfg(feature = "keychain")]
+pub use keychain::KeychainTokenStore;
 pub use memory::InMemoryTokenStore;
 pub use persistent::SqliteTokenStore;
@@ -15,6 +15,7 @@ struct AccountEntry {
     token: OAuthToken,
     label: Option<String>,
     is_active: bool,
+    last_used_at: Option<i64>,
 }
 
 /// An in-memory [`TokenStore`] implementation for testing and ephemeral use.
@@ -113,6 +114,7 @@ impl TokenStore for InMemoryTokenStore {
                     token: token.clone(),
                     label: label.map(String::from),
                     is_active: !has_active,
+                    last_used_at: None,
                 },
             );
         }
@@ -135,6 +137,7 @@ impl TokenStore for InMemoryTokenStore {
                 account_id: id.clone(),
                 label: e.label.clone(),
                 is_active: e.is_active,
+                last_used_at: e.last_used_at,
             })
             .collect();
         // Active first, then alphabetical.
@@ -175,6 +178,28 @@ impl TokenStore for InMemoryTokenStore {
         tokens.sort_by(|a, b| b.2.cmp(&a.2).then(a.0.cmp(&b.0)));
         Ok(tokens.into_iter().map(|(id, tok, _)| (id, tok)).collect())
     }
+
+    async fn clear_all(&self) -> Result<()> {
+        self.data.lock().unwrap().clear();
+        Ok(())
+    }
+
+    async fn touch_last_used(&self, provider: &ProviderId, account_id: &str) -> Result<()> {
+        let key = (provider.clone(), account_id.to_string());
+        if let Some(entry) = self.data.lock().unwrap().get_mut(&key) {
+            entry.last_used_at = Some(crate::persistent::now_unix());
+        }
+        Ok(())
+    }
+
+    async fn load_all_active(&self) -> Result<HashMap<ProviderId, OAuthToken>> {
+        let data = self.data.lock().unwrap();
+        Ok(data
+            .iter()
+            .filter(|(_, e)| e.is_active)
+            .map(|((p, _), e)| (p.clone(), e.token.clone()))
+            .collect())
+    }
 }
 
 #[cfg(test)]
@@ -367,4 +392,46 @@ mod tests {
         let all = store.load_all_tokens(&ProviderId::Claude).await.unwrap();
         assert_eq!(all.len(), 2);
     }
+
+    #[tokio::test]
+    async fn test_clear_all() {
+        let store = InMemoryTokenStore::new();
+        store
+            .save(&ProviderId::Claude, &OAuthToken::new("c"))
+            .await
+            .unwrap();
+        store
+            .save_account(&ProviderId::Codex, "work", None, &OAuthToken::new("w"))
+            .await
+            .unwrap();
+        store.clear_all().await.unwrap();
+        for p in ProviderId::all() {
+            assert!(store.list_accounts(p).await.unwrap().is_empty());
+            assert!(store.load(p).await.unwrap().is_none());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_touch_last_used() {
+        let store = InMemoryTokenStore::new();
+        store
+            .save_account(&ProviderId::Claude, "a", None, &OAuthToken::new("tok-a"))
+            .await
+            .unwrap();
+        store
+            .save_account(&ProviderId::Claude, "b", None, &OAuthToken::new("tok-b"))
+            .await
+            .unwrap();
+        store
+            .touch_last_used(&ProviderId::Claude, "b")
+            .await
+            .unwrap();
+        store
+            .touch_last_used(&ProviderId::Claude, "missing")
+            .await
+            .unwrap();
+        let accounts = store.list_accounts(&ProviderId::Claude).await.unwrap();
+        assert!(accounts[0].last_used_at.is_none());
+        assert!(accounts[1].last_used_at.is_some());
+    }
 }
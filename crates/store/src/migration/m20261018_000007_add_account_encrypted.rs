@@ -0,0 +1,73 @@
+//! Add an `encrypted` flag to `accounts` so rows written by an encrypting
+//! [`crate::SqliteTokenStore`] can live alongside older plaintext rows, and
+//! create `store_meta`, a key/value table seeded with this database's random
+//! token KDF salt.
+
+use base64::Engine as _;
+use base64::engine::general_purpose::STANDARD;
+use sea_orm_migration::{prelude::*, schema::*};
+
+use crate::cipher::generate_salt;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[derive(DeriveIden)]
+enum Accounts {
+    Table,
+    Encrypted,
+}
+
+#[derive(DeriveIden)]
+enum StoreMeta {
+    Table,
+    Key,
+    Value,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Accounts::Table)
+                    .add_column(boolean(Accounts::Encrypted).default(false))
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_table(
+                Table::create()
+                    .table(StoreMeta::Table)
+                    .if_not_exists()
+                    .col(text(StoreMeta::Key).primary_key())
+                    .col(text(StoreMeta::Value))
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .exec_stmt(
+                Query::insert()
+                    .into_table(StoreMeta::Table)
+                    .columns([StoreMeta::Key, StoreMeta::Value])
+                    .values_panic(["kdf_salt".into(), STANDARD.encode(generate_salt()).into()])
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(StoreMeta::Table).to_owned())
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Accounts::Table)
+                    .drop_column(Accounts::Encrypted)
+                    .to_owned(),
+            )
+            .await
+    }
+}
@@ -20,6 +20,8 @@ mod m20260415_000003_create_conversations;
 mod m20260415_000004_create_messages;
 mod m20260415_000005_create_usage_records;
 mod m20260417_000006_add_usage_account_id;
+mod m20261018_000007_add_account_encrypted;
+mod m20261018_000008_add_account_last_used;
 
 pub struct Migrator;
 
@@ -33,6 +35,8 @@ impl MigratorTrait for Migrator {
             Box::new(m20260415_000004_create_messages::Migration),
             Box::new(m20260415_000005_create_usage_records::Migration),
             Box::new(m20260417_000006_add_usage_account_id::Migration),
+            Box::new(m20261018_000007_add_account_encrypted::Migration),
+            Box::new(m20261018_000008_add_account_last_used::Migration),
         ]
     }
 }
@@ -11,6 +11,8 @@ mod history;
 mod token;
 mod usage;
 
+use base64::Engine as _;
+use base64::engine::general_purpose::STANDARD;
 use byokey_types::OAuthToken;
 use sea_orm::{ConnectOptions, ConnectionTrait, Database, DatabaseConnection, Statement};
 use sea_orm_migration::MigratorTrait;
@@ -19,6 +21,7 @@ use std::sync::Mutex;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use crate::migration::{self, Migrator};
+use crate::{TokenCipher, TokenKey};
 
 /// A persistent [`TokenStore`](byokey_types::TokenStore) backed by `SQLite` via `SeaORM`.
 pub struct SqliteTokenStore {
@@ -26,6 +29,8 @@ pub struct SqliteTokenStore {
     db: DatabaseConnection,
     /// In-memory cache of active tokens keyed by provider string.
     cache: Mutex<HashMap<String, OAuthToken>>,
+    /// Encrypts `token_json` on write when set; see [`Self::new_encrypted`].
+    cipher: Option<TokenCipher>,
 }
 
 pub(crate) fn now_unix() -> i64 {
@@ -47,6 +52,29 @@ impl SqliteTokenStore {
     ///
     /// Returns a [`sea_orm::DbErr`] if the connection or migrations fail.
     pub async fn new(database_url: &str) -> std::result::Result<Self, sea_orm::DbErr> {
+        Self::connect(database_url, None).await
+    }
+
+    /// Like [`new`](Self::new), but tokens are encrypted with a cipher built
+    /// from `key` before being written. A passphrase is stretched with the
+    /// database's own random salt. Existing plaintext rows stay readable and
+    /// are re-encrypted the next time they are saved.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`sea_orm::DbErr`] if the connection, migrations or key
+    /// derivation fail.
+    pub async fn new_encrypted(
+        database_url: &str,
+        key: TokenKey,
+    ) -> std::result::Result<Self, sea_orm::DbErr> {
+        Self::connect(database_url, Some(key)).await
+    }
+
+    async fn connect(
+        database_url: &str,
+        key: Option<TokenKey>,
+    ) -> std::result::Result<Self, sea_orm::DbErr> {
         // sqlx emits every PRAGMA / migration query at INFO; silence it.
         // `max_connections` defaults to 1 for SQLite under sea-orm v2, which
         // can deadlock if a connection isn't released between the migration
@@ -56,9 +84,21 @@ impl SqliteTokenStore {
         let db = Database::connect(opt).await?;
         migration::backfill_pre_migration_install(&db).await?;
         Migrator::up(&db, None).await?;
+        heal_active_accounts(&db).await?;
+        let cipher = match key {
+            Some(key) => {
+                let salt = kdf_salt(&db).await?;
+                Some(
+                    key.cipher(&salt)
+                        .map_err(|e| sea_orm::DbErr::Custom(e.to_string()))?,
+                )
+            }
+            None => None,
+        };
         Ok(Self {
             db,
             cache: Mutex::new(HashMap::new()),
+            cipher,
         })
     }
 
@@ -79,3 +119,50 @@ pub(crate) async fn db_exec_raw(
     db.execute_raw(stmt).await?;
     Ok(())
 }
+
+/// Returns this database's KDF salt, generated when its schema was created.
+async fn kdf_salt(db: &impl ConnectionTrait) -> std::result::Result<Vec<u8>, sea_orm::DbErr> {
+    let stmt = Statement::from_string(
+        db.get_database_backend(),
+        "SELECT value FROM store_meta WHERE key = 'kdf_salt'",
+    );
+    let row = db
+        .query_one_raw(stmt)
+        .await?
+        .ok_or_else(|| sea_orm::DbErr::Custom("kdf_salt missing from store_meta".into()))?;
+    let value: String = row.try_get("", "value")?;
+    STANDARD
+        .decode(value)
+        .map_err(|e| sea_orm::DbErr::Custom(format!("invalid kdf_salt: {e}")))
+}
+
+/// Activates the most recently updated account of every provider that has
+/// accounts but none active — e.g. after a crash in the middle of switching
+/// accounts. Returns the number of accounts activated.
+pub(crate) async fn heal_active_accounts(
+    db: &impl ConnectionTrait,
+) -> std::result::Result<u64, sea_orm::DbErr> {
+    let stmt = Statement::from_string(
+        db.get_database_backend(),
+        "UPDATE accounts SET is_active = 1
+         WHERE (provider, account_id) IN (
+             SELECT a.provider, (
+                 SELECT b.account_id FROM accounts b
+                 WHERE b.provider = a.provider
+                 ORDER BY b.updated_at DESC, b.account_id ASC
+                 LIMIT 1
+             )
+             FROM accounts a
+             GROUP BY a.provider
+             HAVING MAX(a.is_active) = 0
+         )",
+    );
+    let healed = db.execute_raw(stmt).await?.rows_affected();
+    if healed > 0 {
+        tracing::warn!(
+            healed,
+            "re-activated accounts for providers with no active account"
+        );
+    }
+    Ok(healed)
+}
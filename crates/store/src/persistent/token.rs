@@ -3,10 +3,84 @@
 use async_trait::async_trait;
 use byokey_types::{AccountInfo, ByokError, OAuthToken, ProviderId, Result, TokenStore};
 use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QueryOrder, TransactionTrait};
+use std::borrow::Cow;
+use std::collections::HashMap;
 
 use super::{SqliteTokenStore, db_exec_raw, now_unix};
+use crate::DB_KEY_ENV;
 use crate::entity::account;
 
+impl SqliteTokenStore {
+    /// Parses a row's token, decrypting it first if the row is encrypted.
+    fn decode_token(&self, m: &account::Model) -> Result<OAuthToken> {
+        let json = if m.encrypted {
+            let cipher = self.cipher.as_ref().ok_or_else(|| {
+                ByokError::Storage(format!(
+                    "token for {}/{} is encrypted; set {DB_KEY_ENV}",
+                    m.provider, m.account_id
+                ))
+            })?;
+            Cow::Owned(cipher.decrypt(&m.token_json, &format!("{}/{}", m.provider, m.account_id))?)
+        } else {
+            Cow::Borrowed(m.token_json.as_str())
+        };
+        serde_json::from_str(&json).map_err(|e| ByokError::Storage(e.to_string()))
+    }
+
+    /// Like [`decode_token`](Self::decode_token), but logs and returns `None`
+    /// on failure so one bad row does not hide every other account.
+    fn decode_or_skip(&self, m: &account::Model) -> Option<OAuthToken> {
+        self.decode_token(m)
+            .inspect_err(|e| {
+                tracing::warn!(
+                    provider = %m.provider,
+                    account_id = %m.account_id,
+                    error = %e,
+                    "skipping undecodable token"
+                );
+            })
+            .ok()
+    }
+
+    /// Activates the most recently updated remaining account of `provider`
+    /// if none is active, e.g. after the active one was removed.
+    async fn promote_if_none_active(&self, provider: &str) -> Result<()> {
+        db_exec_raw(
+            &self.db,
+            "UPDATE accounts SET is_active = 1
+             WHERE provider = ?1
+               AND account_id = (
+                   SELECT account_id FROM accounts WHERE provider = ?1
+                   ORDER BY updated_at DESC, account_id ASC
+                   LIMIT 1
+               )
+               AND NOT EXISTS (
+                   SELECT 1 FROM accounts WHERE provider = ?1 AND is_active = 1
+               )",
+            vec![provider.to_string().into()],
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Serializes a token for storage, returning it with the `encrypted` flag.
+    fn encode_token(
+        &self,
+        provider: &ProviderId,
+        account_id: &str,
+        token: &OAuthToken,
+    ) -> Result<(String, bool)> {
+        let json = serde_json::to_string(token).map_err(|e| ByokError::Storage(e.to_string()))?;
+        match &self.cipher {
+            Some(cipher) => Ok((
+                cipher.encrypt(&json, &format!("{provider}/{account_id}"))?,
+                true,
+            )),
+            None => Ok((json, false)),
+        }
+    }
+}
+
 #[async_trait]
 impl TokenStore for SqliteTokenStore {
     // ── Active-account shortcuts ──────────────────────────────────────────
@@ -28,8 +102,7 @@ impl TokenStore for SqliteTokenStore {
         match row {
             None => Ok(None),
             Some(m) => {
-                let token: OAuthToken = serde_json::from_str(&m.token_json)
-                    .map_err(|e| ByokError::Storage(e.to_string()))?;
+                let token = self.decode_token(&m)?;
                 self.cache.lock().unwrap().insert(key, token.clone());
                 Ok(Some(token))
             }
@@ -62,6 +135,7 @@ impl TokenStore for SqliteTokenStore {
             .filter(account::Column::IsActive.eq(true))
             .exec(&self.db)
             .await?;
+        self.promote_if_none_active(&key).await?;
         self.cache.lock().unwrap().remove(&key);
         Ok(())
     }
@@ -81,8 +155,7 @@ impl TokenStore for SqliteTokenStore {
         match row {
             None => Ok(None),
             Some(m) => {
-                let token: OAuthToken = serde_json::from_str(&m.token_json)
-                    .map_err(|e| ByokError::Storage(e.to_string()))?;
+                let token = self.decode_token(&m)?;
                 Ok(Some(token))
             }
         }
@@ -97,7 +170,7 @@ impl TokenStore for SqliteTokenStore {
     ) -> Result<()> {
         tracing::debug!(%provider, %account_id, "saving account token");
         let key = provider.to_string();
-        let json = serde_json::to_string(token).map_err(|e| ByokError::Storage(e.to_string()))?;
+        let (json, encrypted) = self.encode_token(provider, account_id, token)?;
         let now = now_unix();
 
         let has_active = account::Entity::find()
@@ -112,10 +185,11 @@ impl TokenStore for SqliteTokenStore {
         if let Some(l) = label {
             db_exec_raw(
                 &self.db,
-                "INSERT INTO accounts (provider, account_id, is_active, label, token_json, created_at, updated_at)
-                 VALUES (?, ?, ?, ?, ?, ?, ?)
+                "INSERT INTO accounts (provider, account_id, is_active, label, token_json, encrypted, created_at, updated_at)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?)
                  ON CONFLICT(provider, account_id) DO UPDATE SET
                    token_json = excluded.token_json,
+                   encrypted = excluded.encrypted,
                    label = excluded.label,
                    updated_at = excluded.updated_at",
                 vec![
@@ -124,6 +198,7 @@ impl TokenStore for SqliteTokenStore {
                     is_active_val.into(),
                     l.to_string().into(),
                     json.into(),
+                    encrypted.into(),
                     now.into(),
                     now.into(),
                 ],
@@ -136,16 +211,18 @@ impl TokenStore for SqliteTokenStore {
         } else {
             db_exec_raw(
                 &self.db,
-                "INSERT INTO accounts (provider, account_id, is_active, token_json, created_at, updated_at)
-                 VALUES (?, ?, ?, ?, ?, ?)
+                "INSERT INTO accounts (provider, account_id, is_active, token_json, encrypted, created_at, updated_at)
+                 VALUES (?, ?, ?, ?, ?, ?, ?)
                  ON CONFLICT(provider, account_id) DO UPDATE SET
                    token_json = excluded.token_json,
+                   encrypted = excluded.encrypted,
                    updated_at = excluded.updated_at",
                 vec![
                     key.clone().into(),
                     account_id.to_string().into(),
                     is_active_val.into(),
                     json.into(),
+                    encrypted.into(),
                     now.into(),
                     now.into(),
                 ],
@@ -166,6 +243,7 @@ impl TokenStore for SqliteTokenStore {
         account::Entity::delete_by_id((key.clone(), account_id.to_string()))
             .exec(&self.db)
             .await?;
+        self.promote_if_none_active(&key).await?;
         self.cache.lock().unwrap().remove(&key);
         Ok(())
     }
@@ -185,6 +263,7 @@ impl TokenStore for SqliteTokenStore {
                 account_id: m.account_id,
                 label: m.label,
                 is_active: m.is_active,
+                last_used_at: m.last_used_at,
             })
             .collect())
     }
@@ -230,6 +309,20 @@ impl TokenStore for SqliteTokenStore {
         Ok(())
     }
 
+    async fn touch_last_used(&self, provider: &ProviderId, account_id: &str) -> Result<()> {
+        db_exec_raw(
+            &self.db,
+            "UPDATE accounts SET last_used_at = ? WHERE provider = ? AND account_id = ?",
+            vec![
+                now_unix().into(),
+                provider.to_string().into(),
+                account_id.to_string().into(),
+            ],
+        )
+        .await?;
+        Ok(())
+    }
+
     async fn load_all_tokens(&self, provider: &ProviderId) -> Result<Vec<(String, OAuthToken)>> {
         let key = provider.to_string();
         let rows = account::Entity::find()
@@ -241,17 +334,52 @@ impl TokenStore for SqliteTokenStore {
 
         let mut result = Vec::with_capacity(rows.len());
         for m in rows {
-            let token: OAuthToken = serde_json::from_str(&m.token_json)
-                .map_err(|e| ByokError::Storage(e.to_string()))?;
+            let Some(token) = self.decode_or_skip(&m) else {
+                continue;
+            };
             result.push((m.account_id, token));
         }
         Ok(result)
     }
+
+    /// Deletes every account row and empties the token cache.
+    async fn clear_all(&self) -> Result<()> {
+        account::Entity::delete_many().exec(&self.db).await?;
+        self.cache.lock().unwrap().clear();
+        Ok(())
+    }
+
+    /// Loads every provider's active token in a single query, refreshing the
+    /// per-provider cache. Rows for unknown providers or that cannot be
+    /// decoded are skipped.
+    async fn load_all_active(&self) -> Result<HashMap<ProviderId, OAuthToken>> {
+        let rows = account::Entity::find()
+            .filter(account::Column::IsActive.eq(true))
+            .all(&self.db)
+            .await?;
+
+        let mut result = HashMap::with_capacity(rows.len());
+        for m in rows {
+            let Ok(provider) = m.provider.parse::<ProviderId>() else {
+                continue;
+            };
+            let Some(token) = self.decode_or_skip(&m) else {
+                continue;
+            };
+            self.cache.lock().unwrap().insert(m.provider, token.clone());
+            result.insert(provider, token);
+        }
+        Ok(result)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::TokenKey;
+    use crate::persistent::heal_active_accounts;
+    use base64::Engine as _;
+    use base64::engine::general_purpose::STANDARD;
     use sea_orm::{ConnectionTrait, Database, Statement};
 
     async fn mem() -> SqliteTokenStore {
@@ -463,6 +591,26 @@ mod tests {
         assert_eq!(accounts[0].label.as_deref(), Some("Work"));
     }
 
+    #[tokio::test]
+    async fn test_touch_last_used() {
+        let s = mem().await;
+        s.save_account(&ProviderId::Claude, "a", None, &OAuthToken::new("tok-a"))
+            .await
+            .unwrap();
+        s.save_account(&ProviderId::Claude, "b", None, &OAuthToken::new("tok-b"))
+            .await
+            .unwrap();
+        let accounts = s.list_accounts(&ProviderId::Claude).await.unwrap();
+        assert!(accounts.iter().all(|a| a.last_used_at.is_none()));
+
+        let before = now_unix();
+        s.touch_last_used(&ProviderId::Claude, "b").await.unwrap();
+        let accounts = s.list_accounts(&ProviderId::Claude).await.unwrap();
+        assert_eq!(accounts[0].account_id, "a");
+        assert!(accounts[0].last_used_at.is_none());
+        assert!(accounts[1].last_used_at.is_some_and(|t| t >= before));
+    }
+
     #[tokio::test]
     async fn test_load_all_tokens() {
         let s = mem().await;
@@ -478,6 +626,123 @@ mod tests {
         assert_eq!(all[0].0, "a");
     }
 
+    #[tokio::test]
+    async fn test_load_all_active() {
+        let s = mem().await;
+        s.save(&ProviderId::Claude, &OAuthToken::new("claude-tok"))
+            .await
+            .unwrap();
+        s.save_account(&ProviderId::Codex, "a", None, &OAuthToken::new("codex-a"))
+            .await
+            .unwrap();
+        s.save_account(&ProviderId::Codex, "b", None, &OAuthToken::new("codex-b"))
+            .await
+            .unwrap();
+
+        let all = s.load_all_active().await.unwrap();
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[&ProviderId::Claude].access_token, "claude-tok");
+        assert_eq!(all[&ProviderId::Codex].access_token, "codex-a");
+        assert!(!all.contains_key(&ProviderId::Gemini));
+    }
+
+    #[tokio::test]
+    async fn test_startup_heals_zero_active_accounts() {
+        let s = mem().await;
+        s.save_account(
+            &ProviderId::Claude,
+            "old",
+            None,
+            &OAuthToken::new("tok-old"),
+        )
+        .await
+        .unwrap();
+        s.save_account(
+            &ProviderId::Claude,
+            "new",
+            None,
+            &OAuthToken::new("tok-new"),
+        )
+        .await
+        .unwrap();
+
+        // Simulate a crash between the two UPDATEs in `set_active`.
+        db_exec_raw(
+            &s.db,
+            "UPDATE accounts SET is_active = 0, updated_at = 100 WHERE account_id = 'old'",
+            vec![],
+        )
+        .await
+        .unwrap();
+        db_exec_raw(
+            &s.db,
+            "UPDATE accounts SET is_active = 0, updated_at = 200 WHERE account_id = 'new'",
+            vec![],
+        )
+        .await
+        .unwrap();
+        s.cache.lock().unwrap().clear();
+
+        // Healing runs once at startup, not on every cache miss.
+        assert!(s.load(&ProviderId::Claude).await.unwrap().is_none());
+        assert_eq!(heal_active_accounts(&s.db).await.unwrap(), 1);
+        let tok = s.load(&ProviderId::Claude).await.unwrap().unwrap();
+        assert_eq!(tok.access_token, "tok-new");
+        let accounts = s.list_accounts(&ProviderId::Claude).await.unwrap();
+        assert_eq!(accounts.iter().filter(|a| a.is_active).count(), 1);
+        assert_eq!(accounts[0].account_id, "new");
+        assert!(accounts[0].is_active);
+    }
+
+    #[tokio::test]
+    async fn test_removing_active_account_promotes_another() {
+        let s = mem().await;
+        for id in ["a", "b"] {
+            s.save_account(&ProviderId::Claude, id, None, &OAuthToken::new(id))
+                .await
+                .unwrap();
+        }
+        s.remove_account(&ProviderId::Claude, "a").await.unwrap();
+        let tok = s.load(&ProviderId::Claude).await.unwrap().unwrap();
+        assert_eq!(tok.access_token, "b");
+
+        s.remove(&ProviderId::Claude).await.unwrap();
+        assert!(s.load(&ProviderId::Claude).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_heal_leaves_healthy_providers_alone() {
+        let s = mem().await;
+        s.save_account(&ProviderId::Claude, "a", None, &OAuthToken::new("a"))
+            .await
+            .unwrap();
+        s.save_account(&ProviderId::Claude, "b", None, &OAuthToken::new("b"))
+            .await
+            .unwrap();
+        assert_eq!(heal_active_accounts(&s.db).await.unwrap(), 0);
+        assert!(s.load(&ProviderId::Gemini).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_clear_all() {
+        let s = mem().await;
+        s.save(&ProviderId::Claude, &OAuthToken::new("c"))
+            .await
+            .unwrap();
+        s.save_account(&ProviderId::Codex, "a", None, &OAuthToken::new("a"))
+            .await
+            .unwrap();
+        s.save_account(&ProviderId::Codex, "b", None, &OAuthToken::new("b"))
+            .await
+            .unwrap();
+
+        s.clear_all().await.unwrap();
+        for p in ProviderId::all() {
+            assert!(s.list_accounts(p).await.unwrap().is_empty());
+            assert!(s.load(p).await.unwrap().is_none());
+        }
+    }
+
     #[tokio::test]
     async fn test_remove_account() {
         let s = mem().await;
@@ -493,6 +758,111 @@ mod tests {
         );
     }
 
+    async fn loaded(s: &SqliteTokenStore, id: &str) -> Result<String> {
+        let token = s.load_account(&ProviderId::Claude, id).await?;
+        Ok(token.unwrap().access_token)
+    }
+
+    async fn kdf_salt_row(s: &SqliteTokenStore) -> Option<String> {
+        let stmt = Statement::from_string(
+            s.db.get_database_backend(),
+            "SELECT value FROM store_meta WHERE key = 'kdf_salt'",
+        );
+        s.db.query_one_raw(stmt)
+            .await
+            .unwrap()
+            .map(|row| row.try_get::<String>("", "value").unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_encrypted_store_reads_mixed_rows() {
+        let enc = SqliteTokenStore::new_encrypted("sqlite::memory:", TokenKey::Raw([1; 32]))
+            .await
+            .unwrap();
+        // Same database, no key: stands in for rows written before encryption.
+        let plain = SqliteTokenStore {
+            db: enc.db.clone(),
+            cache: std::sync::Mutex::new(HashMap::new()),
+            cipher: None,
+        };
+        plain
+            .save_account(&ProviderId::Claude, "old", None, &OAuthToken::new("plain"))
+            .await
+            .unwrap();
+        enc.save_account(&ProviderId::Claude, "new", None, &OAuthToken::new("secret"))
+            .await
+            .unwrap();
+
+        let row = account::Entity::find_by_id(("claude".to_string(), "new".to_string()))
+            .one(enc.connection())
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(row.encrypted);
+        assert!(!row.token_json.contains("secret"));
+
+        assert_eq!(loaded(&enc, "old").await.unwrap(), "plain");
+        assert_eq!(loaded(&enc, "new").await.unwrap(), "secret");
+        // Without the key, encrypted rows fail loudly and plaintext ones still load.
+        assert_eq!(loaded(&plain, "old").await.unwrap(), "plain");
+        assert!(matches!(
+            loaded(&plain, "new").await,
+            Err(ByokError::Storage(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_passphrase_salt_is_per_database() {
+        let key = || TokenKey::Passphrase("hunter2".into());
+        let a = SqliteTokenStore::new_encrypted("sqlite::memory:", key())
+            .await
+            .unwrap();
+        let b = SqliteTokenStore::new_encrypted("sqlite::memory:", key())
+            .await
+            .unwrap();
+        let salt_a = kdf_salt_row(&a).await.unwrap();
+        assert_ne!(salt_a, kdf_salt_row(&b).await.unwrap());
+        assert_eq!(
+            super::super::kdf_salt(&a.db).await.unwrap(),
+            STANDARD.decode(&salt_a).unwrap(),
+            "the stored salt is reused, not regenerated"
+        );
+        // Plaintext databases get one too, so encryption can be enabled later.
+        assert!(kdf_salt_row(&mem().await).await.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_bulk_loads_skip_undecodable_rows() {
+        let enc = SqliteTokenStore::new_encrypted("sqlite::memory:", TokenKey::Raw([1; 32]))
+            .await
+            .unwrap();
+        enc.save_account(&ProviderId::Claude, "sealed", None, &OAuthToken::new("s"))
+            .await
+            .unwrap();
+        let plain = SqliteTokenStore {
+            db: enc.db.clone(),
+            cache: std::sync::Mutex::new(HashMap::new()),
+            cipher: None,
+        };
+        plain
+            .save_account(&ProviderId::Claude, "open", None, &OAuthToken::new("p"))
+            .await
+            .unwrap();
+        plain
+            .save_account(&ProviderId::Codex, "sealed", None, &OAuthToken::new("s"))
+            .await
+            .unwrap();
+        enc.save_account(&ProviderId::Codex, "sealed", None, &OAuthToken::new("s"))
+            .await
+            .unwrap();
+
+        let all = plain.load_all_tokens(&ProviderId::Claude).await.unwrap();
+        assert_eq!(all.len(), 1);
+        assert_eq!(all[0].0, "open");
+        let active = plain.load_all_active().await.unwrap();
+        assert!(!active.contains_key(&ProviderId::Codex));
+    }
+
     #[tokio::test]
     async fn test_legacy_migration() {
         use sea_orm_migration::MigratorTrait as _;
@@ -0,0 +1,375 @@
+//! Redis-backed token store, for sharing accounts between byokey instances.
+//!
+//! Key scheme, per provider:
+//! - `byokey:{provider}:accounts` — hash of `account_id` → JSON account record.
+//! - `byokey:{provider}:active` — id of the active account.
+//! - `byokey:{provider}:last_used` — hash of `account_id` → unix seconds of
+//!   its last served request.
+//!
+//! Accounts live in one hash rather than one key each so listing never needs
+//! `KEYS`/`SCAN` and no account id can collide with the `active` pointer.
+
+use async_trait::async_trait;
+use byokey_types::{AccountInfo, ByokError, OAuthToken, ProviderId, Result, TokenStore};
+use redis::AsyncCommands as _;
+use redis::aio::ConnectionManager;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// A [`TokenStore`] backed by Redis.
+#[derive(Clone)]
+pub struct RedisTokenStore {
+    conn: ConnectionManager,
+}
+
+/// Value stored per account in the provider's accounts hash.
+#[derive(Debug, Serialize, Deserialize)]
+struct AccountRecord {
+    token: OAuthToken,
+    #[serde(default)]
+    label: Option<String>,
+}
+
+fn accounts_key(provider: &ProviderId) -> String {
+    format!("byokey:{provider}:accounts")
+}
+
+fn active_key(provider: &ProviderId) -> String {
+    format!("byokey:{provider}:active")
+}
+
+fn last_used_key(provider: &ProviderId) -> String {
+    format!("byokey:{provider}:last_used")
+}
+
+fn storage_err(e: impl std::fmt::Display) -> ByokError {
+    ByokError::Storage(e.to_string())
+}
+
+fn decode(json: &str) -> Result<AccountRecord> {
+    serde_json::from_str(json).map_err(storage_err)
+}
+
+/// Builds the record to store, keeping the existing label when `label` is `None`.
+fn merge_record(existing: Option<&str>, label: Option<&str>, token: &OAuthToken) -> Result<String> {
+    let label = match label {
+        Some(l) => Some(l.to_string()),
+        None => existing.map(decode).transpose()?.and_then(|r| r.label),
+    };
+    serde_json::to_string(&AccountRecord {
+        token: token.clone(),
+        label,
+    })
+    .map_err(storage_err)
+}
+
+impl RedisTokenStore {
+    /// Connects to Redis (e.g. `"redis://127.0.0.1:6379/0"`).
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`redis::RedisError`] if the URL is invalid, names a TLS
+    /// (`rediss://`) server, or the connection fails.
+    pub async fn new(url: &str) -> std::result::Result<Self, redis::RedisError> {
+        if url.starts_with("rediss://") {
+            return Err(redis::RedisError::from((
+                redis::ErrorKind::InvalidClientConfig,
+                "rediss:// (TLS) is not supported; use redis:// over a trusted network or a TLS tunnel",
+            )));
+        }
+        let client = redis::Client::open(url)?;
+        let conn = client.get_connection_manager().await?;
+        Ok(Self { conn })
+    }
+
+    /// Returns the active account id, re-activating the first account
+    /// (alphabetically) if the pointer is missing or dangling.
+    async fn active_account(&self, provider: &ProviderId) -> Result<Option<String>> {
+        let mut conn = self.conn.clone();
+        let active: Option<String> = conn.get(active_key(provider)).await.map_err(storage_err)?;
+        if let Some(id) = &active {
+            let exists: bool = conn
+                .hexists(accounts_key(provider), id)
+                .await
+                .map_err(storage_err)?;
+            if exists {
+                return Ok(active);
+            }
+        }
+        let mut ids: Vec<String> = conn
+            .hkeys(accounts_key(provider))
+            .await
+            .map_err(storage_err)?;
+        ids.sort();
+        let Some(first) = ids.into_iter().next() else {
+            return Ok(None);
+        };
+        let () = conn
+            .set(active_key(provider), &first)
+            .await
+            .map_err(storage_err)?;
+        Ok(Some(first))
+    }
+
+    async fn records(&self, provider: &ProviderId) -> Result<Vec<(String, AccountRecord, bool)>> {
+        let active = self.active_account(provider).await?;
+        let raw: HashMap<String, String> = self
+            .conn
+            .clone()
+            .hgetall(accounts_key(provider))
+            .await
+            .map_err(storage_err)?;
+        let mut records = raw
+            .into_iter()
+            .map(|(id, json)| {
+                let is_active = active.as_deref() == Some(id.as_str());
+                Ok((id, decode(&json)?, is_active))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        // Active first, then alphabetical.
+        records.sort_by(|a, b| b.2.cmp(&a.2).then(a.0.cmp(&b.0)));
+        Ok(records)
+    }
+}
+
+#[async_trait]
+impl TokenStore for RedisTokenStore {
+    // ── Active-account shortcuts ──────────────────────────────────────────
+
+    async fn load(&self, provider: &ProviderId) -> Result<Option<OAuthToken>> {
+        match self.active_account(provider).await? {
+            Some(id) => self.load_account(provider, &id).await,
+            None => Ok(None),
+        }
+    }
+
+    async fn save(&self, provider: &ProviderId, token: &OAuthToken) -> Result<()> {
+        let active = self.active_account(provider).await?;
+        let account_id = active.as_deref().unwrap_or("default");
+        self.save_account(provider, account_id, None, token).await
+    }
+
+    async fn remove(&self, provider: &ProviderId) -> Result<()> {
+        if let Some(id) = self.active_account(provider).await? {
+            self.remove_account(provider, &id).await?;
+        }
+        Ok(())
+    }
+
+    // ── Multi-account operations ──────────────────────────────────────────
+
+    async fn load_account(
+        &self,
+        provider: &ProviderId,
+        account_id: &str,
+    ) -> Result<Option<OAuthToken>> {
+        let json: Option<String> = self
+            .conn
+            .clone()
+            .hget(accounts_key(provider), account_id)
+            .await
+            .map_err(storage_err)?;
+        Ok(json.as_deref().map(decode).transpose()?.map(|r| r.token))
+    }
+
+    async fn save_account(
+        &self,
+        provider: &ProviderId,
+        account_id: &str,
+        label: Option<&str>,
+        token: &OAuthToken,
+    ) -> Result<()> {
+        tracing::debug!(%provider, %account_id, "saving account token");
+        let mut conn = self.conn.clone();
+        let existing: Option<String> = conn
+            .hget(accounts_key(provider), account_id)
+            .await
+            .map_err(storage_err)?;
+        let json = merge_record(existing.as_deref(), label, token)?;
+        let () = conn
+            .hset(accounts_key(provider), account_id, json)
+            .await
+            .map_err(storage_err)?;
+        // The first account saved for a provider becomes active.
+        let _: bool = conn
+            .set_nx(active_key(provider), account_id)
+            .await
+            .map_err(storage_err)?;
+        Ok(())
+    }
+
+    async fn remove_account(&self, provider: &ProviderId, account_id: &str) -> Result<()> {
+        let mut conn = self.conn.clone();
+        let _: usize = conn
+            .hdel(accounts_key(provider), account_id)
+            .await
+            .map_err(storage_err)?;
+        let _: usize = conn
+            .hdel(last_used_key(provider), account_id)
+            .await
+            .map_err(storage_err)?;
+        Ok(())
+    }
+
+    async fn list_accounts(&self, provider: &ProviderId) -> Result<Vec<AccountInfo>> {
+        let last_used: HashMap<String, i64> = self
+            .conn
+            .clone()
+            .hgetall(last_used_key(provider))
+            .await
+            .map_err(storage_err)?;
+        Ok(self
+            .records(provider)
+            .await?
+            .into_iter()
+            .map(|(account_id, record, is_active)| AccountInfo {
+                last_used_at: last_used.get(&account_id).copied(),
+                account_id,
+                label: record.label,
+                is_active,
+            })
+            .collect())
+    }
+
+    async fn touch_last_used(&self, provider: &ProviderId, account_id: &str) -> Result<()> {
+        let mut conn = self.conn.clone();
+        let exists: bool = conn
+            .hexists(accounts_key(provider), account_id)
+            .await
+            .map_err(storage_err)?;
+        if exists {
+            let () = conn
+                .hset(
+                    last_used_key(provider),
+                    account_id,
+                    crate::persistent::now_unix(),
+                )
+                .await
+                .map_err(storage_err)?;
+        }
+        Ok(())
+    }
+
+    async fn set_active(&self, provider: &ProviderId, account_id: &str) -> Result<()> {
+        tracing::debug!(%provider, %account_id, "setting active account");
+        let mut conn = self.conn.clone();
+        let exists: bool = conn
+            .hexists(accounts_key(provider), account_id)
+            .await
+            .map_err(storage_err)?;
+        if !exists {
+            return Err(ByokError::Storage(format!(
+                "account '{account_id}' not found for provider {provider}"
+            )));
+        }
+        let () = conn
+            .set(active_key(provider), account_id)
+            .await
+            .map_err(storage_err)?;
+        Ok(())
+    }
+
+    async fn load_all_tokens(&self, provider: &ProviderId) -> Result<Vec<(String, OAuthToken)>> {
+        Ok(self
+            .records(provider)
+            .await?
+            .into_iter()
+            .map(|(id, record, _)| (id, record.token))
+            .collect())
+    }
+
+    async fn clear_all(&self) -> Result<()> {
+        let keys: Vec<String> = ProviderId::all()
+            .iter()
+            .flat_map(|p| [accounts_key(p), active_key(p), last_used_key(p)])
+            .collect();
+        let _: usize = self.conn.clone().del(keys).await.map_err(storage_err)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_scheme() {
+        assert_eq!(accounts_key(&ProviderId::Claude), "byokey:claude:accounts");
+        assert_eq!(active_key(&ProviderId::Claude), "byokey:claude:active");
+        assert_eq!(
+            last_used_key(&ProviderId::Claude),
+            "byokey:claude:last_used"
+        );
+    }
+
+    #[test]
+    fn test_merge_record_keeps_label_when_none() {
+        let first = merge_record(None, Some("Work"), &OAuthToken::new("a")).unwrap();
+        let second = merge_record(Some(&first), None, &OAuthToken::new("b")).unwrap();
+        let record = decode(&second).unwrap();
+        assert_eq!(record.token.access_token, "b");
+        assert_eq!(record.label.as_deref(), Some("Work"));
+    }
+
+    #[tokio::test]
+    async fn test_rediss_url_rejected() {
+        let Err(err) = RedisTokenStore::new("rediss://localhost:6379").await else {
+            panic!("rediss:// must be rejected");
+        };
+        assert_eq!(err.kind(), redis::ErrorKind::InvalidClientConfig);
+        assert!(err.to_string().contains("rediss://"));
+    }
+
+    /// Runs against a live server when `BYOKEY_TEST_REDIS_URL` is set.
+    #[tokio::test]
+    async fn test_multi_account_roundtrip() {
+        let Ok(url) = std::env::var("BYOKEY_TEST_REDIS_URL") else {
+            return;
+        };
+        let store = RedisTokenStore::new(&url).await.unwrap();
+        store.clear_all().await.unwrap();
+
+        let provider = ProviderId::Claude;
+        store
+            .save_account(&provider, "work", Some("Work"), &OAuthToken::new("w"))
+            .await
+            .unwrap();
+        store
+            .save_account(&provider, "personal", None, &OAuthToken::new("p"))
+            .await
+            .unwrap();
+        assert_eq!(
+            store.load(&provider).await.unwrap().unwrap().access_token,
+            "w"
+        );
+
+        store.set_active(&provider, "personal").await.unwrap();
+        store
+            .save(&provider, &OAuthToken::new("refreshed"))
+            .await
+            .unwrap();
+        let accounts = store.list_accounts(&provider).await.unwrap();
+        assert_eq!(accounts.len(), 2);
+        assert_eq!(accounts[0].account_id, "personal");
+        assert!(accounts[0].is_active);
+        assert_eq!(
+            store.load(&provider).await.unwrap().unwrap().access_token,
+            "refreshed"
+        );
+        assert!(store.set_active(&provider, "missing").await.is_err());
+
+        store.touch_last_used(&provider, "work").await.unwrap();
+        store.touch_last_used(&provider, "missing").await.unwrap();
+        let accounts = store.list_accounts(&provider).await.unwrap();
+        assert!(accounts[0].last_used_at.is_none());
+        assert!(accounts[1].last_used_at.is_some());
+
+        store.remove(&provider).await.unwrap();
+        assert_eq!(
+            store.load(&provider).await.unwrap().unwrap().access_token,
+            "w"
+        );
+        store.clear_all().await.unwrap();
+        assert!(store.load_all_tokens(&provider).await.unwrap().is_empty());
+    }
+}
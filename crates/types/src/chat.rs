@@ -5,8 +5,7 @@
 //! while preserving forward-compatibility through a catch-all extra map.
 
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
-use std::collections::HashMap;
+use serde_json::{Map, Value};
 
 /// A strongly-typed OpenAI-compatible chat completion request body.
 ///
@@ -23,10 +22,14 @@ pub struct ChatRequest {
     #[serde(default)]
     pub stream: bool,
     /// The conversation messages.
+    ///
+    /// May be omitted by legacy clients that send a top-level `prompt`
+    /// instead; see [`ChatRequest::apply_legacy_prompt`].
+    #[serde(default)]
     pub messages: Vec<Value>,
-    /// All remaining fields not captured above.
+    /// All remaining fields not captured above, in client order.
     #[serde(flatten)]
-    pub extra: HashMap<String, Value>,
+    pub extra: Map<String, Value>,
 }
 
 impl ChatRequest {
@@ -47,6 +50,35 @@ impl ChatRequest {
         Value::Object(map)
     }
 
+    /// Converts a legacy completions-style `prompt` string into a single
+    /// user message.
+    ///
+    /// Does nothing when no `prompt` field is present.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ByokError::Translation`](crate::ByokError::Translation) if
+    /// both `prompt` and `messages` are supplied, or if `prompt` is not a
+    /// string.
+    pub fn apply_legacy_prompt(&mut self) -> crate::Result<()> {
+        let Some(prompt) = self.extra.shift_remove("prompt") else {
+            return Ok(());
+        };
+        if !self.messages.is_empty() {
+            return Err(crate::ByokError::Translation(
+                "`prompt` and `messages` are mutually exclusive".into(),
+            ));
+        }
+        let Value::String(prompt) = prompt else {
+            return Err(crate::ByokError::Translation(
+                "`prompt` must be a string".into(),
+            ));
+        };
+        self.messages
+            .push(serde_json::json!({"role": "user", "content": prompt}));
+        Ok(())
+    }
+
     /// Returns a `serde_json::Value` view of the full body without consuming self.
     #[must_use]
     pub fn to_body(&self) -> Value {
@@ -126,6 +158,59 @@ mod tests {
         assert_eq!(req.model, "m");
     }
 
+    #[test]
+    fn test_legacy_prompt_becomes_user_message() {
+        let v = json!({"model": "m", "prompt": "Say hi", "max_tokens": 8});
+        let mut req: ChatRequest = serde_json::from_value(v).unwrap();
+        req.apply_legacy_prompt().unwrap();
+        assert_eq!(
+            req.messages,
+            vec![json!({"role": "user", "content": "Say hi"})]
+        );
+        assert!(!req.extra.contains_key("prompt"));
+        assert_eq!(req.extra["max_tokens"], json!(8));
+    }
+
+    #[test]
+    fn test_legacy_prompt_with_messages_rejected() {
+        let v = json!({
+            "model": "m",
+            "prompt": "Say hi",
+            "messages": [{"role": "user", "content": "hi"}]
+        });
+        let mut req: ChatRequest = serde_json::from_value(v).unwrap();
+        assert!(req.apply_legacy_prompt().is_err());
+    }
+
+    #[test]
+    fn test_field_order_survives_round_trip() {
+        let raw = r#"{"model":"m","messages":[],"zeta":1,"user_id":"u","alpha":0.5}"#;
+        let req: ChatRequest = serde_json::from_str(raw).unwrap();
+        let keys: Vec<&str> = req.extra.keys().map(String::as_str).collect();
+        assert_eq!(keys, ["zeta", "user_id", "alpha"]);
+        let body = serde_json::to_string(&req.into_body()).unwrap();
+        assert!(
+            body.starts_with(r#"{"zeta":1,"user_id":"u","alpha":0.5,"#),
+            "{body}"
+        );
+    }
+
+    #[cfg(feature = "arbitrary-precision")]
+    #[test]
+    fn test_large_integer_and_field_order_survive_round_trip() {
+        let raw = r#"{"model":"m","messages":[],"zeta":1,"user_id":123456789012345678901234567890,"alpha":0.10000000000000000001}"#;
+        let req: ChatRequest = serde_json::from_str(raw).unwrap();
+        let keys: Vec<&str> = req.extra.keys().map(String::as_str).collect();
+        assert_eq!(keys, ["zeta", "user_id", "alpha"]);
+
+        let body = serde_json::to_string(&req.into_body()).unwrap();
+        assert!(
+            body.contains(r#""user_id":123456789012345678901234567890"#),
+            "{body}"
+        );
+        assert!(body.contains(r#""alpha":0.10000000000000000001"#), "{body}");
+    }
+
     #[test]
     fn test_stream_defaults_to_false() {
         let v = json!({"model": "m", "messages": []});
@@ -22,6 +22,15 @@ pub enum ByokError {
     #[error("provider not available: {0}")]
     ProviderUnavailable(crate::ProviderId),
 
+    /// The selected provider is disabled in configuration (`enabled: false`).
+    #[error("provider disabled: {0}")]
+    ProviderDisabled(crate::ProviderId),
+
+    /// The provider's `max_concurrency` limit stayed full while the request
+    /// waited for a slot.
+    #[error("too many concurrent requests to provider: {0}")]
+    ProviderBusy(crate::ProviderId),
+
     /// Request or response format translation failure.
     #[error("translation error: {0}")]
     Translation(String),
@@ -30,6 +39,10 @@ pub enum ByokError {
     #[error("http error: {0}")]
     Http(String),
 
+    /// The upstream did not respond within the configured timeout.
+    #[error("upstream timed out after {0:?}")]
+    Timeout(Duration),
+
     /// JSON serialization or deserialization error.
     #[error("serialization error: {0}")]
     Serialization(#[from] serde_json::Error),
@@ -97,16 +110,20 @@ impl ByokError {
     pub fn is_retryable(&self) -> bool {
         match self {
             Self::Upstream { status, .. } => matches!(status, 408 | 429 | 500 | 502 | 503 | 504),
-            Self::Http(_) => true, // transport errors are retryable
+            Self::Http(_) | Self::Timeout(_) => true, // transport errors are retryable
             _ => false,
         }
     }
 
     /// Returns the server-indicated retry delay, if available.
+    ///
+    /// A busy provider suggests a short, fixed delay: slots free up as soon
+    /// as in-flight requests finish.
     #[must_use]
     pub fn retry_after(&self) -> Option<Duration> {
         match self {
             Self::Upstream { retry_after, .. } => *retry_after,
+            Self::ProviderBusy(_) => Some(Duration::from_secs(1)),
             _ => None,
         }
     }
@@ -131,6 +148,12 @@ mod tests {
         assert!(err.to_string().contains("claude"));
     }
 
+    #[test]
+    fn test_error_display_provider_disabled() {
+        let err = ByokError::ProviderDisabled(crate::ProviderId::Codex);
+        assert_eq!(err.to_string(), "provider disabled: codex");
+    }
+
     #[test]
     fn test_error_display_upstream() {
         let err = ByokError::Upstream {
@@ -231,6 +254,7 @@ mod tests {
     #[test]
     fn test_is_retryable_http_transport() {
         assert!(ByokError::Http("connection refused".into()).is_retryable());
+        assert!(ByokError::Timeout(Duration::from_secs(30)).is_retryable());
     }
 
     #[test]
@@ -7,16 +7,31 @@ use std::fmt;
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, utoipa::ToSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ProviderId {
+    #[serde(alias = "anthropic")]
     Claude,
+    #[serde(alias = "openai", alias = "gpt", alias = "chatgpt")]
     Codex,
+    #[serde(alias = "google")]
     Gemini,
     Kiro,
+    #[serde(alias = "github", alias = "github-copilot", alias = "github_copilot")]
     Copilot,
     Antigravity,
+    #[serde(alias = "alibaba")]
     Qwen,
+    #[serde(alias = "moonshot")]
     Kimi,
+    #[serde(alias = "iflow", alias = "zai", alias = "glm")]
     IFlow,
+    #[serde(alias = "ampcode")]
     Amp,
+    #[serde(alias = "mistralai")]
+    Mistral,
+    /// Any OpenAI-compatible endpoint configured via `base_url`.
+    Custom,
+    /// Offline test provider served by `byokey_provider::mock` (requires its
+    /// `testing` feature). Not listed in [`ProviderId::all`].
+    Mock,
 }
 
 impl fmt::Display for ProviderId {
@@ -32,6 +47,9 @@ impl fmt::Display for ProviderId {
             Self::Kimi => write!(f, "kimi"),
             Self::IFlow => write!(f, "iflow"),
             Self::Amp => write!(f, "amp"),
+            Self::Mistral => write!(f, "mistral"),
+            Self::Custom => write!(f, "custom"),
+            Self::Mock => write!(f, "mock"),
         }
     }
 }
@@ -39,25 +57,35 @@ impl fmt::Display for ProviderId {
 impl std::str::FromStr for ProviderId {
     type Err = crate::ByokError;
 
-    /// Parse a provider name or well-known alias into a [`ProviderId`].
+    /// Parse a provider name or well-known alias into a [`ProviderId`],
+    /// ignoring case and surrounding whitespace.
     ///
     /// # Errors
     ///
     /// Returns [`ByokError::UnsupportedProvider`] if the string does not match
-    /// any known provider name or alias.
+    /// any known provider name or alias; the message lists the valid names.
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
+        match s.trim().to_ascii_lowercase().as_str() {
             "claude" | "anthropic" => Ok(Self::Claude),
-            "codex" | "openai" => Ok(Self::Codex),
+            "codex" | "openai" | "gpt" | "chatgpt" => Ok(Self::Codex),
             "gemini" | "google" => Ok(Self::Gemini),
             "kiro" => Ok(Self::Kiro),
-            "copilot" | "github" => Ok(Self::Copilot),
+            "copilot" | "github" | "github-copilot" | "github_copilot" => Ok(Self::Copilot),
             "antigravity" => Ok(Self::Antigravity),
             "qwen" | "alibaba" => Ok(Self::Qwen),
             "kimi" | "moonshot" => Ok(Self::Kimi),
-            "iflow" | "zai" | "glm" => Ok(Self::IFlow),
+            "iflow" | "i_flow" | "zai" | "glm" => Ok(Self::IFlow),
             "amp" | "ampcode" => Ok(Self::Amp),
-            other => Err(crate::ByokError::UnsupportedProvider(other.to_string())),
+            "mistral" | "mistralai" => Ok(Self::Mistral),
+            "custom" => Ok(Self::Custom),
+            "mock" => Ok(Self::Mock),
+            _ => {
+                let names: Vec<String> = Self::all().iter().map(ToString::to_string).collect();
+                Err(crate::ByokError::UnsupportedProvider(format!(
+                    "{s} (expected one of: {})",
+                    names.join(", ")
+                )))
+            }
         }
     }
 }
@@ -77,10 +105,14 @@ impl ProviderId {
             Self::Kimi => "Kimi (Moonshot)",
             Self::IFlow => "iFlow (Z.ai)",
             Self::Amp => "Amp (AmpCode)",
+            Self::Mistral => "Mistral AI",
+            Self::Custom => "Custom (OpenAI-compatible)",
+            Self::Mock => "Mock (testing)",
         }
     }
 
-    /// Returns all known provider variants.
+    /// Returns all user-facing provider variants ([`ProviderId::Mock`] is
+    /// left out).
     #[must_use]
     pub fn all() -> &'static [Self] {
         &[
@@ -94,6 +126,8 @@ impl ProviderId {
             Self::Kimi,
             Self::IFlow,
             Self::Amp,
+            Self::Mistral,
+            Self::Custom,
         ]
     }
 }
@@ -126,6 +160,8 @@ mod tests {
         assert_eq!(ProviderId::Kimi.to_string(), "kimi");
         assert_eq!(ProviderId::IFlow.to_string(), "iflow");
         assert_eq!(ProviderId::Amp.to_string(), "amp");
+        assert_eq!(ProviderId::Mistral.to_string(), "mistral");
+        assert_eq!(ProviderId::Custom.to_string(), "custom");
     }
 
     #[test]
@@ -146,6 +182,13 @@ mod tests {
         assert_eq!(ProviderId::from_str("kimi").unwrap(), ProviderId::Kimi);
         assert_eq!(ProviderId::from_str("iflow").unwrap(), ProviderId::IFlow);
         assert_eq!(ProviderId::from_str("amp").unwrap(), ProviderId::Amp);
+        assert_eq!(
+            ProviderId::from_str("mistral").unwrap(),
+            ProviderId::Mistral
+        );
+        assert_eq!(ProviderId::from_str("custom").unwrap(), ProviderId::Custom);
+        assert_eq!(ProviderId::from_str("mock").unwrap(), ProviderId::Mock);
+        assert!(!ProviderId::all().contains(&ProviderId::Mock));
     }
 
     #[test]
@@ -171,6 +214,48 @@ mod tests {
         assert!(matches!(err, crate::ByokError::UnsupportedProvider(_)));
     }
 
+    #[test]
+    fn test_from_str_synonyms() {
+        assert_eq!(
+            ProviderId::from_str("github-copilot").unwrap(),
+            ProviderId::Copilot
+        );
+        assert_eq!(ProviderId::from_str("gpt").unwrap(), ProviderId::Codex);
+        assert_eq!(ProviderId::from_str("OpenAI").unwrap(), ProviderId::Codex);
+        assert_eq!(
+            ProviderId::from_str(" Claude ").unwrap(),
+            ProviderId::Claude
+        );
+    }
+
+    #[test]
+    fn test_from_str_unknown_lists_names() {
+        let msg = ProviderId::from_str("no-such-provider")
+            .unwrap_err()
+            .to_string();
+        assert!(msg.contains("no-such-provider"));
+        assert!(msg.contains("expected one of: claude, codex, gemini"));
+        for provider in ProviderId::all() {
+            assert!(msg.contains(&provider.to_string()), "{provider} not listed");
+        }
+        assert!(msg.contains("mistral"));
+    }
+
+    #[test]
+    fn test_serde_aliases() {
+        let parsed: Vec<ProviderId> =
+            serde_json::from_str(r#"["github-copilot", "openai", "anthropic", "iflow"]"#).unwrap();
+        assert_eq!(
+            parsed,
+            [
+                ProviderId::Copilot,
+                ProviderId::Codex,
+                ProviderId::Claude,
+                ProviderId::IFlow
+            ]
+        );
+    }
+
     #[test]
     fn test_serde_roundtrip() {
         for p in [
@@ -184,6 +269,7 @@ mod tests {
             ProviderId::Kimi,
             ProviderId::IFlow,
             ProviderId::Amp,
+            ProviderId::Mistral,
         ] {
             let json = serde_json::to_string(&p).unwrap();
             let back: ProviderId = serde_json::from_str(&json).unwrap();
@@ -47,7 +47,19 @@ impl OAuthToken {
         self
     }
 
+    /// Return the time remaining until `expires_at`.
+    ///
+    /// Returns `None` when the token has no `expires_at` (it never expires)
+    /// and [`Duration::ZERO`] once the expiry timestamp has passed.
+    #[must_use]
+    pub fn expires_in(&self) -> Option<Duration> {
+        let expires_at = self.expires_at?;
+        Some(Duration::from_secs(expires_at.saturating_sub(unix_now())))
+    }
+
     /// Return `true` if the token expires within 60 seconds.
+    ///
+    /// A token without `expires_at` never expires.
     #[must_use]
     pub fn is_expired(&self) -> bool {
         let Some(expires_at) = self.expires_at else {
@@ -105,6 +117,10 @@ pub struct AccountInfo {
     pub label: Option<String>,
     /// Whether this account is the active one for its provider.
     pub is_active: bool,
+    /// When the account last served a request (Unix seconds), if the store
+    /// tracks it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_used_at: Option<i64>,
 }
 
 #[cfg(test)]
@@ -210,6 +226,33 @@ mod tests {
         assert!(!t.should_proactive_refresh());
     }
 
+    #[test]
+    fn test_expires_in_absent() {
+        let t = OAuthToken::new("tok");
+        assert!(t.expires_in().is_none());
+        assert!(!t.is_expired());
+    }
+
+    #[test]
+    fn test_expires_in_future() {
+        let t = OAuthToken::new("tok").with_expiry(3600);
+        let left = t.expires_in().unwrap();
+        assert!(left > Duration::from_secs(3590) && left <= Duration::from_hours(1));
+        assert!(!t.is_expired());
+    }
+
+    #[test]
+    fn test_expires_in_past_saturates_to_zero() {
+        let t = OAuthToken {
+            access_token: "tok".into(),
+            refresh_token: None,
+            expires_at: Some(past_secs(100)),
+            token_type: None,
+        };
+        assert_eq!(t.expires_in(), Some(Duration::ZERO));
+        assert!(t.is_expired());
+    }
+
     #[test]
     fn test_serde_roundtrip() {
         let t = OAuthToken::new("access")
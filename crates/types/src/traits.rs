@@ -9,6 +9,7 @@ use async_trait::async_trait;
 use bytes::Bytes;
 use futures_core::Stream;
 use serde_json::Value;
+use std::collections::HashMap;
 use std::pin::Pin;
 
 /// A pinned, sendable stream of SSE byte chunks.
@@ -100,10 +101,46 @@ pub trait TokenStore: Send + Sync {
         ))
     }
 
+    /// Record that an account just served a request, updating its
+    /// [`AccountInfo::last_used_at`]. The default implementation does nothing.
+    async fn touch_last_used(&self, _provider: &ProviderId, _account_id: &str) -> Result<()> {
+        Ok(())
+    }
+
     /// Load all valid tokens for a provider (for round-robin rotation).
     async fn load_all_tokens(&self, _provider: &ProviderId) -> Result<Vec<(String, OAuthToken)>> {
         Ok(Vec::new())
     }
+
+    /// Remove every account of every provider.
+    ///
+    /// The default implementation removes each listed account, then the
+    /// active token (for stores without multi-account support).
+    async fn clear_all(&self) -> Result<()> {
+        for provider in ProviderId::all() {
+            for account in self.list_accounts(provider).await? {
+                self.remove_account(provider, &account.account_id).await?;
+            }
+            if self.load(provider).await?.is_some() {
+                self.remove(provider).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Load the active-account token of every provider that has one.
+    ///
+    /// The default implementation calls [`load`](Self::load) per provider;
+    /// stores that can fetch everything in one query should override it.
+    async fn load_all_active(&self) -> Result<HashMap<ProviderId, OAuthToken>> {
+        let mut tokens = HashMap::new();
+        for provider in ProviderId::all() {
+            if let Some(token) = self.load(provider).await? {
+                tokens.insert(provider.clone(), token);
+            }
+        }
+        Ok(tokens)
+    }
 }
 
 /// Summary of a stored conversation.
@@ -267,4 +304,15 @@ pub trait ProviderExecutor: Send + Sync {
     async fn chat_completion(&self, request: ChatRequest) -> Result<ProviderResponse>;
     /// List the model identifiers supported by this provider.
     fn supported_models(&self) -> Vec<String>;
+    /// The provider requests are sent to, if this executor targets one.
+    fn provider_id(&self) -> Option<ProviderId> {
+        None
+    }
+    /// Checks that the executor's credentials currently work, with the
+    /// cheapest authenticated call the provider offers. Returns `None` for
+    /// providers without such a call, so they are reported as unchecked
+    /// rather than healthy.
+    async fn health_check(&self) -> Option<Result<()>> {
+        None
+    }
 }
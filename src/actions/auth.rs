@@ -1,8 +1,9 @@
 use anyhow::Result;
 use byokey_auth::AuthManager;
 use byokey_daemon::process::ServerStatus;
-use byokey_types::{OAuthToken, ProviderId};
-use std::{path::PathBuf, sync::Arc};
+use byokey_types::{AccountInfo, OAuthToken, ProviderId, TokenState};
+use std::path::PathBuf;
+use std::time::Duration;
 
 pub struct AuthCmd {
     auth: AuthManager,
@@ -11,17 +12,32 @@ pub struct AuthCmd {
 impl AuthCmd {
     pub async fn new(db: Option<PathBuf>) -> Result<Self> {
         eprintln!("[auth] opening store...");
-        let store = Arc::new(crate::open_store(db).await?);
+        let store = crate::open_token_store(db).await?;
         eprintln!("[auth] creating http client...");
         let auth = AuthManager::new(store, rquest::Client::new());
         eprintln!("[auth] ready");
         Ok(Self { auth })
     }
 
-    pub async fn login(&self, provider: ProviderId, account: Option<String>) -> Result<()> {
-        byokey_auth::flow::login(&provider, &self.auth, account.as_deref())
-            .await
-            .map_err(|e| anyhow::anyhow!("login failed: {e}"))?;
+    pub async fn login(
+        &self,
+        provider: ProviderId,
+        account: Option<String>,
+        label: Option<String>,
+        proxy: Option<String>,
+        device: bool,
+    ) -> Result<()> {
+        let proxy = match proxy {
+            Some(url) => Some(url),
+            None => configured_proxy_url()?,
+        };
+        let (account, label, proxy) = (account.as_deref(), label.as_deref(), proxy.as_deref());
+        let result = if device || is_headless() {
+            byokey_auth::flow::login_pasted(&provider, &self.auth, account, label, proxy).await
+        } else {
+            byokey_auth::flow::login(&provider, &self.auth, account, label, proxy).await
+        };
+        result.map_err(|e| anyhow::anyhow!("login failed: {e}"))?;
         Ok(())
     }
 
@@ -132,32 +148,105 @@ impl AuthCmd {
         Ok(())
     }
 
-    pub async fn status(&self) -> Result<()> {
-        match byokey_daemon::process::status() {
-            Ok(ServerStatus::Running { pid }) => println!("server: running (pid {pid})"),
-            Ok(ServerStatus::Stale { .. }) => println!("server: not running (stale pid file)"),
-            Ok(ServerStatus::Stopped) | Err(_) => println!("server: not running"),
+    /// Remove every credential for every provider, after confirmation
+    /// unless `yes` is set.
+    pub async fn logout_all(&self, yes: bool) -> Result<()> {
+        if !yes {
+            use std::io::Write as _;
+            print!("Remove ALL accounts for ALL providers? [y/N] ");
+            std::io::stdout().flush()?;
+            let mut answer = String::new();
+            std::io::stdin().read_line(&mut answer)?;
+            if !matches!(answer.trim(), "y" | "Y" | "yes" | "YES") {
+                println!("aborted");
+                return Ok(());
+            }
         }
-        println!();
+        self.auth
+            .clear_all_tokens()
+            .await
+            .map_err(|e| anyhow::anyhow!("logout failed: {e}"))?;
+        println!("all providers logged out");
+        Ok(())
+    }
 
+    /// Prints server state and each provider's authentication status, or
+    /// with `json` a machine-readable array of active-account statuses.
+    pub async fn status(&self, json: bool) -> Result<()> {
+        let mut rows = Vec::new();
+        let mut lines = Vec::new();
+        let states = self.auth.token_states_all().await;
         for provider in ProviderId::all() {
             let accounts = self.auth.list_accounts(provider).await.unwrap_or_default();
-            if accounts.is_empty() {
-                println!("{provider}: not authenticated");
-            } else if accounts.len() == 1 {
-                let status = if self.auth.is_authenticated(provider).await {
+            let Some(active) = accounts
+                .iter()
+                .find(|a| a.is_active)
+                .or_else(|| accounts.first())
+            else {
+                lines.push(format!("{provider}: not authenticated"));
+                rows.push(serde_json::json!({
+                    "provider": provider.to_string(),
+                    "account": null,
+                    "label": null,
+                    "state": "not_authenticated",
+                    "expires_in_secs": null,
+                }));
+                continue;
+            };
+            let token = self
+                .auth
+                .get_all_tokens(provider)
+                .await
+                .unwrap_or_default()
+                .into_iter()
+                .find(|(id, _)| *id == active.account_id)
+                .map(|(_, token)| token);
+            let authenticated = states
+                .get(provider)
+                .is_some_and(|s| *s != TokenState::Invalid);
+            let expiry = token.as_ref().map_or_else(String::new, |t| {
+                format!(" ({})", describe_expiry(t.expires_in()))
+            });
+
+            if accounts.len() == 1 {
+                let status = if authenticated {
                     "authenticated"
                 } else {
                     "expired"
                 };
-                println!("{provider}: {status}");
-            } else {
-                let active = accounts.iter().find(|a| a.is_active);
                 let label = active
-                    .and_then(|a| a.label.as_deref())
-                    .unwrap_or_else(|| active.map_or("?", |a| a.account_id.as_str()));
-                println!("{provider}: {} account(s), active: {label}", accounts.len());
+                    .label
+                    .as_deref()
+                    .map_or(String::new(), |l| format!(" [{l}]"));
+                lines.push(format!("{provider}: {status}{label}{expiry}"));
+            } else {
+                let label = active.label.as_deref().unwrap_or(&active.account_id);
+                lines.push(format!(
+                    "{provider}: {} account(s), active: {label}{expiry}",
+                    accounts.len()
+                ));
             }
+            rows.push(serde_json::json!({
+                "provider": provider.to_string(),
+                "account": active.account_id,
+                "label": active.label,
+                "state": if authenticated { "authenticated" } else { "expired" },
+                "expires_in_secs": token.and_then(|t| t.expires_in()).map(|d| d.as_secs()),
+            }));
+        }
+
+        if json {
+            println!("{}", serde_json::to_string_pretty(&rows)?);
+            return Ok(());
+        }
+        match byokey_daemon::process::status() {
+            Ok(ServerStatus::Running { pid }) => println!("server: running (pid {pid})"),
+            Ok(ServerStatus::Stale { .. }) => println!("server: not running (stale pid file)"),
+            Ok(ServerStatus::Stopped) | Err(_) => println!("server: not running"),
+        }
+        println!();
+        for line in lines {
+            println!("{line}");
         }
         Ok(())
     }
@@ -177,13 +266,24 @@ impl AuthCmd {
                     .label
                     .as_deref()
                     .map_or(String::new(), |l| format!(" [{l}]"));
-                println!("  {}{label}{marker}", a.account_id);
+                let now = std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .map_or(0, |d| d.as_secs());
+                println!(
+                    "  {}{label}{marker} — {}",
+                    a.account_id,
+                    describe_last_used(a.last_used_at, now)
+                );
             }
         }
         Ok(())
     }
 
-    pub async fn switch(&self, provider: ProviderId, account: String) -> Result<()> {
+    pub async fn switch(&self, provider: ProviderId, account: Option<String>) -> Result<()> {
+        let account = match account {
+            Some(account) => account,
+            None => self.pick_account(&provider).await?,
+        };
         self.auth
             .set_active_account(&provider, &account)
             .await
@@ -191,4 +291,164 @@ impl AuthCmd {
         println!("{provider}: switched to account '{account}'");
         Ok(())
     }
+
+    /// Lists `provider`'s accounts and prompts for one by number.
+    async fn pick_account(&self, provider: &ProviderId) -> Result<String> {
+        use std::io::{IsTerminal as _, Write as _};
+
+        if !std::io::stdin().is_terminal() {
+            anyhow::bail!("no account given; pass one explicitly when not on a terminal");
+        }
+        let accounts = self
+            .auth
+            .list_accounts(provider)
+            .await
+            .map_err(|e| anyhow::anyhow!("{e}"))?;
+        if accounts.is_empty() {
+            anyhow::bail!("{provider}: no accounts");
+        }
+        for (i, a) in accounts.iter().enumerate() {
+            let marker = if a.is_active { " (active)" } else { "" };
+            let label = a
+                .label
+                .as_deref()
+                .map_or(String::new(), |l| format!(" [{l}]"));
+            println!("  {}) {}{label}{marker}", i + 1, a.account_id);
+        }
+        print!("Select account [1-{}]: ", accounts.len());
+        std::io::stdout().flush()?;
+        let mut answer = String::new();
+        std::io::stdin().read_line(&mut answer)?;
+        let choice: usize = answer
+            .trim()
+            .parse()
+            .map_err(|_| anyhow::anyhow!("not a number: {}", answer.trim()))?;
+        select_account(&accounts, choice)
+    }
+}
+
+/// Returns the account id at 1-based position `choice`.
+fn select_account(accounts: &[AccountInfo], choice: usize) -> Result<String> {
+    choice
+        .checked_sub(1)
+        .and_then(|i| accounts.get(i))
+        .map(|a| a.account_id.clone())
+        .ok_or_else(|| anyhow::anyhow!("choose a number between 1 and {}", accounts.len()))
+}
+
+/// Human-readable time until a token expires, e.g. `expires in 14m`.
+fn describe_expiry(expires_in: Option<Duration>) -> String {
+    let Some(left) = expires_in else {
+        return "no expiry".to_string();
+    };
+    let secs = left.as_secs();
+    match secs {
+        0 => "expired".to_string(),
+        1..60 => format!("expires in {secs}s"),
+        60..3600 => format!("expires in {}m", secs / 60),
+        3600..86400 => format!("expires in {}h {}m", secs / 3600, secs % 3600 / 60),
+        _ => format!("expires in {}d", secs / 86400),
+    }
+}
+
+/// Human-readable age of an account's last use at unix time `now`, e.g.
+/// `last used 3h ago`.
+fn describe_last_used(last_used_at: Option<i64>, now: u64) -> String {
+    let Some(at) = last_used_at.and_then(|t| u64::try_from(t).ok()) else {
+        return "never used".to_string();
+    };
+    let secs = now.saturating_sub(at);
+    match secs {
+        0..60 => "last used just now".to_string(),
+        60..3600 => format!("last used {}m ago", secs / 60),
+        3600..86400 => format!("last used {}h ago", secs / 3600),
+        _ => format!("last used {}d ago", secs / 86400),
+    }
+}
+
+/// Whether a browser opened here could not reach this machine's `localhost`:
+/// an SSH session, or a Unix desktop-less session.
+fn is_headless() -> bool {
+    let set = |var: &str| std::env::var_os(var).is_some_and(|v| !v.is_empty());
+    set("SSH_CONNECTION")
+        || (cfg!(all(unix, not(target_os = "macos"))) && !set("DISPLAY") && !set("WAYLAND_DISPLAY"))
+}
+
+/// Reads `proxy_url` from the default config file, if one exists.
+fn configured_proxy_url() -> Result<Option<String>> {
+    let Ok(path) = byokey_daemon::paths::config_path() else {
+        return Ok(None);
+    };
+    if !path.exists() {
+        return Ok(None);
+    }
+    let config = byokey_config::Config::from_file(&path)
+        .map_err(|e| anyhow::anyhow!("config error: {e}"))?;
+    Ok(config.proxy_url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn accounts() -> Vec<AccountInfo> {
+        ["default", "work", "personal"]
+            .into_iter()
+            .map(|id| AccountInfo {
+                account_id: id.to_string(),
+                label: None,
+                is_active: id == "default",
+                last_used_at: None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn describe_expiry_formats() {
+        assert_eq!(describe_expiry(None), "no expiry");
+        assert_eq!(describe_expiry(Some(Duration::ZERO)), "expired");
+        assert_eq!(
+            describe_expiry(Some(Duration::from_secs(45))),
+            "expires in 45s"
+        );
+        assert_eq!(
+            describe_expiry(Some(Duration::from_secs(14 * 60 + 5))),
+            "expires in 14m"
+        );
+        assert_eq!(
+            describe_expiry(Some(Duration::from_secs(2 * 3600 + 300))),
+            "expires in 2h 5m"
+        );
+        assert_eq!(
+            describe_expiry(Some(Duration::from_secs(3 * 86400))),
+            "expires in 3d"
+        );
+    }
+
+    #[test]
+    fn describe_last_used_formats() {
+        let now = 1_000_000;
+        assert_eq!(describe_last_used(None, now), "never used");
+        assert_eq!(describe_last_used(Some(999_990), now), "last used just now");
+        assert_eq!(
+            describe_last_used(Some(1_000_000 - 5 * 60), now),
+            "last used 5m ago"
+        );
+        assert_eq!(
+            describe_last_used(Some(1_000_000 - 2 * 86400), now),
+            "last used 2d ago"
+        );
+    }
+
+    #[test]
+    fn select_account_is_one_based() {
+        assert_eq!(select_account(&accounts(), 1).unwrap(), "default");
+        assert_eq!(select_account(&accounts(), 3).unwrap(), "personal");
+    }
+
+    #[test]
+    fn select_account_rejects_out_of_range() {
+        assert!(select_account(&accounts(), 0).is_err());
+        assert!(select_account(&accounts(), 4).is_err());
+    }
 }
@@ -0,0 +1,244 @@
+use anyhow::Result;
+use byokey_auth::AuthManager;
+use byokey_config::Config;
+use byokey_types::{
+    ChatRequest, ProviderId,
+    traits::{ProviderExecutor, ProviderResponse},
+};
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Outcome of a single benchmark request.
+#[derive(Debug, Clone)]
+pub struct Sample {
+    pub latency: Duration,
+    pub ok: bool,
+    pub output_tokens: u64,
+}
+
+/// Aggregated benchmark results.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchReport {
+    pub requests: usize,
+    pub errors: usize,
+    pub p50: Duration,
+    pub p95: Duration,
+    pub tokens_per_sec: f64,
+}
+
+impl BenchReport {
+    /// Summarises samples; latency percentiles cover successful requests only.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn from_samples(samples: &[Sample]) -> Self {
+        let mut latencies: Vec<Duration> =
+            samples.iter().filter(|s| s.ok).map(|s| s.latency).collect();
+        latencies.sort_unstable();
+        let busy: Duration = latencies.iter().sum();
+        let tokens: u64 = samples
+            .iter()
+            .filter(|s| s.ok)
+            .map(|s| s.output_tokens)
+            .sum();
+        Self {
+            requests: samples.len(),
+            errors: samples.iter().filter(|s| !s.ok).count(),
+            p50: percentile(&latencies, 50),
+            p95: percentile(&latencies, 95),
+            tokens_per_sec: if busy.is_zero() {
+                0.0
+            } else {
+                tokens as f64 / busy.as_secs_f64()
+            },
+        }
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn error_rate(&self) -> f64 {
+        if self.requests == 0 {
+            0.0
+        } else {
+            self.errors as f64 / self.requests as f64 * 100.0
+        }
+    }
+}
+
+/// Nearest-rank percentile of an ascending slice.
+fn percentile(sorted: &[Duration], pct: usize) -> Duration {
+    if sorted.is_empty() {
+        return Duration::ZERO;
+    }
+    let rank = (pct * sorted.len()).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
+
+fn bench_request(model: &str) -> ChatRequest {
+    ChatRequest {
+        model: model.to_string(),
+        stream: false,
+        messages: vec![serde_json::json!({"role": "user", "content": "Reply with OK."})],
+        extra: [("max_tokens".to_string(), serde_json::json!(16))]
+            .into_iter()
+            .collect(),
+    }
+}
+
+/// Sends `n` sequential requests through `executor`, timing each one.
+pub async fn run_bench(executor: &dyn ProviderExecutor, model: &str, n: usize) -> Vec<Sample> {
+    let mut samples = Vec::with_capacity(n);
+    for i in 0..n {
+        let started = Instant::now();
+        let result = executor.chat_completion(bench_request(model)).await;
+        let latency = started.elapsed();
+        let sample = match result {
+            Ok(ProviderResponse::Complete(json)) => Sample {
+                latency,
+                ok: true,
+                output_tokens: json
+                    .pointer("/usage/completion_tokens")
+                    .and_then(serde_json::Value::as_u64)
+                    .unwrap_or(0),
+            },
+            Ok(ProviderResponse::Stream(_)) => Sample {
+                latency,
+                ok: true,
+                output_tokens: 0,
+            },
+            Err(e) => {
+                eprintln!("[bench] request {} failed: {e}", i + 1);
+                Sample {
+                    latency,
+                    ok: false,
+                    output_tokens: 0,
+                }
+            }
+        };
+        samples.push(sample);
+    }
+    samples
+}
+
+pub async fn cmd_bench(
+    model: String,
+    n: usize,
+    config_path: Option<PathBuf>,
+    db: Option<PathBuf>,
+) -> Result<()> {
+    let config_path = config_path.or_else(|| {
+        let default = byokey_daemon::paths::config_path().ok()?;
+        default.exists().then_some(default)
+    });
+    let config = match config_path {
+        Some(path) => Config::from_file(&path).map_err(|e| anyhow::anyhow!("config error: {e}"))?,
+        None => Config::default().with_env_overrides(),
+    };
+
+    let store = crate::open_token_store(db).await?;
+    let auth = Arc::new(AuthManager::new(store, rquest::Client::new()));
+    let mut oauth_providers = HashSet::new();
+    for p in ProviderId::all() {
+        if auth.is_authenticated(p).await {
+            oauth_providers.insert(p.clone());
+        }
+    }
+
+    let resolved = config.resolve_alias(&model);
+    let (provider_hint, bare_model) =
+        byokey_provider::parse_qualified_model_with(&resolved, config.custom_model_prefix());
+    let versions = byokey_proxy::VersionStore::empty();
+    let executor = byokey_provider::make_executor_for_model(
+        bare_model,
+        |p| Some(config.providers.get(p).cloned().unwrap_or_default()),
+        &oauth_providers,
+        provider_hint.as_ref(),
+        auth,
+        rquest::Client::new(),
+        None,
+        &versions,
+    )
+    .map_err(|e| anyhow::anyhow!("{e}"))?;
+
+    eprintln!("[bench] sending {n} request(s) to {bare_model}...");
+    let samples = run_bench(executor.as_ref(), bare_model, n).await;
+    let report = BenchReport::from_samples(&samples);
+
+    println!("model:       {bare_model}");
+    println!("requests:    {}", report.requests);
+    println!(
+        "errors:      {} ({:.1}%)",
+        report.errors,
+        report.error_rate()
+    );
+    println!("p50:         {} ms", report.p50.as_millis());
+    println!("p95:         {} ms", report.p95.as_millis());
+    println!("tokens/sec:  {:.1}", report.tokens_per_sec);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct StubExecutor {
+        calls: AtomicUsize,
+    }
+
+    #[async_trait::async_trait]
+    impl ProviderExecutor for StubExecutor {
+        async fn chat_completion(
+            &self,
+            request: ChatRequest,
+        ) -> byokey_types::Result<ProviderResponse> {
+            assert!(!request.stream);
+            let call = self.calls.fetch_add(1, Ordering::SeqCst);
+            if call == 0 {
+                return Err(byokey_types::ByokError::Http("boom".into()));
+            }
+            Ok(ProviderResponse::Complete(serde_json::json!({
+                "usage": {"prompt_tokens": 5, "completion_tokens": 2}
+            })))
+        }
+
+        fn supported_models(&self) -> Vec<String> {
+            vec!["stub".into()]
+        }
+    }
+
+    #[tokio::test]
+    async fn run_bench_records_n_results() {
+        let stub = StubExecutor {
+            calls: AtomicUsize::new(0),
+        };
+        let samples = run_bench(&stub, "stub", 5).await;
+        assert_eq!(samples.len(), 5);
+        assert_eq!(stub.calls.load(Ordering::SeqCst), 5);
+
+        let report = BenchReport::from_samples(&samples);
+        assert_eq!(report.requests, 5);
+        assert_eq!(report.errors, 1);
+        assert!(
+            samples
+                .iter()
+                .filter(|s| s.ok)
+                .all(|s| s.output_tokens == 2)
+        );
+    }
+
+    #[test]
+    fn percentiles_use_nearest_rank() {
+        let samples: Vec<Sample> = (1..=20)
+            .map(|ms| Sample {
+                latency: Duration::from_millis(ms),
+                ok: true,
+                output_tokens: 1,
+            })
+            .collect();
+        let report = BenchReport::from_samples(&samples);
+        assert_eq!(report.p50, Duration::from_millis(10));
+        assert_eq!(report.p95, Duration::from_millis(19));
+        assert_eq!(report.errors, 0);
+        assert!(report.tokens_per_sec > 0.0);
+    }
+}
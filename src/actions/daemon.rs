@@ -1,5 +1,7 @@
 use anyhow::Result;
+use byokey_daemon::process::StopResult;
 use clap::Subcommand;
+use std::time::Duration;
 
 use crate::DaemonArgs;
 
@@ -9,6 +11,10 @@ pub enum ServiceAction {
     Install {
         #[command(flatten)]
         daemon: DaemonArgs,
+        /// Wait until the service accepts connections on its port and fail
+        /// if it does not come up.
+        #[arg(long, alias = "foreground")]
+        wait: bool,
     },
     /// Uninstall the OS-managed service.
     Uninstall,
@@ -22,7 +28,7 @@ pub enum ServiceAction {
 
 pub fn cmd_service(action: ServiceAction) -> Result<()> {
     match action {
-        ServiceAction::Install { daemon } => cmd_service_install(daemon),
+        ServiceAction::Install { daemon, wait } => cmd_service_install(daemon, wait),
         ServiceAction::Uninstall => cmd_service_uninstall(),
         ServiceAction::Start => cmd_service_start(),
         ServiceAction::Stop => cmd_service_stop(),
@@ -61,10 +67,12 @@ pub fn cmd_start(args: DaemonArgs) -> Result<()> {
 }
 
 pub fn cmd_stop() -> Result<()> {
-    let result = byokey_daemon::process::stop()?;
-    match result.pid {
-        Some(pid) => println!("byokey stopped (pid {pid})"),
-        None => println!("byokey stopped"),
+    match byokey_daemon::process::stop()? {
+        StopResult::Stopped { pid } => println!("byokey stopped (pid {pid})"),
+        StopResult::NotRunning {
+            stale_pid: Some(pid),
+        } => println!("byokey is not running (removed stale PID file for pid {pid})"),
+        StopResult::NotRunning { stale_pid: None } => println!("byokey is not running"),
     }
     Ok(())
 }
@@ -84,7 +92,8 @@ pub fn cmd_reload() -> Result<()> {
 
 // ── Service (OS-managed) ─────────────────────────────────────────────────────
 
-fn cmd_service_install(args: DaemonArgs) -> Result<()> {
+fn cmd_service_install(args: DaemonArgs, wait: bool) -> Result<()> {
+    let target = wait.then(|| listen_target(&args));
     let result = byokey_daemon::service::install(service_opts(args))?;
     println!("service installed ({})", result.backend);
     println!("label:   {}", result.label);
@@ -92,9 +101,36 @@ fn cmd_service_install(args: DaemonArgs) -> Result<()> {
     // the service immediately on all backends. Start it now for convenience.
     byokey_daemon::service::start()?;
     println!("service started");
+    if let Some((host, port)) = target {
+        if !byokey_daemon::health::wait_for_port(&host, port, HEALTH_TIMEOUT) {
+            anyhow::bail!(
+                "service is not serving on {host}:{port} after {}s; check its logs",
+                HEALTH_TIMEOUT.as_secs()
+            );
+        }
+        println!("service healthy on {host}:{port}");
+    }
     Ok(())
 }
 
+const HEALTH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Resolves the address the server will listen on: CLI overrides first,
+/// then the config file, then defaults.
+fn listen_target(args: &DaemonArgs) -> (String, u16) {
+    let config_path = args.server.config.clone().or_else(|| {
+        let default = byokey_daemon::paths::config_path().ok()?;
+        default.exists().then_some(default)
+    });
+    let config = config_path
+        .and_then(|p| byokey_config::Config::from_file(&p).ok())
+        .unwrap_or_default();
+    (
+        args.server.host.clone().unwrap_or(config.host),
+        args.server.port.unwrap_or(config.port),
+    )
+}
+
 fn cmd_service_uninstall() -> Result<()> {
     byokey_daemon::service::uninstall()?;
     println!("service uninstalled");
@@ -0,0 +1,329 @@
+use anyhow::{Result, bail};
+use byokey_auth::AuthManager;
+use byokey_config::Config;
+use byokey_types::{ProviderId, TokenState};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// How long the `proxy_url` connectivity check waits for a TCP connection.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Level {
+    Pass,
+    Warn,
+    Fail,
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Pass => "pass",
+            Self::Warn => "warn",
+            Self::Fail => "FAIL",
+        })
+    }
+}
+
+/// One line of the doctor checklist.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Check {
+    pub level: Level,
+    pub name: String,
+    pub detail: String,
+}
+
+impl Check {
+    fn new(level: Level, name: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self {
+            level,
+            name: name.into(),
+            detail: detail.into(),
+        }
+    }
+}
+
+/// Providers are enabled unless configured with `enabled: false`.
+fn is_enabled(config: &Config, provider: &ProviderId) -> bool {
+    config.providers.get(provider).is_none_or(|pc| pc.enabled)
+}
+
+/// Flags `backend` and `fallback` settings that point at a disabled provider.
+///
+/// A disabled `backend` fails every request, so it is a hard failure; a
+/// disabled `fallback` only removes the safety net.
+pub fn check_routing(config: &Config) -> Vec<Check> {
+    let mut checks = Vec::new();
+    for provider in ProviderId::all() {
+        let Some(pc) = config.providers.get(provider).filter(|pc| pc.enabled) else {
+            continue;
+        };
+        if let Some(backend) = &pc.backend
+            && !is_enabled(config, backend)
+        {
+            checks.push(Check::new(
+                Level::Fail,
+                format!("{provider} backend"),
+                format!("routes through {backend}, which is disabled"),
+            ));
+        }
+        if let Some(fallback) = &pc.fallback
+            && !is_enabled(config, fallback)
+        {
+            checks.push(Check::new(
+                Level::Warn,
+                format!("{provider} fallback"),
+                format!("falls back to {fallback}, which is disabled"),
+            ));
+        }
+    }
+    checks
+}
+
+/// Resolves `proxy_url` to the `host:port` to connect to.
+///
+/// # Errors
+///
+/// Returns a description of the problem if the URL is malformed.
+pub fn proxy_address(proxy_url: &str) -> std::result::Result<String, String> {
+    let url = rquest::Url::parse(proxy_url).map_err(|e| format!("invalid URL: {e}"))?;
+    let host = url.host_str().ok_or("URL has no host")?;
+    let port = url
+        .port_or_known_default()
+        .or_else(|| url.scheme().starts_with("socks").then_some(1080))
+        .ok_or("URL has no port")?;
+    Ok(format!("{host}:{port}"))
+}
+
+async fn check_proxy(proxy_url: &str) -> Check {
+    let addr = match proxy_address(proxy_url) {
+        Ok(addr) => addr,
+        Err(e) => return Check::new(Level::Fail, "proxy_url", format!("{proxy_url}: {e}")),
+    };
+    match tokio::time::timeout(CONNECT_TIMEOUT, tokio::net::TcpStream::connect(&addr)).await {
+        Ok(Ok(_)) => Check::new(Level::Pass, "proxy_url", format!("{addr} reachable")),
+        Ok(Err(e)) => Check::new(Level::Fail, "proxy_url", format!("{addr}: {e}")),
+        Err(_) => Check::new(
+            Level::Fail,
+            "proxy_url",
+            format!(
+                "{addr}: no connection within {}s",
+                CONNECT_TIMEOUT.as_secs()
+            ),
+        ),
+    }
+}
+
+/// Checks that the SQLite database at `path` (or its directory, if it does
+/// not exist yet) can be written.
+fn check_db_writable(path: &Path) -> Check {
+    let result = if path.exists() {
+        std::fs::OpenOptions::new().write(true).open(path).map(drop)
+    } else {
+        let dir = path.parent().unwrap_or_else(|| Path::new("."));
+        std::fs::create_dir_all(dir).and_then(|()| {
+            let probe = dir.join(format!(".byokey-doctor-{}", std::process::id()));
+            std::fs::write(&probe, b"")?;
+            std::fs::remove_file(&probe)
+        })
+    };
+    match result {
+        Ok(()) => Check::new(Level::Pass, "database", path.display().to_string()),
+        Err(e) => Check::new(
+            Level::Fail,
+            "database",
+            format!("{} is not writable: {e}", path.display()),
+        ),
+    }
+}
+
+/// Reports how each provider will authenticate. Providers that are neither
+/// configured nor logged in are left out.
+async fn check_providers(config: &Config, auth: &AuthManager) -> Vec<Check> {
+    let states = auth.token_states_all().await;
+    let mut checks = Vec::new();
+    for provider in ProviderId::all() {
+        let configured = config.providers.get(provider);
+        if !is_enabled(config, provider) {
+            if configured.is_some() {
+                checks.push(Check::new(Level::Pass, provider.to_string(), "disabled"));
+            }
+            continue;
+        }
+        if configured.is_some_and(|pc| pc.api_key.is_some() || !pc.api_keys.is_empty()) {
+            checks.push(Check::new(Level::Pass, provider.to_string(), "API key"));
+            continue;
+        }
+        if *provider == ProviderId::Custom {
+            if configured.is_some_and(|pc| pc.base_url.is_some()) {
+                checks.push(Check::new(Level::Pass, provider.to_string(), "base_url"));
+            }
+            continue;
+        }
+        let check = match states.get(provider) {
+            Some(TokenState::Valid) => Check::new(Level::Pass, provider.to_string(), "logged in"),
+            Some(TokenState::Expired) => Check::new(
+                Level::Warn,
+                provider.to_string(),
+                "token expired; it is refreshed on next use",
+            ),
+            _ if configured.is_some() => Check::new(
+                Level::Warn,
+                provider.to_string(),
+                format!("no credentials; run `byokey login {provider}` or set api_key"),
+            ),
+            _ => continue,
+        };
+        checks.push(check);
+    }
+    checks
+}
+
+/// Runs every check and prints the checklist, failing if any check failed.
+///
+/// There is no TLS certificate check: byokey serves plain HTTP (or a Unix
+/// socket) and the config has no `tls.cert`/`tls.key` settings to verify.
+pub async fn cmd_doctor(config_path: Option<PathBuf>, db: Option<PathBuf>) -> Result<()> {
+    let mut checks = Vec::new();
+
+    let config_path = config_path.or_else(|| {
+        let default = byokey_daemon::paths::config_path().ok()?;
+        default.exists().then_some(default)
+    });
+    let config = match &config_path {
+        Some(path) if !path.exists() => {
+            checks.push(Check::new(
+                Level::Fail,
+                "config",
+                format!("{} does not exist", path.display()),
+            ));
+            None
+        }
+        Some(path) => match Config::from_file(path) {
+            Ok(config) => {
+                checks.push(Check::new(
+                    Level::Pass,
+                    "config",
+                    path.display().to_string(),
+                ));
+                Some(config)
+            }
+            Err(e) => {
+                checks.push(Check::new(
+                    Level::Fail,
+                    "config",
+                    format!("{}: {e}", path.display()),
+                ));
+                None
+            }
+        },
+        None => {
+            checks.push(Check::new(
+                Level::Pass,
+                "config",
+                "no config file; using defaults",
+            ));
+            Some(Config::default().with_env_overrides())
+        }
+    };
+
+    if let Some(config) = &config {
+        checks.extend(check_routing(config));
+        if let Some(proxy_url) = &config.proxy_url {
+            checks.push(check_proxy(proxy_url).await);
+        }
+    }
+
+    let db_path = match &db {
+        Some(_) if crate::redis_url(db.as_ref()).is_some() => None,
+        Some(path) => Some(path.clone()),
+        None => byokey_daemon::paths::db_path().ok(),
+    };
+    let db_ok = match db_path {
+        Some(path) => {
+            let check = check_db_writable(&path);
+            let ok = check.level == Level::Pass;
+            checks.push(check);
+            ok
+        }
+        None => true,
+    };
+
+    if db_ok {
+        match crate::open_token_store(db).await {
+            Ok(store) => {
+                let auth = AuthManager::new(store, rquest::Client::new());
+                let config = config.unwrap_or_default();
+                checks.extend(check_providers(&config, &auth).await);
+            }
+            Err(e) => checks.push(Check::new(Level::Fail, "token store", format!("{e:#}"))),
+        }
+    }
+
+    for check in &checks {
+        println!("[{}] {}: {}", check.level, check.name, check.detail);
+    }
+    let failed = checks.iter().filter(|c| c.level == Level::Fail).count();
+    if failed > 0 {
+        bail!("{failed} check(s) failed");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use byokey_config::ProviderConfig;
+
+    #[test]
+    fn disabled_backend_fails_and_disabled_fallback_warns() {
+        let mut config = Config::default();
+        config.providers.insert(
+            ProviderId::Claude,
+            ProviderConfig {
+                backend: Some(ProviderId::Copilot),
+                fallback: Some(ProviderId::Gemini),
+                ..Default::default()
+            },
+        );
+        for disabled in [ProviderId::Copilot, ProviderId::Gemini] {
+            config.providers.insert(
+                disabled,
+                ProviderConfig {
+                    enabled: false,
+                    ..Default::default()
+                },
+            );
+        }
+
+        let levels: Vec<_> = check_routing(&config)
+            .into_iter()
+            .map(|c| (c.name, c.level))
+            .collect();
+        assert_eq!(
+            levels,
+            [
+                ("claude backend".to_string(), Level::Fail),
+                ("claude fallback".to_string(), Level::Warn),
+            ]
+        );
+    }
+
+    #[test]
+    fn proxy_address_uses_scheme_default_port() {
+        assert_eq!(
+            proxy_address("http://proxy.local").as_deref(),
+            Ok("proxy.local:80")
+        );
+        assert_eq!(
+            proxy_address("socks5://127.0.0.1").as_deref(),
+            Ok("127.0.0.1:1080")
+        );
+        assert_eq!(
+            proxy_address("http://10.0.0.1:3128").as_deref(),
+            Ok("10.0.0.1:3128")
+        );
+        assert!(proxy_address("not a url").is_err());
+    }
+}
@@ -1,5 +1,7 @@
 pub mod amp;
 pub mod auth;
+pub mod bench;
 pub mod daemon;
+pub mod doctor;
 pub mod serve;
 pub mod telemetry;
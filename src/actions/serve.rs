@@ -1,8 +1,9 @@
 use anyhow::Result;
 use arc_swap::ArcSwap;
+use axum::Router;
 use byokey_auth::AuthManager;
-use byokey_config::{Config, ConfigWatcher, LogConfig, LogFormat};
-use byokey_proxy::AppState;
+use byokey_config::{Config, ConfigWatcher, LogConfig, LogFormat, UsageConfig};
+use byokey_proxy::{AppState, UsageSnapshot};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Instant;
@@ -18,6 +19,20 @@ use crate::ServerArgs;
 use crate::actions::telemetry;
 use crate::control_server::{self, ControlState};
 
+/// Attempts to access the config file before `serve` falls back to defaults.
+const CONFIG_LOAD_ATTEMPTS: u32 = 5;
+
+/// Prefix of a `host` value that names a Unix socket path (`unix:/path`).
+const UNIX_HOST_PREFIX: &str = "unix:";
+
+/// The HTTP listener: a TCP address, or a Unix socket when `host` is
+/// `unix:/path`.
+enum HttpListener {
+    Tcp(tokio::net::TcpListener),
+    #[cfg(unix)]
+    Unix(tokio::net::UnixListener),
+}
+
 fn init_logging(cfg: &LogConfig, log_file: Option<PathBuf>) -> Option<WorkerGuard> {
     let env_filter =
         EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(&cfg.level));
@@ -74,15 +89,26 @@ pub async fn cmd_serve(args: ServerArgs) -> Result<()> {
     // Load config first so we can use log settings.
     let (config_arc, config_watcher): (Arc<ArcSwap<Config>>, Option<Arc<ConfigWatcher>>) =
         if let Some(ref path) = effective_path {
+            // Retry while the file is inaccessible, then start on defaults, so
+            // autostart at boot survives a mount that is not ready yet. A file
+            // that exists but does not load is fatal.
             let watcher = Arc::new(
-                ConfigWatcher::new(path.clone())
-                    .map_err(|e| anyhow::anyhow!("config error: {e}"))?,
+                ConfigWatcher::new_with_retry(path.clone(), CONFIG_LOAD_ATTEMPTS)
+                    .await
+                    .map_err(|e| anyhow::anyhow!("failed to load {}: {e}", path.display()))?,
             );
             let arc = watcher.arc();
-            Arc::clone(&watcher).watch();
+            if path.exists() {
+                Arc::clone(&watcher).watch();
+            }
             (arc, Some(watcher))
         } else {
-            (Arc::new(ArcSwap::from_pointee(Config::default())), None)
+            (
+                Arc::new(ArcSwap::from_pointee(
+                    Config::default().with_env_overrides(),
+                )),
+                None,
+            )
         };
 
     let snapshot = config_arc.load();
@@ -100,13 +126,39 @@ pub async fn cmd_serve(args: ServerArgs) -> Result<()> {
         tracing::info!("sentry enabled");
     }
 
+    if let Some(watcher) = &config_watcher {
+        byokey_provider::set_user_models(watcher.models());
+    }
+
     // CLI overrides for listen address.
     let effective_host = host.as_deref().unwrap_or(&snapshot.host).to_owned();
     let effective_port = port.unwrap_or(snapshot.port);
-    let addr = format!("{effective_host}:{effective_port}");
+    let unix_path = effective_host
+        .strip_prefix(UNIX_HOST_PREFIX)
+        .map(PathBuf::from);
+    #[cfg(not(unix))]
+    if unix_path.is_some() {
+        anyhow::bail!("`{UNIX_HOST_PREFIX}` hosts are only supported on Unix platforms");
+    }
+    let addr = if unix_path.is_some() {
+        effective_host.clone()
+    } else {
+        format!("{effective_host}:{effective_port}")
+    };
 
-    let store = Arc::new(crate::open_store(db).await?);
-    let auth = Arc::new(AuthManager::new(store.clone(), rquest::Client::new()));
+    // A `redis://` --db shares tokens between instances; usage history
+    // stays in the local SQLite database.
+    let (store, usage_store): (
+        Arc<dyn byokey_types::TokenStore>,
+        Arc<dyn byokey_types::UsageStore>,
+    ) = if crate::redis_url(db.as_ref()).is_some() {
+        let usage = Arc::new(crate::open_store(None).await?);
+        (crate::open_token_store(db).await?, usage)
+    } else {
+        let sqlite = Arc::new(crate::open_store(db).await?);
+        (sqlite.clone(), sqlite)
+    };
+    let auth = Arc::new(AuthManager::new(store, rquest::Client::new()));
 
     // Background token refresh: check every 60s, refresh tokens within 5 min of expiry.
     let _refresh_handle = auth.spawn_refresh_loop(
@@ -117,7 +169,6 @@ pub async fn cmd_serve(args: ServerArgs) -> Result<()> {
     // Fetch remote version/fingerprint info (falls back to compile-time defaults).
     let versions = byokey_proxy::VersionStore::fetch(&rquest::Client::new()).await;
 
-    let usage_store: Arc<dyn byokey_types::UsageStore> = store;
     let state = AppState::new(
         Arc::clone(&config_arc),
         auth,
@@ -125,9 +176,22 @@ pub async fn cmd_serve(args: ServerArgs) -> Result<()> {
         versions,
     );
 
-    // Pre-load cumulative usage from persisted records so the in-memory snapshot
-    // reflects historical totals even after a restart.
-    if let Ok(totals) = usage_store.totals(None, None).await {
+    // Restore the usage snapshot saved by the previous run. Without one,
+    // pre-load cumulative totals from persisted records so the in-memory
+    // snapshot still reflects historical usage after a restart.
+    let usage_path = usage_snapshot_path(&snapshot.usage);
+    let restored = match usage_path.as_deref().map(UsageSnapshot::load) {
+        Some(Ok(Some(saved))) => {
+            state.usage.restore(saved);
+            true
+        }
+        Some(Err(e)) => {
+            tracing::warn!(error = %e, "failed to load usage snapshot, starting from history");
+            false
+        }
+        _ => false,
+    };
+    if !restored && let Ok(totals) = usage_store.totals(None, None).await {
         for bucket in &totals {
             state.usage.preload(
                 &bucket.model,
@@ -137,6 +201,10 @@ pub async fn cmd_serve(args: ServerArgs) -> Result<()> {
             );
         }
     }
+    if let Some(path) = &usage_path {
+        let interval = std::time::Duration::from_secs(snapshot.usage.flush_interval_secs.max(1));
+        state.usage.spawn_snapshot_writer(path.clone(), interval);
+    }
     let app = byokey_proxy::make_router(Arc::clone(&state));
 
     // Acquire the HTTP listener. Prefer a pre-opened fd from systemfd /
@@ -144,25 +212,32 @@ pub async fn cmd_serve(args: ServerArgs) -> Result<()> {
     // EADDRINUSE in dev loops). Fall back to a fresh sync bind so that
     // EADDRINUSE surfaces immediately — `tokio::net::TcpListener::bind`
     // routes through async DNS and can hang in this process's runtime.
-    let listener = match listenfd::ListenFd::from_env().take_tcp_listener(0) {
-        Ok(Some(l)) => {
-            tracing::info!("using inherited TCP listener from environment");
-            l.set_nonblocking(true)
-                .map_err(|e| anyhow::anyhow!("set_nonblocking: {e}"))?;
-            tokio::net::TcpListener::from_std(l).map_err(|e| anyhow::anyhow!("from_std: {e}"))?
-        }
-        _ => {
-            let parsed: std::net::SocketAddr = addr
-                .parse()
-                .map_err(|e| anyhow::anyhow!("invalid address {addr}: {e}"))?;
-            let std_listener = std::net::TcpListener::bind(parsed)
-                .map_err(|e| anyhow::anyhow!("bind {addr}: {e}"))?;
-            std_listener
-                .set_nonblocking(true)
-                .map_err(|e| anyhow::anyhow!("set_nonblocking: {e}"))?;
-            tokio::net::TcpListener::from_std(std_listener)
-                .map_err(|e| anyhow::anyhow!("from_std: {e}"))?
-        }
+    let listener = match &unix_path {
+        #[cfg(unix)]
+        Some(path) => HttpListener::Unix(
+            bind_unix(path).map_err(|e| anyhow::anyhow!("bind {}: {e}", path.display()))?,
+        ),
+        _ => HttpListener::Tcp(match listenfd::ListenFd::from_env().take_tcp_listener(0) {
+            Ok(Some(l)) => {
+                tracing::info!("using inherited TCP listener from environment");
+                l.set_nonblocking(true)
+                    .map_err(|e| anyhow::anyhow!("set_nonblocking: {e}"))?;
+                tokio::net::TcpListener::from_std(l)
+                    .map_err(|e| anyhow::anyhow!("from_std: {e}"))?
+            }
+            _ => {
+                let parsed: std::net::SocketAddr = addr
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("invalid address {addr}: {e}"))?;
+                let std_listener = std::net::TcpListener::bind(parsed)
+                    .map_err(|e| anyhow::anyhow!("bind {addr}: {e}"))?;
+                std_listener
+                    .set_nonblocking(true)
+                    .map_err(|e| anyhow::anyhow!("set_nonblocking: {e}"))?;
+                tokio::net::TcpListener::from_std(std_listener)
+                    .map_err(|e| anyhow::anyhow!("from_std: {e}"))?
+            }
+        }),
     };
 
     // ── Control socket + unified shutdown signal ───────────────────────────
@@ -190,18 +265,45 @@ pub async fn cmd_serve(args: ServerArgs) -> Result<()> {
     tracing::info!(socket = %sock_path.display(), "control socket ready");
 
     spawn_signal_handler(Arc::clone(&shutdown));
+    let grace_seconds = snapshot.shutdown_grace_seconds;
     drop(snapshot);
     tracing::info!(addr = %addr, "byokey listening");
 
-    let shutdown_for_serve = Arc::clone(&shutdown);
-    let serve_result = axum::serve(listener, app)
-        .with_graceful_shutdown(async move {
-            shutdown_for_serve.notified().await;
-        })
-        .await
-        .map_err(anyhow::Error::from);
+    let server = serve_http(listener, app, Arc::clone(&shutdown));
+    // Graceful shutdown waits for every open connection, which a stuck SSE
+    // stream would hold forever; give up once the grace period elapses.
+    let in_flight = state.in_flight.clone();
+    let drain_deadline = async {
+        shutdown.notified().await;
+        tracing::info!(
+            in_flight = in_flight.count(),
+            grace_seconds,
+            "shutting down, draining in-flight requests"
+        );
+        tokio::time::sleep(std::time::Duration::from_secs(grace_seconds)).await;
+    };
+    let serve_result = tokio::select! {
+        result = server => result.map_err(anyhow::Error::from),
+        () = drain_deadline => {
+            tracing::warn!(
+                in_flight = in_flight.count(),
+                grace_seconds,
+                "shutdown grace period elapsed, dropping remaining requests"
+            );
+            Ok(())
+        }
+    };
 
     ctl_handle.cleanup();
+    if let Some(path) = &unix_path {
+        let _ = std::fs::remove_file(path);
+    }
+
+    if let Some(path) = &usage_path
+        && let Err(e) = state.usage.save_snapshot(path).await
+    {
+        tracing::warn!(error = %e, path = %path.display(), "failed to write usage snapshot");
+    }
 
     // Aux tokio tasks (config watcher, thread index watcher, control listener)
     // keep the runtime alive after axum::serve returns. Since the HTTP side has
@@ -213,6 +315,66 @@ pub async fn cmd_serve(args: ServerArgs) -> Result<()> {
     serve_result
 }
 
+/// Binds a Unix socket at `path`, creating its directory and replacing a
+/// stale socket left by a previous run. The socket is owner-only (0600).
+///
+/// # Errors
+///
+/// Fails if another server is accepting on `path`, if `path` exists and is
+/// not a socket, or if binding fails.
+#[cfg(unix)]
+fn bind_unix(path: &Path) -> std::io::Result<tokio::net::UnixListener> {
+    use std::os::unix::fs::{FileTypeExt as _, PermissionsExt as _};
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    if let Ok(meta) = std::fs::symlink_metadata(path) {
+        if !meta.file_type().is_socket() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::AlreadyExists,
+                "path exists and is not a socket",
+            ));
+        }
+        if std::os::unix::net::UnixStream::connect(path).is_ok() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::AddrInUse,
+                "another server is listening on this socket",
+            ));
+        }
+        std::fs::remove_file(path)?;
+    }
+    let listener = tokio::net::UnixListener::bind(path)?;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+    Ok(listener)
+}
+
+/// Serves `app` on `listener` until `shutdown` is notified, then drains
+/// open connections.
+async fn serve_http(
+    listener: HttpListener,
+    app: Router,
+    shutdown: Arc<Notify>,
+) -> std::io::Result<()> {
+    let signal = async move { shutdown.notified().await };
+    match listener {
+        HttpListener::Tcp(l) => axum::serve(l, app).with_graceful_shutdown(signal).await,
+        #[cfg(unix)]
+        HttpListener::Unix(l) => axum::serve(l, app).with_graceful_shutdown(signal).await,
+    }
+}
+
+/// Where the usage snapshot lives, or `None` when persistence is off.
+fn usage_snapshot_path(cfg: &UsageConfig) -> Option<PathBuf> {
+    if !cfg.persist {
+        return None;
+    }
+    cfg.file
+        .as_ref()
+        .map(PathBuf::from)
+        .or_else(|| byokey_daemon::paths::usage_path().ok())
+}
+
 fn spawn_signal_handler(shutdown: Arc<Notify>) {
     tokio::spawn(async move {
         #[cfg(unix)]
@@ -245,3 +407,50 @@ fn spawn_signal_handler(shutdown: Arc<Notify>) {
         shutdown.notify_waiters();
     });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(unix)]
+    use tokio::io::{AsyncReadExt as _, AsyncWriteExt as _};
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn unix_socket_serves_healthz() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("run").join("byokey.sock");
+        let state = AppState::new(
+            Arc::new(ArcSwap::from_pointee(Config::default())),
+            Arc::new(AuthManager::new(
+                Arc::new(byokey_store::InMemoryTokenStore::new()),
+                rquest::Client::new(),
+            )),
+            None,
+            byokey_proxy::VersionStore::empty(),
+        );
+        let listener = HttpListener::Unix(bind_unix(&path).unwrap());
+        let server = tokio::spawn(serve_http(
+            listener,
+            byokey_proxy::make_router(state),
+            Arc::new(Notify::new()),
+        ));
+
+        let mut stream = tokio::net::UnixStream::connect(&path).await.unwrap();
+        stream
+            .write_all(b"GET /healthz HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+            .await
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 200"), "{response}");
+
+        // A live socket is never replaced; a stale one left behind is.
+        assert_eq!(
+            bind_unix(&path).unwrap_err().kind(),
+            std::io::ErrorKind::AddrInUse
+        );
+        server.abort();
+        let _ = server.await;
+        assert!(bind_unix(&path).is_ok());
+    }
+}
@@ -1,13 +1,14 @@
 mod actions;
 mod control_server;
 
-use actions::{amp, auth, daemon, serve};
+use actions::{amp, auth, bench, daemon, doctor, serve};
 
 use anyhow::Result;
-use byokey_store::SqliteTokenStore;
-use byokey_types::ProviderId;
+use byokey_store::{SqliteTokenStore, TokenKey};
+use byokey_types::{ProviderId, TokenStore};
 use clap::{CommandFactory, Parser, Subcommand};
 use std::path::PathBuf;
+use std::sync::Arc;
 
 #[derive(Parser, Debug)]
 #[command(
@@ -30,10 +31,12 @@ struct ServerArgs {
     /// Override the listening port (default: 8018).
     #[arg(short, long)]
     port: Option<u16>,
-    /// Override the listening address (default: 127.0.0.1).
+    /// Override the listening address (default: 127.0.0.1), or `unix:<PATH>`
+    /// to listen on a Unix socket.
     #[arg(long)]
     host: Option<String>,
-    /// SQLite database path (default: ~/.byokey/tokens.db).
+    /// SQLite database path (default: ~/.byokey/tokens.db), or a
+    /// `redis://` URL to keep tokens in Redis (`redis` feature).
     #[arg(long, value_name = "PATH")]
     db: Option<PathBuf>,
     /// Log file path. If set, logs are written to this file with daily rotation.
@@ -51,7 +54,8 @@ struct DaemonArgs {
 /// Shared arguments for commands that access the token store.
 #[derive(clap::Args, Debug)]
 struct StoreArgs {
-    /// SQLite database path (default: ~/.byokey/tokens.db).
+    /// SQLite database path (default: ~/.byokey/tokens.db), or a
+    /// `redis://` URL to keep tokens in Redis (`redis` feature).
     #[arg(long, value_name = "PATH")]
     db: Option<PathBuf>,
 }
@@ -89,6 +93,17 @@ enum Commands {
         /// Account identifier (e.g. `work`, `personal`). Defaults to `default`.
         #[arg(long, value_name = "NAME")]
         account: Option<String>,
+        /// Human-readable label to show in `accounts`, `status` and UIs.
+        #[arg(long, value_name = "TEXT")]
+        label: Option<String>,
+        /// Proxy URL for the login flow (defaults to `proxy_url` from the config file).
+        #[arg(long, value_name = "URL")]
+        proxy: Option<String>,
+        /// Headless login: print the authorization URL and paste back the URL
+        /// the browser was redirected to, instead of listening on localhost.
+        /// Implied over SSH or without a display.
+        #[arg(long)]
+        device: bool,
         #[command(flatten)]
         store: StoreArgs,
     },
@@ -135,15 +150,25 @@ enum Commands {
     /// Remove stored credentials for a provider.
     Logout {
         /// Provider name.
-        provider: ProviderId,
+        #[arg(required_unless_present = "all")]
+        provider: Option<ProviderId>,
         /// Account identifier. If omitted, removes the active account.
         #[arg(long, value_name = "NAME")]
         account: Option<String>,
+        /// Remove every account for every provider.
+        #[arg(long, conflicts_with_all = ["provider", "account"])]
+        all: bool,
+        /// Skip the confirmation prompt for `--all`.
+        #[arg(long, short = 'y')]
+        yes: bool,
         #[command(flatten)]
         store: StoreArgs,
     },
     /// Show authentication status for all providers.
     Status {
+        /// Print a JSON array of `{provider, account, label, state, expires_in_secs}`.
+        #[arg(long)]
+        json: bool,
         #[command(flatten)]
         store: StoreArgs,
     },
@@ -164,8 +189,31 @@ enum Commands {
     Switch {
         /// Provider name.
         provider: ProviderId,
-        /// Account identifier to make active.
-        account: String,
+        /// Account identifier to make active. Omit to pick from a numbered
+        /// list (interactive terminals only).
+        account: Option<String>,
+        #[command(flatten)]
+        store: StoreArgs,
+    },
+    /// Measure provider latency by sending N trivial requests to a model.
+    Bench {
+        /// Model to benchmark (aliases and `provider/model` are resolved).
+        #[arg(long)]
+        model: String,
+        /// Number of requests to send.
+        #[arg(long, default_value_t = 20)]
+        n: usize,
+        /// Path to the configuration file (JSON or YAML).
+        #[arg(short, long, value_name = "FILE")]
+        config: Option<PathBuf>,
+        #[command(flatten)]
+        store: StoreArgs,
+    },
+    /// Diagnose common setup problems (config, database, proxy, logins).
+    Doctor {
+        /// Path to the configuration file (JSON or YAML).
+        #[arg(short, long, value_name = "FILE")]
+        config: Option<PathBuf>,
         #[command(flatten)]
         store: StoreArgs,
     },
@@ -194,11 +242,14 @@ async fn run(command: Commands) -> Result<()> {
         Commands::Login {
             provider,
             account,
+            label,
+            proxy,
+            device,
             store,
         } => {
             auth::AuthCmd::new(store.db)
                 .await?
-                .login(provider, account)
+                .login(provider, account, label, proxy, device)
                 .await
         }
         Commands::AddApiKey {
@@ -244,15 +295,24 @@ async fn run(command: Commands) -> Result<()> {
         Commands::Logout {
             provider,
             account,
+            all,
+            yes,
             store,
         } => {
-            auth::AuthCmd::new(store.db)
-                .await?
-                .logout(provider, account)
-                .await
+            let cmd = auth::AuthCmd::new(store.db).await?;
+            match provider {
+                Some(provider) if !all => cmd.logout(provider, account).await,
+                _ => cmd.logout_all(yes).await,
+            }
         }
-        Commands::Status { store } => auth::AuthCmd::new(store.db).await?.status().await,
+        Commands::Status { json, store } => auth::AuthCmd::new(store.db).await?.status(json).await,
         Commands::Tui { url } => byokey_tui::run(url).await,
+        Commands::Bench {
+            model,
+            n,
+            config,
+            store,
+        } => bench::cmd_bench(model, n, config, store.db).await,
         Commands::Accounts { provider, store } => {
             auth::AuthCmd::new(store.db).await?.accounts(provider).await
         }
@@ -266,6 +326,7 @@ async fn run(command: Commands) -> Result<()> {
                 .switch(provider, account)
                 .await
         }
+        Commands::Doctor { config, store } => doctor::cmd_doctor(config, store.db).await,
         Commands::Amp { action } => amp::cmd_amp(action),
         Commands::Openapi => {
             use utoipa::OpenApi as _;
@@ -298,6 +359,37 @@ async fn main() {
     }
 }
 
+/// Returns `db` if it names a Redis server rather than a file.
+pub(crate) fn redis_url(db: Option<&PathBuf>) -> Option<&str> {
+    db.and_then(|p| p.to_str())
+        .filter(|s| s.starts_with("redis://") || s.starts_with("rediss://"))
+}
+
+/// Opens the token store named by `--db`: Redis for a `redis://` URL,
+/// otherwise the SQLite database.
+pub(crate) async fn open_token_store(db: Option<PathBuf>) -> Result<Arc<dyn TokenStore>> {
+    if let Some(url) = redis_url(db.as_ref()) {
+        return open_redis_store(url).await;
+    }
+    Ok(Arc::new(open_store(db).await?))
+}
+
+#[cfg(feature = "redis")]
+async fn open_redis_store(url: &str) -> Result<Arc<dyn TokenStore>> {
+    let store = byokey_store::RedisTokenStore::new(url)
+        .await
+        .map_err(|e| anyhow::anyhow!("redis error: {e}"))?;
+    Ok(Arc::new(store))
+}
+
+#[cfg(not(feature = "redis"))]
+#[allow(clippy::unused_async)] // Mirrors the `redis` build's signature.
+async fn open_redis_store(url: &str) -> Result<Arc<dyn TokenStore>> {
+    anyhow::bail!(
+        "`--db {url}` needs a build with the `redis` feature (`cargo install --features redis`)"
+    )
+}
+
 pub(crate) async fn open_store(db: Option<PathBuf>) -> Result<SqliteTokenStore> {
     let path = match db {
         Some(p) => p,
@@ -307,7 +399,9 @@ pub(crate) async fn open_store(db: Option<PathBuf>) -> Result<SqliteTokenStore>
         std::fs::create_dir_all(parent)?;
     }
     let url = format!("sqlite://{}?mode=rwc", path.display());
-    SqliteTokenStore::new(&url)
-        .await
-        .map_err(|e| anyhow::anyhow!("database error: {e}"))
+    let store = match TokenKey::from_env()? {
+        Some(key) => SqliteTokenStore::new_encrypted(&url, key).await,
+        None => SqliteTokenStore::new(&url).await,
+    };
+    store.map_err(|e| anyhow::anyhow!("database error: {e}"))
 }